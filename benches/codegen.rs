@@ -3,7 +3,7 @@ use criterion::Criterion;
 
 fn bench(c: &mut Criterion) {
     cornucopia::container::cleanup(false).ok();
-    cornucopia::container::setup(false).unwrap();
+    cornucopia::container::setup(&Default::default()).unwrap();
     let client = &mut cornucopia_conn().unwrap();
 
     cornucopia::load_schema(client, &["../codegen_test/schema.sql"]).unwrap();
@@ -17,6 +17,7 @@ fn bench(c: &mut Criterion) {
                     gen_sync: true,
                     gen_async: false,
                     derive_ser: true,
+                    ..Default::default()
                 },
             )
             .unwrap()
@@ -32,6 +33,7 @@ fn bench(c: &mut Criterion) {
                     gen_sync: true,
                     gen_async: false,
                     derive_ser: true,
+                    ..Default::default()
                 },
             )
             .unwrap()
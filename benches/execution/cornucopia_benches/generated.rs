@@ -11,6 +11,7 @@ pub mod types {}
 #[allow(dead_code)]
 pub mod queries {
     pub mod bench {
+        /// Source: `queries/bench.sql:7`
         #[derive(Debug)]
         pub struct InsertUserParams<
             T1: cornucopia_async::StringSql,
@@ -19,6 +20,7 @@ pub mod queries {
             pub name: T1,
             pub hair_color: Option<T2>,
         }
+        /// Source: `queries/bench.sql:5`
         #[derive(Debug, Clone, PartialEq)]
         pub struct User {
             pub id: i32,
@@ -45,6 +47,21 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_async::FromPgRow<'a> for UserBorrowed<'a> {
+            fn from_row(row: &'a tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                Ok(Self {
+                    id: row.try_get("id")?,
+                    name: row.try_get("name")?,
+                    hair_color: row.try_get("hair_color")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_async::FromPgRow<'a> for User {
+            fn from_row(row: &'a tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                <UserBorrowed<'a> as cornucopia_async::FromPgRow<'a>>::from_row(row).map(Into::into)
+            }
+        }
+        /// Source: `queries/bench.sql:10`
         #[derive(Debug, Clone, PartialEq)]
         pub struct Post {
             pub id: i32,
@@ -75,6 +92,22 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_async::FromPgRow<'a> for PostBorrowed<'a> {
+            fn from_row(row: &'a tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                Ok(Self {
+                    id: row.try_get("id")?,
+                    user_id: row.try_get("user_id")?,
+                    title: row.try_get("title")?,
+                    body: row.try_get("body")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_async::FromPgRow<'a> for Post {
+            fn from_row(row: &'a tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                <PostBorrowed<'a> as cornucopia_async::FromPgRow<'a>>::from_row(row).map(Into::into)
+            }
+        }
+        /// Source: `queries/bench.sql:15`
         #[derive(Debug, Clone, PartialEq)]
         pub struct Comment {
             pub id: i32,
@@ -95,6 +128,22 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_async::FromPgRow<'a> for CommentBorrowed<'a> {
+            fn from_row(row: &'a tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                Ok(Self {
+                    id: row.try_get("id")?,
+                    post_id: row.try_get("post_id")?,
+                    text: row.try_get("text")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_async::FromPgRow<'a> for Comment {
+            fn from_row(row: &'a tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                <CommentBorrowed<'a> as cornucopia_async::FromPgRow<'a>>::from_row(row)
+                    .map(Into::into)
+            }
+        }
+        /// Source: `queries/bench.sql:20`
         #[derive(Debug, Clone, PartialEq)]
         pub struct SelectComplex {
             pub myuser_id: i32,
@@ -137,6 +186,25 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_async::FromPgRow<'a> for SelectComplexBorrowed<'a> {
+            fn from_row(row: &'a tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                Ok(Self {
+                    myuser_id: row.try_get("myuser_id")?,
+                    name: row.try_get("name")?,
+                    hair_color: row.try_get("hair_color")?,
+                    post_id: row.try_get("post_id")?,
+                    user_id: row.try_get("user_id")?,
+                    title: row.try_get("title")?,
+                    body: row.try_get("body")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_async::FromPgRow<'a> for SelectComplex {
+            fn from_row(row: &'a tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                <SelectComplexBorrowed<'a> as cornucopia_async::FromPgRow<'a>>::from_row(row)
+                    .map(Into::into)
+            }
+        }
         pub mod sync {
             use postgres::{fallible_iterator::FallibleIterator, GenericClient};
             pub struct UserQuery<'a, C: GenericClient, T, const N: usize> {
@@ -343,6 +411,11 @@ pub mod queries {
                     Ok(it)
                 }
             }
+            /// ```sql
+            /// SELECT * FROM users
+            /// ```
+            ///
+            /// Source: `queries/bench.sql:5`
             pub fn users() -> UsersStmt {
                 UsersStmt(cornucopia_sync::private::Stmt::new("SELECT * FROM users"))
             }
@@ -365,6 +438,11 @@ pub mod queries {
                     }
                 }
             }
+            /// ```sql
+            /// INSERT INTO users (name, hair_color) VALUES ($1, $2)
+            /// ```
+            ///
+            /// Source: `queries/bench.sql:7`
             pub fn insert_user() -> InsertUserStmt {
                 InsertUserStmt(cornucopia_sync::private::Stmt::new(
                     "INSERT INTO users (name, hair_color) VALUES ($1, $2)",
@@ -408,6 +486,26 @@ pub mod queries {
                     self.bind(client, &params.name, &params.hair_color)
                 }
             }
+            impl<
+                    'a,
+                    C: GenericClient,
+                    T1: cornucopia_sync::StringSql,
+                    T2: cornucopia_sync::StringSql,
+                > cornucopia_sync::Execute<C> for super::InsertUserParams<T1, T2>
+            {
+                fn execute(&self, client: &mut C) -> Result<u64, postgres::Error> {
+                    let name = &self.name;
+                    let hair_color = &self.hair_color;
+                    let mut stmt = insert_user();
+                    let stmt = stmt.0.prepare(client)?;
+                    client.execute(stmt, &[name, hair_color])
+                }
+            }
+            /// ```sql
+            /// SELECT * FROM posts
+            /// ```
+            ///
+            /// Source: `queries/bench.sql:10`
             pub fn posts() -> PostsStmt {
                 PostsStmt(cornucopia_sync::private::Stmt::new("SELECT * FROM posts"))
             }
@@ -431,6 +529,11 @@ pub mod queries {
                     }
                 }
             }
+            /// ```sql
+            /// SELECT * FROM posts WHERE user_id = ANY($1)
+            /// ```
+            ///
+            /// Source: `queries/bench.sql:12`
             pub fn post_by_user_ids() -> PostByUserIdsStmt {
                 PostByUserIdsStmt(cornucopia_sync::private::Stmt::new(
                     "SELECT * FROM posts WHERE user_id = ANY($1)",
@@ -457,6 +560,11 @@ pub mod queries {
                     }
                 }
             }
+            /// ```sql
+            /// SELECT * FROM comments
+            /// ```
+            ///
+            /// Source: `queries/bench.sql:15`
             pub fn comments() -> CommentsStmt {
                 CommentsStmt(cornucopia_sync::private::Stmt::new(
                     "SELECT * FROM comments",
@@ -481,6 +589,11 @@ pub mod queries {
                     }
                 }
             }
+            /// ```sql
+            /// SELECT * FROM comments WHERE post_id = ANY($1)
+            /// ```
+            ///
+            /// Source: `queries/bench.sql:17`
             pub fn comments_by_post_id() -> CommentsByPostIdStmt {
                 CommentsByPostIdStmt(cornucopia_sync::private::Stmt::new(
                     "SELECT * FROM comments WHERE post_id = ANY($1)",
@@ -506,8 +619,13 @@ pub mod queries {
                     }
                 }
             }
+            /// ```sql
+            /// SELECT u.id as myuser_id, u.name, u.hair_color, p.id as post_id, p.user_id, p.title, p.body FROM users as u LEFT JOIN posts as p on u.id = p.user_id
+            /// ```
+            ///
+            /// Source: `queries/bench.sql:20`
             pub fn select_complex() -> SelectComplexStmt {
-                SelectComplexStmt(cornucopia_sync :: private :: Stmt :: new("SELECT u.id as myuser_id, u.name, u.hair_color, p.id as post_id, p.user_id, p.title, p.body FROM users as u LEFT JOIN posts as p on u.id = p.user_id"))
+                SelectComplexStmt(cornucopia_sync::private::Stmt::new("SELECT u.id as myuser_id, u.name, u.hair_color, p.id as post_id, p.user_id, p.title, p.body FROM users as u LEFT JOIN posts as p on u.id = p.user_id"))
             }
             pub struct SelectComplexStmt(cornucopia_sync::private::Stmt);
             impl SelectComplexStmt {
@@ -757,6 +875,11 @@ pub mod queries {
                     Ok(it)
                 }
             }
+            /// ```sql
+            /// SELECT * FROM users
+            /// ```
+            ///
+            /// Source: `queries/bench.sql:5`
             pub fn users() -> UsersStmt {
                 UsersStmt(cornucopia_async::private::Stmt::new("SELECT * FROM users"))
             }
@@ -779,6 +902,11 @@ pub mod queries {
                     }
                 }
             }
+            /// ```sql
+            /// INSERT INTO users (name, hair_color) VALUES ($1, $2)
+            /// ```
+            ///
+            /// Source: `queries/bench.sql:7`
             pub fn insert_user() -> InsertUserStmt {
                 InsertUserStmt(cornucopia_async::private::Stmt::new(
                     "INSERT INTO users (name, hair_color) VALUES ($1, $2)",
@@ -834,6 +962,37 @@ pub mod queries {
                     Box::pin(self.bind(client, &params.name, &params.hair_color))
                 }
             }
+            impl<
+                    'a,
+                    C: GenericClient + Send + Sync,
+                    T1: cornucopia_async::StringSql,
+                    T2: cornucopia_async::StringSql,
+                > cornucopia_async::Execute<'a, C> for super::InsertUserParams<T1, T2>
+            {
+                fn execute(
+                    &'a self,
+                    client: &'a C,
+                ) -> std::pin::Pin<
+                    Box<
+                        dyn futures::Future<Output = Result<u64, tokio_postgres::Error>>
+                            + Send
+                            + 'a,
+                    >,
+                > {
+                    Box::pin(async move {
+                        let name = &self.name;
+                        let hair_color = &self.hair_color;
+                        let mut stmt = insert_user();
+                        let stmt = stmt.0.prepare(client).await?;
+                        client.execute(stmt, &[name, hair_color]).await
+                    })
+                }
+            }
+            /// ```sql
+            /// SELECT * FROM posts
+            /// ```
+            ///
+            /// Source: `queries/bench.sql:10`
             pub fn posts() -> PostsStmt {
                 PostsStmt(cornucopia_async::private::Stmt::new("SELECT * FROM posts"))
             }
@@ -857,6 +1016,11 @@ pub mod queries {
                     }
                 }
             }
+            /// ```sql
+            /// SELECT * FROM posts WHERE user_id = ANY($1)
+            /// ```
+            ///
+            /// Source: `queries/bench.sql:12`
             pub fn post_by_user_ids() -> PostByUserIdsStmt {
                 PostByUserIdsStmt(cornucopia_async::private::Stmt::new(
                     "SELECT * FROM posts WHERE user_id = ANY($1)",
@@ -883,6 +1047,11 @@ pub mod queries {
                     }
                 }
             }
+            /// ```sql
+            /// SELECT * FROM comments
+            /// ```
+            ///
+            /// Source: `queries/bench.sql:15`
             pub fn comments() -> CommentsStmt {
                 CommentsStmt(cornucopia_async::private::Stmt::new(
                     "SELECT * FROM comments",
@@ -907,6 +1076,11 @@ pub mod queries {
                     }
                 }
             }
+            /// ```sql
+            /// SELECT * FROM comments WHERE post_id = ANY($1)
+            /// ```
+            ///
+            /// Source: `queries/bench.sql:17`
             pub fn comments_by_post_id() -> CommentsByPostIdStmt {
                 CommentsByPostIdStmt(cornucopia_async::private::Stmt::new(
                     "SELECT * FROM comments WHERE post_id = ANY($1)",
@@ -932,8 +1106,13 @@ pub mod queries {
                     }
                 }
             }
+            /// ```sql
+            /// SELECT u.id as myuser_id, u.name, u.hair_color, p.id as post_id, p.user_id, p.title, p.body FROM users as u LEFT JOIN posts as p on u.id = p.user_id
+            /// ```
+            ///
+            /// Source: `queries/bench.sql:20`
             pub fn select_complex() -> SelectComplexStmt {
-                SelectComplexStmt(cornucopia_async :: private :: Stmt :: new("SELECT u.id as myuser_id, u.name, u.hair_color, p.id as post_id, p.user_id, p.title, p.body FROM users as u LEFT JOIN posts as p on u.id = p.user_id"))
+                SelectComplexStmt(cornucopia_async::private::Stmt::new("SELECT u.id as myuser_id, u.name, u.hair_color, p.id as post_id, p.user_id, p.title, p.body FROM users as u LEFT JOIN posts as p on u.id = p.user_id"))
             }
             pub struct SelectComplexStmt(cornucopia_async::private::Stmt);
             impl SelectComplexStmt {
@@ -127,7 +127,7 @@ fn prepare_full(client: &mut Client) {
 
 fn bench(c: &mut Criterion) {
     cornucopia::container::cleanup(false).ok();
-    cornucopia::container::setup(false).unwrap();
+    cornucopia::container::setup(&Default::default()).unwrap();
     let client = &mut cornucopia_conn().unwrap();
     let rt: &'static Runtime = Box::leak(Box::new(Runtime::new().unwrap()));
     let async_client = &mut rt.block_on(async {
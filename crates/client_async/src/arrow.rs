@@ -0,0 +1,3 @@
+//! Arrow crates used by the record batch conversions generated with `--arrow`.
+
+pub use cornucopia_client_core::{arrow_array as array, arrow_schema as schema};
@@ -0,0 +1,29 @@
+//! Read-through caching of the read queries generated with `--cache`, whose rows can be
+//! fetched with `.cached(&cache)` to consult a [`QueryCache`] before the database. Results
+//! are keyed by query id and a hash of the parameters, and stored as JSON so that they can
+//! be shared through an external cache such as Redis.
+
+use async_trait::async_trait;
+
+pub use cornucopia_client_core::{CacheKey, Cacheable, MemoryCache};
+
+/// Cache of query results consulted by cached queries. Failing to read or write the cache
+/// is expected to behave as a miss, so that queries fall back to the database.
+#[async_trait]
+pub trait QueryCache: Send + Sync {
+    /// Cached value of `key`, if any.
+    async fn get(&self, key: &CacheKey) -> Option<Vec<u8>>;
+    /// Caches `value` for `key`, for as long as the cache sees fit.
+    async fn put(&self, key: &CacheKey, value: Vec<u8>);
+}
+
+#[async_trait]
+impl QueryCache for MemoryCache {
+    async fn get(&self, key: &CacheKey) -> Option<Vec<u8>> {
+        MemoryCache::get(self, key)
+    }
+
+    async fn put(&self, key: &CacheKey, value: Vec<u8>) {
+        MemoryCache::put(self, key, value);
+    }
+}
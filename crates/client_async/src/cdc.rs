@@ -0,0 +1,5 @@
+//! Typed decoding of logical replication changes, for the tables declared with `--%`
+//! in query files. Changes are read from a `wal2json` replication slot using
+//! `format-version` 2, for example with `pg_logical_slot_get_changes`.
+
+pub use cornucopia_client_core::{decode_wal2json, Change, Table};
@@ -0,0 +1,40 @@
+//! Concurrency limits of queries annotated with `--! max_concurrency`. Their generated
+//! `bind_limited` variant waits for a permit of the query's [`ConcurrencyLimit`], found in
+//! the generated `concurrency` registry, before executing.
+
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Maximum number of concurrent executions of a query.
+pub struct ConcurrencyLimit {
+    max: usize,
+    semaphore: Semaphore,
+}
+
+impl ConcurrencyLimit {
+    /// Allows up to `max` concurrent executions.
+    pub const fn new(max: usize) -> Self {
+        Self {
+            max,
+            semaphore: Semaphore::const_new(max),
+        }
+    }
+
+    /// Waits until fewer than `max` executions are running. The execution lasts as long as
+    /// the returned permit is alive.
+    pub async fn acquire(&self) -> SemaphorePermit<'_> {
+        self.semaphore
+            .acquire()
+            .await
+            .expect("concurrency limits are never closed")
+    }
+
+    /// Maximum number of concurrent executions.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Number of executions that could start right now without waiting.
+    pub fn available(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+}
@@ -1,13 +1,14 @@
 use async_trait::async_trait;
+use bytes::Buf;
 use deadpool_postgres::{
     Client as DeadpoolClient, ClientWrapper, Transaction as DeadpoolTransaction,
 };
 use tokio_postgres::{
-    types::BorrowToSql, Client as PgClient, Error, RowStream, Statement, ToStatement,
-    Transaction as PgTransaction,
+    types::BorrowToSql, Client as PgClient, CopyInSink, CopyOutStream, Error, RowStream, Statement,
+    ToStatement, Transaction as PgTransaction,
 };
 
-use crate::generic_client::GenericClient;
+use crate::generic_client::{GenericClient, GenericClientExt};
 
 #[async_trait]
 impl GenericClient for DeadpoolClient {
@@ -15,6 +16,10 @@ impl GenericClient for DeadpoolClient {
         ClientWrapper::prepare_cached(self, query).await
     }
 
+    async fn prepare_uncached(&self, query: &str) -> Result<Statement, Error> {
+        PgClient::prepare(self, query).await
+    }
+
     async fn execute<T>(
         &self,
         query: &T,
@@ -70,12 +75,38 @@ impl GenericClient for DeadpoolClient {
     }
 }
 
+#[async_trait]
+impl GenericClientExt for DeadpoolClient {
+    async fn copy_in<T, U>(&self, statement: &T) -> Result<CopyInSink<U>, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+        U: Buf + 'static + Send,
+    {
+        PgClient::copy_in(self, statement).await
+    }
+
+    async fn copy_out<T>(&self, statement: &T) -> Result<CopyOutStream, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        PgClient::copy_out(self, statement).await
+    }
+
+    async fn batch_execute(&self, query: &str) -> Result<(), Error> {
+        PgClient::batch_execute(self, query).await
+    }
+}
+
 #[async_trait]
 impl GenericClient for DeadpoolTransaction<'_> {
     async fn prepare(&self, query: &str) -> Result<Statement, Error> {
         DeadpoolTransaction::prepare_cached(self, query).await
     }
 
+    async fn prepare_uncached(&self, query: &str) -> Result<Statement, Error> {
+        PgTransaction::prepare(self, query).await
+    }
+
     async fn execute<T>(
         &self,
         query: &T,
@@ -130,3 +161,25 @@ impl GenericClient for DeadpoolTransaction<'_> {
         PgTransaction::query_raw(self, statement, params).await
     }
 }
+
+#[async_trait]
+impl GenericClientExt for DeadpoolTransaction<'_> {
+    async fn copy_in<T, U>(&self, statement: &T) -> Result<CopyInSink<U>, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+        U: Buf + 'static + Send,
+    {
+        PgTransaction::copy_in(self, statement).await
+    }
+
+    async fn copy_out<T>(&self, statement: &T) -> Result<CopyOutStream, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        PgTransaction::copy_out(self, statement).await
+    }
+
+    async fn batch_execute(&self, query: &str) -> Result<(), Error> {
+        PgTransaction::batch_execute(self, query).await
+    }
+}
@@ -1,6 +1,8 @@
 use async_trait::async_trait;
+use bytes::Buf;
 use tokio_postgres::{
-    types::BorrowToSql, Client, Error, RowStream, Statement, ToStatement, Transaction,
+    types::BorrowToSql, Client, CopyInSink, CopyOutStream, Error, RowStream, Statement,
+    ToStatement, Transaction,
 };
 
 /// Abstraction over multiple types of asynchronous clients.
@@ -11,6 +13,11 @@ use tokio_postgres::{
 #[async_trait]
 pub trait GenericClient: Send + Sync {
     async fn prepare(&self, query: &str) -> Result<Statement, Error>;
+    /// Prepares `query` bypassing the client's statement cache, if it has one, for
+    /// statements whose text changes with each execution.
+    async fn prepare_uncached(&self, query: &str) -> Result<Statement, Error> {
+        self.prepare(query).await
+    }
     async fn execute<T>(
         &self,
         query: &T,
@@ -48,6 +55,23 @@ pub trait GenericClient: Send + Sync {
         I::IntoIter: ExactSizeIterator;
 }
 
+/// Bulk operations of asynchronous clients, copying rows in and out and running batches of
+/// statements. They live apart from [`GenericClient`] so that implementing it doesn't
+/// require them.
+#[async_trait]
+pub trait GenericClientExt: GenericClient {
+    async fn copy_in<T, U>(&self, statement: &T) -> Result<CopyInSink<U>, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+        U: Buf + 'static + Send;
+
+    async fn copy_out<T>(&self, statement: &T) -> Result<CopyOutStream, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send;
+
+    async fn batch_execute(&self, query: &str) -> Result<(), Error>;
+}
+
 #[async_trait]
 impl GenericClient for Transaction<'_> {
     async fn prepare(&self, query: &str) -> Result<Statement, Error> {
@@ -109,6 +133,28 @@ impl GenericClient for Transaction<'_> {
     }
 }
 
+#[async_trait]
+impl GenericClientExt for Transaction<'_> {
+    async fn copy_in<T, U>(&self, statement: &T) -> Result<CopyInSink<U>, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+        U: Buf + 'static + Send,
+    {
+        Transaction::copy_in(self, statement).await
+    }
+
+    async fn copy_out<T>(&self, statement: &T) -> Result<CopyOutStream, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        Transaction::copy_out(self, statement).await
+    }
+
+    async fn batch_execute(&self, query: &str) -> Result<(), Error> {
+        Transaction::batch_execute(self, query).await
+    }
+}
+
 #[async_trait]
 impl GenericClient for Client {
     async fn prepare(&self, query: &str) -> Result<Statement, Error> {
@@ -169,3 +215,25 @@ impl GenericClient for Client {
         Client::query_raw(self, statement, params).await
     }
 }
+
+#[async_trait]
+impl GenericClientExt for Client {
+    async fn copy_in<T, U>(&self, statement: &T) -> Result<CopyInSink<U>, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+        U: Buf + 'static + Send,
+    {
+        Client::copy_in(self, statement).await
+    }
+
+    async fn copy_out<T>(&self, statement: &T) -> Result<CopyOutStream, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        Client::copy_out(self, statement).await
+    }
+
+    async fn batch_execute(&self, query: &str) -> Result<(), Error> {
+        Client::batch_execute(self, query).await
+    }
+}
@@ -0,0 +1,123 @@
+//! Group commit of the executions submitted concurrently by many tasks, for write-heavy
+//! workloads such as telemetry where a round trip per row dominates. Items are buffered by a
+//! background task and flushed together, typically as a single multi-row insert, once
+//! `max_items` are pending or `max_delay` has elapsed since the first one. Each submitter
+//! receives the result of its own item, matched by the item's position in the batch as
+//! PostgreSQL doesn't guarantee that `RETURNING` rows come back in the order of the input.
+
+use std::{fmt::Display, future::Future, sync::Arc, time::Duration};
+
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::{timeout_at, Instant},
+};
+
+/// Error returned to the submitter of an item.
+#[derive(Debug, Clone)]
+pub enum Error {
+    /// The flush of the item's batch failed, failing every item of the batch.
+    Db(Arc<tokio_postgres::Error>),
+    /// The flush of the item's batch returned no result at the item's position.
+    MissingResult,
+    /// The background task stopped before the item was flushed, as a flush panicked.
+    Closed,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Db(e) => write!(f, "couldn't flush batch: {e}"),
+            Error::MissingResult => write!(f, "batch flush returned no result for the item"),
+            Error::Closed => write!(f, "group commit task stopped"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Db(e) => Some(e.as_ref()),
+            Error::MissingResult | Error::Closed => None,
+        }
+    }
+}
+
+type Pending<T, R> = (T, oneshot::Sender<Result<R, Error>>);
+
+/// Handle submitting items to a background task flushing them in batches. Clones share
+/// the same batches, and pending items are still flushed once every handle is dropped.
+pub struct GroupCommit<T, R> {
+    sender: mpsc::Sender<Pending<T, R>>,
+}
+
+impl<T, R> Clone for GroupCommit<T, R> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<T: Send + 'static, R: Send + 'static> GroupCommit<T, R> {
+    /// Spawns the background task flushing batches of up to `max_items` items with `flush`,
+    /// at most `max_delay` after the first item of a batch was submitted. `flush` returns
+    /// the result of each item along with the item's position in the batch, starting at 1
+    /// like the ordinality of `unnest(...) WITH ORDINALITY`, in any order.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn spawn<F, Fut>(max_items: usize, max_delay: Duration, mut flush: F) -> Self
+    where
+        F: FnMut(Vec<T>) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<Vec<(usize, R)>, tokio_postgres::Error>> + Send + 'static,
+    {
+        let max_items = max_items.max(1);
+        let (sender, mut receiver) = mpsc::channel::<Pending<T, R>>(max_items);
+        tokio::spawn(async move {
+            while let Some(first) = receiver.recv().await {
+                let deadline = Instant::now() + max_delay;
+                let mut batch = vec![first];
+                while batch.len() < max_items {
+                    match timeout_at(deadline, receiver.recv()).await {
+                        Ok(Some(pending)) => batch.push(pending),
+                        // Flush on time out, or once every handle is dropped
+                        Ok(None) | Err(_) => break,
+                    }
+                }
+                let (items, submitters): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+                match flush(items).await {
+                    Ok(results) => {
+                        let mut routed: Vec<_> = submitters.iter().map(|_| None).collect();
+                        for (position, result) in results {
+                            if let Some(slot @ None) =
+                                position.checked_sub(1).and_then(|idx| routed.get_mut(idx))
+                            {
+                                *slot = Some(result);
+                            }
+                        }
+                        for (submitter, result) in submitters.into_iter().zip(routed) {
+                            // The submitter may have stopped waiting
+                            let _ = submitter.send(result.ok_or(Error::MissingResult));
+                        }
+                    }
+                    Err(e) => {
+                        let e = Arc::new(e);
+                        for submitter in submitters {
+                            let _ = submitter.send(Err(Error::Db(e.clone())));
+                        }
+                    }
+                }
+            }
+        });
+        Self { sender }
+    }
+
+    /// Submits `item`, resolving to its result once its batch is flushed.
+    pub async fn submit(&self, item: T) -> Result<R, Error> {
+        let (submitter, result) = oneshot::channel();
+        self.sender
+            .send((item, submitter))
+            .await
+            .map_err(|_| Error::Closed)?;
+        result.await.map_err(|_| Error::Closed)?
+    }
+}
@@ -0,0 +1,41 @@
+use crate::GenericClient;
+
+pub use cornucopia_client_core::{IntrospectionReport, QueryInfo, QueryReport};
+
+/// Prepares every query of the generated `registry` against the database, reporting
+/// which ones don't match its current schema.
+pub async fn report<C: GenericClient>(client: &C, queries: &[QueryInfo]) -> IntrospectionReport {
+    let mut reports = Vec::with_capacity(queries.len());
+    for query in queries {
+        reports.push(QueryReport {
+            query: *query,
+            error: prepare(client, query).await.err().map(|e| e.to_string()),
+        });
+    }
+    IntrospectionReport { queries: reports }
+}
+
+/// Prepares a query with its search path, restoring the previous one afterwards.
+async fn prepare<C: GenericClient>(
+    client: &C,
+    query: &QueryInfo,
+) -> Result<(), tokio_postgres::Error> {
+    let Some(search_path) = query.search_path else {
+        return client.prepare(query.sql).await.map(drop);
+    };
+    let previous: String = client
+        .query_one("SELECT current_setting('search_path')", &[])
+        .await?
+        .get(0);
+    client
+        .execute(
+            "SELECT set_config('search_path', $1, false)",
+            &[&search_path],
+        )
+        .await?;
+    let prepared = client.prepare(query.sql).await.map(drop);
+    client
+        .execute("SELECT set_config('search_path', $1, false)", &[&previous])
+        .await?;
+    prepared
+}
@@ -1,18 +1,70 @@
 #[doc(hidden)]
 pub mod private;
 
-pub use crate::generic_client::GenericClient;
-pub use cornucopia_client_core::{ArrayIterator, ArraySql, BytesSql, IterSql, StringSql};
+pub use crate::generic_client::{GenericClient, GenericClientExt};
+pub use cornucopia_client_core::{
+    check_text_len, ArrayIterator, ArraySql, BytesSql, FromPgRow, IterSql, Page, Range, StringSql,
+    TextTooLong,
+};
 
 #[cfg(feature = "with-serde_json-1")]
 pub use cornucopia_client_core::JsonSql;
 
+#[cfg(feature = "with-bigdecimal-0_4")]
+pub use cornucopia_client_core::Numeric;
+
+/// sqlx crate used by the type bridges generated with `--sqlx`.
+#[cfg(feature = "sqlx")]
+pub use cornucopia_client_core::sqlx;
+
+/// Diesel crate used by the type bridges generated with `--diesel`.
+#[cfg(feature = "diesel")]
+pub use cornucopia_client_core::diesel;
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "cache")]
+pub mod cache;
+#[cfg(feature = "cdc")]
+pub mod cdc;
+#[cfg(feature = "concurrency")]
+pub mod concurrency;
+#[cfg(feature = "csv")]
+pub mod csv;
 #[cfg(feature = "deadpool")]
 mod deadpool;
+pub mod encryption;
+#[cfg(feature = "explain")]
+pub mod explain;
 mod generic_client;
+#[cfg(feature = "group-commit")]
+pub mod group_commit;
+#[cfg(feature = "introspection")]
+pub mod introspection;
+#[cfg(feature = "notifications")]
+pub mod notifications;
+#[cfg(feature = "replicas")]
+pub mod replicas;
+pub mod snapshot;
+#[cfg(feature = "sqlcommenter")]
+pub mod sqlcommenter;
+pub mod tenancy;
+pub mod transaction;
 
 /// This trait allows you to bind parameters to a query using a single
 /// struct, rather than passing each bind parameter as a function parameter.
 pub trait Params<'a, P, O, C> {
     fn params(&'a mut self, client: &'a C, params: &'a P) -> O;
 }
+
+/// This trait allows you to execute a query directly from the struct holding its
+/// parameters. Its statement is prepared on each call, bind the parameters with
+/// [`Params`] to reuse it instead.
+pub trait Execute<'a, C> {
+    fn execute(
+        &'a self,
+        client: &'a C,
+    ) -> std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<u64, tokio_postgres::Error>> + Send + 'a>,
+    >;
+}
@@ -0,0 +1,94 @@
+use std::fmt::Display;
+
+use futures_util::{Stream, StreamExt};
+use tokio_postgres::{AsyncMessage, Notification};
+
+use crate::GenericClient;
+
+use cornucopia_client_core::serde::Serialize;
+
+pub use cornucopia_client_core::Channel;
+
+/// Error returned while sending or receiving typed notifications.
+#[derive(Debug)]
+pub enum Error {
+    /// The connection to the database failed.
+    Db(tokio_postgres::Error),
+    /// A payload didn't match the type of its channel.
+    Decode(serde_json::Error),
+    /// A payload couldn't be encoded.
+    Encode(serde_json::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Db(e) => write!(f, "couldn't receive notification: {e}"),
+            Error::Decode(e) => write!(f, "couldn't decode notification payload: {e}"),
+            Error::Encode(e) => write!(f, "couldn't encode notification payload: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Db(e) => Some(e),
+            Error::Decode(e) | Error::Encode(e) => Some(e),
+        }
+    }
+}
+
+/// Subscribes `client` to the notifications of `C`.
+///
+/// Notifications are delivered through the connection: poll it with
+/// `Connection::poll_message` and pass the received messages to [`payloads`], or the
+/// received `AsyncMessage::Notification`s to [`decode`].
+pub async fn listen<C: Channel, G: GenericClient>(client: &G) -> Result<(), tokio_postgres::Error> {
+    client
+        .execute(cornucopia_client_core::listen_sql::<C>().as_str(), &[])
+        .await
+        .map(drop)
+}
+
+/// Sends `payload` on the channel of `C`, delivered to its listeners once the current
+/// transaction commits.
+pub async fn notify<C: Channel, G: GenericClient>(
+    client: &G,
+    payload: &C::Payload,
+) -> Result<(), Error>
+where
+    C::Payload: Serialize,
+{
+    let payload = cornucopia_client_core::encode::<C>(payload).map_err(Error::Encode)?;
+    client
+        .execute(cornucopia_client_core::NOTIFY_SQL, &[&C::NAME, &payload])
+        .await
+        .map_err(Error::Db)?;
+    Ok(())
+}
+
+/// Decodes `notification`, returning `None` if it wasn't sent on the channel of `C`.
+pub fn decode<C: Channel>(
+    notification: &Notification,
+) -> Option<Result<C::Payload, serde_json::Error>> {
+    cornucopia_client_core::decode::<C>(notification.channel(), notification.payload())
+}
+
+/// Yields the decoded payloads of `C` from the `messages` of a connection, skipping the
+/// notifications sent on other channels and the other messages. The messages are
+/// typically polled with `futures::stream::poll_fn(move |cx| connection.poll_message(cx))`.
+pub fn payloads<C: Channel, S>(messages: S) -> impl Stream<Item = Result<C::Payload, Error>>
+where
+    S: Stream<Item = Result<AsyncMessage, tokio_postgres::Error>>,
+{
+    messages.filter_map(|message| async move {
+        match message {
+            Ok(AsyncMessage::Notification(notification)) => {
+                decode::<C>(&notification).map(|payload| payload.map_err(Error::Decode))
+            }
+            Ok(_) => None,
+            Err(e) => Some(Err(Error::Db(e))),
+        }
+    })
+}
@@ -1,20 +1,113 @@
-pub use cornucopia_client_core::{slice_iter, Domain, DomainArray};
+#[cfg(feature = "obfuscated-sql")]
+pub use cornucopia_client_core::SqlTable;
+pub use cornucopia_client_core::{set_snapshot_sql, slice_iter, Domain, DomainArray};
 
 use crate::generic_client::GenericClient;
-use tokio_postgres::{Error, Statement};
+use std::borrow::Cow;
+
+use tokio_postgres::{error::SqlState, Error, Statement};
 
 /// Cached statement
 pub struct Stmt {
-    query: &'static str,
+    query: Cow<'static, str>,
     cached: Option<Statement>,
+    #[cfg(feature = "sqlcommenter")]
+    tagged: Option<Statement>,
+    #[cfg(feature = "explain")]
+    id: Option<&'static str>,
+    #[cfg(feature = "explain")]
+    explainer: Option<crate::explain::Explainer>,
+    #[cfg(feature = "cache")]
+    schema: Option<cornucopia_client_core::TenantSchema>,
+    retry: Option<crate::transaction::RetryHook>,
 }
 
 impl Stmt {
     #[must_use]
     pub fn new(query: &'static str) -> Self {
         Self {
-            query,
+            query: Cow::Borrowed(query),
             cached: None,
+            #[cfg(feature = "sqlcommenter")]
+            tagged: None,
+            #[cfg(feature = "explain")]
+            id: None,
+            #[cfg(feature = "explain")]
+            explainer: None,
+            #[cfg(feature = "cache")]
+            schema: None,
+            retry: None,
+        }
+    }
+
+    /// Runs this statement against the `schema` of a tenant instead of the template schema,
+    /// referenced at the byte `references` of its SQL.
+    #[must_use]
+    pub fn in_schema(
+        mut self,
+        references: &[std::ops::Range<usize>],
+        schema: &cornucopia_client_core::TenantSchema,
+    ) -> Self {
+        self.query = Cow::Owned(schema.substitute(&self.query, references));
+        #[cfg(feature = "cache")]
+        {
+            self.schema = Some(schema.clone());
+        }
+        self
+    }
+
+    /// Retries the failed transactions of this statement as decided by `hook`.
+    #[must_use]
+    pub fn retried(mut self, hook: crate::transaction::RetryHook) -> Self {
+        self.retry = Some(hook);
+        self
+    }
+
+    /// Whether a transaction of this statement that failed with `err` after `attempts`
+    /// attempts is retried.
+    pub fn should_retry(&self, attempts: u32, err: &Error) -> bool {
+        let retryable = err.code().is_some_and(|code| {
+            code == &SqlState::T_R_SERIALIZATION_FAILURE || code == &SqlState::T_R_DEADLOCK_DETECTED
+        });
+        retryable && self.retry.is_some_and(|hook| hook(attempts, err))
+    }
+
+    /// Marks this statement as explainable, under the query `id`.
+    #[cfg(feature = "explain")]
+    #[must_use]
+    pub fn explained(mut self, id: &'static str) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Samples the plans of this statement's executions with `explainer`.
+    #[cfg(feature = "explain")]
+    #[must_use]
+    pub fn explained_by(mut self, explainer: &crate::explain::Explainer) -> Self {
+        self.explainer = Some(explainer.clone());
+        self
+    }
+
+    /// Queues this execution to be explained if sampled. Failing to prepare the statement
+    /// is left to its execution.
+    #[cfg(feature = "explain")]
+    pub async fn explain_sample<C: GenericClient>(
+        &mut self,
+        client: &C,
+        params: &[&(dyn tokio_postgres::types::ToSql + Sync)],
+    ) {
+        let Some(id) = self.id else {
+            return;
+        };
+        if !self.explainer.as_ref().is_some_and(|it| it.sample()) {
+            return;
+        }
+        let Ok(stmt) = self.prepare(client).await else {
+            return;
+        };
+        let types = stmt.params().to_vec();
+        if let Some(explainer) = &self.explainer {
+            explainer.send(id, &self.query, &types, params);
         }
     }
 
@@ -22,11 +115,61 @@ impl Stmt {
         &'a mut self,
         client: &C,
     ) -> Result<&'a Statement, Error> {
+        #[cfg(feature = "sqlcommenter")]
+        if let Some(query) = crate::sqlcommenter::tagged(&self.query) {
+            // Tagged statements are unique to their execution, caching them would leak
+            return Ok(self.tagged.insert(client.prepare_uncached(&query).await?));
+        }
         if self.cached.is_none() {
-            let stmt = client.prepare(self.query).await?;
+            let stmt = client.prepare(&self.query).await?;
             self.cached = Some(stmt);
         }
         // the statement is always prepared at this point
         Ok(unsafe { self.cached.as_ref().unwrap_unchecked() })
     }
 }
+
+/// Key of the result fetched by `method` of the query `id`, in the tenant schema of its
+/// statement if any, preparing the statement to encode `params`.
+#[cfg(feature = "cache")]
+pub async fn cache_key<C: GenericClient>(
+    client: &C,
+    stmt: &mut Stmt,
+    id: &'static str,
+    method: &str,
+    params: &[&(dyn tokio_postgres::types::ToSql + Sync)],
+) -> Result<Option<cornucopia_client_core::CacheKey>, Error> {
+    let schema = stmt.schema.clone();
+    let stmt = stmt.prepare(client).await?;
+    Ok(cornucopia_client_core::CacheKey::new(
+        id,
+        schema.as_ref(),
+        method,
+        stmt.params(),
+        params,
+    ))
+}
+
+/// Returns the cached result of `key`, or runs `query` and caches its result.
+#[cfg(feature = "cache")]
+pub async fn read_through<T: cornucopia_client_core::Cacheable>(
+    cache: &dyn crate::cache::QueryCache,
+    key: Option<cornucopia_client_core::CacheKey>,
+    query: impl std::future::Future<Output = Result<T, Error>>,
+) -> Result<T, Error> {
+    let Some(key) = key else {
+        return query.await;
+    };
+    if let Some(value) = cache
+        .get(&key)
+        .await
+        .and_then(|value| cornucopia_client_core::decode_cached(&value))
+    {
+        return Ok(value);
+    }
+    let value = query.await?;
+    if let Some(value) = cornucopia_client_core::encode_cached(&value) {
+        cache.put(&key, value).await;
+    }
+    Ok(value)
+}
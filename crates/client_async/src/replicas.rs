@@ -0,0 +1,61 @@
+//! Read-your-writes consistency for applications routing reads to replicas. Generated
+//! mutating queries have a `bind_tracked` variant capturing the WAL position of the
+//! primary after their execution, which [`wait_for_lsn`] waits for on a replica.
+
+use std::time::Duration;
+
+use cornucopia_client_core::{CURRENT_LSN_SQL, REPLAYED_LSN_SQL};
+use tokio::time::Instant;
+
+use crate::GenericClient;
+
+pub use cornucopia_client_core::ConsistencyToken;
+pub use tokio_postgres::types::PgLsn;
+
+/// Delay between two replay position checks of [`wait_for_lsn`].
+pub const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Records the current WAL position of the primary `client` in `token`.
+///
+/// Use it after mutating queries returning rows, which have no `bind_tracked` variant.
+/// Inside a transaction, the position of its commit isn't known yet: its writes are
+/// recorded as pending until this is called again once it is committed.
+pub async fn capture<C: GenericClient>(
+    client: &C,
+    token: &mut ConsistencyToken,
+) -> Result<PgLsn, tokio_postgres::Error> {
+    let row = client.query_one(CURRENT_LSN_SQL, &[]).await?;
+    let lsn = row.get(0);
+    if row.get(1) {
+        token.observe_uncommitted();
+    } else {
+        token.observe(lsn);
+    }
+    Ok(lsn)
+}
+
+/// Waits until the replica `client` has replayed the writes recorded in `token`,
+/// returning `false` if it didn't catch up within `timeout`. Returns immediately when
+/// `client` is a primary, or with `false` if `token` has pending writes.
+pub async fn wait_for_lsn<C: GenericClient>(
+    client: &C,
+    token: &ConsistencyToken,
+    timeout: Duration,
+) -> Result<bool, tokio_postgres::Error> {
+    if token.is_pending() {
+        return Ok(false);
+    }
+    let Some(lsn) = token.lsn() else {
+        return Ok(true);
+    };
+    let deadline = Instant::now() + timeout;
+    loop {
+        if client.query_one(REPLAYED_LSN_SQL, &[&lsn]).await?.get(0) {
+            return Ok(true);
+        }
+        if Instant::now() >= deadline {
+            return Ok(false);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
@@ -0,0 +1,41 @@
+//! Point-in-time reads across several queries, for reports that must stay consistent
+//! while the database changes. A [`Snapshot`] exported by a transaction kept open is read
+//! by the generated `bind_at` functions of queries annotated with `--! snapshot`, each in
+//! its own transaction, possibly on other connections of a pool.
+
+use cornucopia_client_core::{EXPORT_SNAPSHOT_SQL, SNAPSHOT_TRANSACTION_SQL};
+use tokio_postgres::{Client, Error, Transaction};
+
+/// Snapshot of the database exported by a read-only transaction, valid until released.
+pub struct Snapshot<'a> {
+    transaction: Transaction<'a>,
+    id: String,
+}
+
+impl<'a> Snapshot<'a> {
+    /// Opens a read-only repeatable read transaction on `client` and exports its snapshot.
+    pub async fn export(client: &'a mut Client) -> Result<Self, Error> {
+        let transaction = client.transaction().await?;
+        transaction.batch_execute(SNAPSHOT_TRANSACTION_SQL).await?;
+        let id = transaction
+            .query_one(EXPORT_SNAPSHOT_SQL, &[])
+            .await?
+            .get(0);
+        Ok(Self { transaction, id })
+    }
+
+    /// Id of the snapshot, as given to `bind_at` functions.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Transaction exporting the snapshot, whose queries also read as of the snapshot.
+    pub fn transaction(&self) -> &Transaction<'a> {
+        &self.transaction
+    }
+
+    /// Ends the exporting transaction, after which the snapshot can't be read anymore.
+    pub async fn release(self) -> Result<(), Error> {
+        self.transaction.commit().await
+    }
+}
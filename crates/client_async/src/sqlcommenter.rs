@@ -0,0 +1,24 @@
+use std::future::Future;
+
+pub use cornucopia_client_core::SqlComment;
+
+tokio::task_local! {
+    static SQL_COMMENT: SqlComment;
+}
+
+/// Runs `f` in a scope where every query executed by generated code
+/// is tagged with `comment` using the sqlcommenter format.
+///
+/// Tagged statements are prepared on each execution instead of being cached,
+/// since their text changes with the comment.
+pub async fn with_sql_comment<F: Future>(comment: SqlComment, f: F) -> F::Output {
+    SQL_COMMENT.scope(comment, f).await
+}
+
+/// Returns `query` tagged with the comment of the current task, if any.
+pub(crate) fn tagged(query: &str) -> Option<String> {
+    SQL_COMMENT
+        .try_with(|comment| comment.append_to(query))
+        .ok()
+        .flatten()
+}
@@ -0,0 +1,5 @@
+//! Schema-per-tenant databases. Queries written against a template schema, named by
+//! `--tenant-schema`, have a generated `_for` constructor running them against the schema
+//! of a tenant instead, such as `users_for(&tenant)` for `users`.
+
+pub use cornucopia_client_core::{InvalidSchemaName, TenantSchema};
@@ -0,0 +1,9 @@
+//! Transactions of the queries annotated with `--! isolation`, possibly along with
+//! `--! read_only`, `--! timeout` or `--! set`, whose generated `bind_isolated` functions run
+//! in their own transaction.
+
+use tokio_postgres::Error;
+
+/// Decides whether a transaction that failed after `attempts` attempts is retried, given
+/// to the `retried` method of the statements generated with `bind_isolated`.
+pub type RetryHook = fn(attempts: u32, err: &Error) -> bool;
@@ -0,0 +1,129 @@
+use std::{collections::HashMap, fmt::Display, sync::Mutex};
+
+use postgres_types::{private::BytesMut, IsNull, ToSql, Type};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::TenantSchema;
+
+/// Key of a cached query result: the id of the query and a hash of its parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    /// Id of the query, as `module.query`
+    pub id: &'static str,
+    /// Hash of the fetching method and of the parameters, as sent to the database
+    pub hash: u128,
+}
+
+impl CacheKey {
+    /// Key of the result fetched by `method` (`one`, `opt` or `all`) of the query `id`,
+    /// hashing the binary encoding of `params` as the statement's parameter `types`.
+    /// Queries run in the `schema` of a tenant are keyed apart from other tenants'.
+    /// `None` if a parameter can't be encoded, which fails the query itself.
+    pub fn new(
+        id: &'static str,
+        schema: Option<&TenantSchema>,
+        method: &str,
+        types: &[Type],
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Option<Self> {
+        let mut hash = Fnv128::new();
+        // Schema names can't hold a NUL byte, which ends them
+        if let Some(schema) = schema {
+            hash.write(schema.as_str().as_bytes());
+            hash.write(&[0]);
+        }
+        hash.write(method.as_bytes());
+        let mut buf = BytesMut::new();
+        for (param, ty) in params.iter().zip(types) {
+            buf.clear();
+            // Tell NULL apart from an empty value
+            match param.to_sql_checked(ty, &mut buf).ok()? {
+                IsNull::Yes => hash.write(&[0]),
+                IsNull::No => {
+                    hash.write(&[1]);
+                    hash.write(&(buf.len() as u64).to_be_bytes());
+                    hash.write(&buf);
+                }
+            }
+        }
+        Some(Self {
+            id,
+            hash: hash.finish(),
+        })
+    }
+}
+
+impl Display for CacheKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{:032x}", self.id, self.hash)
+    }
+}
+
+/// 128-bit FNV-1a, stable across builds so that caches can be shared by processes.
+struct Fnv128(u128);
+
+impl Fnv128 {
+    const OFFSET: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+
+    fn new() -> Self {
+        Self(Self::OFFSET)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u128::from(*byte)).wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn finish(&self) -> u128 {
+        self.0
+    }
+}
+
+/// Query results that can be cached, encoded as JSON.
+pub trait Cacheable: Serialize + DeserializeOwned {}
+
+impl<T: Serialize + DeserializeOwned> Cacheable for T {}
+
+/// Encodes a query result into a cache value.
+pub fn encode<T: Cacheable>(value: &T) -> Option<Vec<u8>> {
+    serde_json::to_vec(value).ok()
+}
+
+/// Decodes a cache value, `None` if it was written for another result type.
+pub fn decode<T: Cacheable>(value: &[u8]) -> Option<T> {
+    serde_json::from_slice(value).ok()
+}
+
+/// Unbounded in-memory cache of query results, kept until invalidated.
+#[derive(Debug, Default)]
+pub struct MemoryCache(Mutex<HashMap<CacheKey, Vec<u8>>>);
+
+impl MemoryCache {
+    /// An empty cache.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cached value of `key`, if any.
+    pub fn get(&self, key: &CacheKey) -> Option<Vec<u8>> {
+        self.0.lock().unwrap().get(key).cloned()
+    }
+
+    /// Caches `value` for `key`, replacing any previous value.
+    pub fn put(&self, key: &CacheKey, value: Vec<u8>) {
+        self.0.lock().unwrap().insert(*key, value);
+    }
+
+    /// Drops every cached result of the query `id`, typically after writing to its tables.
+    pub fn invalidate(&self, id: &str) {
+        self.0.lock().unwrap().retain(|key, _| key.id != id);
+    }
+
+    /// Drops every cached result.
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+}
@@ -0,0 +1,72 @@
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{Map, Value};
+
+/// A table whose changes are decoded from logical replication messages.
+///
+/// Generated tables only have boolean, integer, float and string columns, the types whose
+/// values `wal2json` writes as plain JSON values.
+pub trait Table: DeserializeOwned {
+    /// Schema of the table.
+    const SCHEMA: &'static str;
+    /// Name of the table.
+    const NAME: &'static str;
+}
+
+/// A change of a row of the table `T`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change<T> {
+    Insert(T),
+    Update {
+        /// Replica identity (usually the primary key) of the row before the update.
+        identity: Map<String, Value>,
+        new: T,
+    },
+    Delete {
+        /// Replica identity (usually the primary key) of the deleted row.
+        identity: Map<String, Value>,
+    },
+}
+
+#[derive(Deserialize)]
+struct Message {
+    action: String,
+    #[serde(default)]
+    schema: String,
+    #[serde(default)]
+    table: String,
+    #[serde(default)]
+    columns: Vec<Column>,
+    #[serde(default)]
+    identity: Vec<Column>,
+}
+
+#[derive(Deserialize)]
+struct Column {
+    name: String,
+    value: Value,
+}
+
+fn to_map(columns: Vec<Column>) -> Map<String, Value> {
+    columns.into_iter().map(|c| (c.name, c.value)).collect()
+}
+
+/// Decodes a `wal2json` message (`format-version` 2), returning `None` if it isn't a row
+/// change of the table `T`, such as transaction boundaries or changes of other tables.
+pub fn decode_wal2json<T: Table>(message: &str) -> Result<Option<Change<T>>, serde_json::Error> {
+    let message: Message = serde_json::from_str(message)?;
+    if message.schema != T::SCHEMA || message.table != T::NAME {
+        return Ok(None);
+    }
+    let row = |columns| serde_json::from_value(Value::Object(to_map(columns)));
+    Ok(Some(match message.action.as_str() {
+        "I" => Change::Insert(row(message.columns)?),
+        "U" => Change::Update {
+            identity: to_map(message.identity),
+            new: row(message.columns)?,
+        },
+        "D" => Change::Delete {
+            identity: to_map(message.identity),
+        },
+        _ => return Ok(None),
+    }))
+}
@@ -0,0 +1,24 @@
+use std::io::Write;
+
+use serde::Serialize;
+
+pub use csv::Error as CsvError;
+
+/// Writes `rows` as CSV records to `writer`, preceded by a `header` record naming
+/// their columns.
+pub fn write_csv<'a, R: Serialize + 'a, W: Write>(
+    header: &[&str],
+    rows: impl IntoIterator<Item = &'a R>,
+    writer: W,
+) -> Result<(), CsvError> {
+    // Struct field names would be the Rust identifiers rather than the column names
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(writer);
+    writer.write_record(header)?;
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
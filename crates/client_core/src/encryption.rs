@@ -0,0 +1,71 @@
+use std::{
+    error::Error,
+    fmt::{Debug, Formatter},
+};
+
+use postgres_types::{accepts, private::BytesMut, to_sql_checked, FromSql, IsNull, ToSql, Type};
+
+/// Encrypts the fields declared encrypted before they are written to their `bytea` column,
+/// and decrypts them once read, such as with a key held by the application.
+pub trait Codec: Send + Sync {
+    /// Ciphertext of `plaintext`, written to the column.
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, Box<dyn Error + Sync + Send>>;
+    /// Plaintext of the `ciphertext` read from the column.
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, Box<dyn Error + Sync + Send>>;
+}
+
+/// Ciphertext of a field declared encrypted, as stored in its `bytea` column. Plaintexts
+/// are explicitly encrypted and decrypted with a [`Codec`], whose failures are returned
+/// as errors. Its `Debug` output is redacted.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Encrypted(Vec<u8>);
+
+impl Encrypted {
+    /// Encrypts `plaintext` with `codec`.
+    pub fn encrypt(
+        codec: &dyn Codec,
+        plaintext: &str,
+    ) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        Ok(Self(codec.encrypt(plaintext.as_bytes())?))
+    }
+
+    /// Decrypts the plaintext with `codec`.
+    pub fn decrypt(&self, codec: &dyn Codec) -> Result<String, Box<dyn Error + Sync + Send>> {
+        Ok(String::from_utf8(codec.decrypt(&self.0)?)?)
+    }
+
+    /// Ciphertext, as stored in the column.
+    #[must_use]
+    pub fn ciphertext(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Debug for Encrypted {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Encrypted(***)")
+    }
+}
+
+impl<'a> FromSql<'a> for Encrypted {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        Ok(Self(raw.to_vec()))
+    }
+
+    accepts!(BYTEA);
+}
+
+impl ToSql for Encrypted {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        out.extend_from_slice(&self.0);
+        Ok(IsNull::No)
+    }
+
+    accepts!(BYTEA);
+    to_sql_checked!();
+}
@@ -0,0 +1,146 @@
+use std::{
+    error::Error,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use postgres_types::{private::BytesMut, to_sql_checked, FromSql, IsNull, ToSql, Type};
+
+/// Prefix running a statement under `EXPLAIN`, reporting its actual plan as JSON.
+pub const EXPLAIN_PREFIX: &str = "EXPLAIN (ANALYZE, FORMAT JSON) ";
+
+/// Plan of a sampled query execution.
+#[derive(Debug, Clone, Copy)]
+pub struct ExplainedQuery<'a> {
+    /// Id of the query, as `module.query`
+    pub id: &'a str,
+    /// SQL of the query
+    pub sql: &'a str,
+    /// Output of `EXPLAIN (ANALYZE, FORMAT JSON)`
+    pub plan: &'a str,
+}
+
+/// Picks the executions whose plan is sampled, evenly spread at a given rate.
+pub struct ExplainSampler {
+    executions: AtomicU64,
+    rate: AtomicU64,
+}
+
+impl ExplainSampler {
+    /// A sampler picking no execution.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            executions: AtomicU64::new(0),
+            rate: AtomicU64::new(0),
+        }
+    }
+
+    /// Samples a `rate` fraction of the executions, between 0 and 1.
+    pub fn set_rate(&self, rate: f64) {
+        self.rate
+            .store(rate.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+        self.executions.store(0, Ordering::Relaxed);
+    }
+
+    /// Whether the next execution is sampled.
+    pub fn sample(&self) -> bool {
+        let rate = f64::from_bits(self.rate.load(Ordering::Relaxed));
+        if rate == 0.0 {
+            return false;
+        }
+        let execution = self.executions.fetch_add(1, Ordering::Relaxed) as f64;
+        ((execution + 1.0) * rate).floor() > (execution * rate).floor()
+    }
+}
+
+impl Default for ExplainSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// JSON text of a plan, as returned by `EXPLAIN (FORMAT JSON)`.
+pub struct JsonPlan(pub String);
+
+impl<'a> FromSql<'a> for JsonPlan {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        Ok(Self(std::str::from_utf8(raw)?.to_owned()))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        ty == &Type::JSON
+    }
+}
+
+/// Sampled execution explained away from the connection running it, owning its SQL and its
+/// parameters as encoded for the statement.
+#[derive(Debug, Clone)]
+pub struct ExplainRequest {
+    /// Id of the query, as `module.query`
+    pub id: &'static str,
+    /// SQL of the query
+    pub sql: String,
+    params: Vec<EncodedParam>,
+}
+
+impl ExplainRequest {
+    /// Execution of the query `id` binding `params`, encoded as the statement's parameter
+    /// `types`. `None` if a parameter can't be encoded, which fails the query itself.
+    pub fn new(
+        id: &'static str,
+        sql: &str,
+        types: &[Type],
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Option<Self> {
+        let mut buf = BytesMut::new();
+        let params = params
+            .iter()
+            .zip(types)
+            .map(|(param, ty)| {
+                buf.clear();
+                Some(EncodedParam(
+                    match param.to_sql_checked(ty, &mut buf).ok()? {
+                        IsNull::Yes => None,
+                        IsNull::No => Some(buf.to_vec()),
+                    },
+                ))
+            })
+            .collect::<Option<_>>()?;
+        Some(Self {
+            id,
+            sql: sql.to_string(),
+            params,
+        })
+    }
+
+    /// Parameters to bind to the explained statement.
+    pub fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.params
+            .iter()
+            .map(|it| it as &(dyn ToSql + Sync))
+            .collect()
+    }
+}
+
+/// Parameter in its binary encoding, `None` for `NULL`.
+#[derive(Debug, Clone)]
+struct EncodedParam(Option<Vec<u8>>);
+
+impl ToSql for EncodedParam {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        Ok(match &self.0 {
+            Some(raw) => {
+                out.extend_from_slice(raw);
+                IsNull::No
+            }
+            None => IsNull::Yes,
+        })
+    }
+
+    // Encoded for the type the statement expects
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+
+    to_sql_checked!();
+}
@@ -0,0 +1,39 @@
+use serde::Serialize;
+
+/// Description of a query generated by cornucopia, as listed in the generated `registry` module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct QueryInfo {
+    /// Name of the module (query file) declaring the query.
+    pub module: &'static str,
+    /// Name of the query, including its version suffix if any (`name@v2`).
+    pub name: &'static str,
+    /// SQL sent to the database.
+    pub sql: &'static str,
+    /// Search path the query was prepared with, declared by its module's `--@ schema`
+    /// annotation.
+    pub search_path: Option<&'static str>,
+}
+
+/// Verification result of a single query against the database schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueryReport {
+    #[serde(flatten)]
+    pub query: QueryInfo,
+    /// Error returned by the database when preparing the query, if any.
+    pub error: Option<String>,
+}
+
+/// Serializable report of the queries run by an application and whether they
+/// still match the database schema, meant to be exposed on a debug endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IntrospectionReport {
+    pub queries: Vec<QueryReport>,
+}
+
+impl IntrospectionReport {
+    /// Returns `true` if every query could be prepared against the database.
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        self.queries.iter().all(|it| it.error.is_none())
+    }
+}
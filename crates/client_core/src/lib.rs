@@ -1,11 +1,73 @@
 mod array_iterator;
+#[cfg(feature = "cache")]
+mod cache;
+#[cfg(feature = "cdc")]
+mod cdc;
+#[cfg(feature = "csv")]
+mod csv;
 mod domain;
+mod encryption;
+#[cfg(feature = "explain")]
+mod explain;
+#[cfg(feature = "introspection")]
+mod introspection;
+#[cfg(feature = "notifications")]
+mod notifications;
+#[cfg(feature = "with-bigdecimal-0_4")]
+mod numeric;
+#[cfg(feature = "obfuscated-sql")]
+mod obfuscation;
+mod page;
+mod range;
+#[cfg(feature = "replicas")]
+mod replicas;
+mod row;
+mod snapshot;
+mod sqlcommenter;
+mod tenancy;
+mod text_len;
 mod type_traits;
 mod utils;
 
 pub use array_iterator::ArrayIterator;
+#[cfg(feature = "cache")]
+pub use cache::{
+    decode as decode_cached, encode as encode_cached, CacheKey, Cacheable, MemoryCache,
+};
+#[cfg(feature = "cdc")]
+pub use cdc::{decode_wal2json, Change, Table};
+#[cfg(feature = "csv")]
+pub use csv::{write_csv, CsvError};
+#[cfg(feature = "diesel")]
+pub use diesel;
 pub use domain::{Domain, DomainArray};
+pub use encryption::{Codec, Encrypted};
+#[cfg(feature = "explain")]
+pub use explain::{ExplainRequest, ExplainSampler, ExplainedQuery, JsonPlan, EXPLAIN_PREFIX};
+#[cfg(feature = "introspection")]
+pub use introspection::{IntrospectionReport, QueryInfo, QueryReport};
+#[cfg(feature = "notifications")]
+pub use notifications::{decode, encode, listen_sql, Channel, NOTIFY_SQL};
+#[cfg(feature = "with-bigdecimal-0_4")]
+pub use numeric::Numeric;
+#[cfg(feature = "obfuscated-sql")]
+pub use obfuscation::SqlTable;
+pub use page::Page;
+pub use range::Range;
+#[cfg(feature = "replicas")]
+pub use replicas::{ConsistencyToken, CURRENT_LSN_SQL, REPLAYED_LSN_SQL};
+pub use row::FromPgRow;
+#[cfg(feature = "serde")]
+pub use serde;
+pub use snapshot::{set_snapshot_sql, EXPORT_SNAPSHOT_SQL, SNAPSHOT_TRANSACTION_SQL};
+pub use sqlcommenter::SqlComment;
+#[cfg(feature = "sqlx")]
+pub use sqlx;
+pub use tenancy::{InvalidSchemaName, TenantSchema};
+pub use text_len::{check_text_len, TextTooLong};
 pub use type_traits::{ArraySql, BytesSql, IterSql, StringSql};
+#[cfg(feature = "arrow")]
+pub use {arrow_array, arrow_schema};
 
 #[cfg(feature = "with-serde_json-1")]
 pub use type_traits::JsonSql;
@@ -0,0 +1,36 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+/// A `LISTEN`/`NOTIFY` channel declared in a query file, whose payloads are JSON documents.
+pub trait Channel {
+    /// Name of the channel in the database.
+    const NAME: &'static str;
+    /// Type of the payloads, received as JSON. Sending them also requires `Serialize`.
+    type Payload: DeserializeOwned;
+}
+
+/// Returns the statement subscribing to the notifications of `C`.
+#[must_use]
+pub fn listen_sql<C: Channel>() -> String {
+    format!("LISTEN \"{}\"", C::NAME.replace('"', "\"\""))
+}
+
+/// Decodes the `payload` of a notification received on `channel`, returning `None` if
+/// the notification wasn't sent on the channel of `C`.
+pub fn decode<C: Channel>(
+    channel: &str,
+    payload: &str,
+) -> Option<Result<C::Payload, serde_json::Error>> {
+    (channel == C::NAME).then(|| serde_json::from_str(payload))
+}
+
+/// Statement sending a notification, taking the name of the channel and the encoded
+/// payload as parameters.
+pub const NOTIFY_SQL: &str = "SELECT pg_notify($1, $2)";
+
+/// Encodes `payload` to be sent on the channel of `C`.
+pub fn encode<C: Channel>(payload: &C::Payload) -> Result<String, serde_json::Error>
+where
+    C::Payload: Serialize,
+{
+    serde_json::to_string(payload)
+}
@@ -0,0 +1,120 @@
+use std::error::Error;
+
+use bigdecimal::{
+    num_bigint::{BigInt, BigUint, Sign},
+    BigDecimal,
+};
+use postgres_types::{accepts, private::BytesMut, to_sql_checked, FromSql, IsNull, ToSql, Type};
+
+/// Sign of a positive number in the binary format of `numeric`
+const POSITIVE: u16 = 0x0000;
+/// Sign of a negative number in the binary format of `numeric`
+const NEGATIVE: u16 = 0x4000;
+/// Base of the digits of the binary format of `numeric`
+const BASE: u32 = 10_000;
+
+/// `numeric` value read and written as a [`BigDecimal`], which `postgres` has no mapping
+/// for. Rows hold it as their borrowed field type and convert it into a [`BigDecimal`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Numeric(pub BigDecimal);
+
+impl From<BigDecimal> for Numeric {
+    fn from(value: BigDecimal) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Numeric> for BigDecimal {
+    fn from(value: Numeric) -> Self {
+        value.0
+    }
+}
+
+impl<'a> FromSql<'a> for Numeric {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let word = |idx: usize| -> Result<u16, Box<dyn Error + Sync + Send>> {
+            match raw.get(idx * 2..idx * 2 + 2) {
+                Some(bytes) => Ok(u16::from_be_bytes([bytes[0], bytes[1]])),
+                None => Err("invalid buffer size".into()),
+            }
+        };
+        let nb_digits = word(0)? as usize;
+        let weight = word(1)? as i16;
+        let sign = match word(2)? {
+            POSITIVE => Sign::Plus,
+            NEGATIVE => Sign::Minus,
+            _ => return Err("NaN and infinite numerics can't be read as a BigDecimal".into()),
+        };
+        let scale = word(3)?;
+        let mut int = BigInt::default();
+        for idx in 0..nb_digits {
+            int = int * BASE + word(4 + idx)?;
+        }
+        if sign == Sign::Minus {
+            int = -int;
+        }
+        // The last digit is the one of 10000^(weight - nb_digits + 1)
+        let exponent = 4 * (nb_digits as i64 - 1 - i64::from(weight));
+        Ok(Self(
+            BigDecimal::new(int, exponent).with_scale(i64::from(scale)),
+        ))
+    }
+
+    accepts!(NUMERIC);
+}
+
+impl ToSql for Numeric {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let (int, mut exponent) = self.0.as_bigint_and_exponent();
+        let scale = u16::try_from(exponent.max(0)).map_err(|_| "numeric scale out of range")?;
+        let (sign, mut digits) = int.into_parts();
+        // Align the fractional digits on base 10000 digits
+        if exponent < 0 {
+            digits *= pow10(exponent.unsigned_abs());
+            exponent = 0;
+        }
+        let padding = (4 - exponent % 4) % 4;
+        digits *= pow10(padding as u64);
+        exponent += padding;
+        let decimal = digits.to_string();
+        let decimal = format!("{}{decimal}", "0".repeat((4 - decimal.len() % 4) % 4));
+        let mut groups: Vec<i16> = decimal
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| std::str::from_utf8(chunk).unwrap().parse().unwrap())
+            .collect();
+        let mut weight = groups.len() as i64 - exponent / 4 - 1;
+        let leading_zeros = groups.iter().take_while(|it| **it == 0).count();
+        groups.drain(..leading_zeros);
+        weight -= leading_zeros as i64;
+        while groups.last() == Some(&0) {
+            groups.pop();
+        }
+        if groups.is_empty() {
+            weight = 0;
+        }
+        let nb_digits = i16::try_from(groups.len()).map_err(|_| "numeric out of range")?;
+        let weight = i16::try_from(weight).map_err(|_| "numeric out of range")?;
+        out.extend_from_slice(&nb_digits.to_be_bytes());
+        out.extend_from_slice(&weight.to_be_bytes());
+        let sign = if sign == Sign::Minus {
+            NEGATIVE
+        } else {
+            POSITIVE
+        };
+        out.extend_from_slice(&sign.to_be_bytes());
+        out.extend_from_slice(&scale.to_be_bytes());
+        for group in groups {
+            out.extend_from_slice(&group.to_be_bytes());
+        }
+        Ok(IsNull::No)
+    }
+
+    accepts!(NUMERIC);
+    to_sql_checked!();
+}
+
+/// `10^exponent`
+fn pow10(exponent: u64) -> BigUint {
+    BigUint::from(10u32).pow(exponent as u32)
+}
@@ -0,0 +1,76 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+/// Concatenated SQL and the byte range of each statement, keyed by hash
+type Statements = (String, HashMap<u64, (usize, usize)>);
+
+/// SQL of the statements of a generated file, compressed then masked so that it can't
+/// be read from the binary as is, and resolved by the hash of the statements' names.
+/// This deters casual inspection, it is not encryption.
+///
+/// The decompressed table is a sequence of entries made of the hash (`u64`, little
+/// endian), the length (`u32`, little endian) and the SQL of a statement.
+pub struct SqlTable {
+    compressed: &'static [u8],
+    statements: OnceLock<Statements>,
+}
+
+impl SqlTable {
+    #[must_use]
+    pub const fn new(compressed: &'static [u8]) -> Self {
+        Self {
+            compressed,
+            statements: OnceLock::new(),
+        }
+    }
+
+    /// Returns the SQL of the statement whose name hashes to `hash`, decompressing
+    /// the table on first use.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table is malformed or doesn't contain the statement, which can't
+    /// happen with generated tables.
+    pub fn get(&'static self, hash: u64) -> &'static str {
+        let (sql, offsets) = self.statements.get_or_init(|| decode(self.compressed));
+        let (start, end) = offsets[&hash];
+        &sql[start..end]
+    }
+}
+
+fn decode(compressed: &[u8]) -> Statements {
+    let compressed: Vec<u8> = compressed
+        .iter()
+        .zip(keystream())
+        .map(|(b, k)| b ^ k)
+        .collect();
+    let bytes = miniz_oxide::inflate::decompress_to_vec(&compressed).expect("valid SQL table");
+    let mut sql = String::with_capacity(bytes.len());
+    let mut offsets = HashMap::new();
+    let mut rest = bytes.as_slice();
+    while !rest.is_empty() {
+        let (hash, tail) = rest.split_at(8);
+        let (len, tail) = tail.split_at(4);
+        let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+        let (statement, tail) = tail.split_at(len);
+        let start = sql.len();
+        sql.push_str(std::str::from_utf8(statement).expect("valid SQL table"));
+        offsets.insert(
+            u64::from_le_bytes(hash.try_into().unwrap()),
+            (start, sql.len()),
+        );
+        rest = tail;
+    }
+    (sql, offsets)
+}
+
+/// Xorshift keystream masking the compressed table, as small tables are stored
+/// uncompressed by deflate. Must match the one used by the code generator.
+fn keystream() -> impl Iterator<Item = u8> {
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    std::iter::repeat_with(move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state as u8
+    })
+}
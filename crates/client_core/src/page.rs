@@ -0,0 +1,24 @@
+/// Rows of a query annotated with `--! paginated`, fetched in the order of its cursor
+/// column, along with the cursor to fetch the next page from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T, K> {
+    /// Rows of the page, in cursor order
+    pub items: Vec<T>,
+    /// Cursor of the last row of the page, `None` if no rows follow it
+    pub next_cursor: Option<K>,
+}
+
+impl<T, K> Page<T, K> {
+    /// Page of the first `limit` of `rows`, each paired with its cursor. Pages are fetched
+    /// with one more row than their limit, to tell whether any row follows them.
+    pub fn new(mut rows: Vec<(T, K)>, limit: u32) -> Self {
+        let limit = limit as usize;
+        let has_next = rows.len() > limit;
+        rows.truncate(limit);
+        let (items, mut cursors): (Vec<_>, Vec<_>) = rows.into_iter().unzip();
+        Self {
+            items,
+            next_cursor: if has_next { cursors.pop() } else { None },
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::{error::Error, ops::Bound};
+
+use postgres_protocol::types::{self, RangeBound};
+use postgres_types::{private::BytesMut, FromSql, IsNull, Kind, ToSql, Type};
+
+/// A PostgreSQL range, such as an `int4range`, a `daterange` or a `tstzrange`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Range<T> {
+    /// A range containing no value
+    Empty,
+    /// A range between two bounds, which may be unbounded
+    Nonempty(Bound<T>, Bound<T>),
+}
+
+impl<T> Range<T> {
+    /// A range between `lower` and `upper`.
+    pub fn new(lower: Bound<T>, upper: Bound<T>) -> Self {
+        Self::Nonempty(lower, upper)
+    }
+
+    /// Is this range empty
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Self::Empty)
+    }
+
+    /// Lower bound of this range, `None` if it is empty.
+    pub fn lower(&self) -> Option<Bound<&T>> {
+        match self {
+            Self::Empty => None,
+            Self::Nonempty(lower, _) => Some(lower.as_ref()),
+        }
+    }
+
+    /// Upper bound of this range, `None` if it is empty.
+    pub fn upper(&self) -> Option<Bound<&T>> {
+        match self {
+            Self::Empty => None,
+            Self::Nonempty(_, upper) => Some(upper.as_ref()),
+        }
+    }
+
+    /// Maps the bounds of this range, such as from borrowed to owned values.
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> Range<U> {
+        match self {
+            Self::Empty => Range::Empty,
+            Self::Nonempty(lower, upper) => Range::Nonempty(lower.map(&mut f), upper.map(f)),
+        }
+    }
+}
+
+impl<T: PartialOrd> Range<T> {
+    /// Does this range contain `value`
+    pub fn contains(&self, value: &T) -> bool {
+        match self {
+            Self::Empty => false,
+            Self::Nonempty(lower, upper) => {
+                std::ops::RangeBounds::contains(&(lower.as_ref(), upper.as_ref()), value)
+            }
+        }
+    }
+}
+
+/// `start..end`, with an inclusive lower bound and an exclusive upper bound as PostgreSQL
+/// normalizes discrete ranges.
+impl<T> From<std::ops::Range<T>> for Range<T> {
+    fn from(range: std::ops::Range<T>) -> Self {
+        Self::Nonempty(Bound::Included(range.start), Bound::Excluded(range.end))
+    }
+}
+
+/// Element type of a range type, escaping domains over range types.
+fn element_type(ty: &Type) -> Option<&Type> {
+    match ty.kind() {
+        Kind::Range(inner) => Some(inner),
+        Kind::Domain(inner) => element_type(inner),
+        _ => None,
+    }
+}
+
+fn bound_from_sql<'a, T: FromSql<'a>>(
+    ty: &Type,
+    bound: RangeBound<Option<&'a [u8]>>,
+) -> Result<Bound<T>, Box<dyn Error + Sync + Send>> {
+    Ok(match bound {
+        RangeBound::Inclusive(raw) => Bound::Included(T::from_sql_nullable(ty, raw)?),
+        RangeBound::Exclusive(raw) => Bound::Excluded(T::from_sql_nullable(ty, raw)?),
+        RangeBound::Unbounded => Bound::Unbounded,
+    })
+}
+
+fn bound_to_sql<T: ToSql>(
+    ty: &Type,
+    bound: &Bound<T>,
+    out: &mut BytesMut,
+) -> Result<RangeBound<postgres_protocol::IsNull>, Box<dyn Error + Sync + Send>> {
+    let mut value_to_sql = |value: &T| {
+        Ok::<_, Box<dyn Error + Sync + Send>>(match value.to_sql(ty, out)? {
+            IsNull::Yes => postgres_protocol::IsNull::Yes,
+            IsNull::No => postgres_protocol::IsNull::No,
+        })
+    };
+    Ok(match bound {
+        Bound::Included(value) => RangeBound::Inclusive(value_to_sql(value)?),
+        Bound::Excluded(value) => RangeBound::Exclusive(value_to_sql(value)?),
+        Bound::Unbounded => RangeBound::Unbounded,
+    })
+}
+
+impl<'a, T: FromSql<'a>> FromSql<'a> for Range<T> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let inner = element_type(ty).ok_or("expected a range type")?;
+        Ok(match types::range_from_sql(raw)? {
+            types::Range::Empty => Self::Empty,
+            types::Range::Nonempty(lower, upper) => {
+                Self::Nonempty(bound_from_sql(inner, lower)?, bound_from_sql(inner, upper)?)
+            }
+        })
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        element_type(ty).is_some_and(T::accepts)
+    }
+}
+
+impl<T: ToSql> ToSql for Range<T> {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let inner = element_type(ty).ok_or("expected a range type")?;
+        match self {
+            Self::Empty => types::empty_range_to_sql(out),
+            Self::Nonempty(lower, upper) => types::range_to_sql(
+                |out| bound_to_sql(inner, lower, out),
+                |out| bound_to_sql(inner, upper, out),
+                out,
+            )?,
+        }
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        element_type(ty).is_some_and(T::accepts)
+    }
+
+    postgres_types::to_sql_checked!();
+}
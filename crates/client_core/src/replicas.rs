@@ -0,0 +1,51 @@
+use postgres_types::PgLsn;
+
+/// Returns the current WAL write position of a primary, and whether the current transaction
+/// wrote anything, its commit being then yet to be written.
+pub const CURRENT_LSN_SQL: &str =
+    "SELECT pg_current_wal_lsn(), txid_current_if_assigned() IS NOT NULL";
+
+/// Returns whether a replica has replayed the WAL up to `$1`, always true on a primary.
+pub const REPLAYED_LSN_SQL: &str = "SELECT coalesce(pg_last_wal_replay_lsn() >= $1, true)";
+
+/// Highest WAL position written by a session, used to read its own writes from replicas.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConsistencyToken {
+    lsn: Option<PgLsn>,
+    pending: bool,
+}
+
+impl ConsistencyToken {
+    /// A token that doesn't require waiting for any write.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            lsn: None,
+            pending: false,
+        }
+    }
+
+    /// Highest WAL position observed, if any write was captured.
+    #[must_use]
+    pub const fn lsn(&self) -> Option<PgLsn> {
+        self.lsn
+    }
+
+    /// Whether writes of a transaction were recorded before its commit, whose position
+    /// is only known once captured after the commit.
+    #[must_use]
+    pub const fn is_pending(&self) -> bool {
+        self.pending
+    }
+
+    /// Records the committed writes up to `lsn`, keeping the highest position observed.
+    pub fn observe(&mut self, lsn: PgLsn) {
+        self.lsn = Some(self.lsn.map_or(lsn, |current| current.max(lsn)));
+        self.pending = false;
+    }
+
+    /// Records writes of a transaction that isn't committed yet.
+    pub fn observe_uncommitted(&mut self) {
+        self.pending = true;
+    }
+}
@@ -0,0 +1,8 @@
+/// This trait allows you to extract a generated row struct from a row you obtained
+/// yourself, such as the rows of an existing statement. Columns are read by name.
+///
+/// The rows and errors of `postgres` are those of `tokio_postgres`, so this trait is shared
+/// by the sync and async clients.
+pub trait FromPgRow<'a>: Sized {
+    fn from_row(row: &'a tokio_postgres::Row) -> Result<Self, tokio_postgres::Error>;
+}
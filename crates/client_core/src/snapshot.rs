@@ -0,0 +1,17 @@
+/// Characteristics of the transactions exporting or reading a snapshot, which must be at
+/// least repeatable read.
+pub const SNAPSHOT_TRANSACTION_SQL: &str =
+    "SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY";
+
+/// Returns the id of the current transaction's snapshot, valid while it stays open.
+pub const EXPORT_SNAPSHOT_SQL: &str = "SELECT pg_export_snapshot()";
+
+/// Sets the snapshot of a transaction to the exported snapshot `id`, before its first
+/// query. `SET TRANSACTION SNAPSHOT` can't bind its id as a parameter.
+#[must_use]
+pub fn set_snapshot_sql(id: &str) -> String {
+    format!(
+        "{SNAPSHOT_TRANSACTION_SQL}; SET TRANSACTION SNAPSHOT '{}'",
+        id.replace('\'', "''")
+    )
+}
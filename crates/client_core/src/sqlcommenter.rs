@@ -0,0 +1,61 @@
+use std::fmt::Write;
+
+/// Tags appended to executed statements as a [sqlcommenter](https://google.github.io/sqlcommenter/spec/)
+/// comment, so APM tools can correlate database spans with application traces.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SqlComment {
+    /// Name of the controller (or handler) issuing the query.
+    pub controller: Option<String>,
+    /// Name of the action (or route) issuing the query.
+    pub action: Option<String>,
+    /// W3C trace context `traceparent` header.
+    pub traceparent: Option<String>,
+    /// W3C trace context `tracestate` header.
+    pub tracestate: Option<String>,
+}
+
+impl SqlComment {
+    /// Renders the comment, returning `None` when there is no tag to append.
+    #[must_use]
+    pub fn render(&self) -> Option<String> {
+        // Keys must be sorted lexicographically
+        let tags = [
+            ("action", &self.action),
+            ("controller", &self.controller),
+            ("traceparent", &self.traceparent),
+            ("tracestate", &self.tracestate),
+        ];
+        let mut out = String::new();
+        for (key, value) in tags {
+            if let Some(value) = value {
+                out.push_str(if out.is_empty() { "/*" } else { "," });
+                write!(out, "{key}='{}'", url_encode(value)).unwrap();
+            }
+        }
+        if out.is_empty() {
+            None
+        } else {
+            out.push_str("*/");
+            Some(out)
+        }
+    }
+
+    /// Appends the comment to `query`, if there is any tag to append.
+    #[must_use]
+    pub fn append_to(&self, query: &str) -> Option<String> {
+        self.render().map(|comment| format!("{query} {comment}"))
+    }
+}
+
+/// Percent-encodes everything but unreserved characters.
+fn url_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(byte as char);
+        } else {
+            write!(out, "%{byte:02X}").unwrap();
+        }
+    }
+    out
+}
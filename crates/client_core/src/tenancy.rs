@@ -0,0 +1,68 @@
+use std::{fmt::Display, ops::Range};
+
+/// Name of a tenant's schema, substituted for the template schema of generated queries.
+/// Names match `^[a-z_][a-z0-9_]{0,62}$`, so that they are valid unquoted identifiers
+/// that can't inject SQL.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TenantSchema(String);
+
+impl TenantSchema {
+    /// Validates a tenant's schema name.
+    pub fn new(name: impl Into<String>) -> Result<Self, InvalidSchemaName> {
+        let name = name.into();
+        let mut chars = name.chars();
+        let is_valid = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+            && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            && name.len() <= 63;
+        if is_valid {
+            Ok(Self(name))
+        } else {
+            Err(InvalidSchemaName(name))
+        }
+    }
+
+    /// Name of the schema.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Replaces the references to the template schema in `sql`, at the byte `references`
+    /// located by the generator, with references to this schema. Literals, comments and
+    /// longer identifiers are left as written.
+    #[must_use]
+    pub fn substitute(&self, sql: &str, references: &[Range<usize>]) -> String {
+        let mut out = String::with_capacity(sql.len());
+        let mut copied = 0;
+        for reference in references {
+            out.push_str(&sql[copied..reference.start]);
+            out.push_str(&self.0);
+            copied = reference.end;
+        }
+        out.push_str(&sql[copied..]);
+        out
+    }
+}
+
+impl Display for TenantSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Schema name rejected by [`TenantSchema::new`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidSchemaName(pub String);
+
+impl Display for InvalidSchemaName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid tenant schema name `{}`, expected lowercase letters, digits and underscores",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for InvalidSchemaName {}
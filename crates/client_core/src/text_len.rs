@@ -0,0 +1,55 @@
+use std::fmt::Display;
+
+use postgres_types::{private::BytesMut, IsNull, ToSql, Type};
+
+/// Text field holding more characters than the `varchar(n)` column it is
+/// written to allows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextTooLong {
+    /// Name of the field
+    pub field: &'static str,
+    /// Number of characters allowed by the column
+    pub max_len: usize,
+    /// Number of characters of the field, without its trailing spaces
+    pub len: usize,
+}
+
+impl Display for TextTooLong {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`{}` holds {} characters, more than the {} allowed by its column",
+            self.field, self.len, self.max_len
+        )
+    }
+}
+
+impl std::error::Error for TextTooLong {}
+
+/// Checks that the text `value` of `field` fits in a column of `max_len` characters. As
+/// PostgreSQL truncates trailing spaces exceeding the limit, they are not counted. Null
+/// values always fit.
+pub fn check_text_len(
+    field: &'static str,
+    value: &impl ToSql,
+    max_len: usize,
+) -> Result<(), TextTooLong> {
+    let mut buf = BytesMut::new();
+    // Text is sent as its UTF-8 bytes
+    let Ok(IsNull::No) = value.to_sql(&Type::TEXT, &mut buf) else {
+        return Ok(());
+    };
+    let len = String::from_utf8_lossy(&buf)
+        .trim_end_matches(' ')
+        .chars()
+        .count();
+    if len > max_len {
+        Err(TextTooLong {
+            field,
+            max_len,
+            len,
+        })
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+//! Read-through caching of the read queries generated with `--cache`, whose rows can be
+//! fetched with `.cached(&cache)` to consult a [`QueryCache`] before the database. Results
+//! are keyed by query id and a hash of the parameters, and stored as JSON so that they can
+//! be shared through an external cache such as Redis.
+
+pub use cornucopia_client_core::{CacheKey, Cacheable, MemoryCache};
+
+/// Cache of query results consulted by cached queries. Failing to read or write the cache
+/// is expected to behave as a miss, so that queries fall back to the database.
+pub trait QueryCache {
+    /// Cached value of `key`, if any.
+    fn get(&self, key: &CacheKey) -> Option<Vec<u8>>;
+    /// Caches `value` for `key`, for as long as the cache sees fit.
+    fn put(&self, key: &CacheKey, value: Vec<u8>);
+}
+
+impl QueryCache for MemoryCache {
+    fn get(&self, key: &CacheKey) -> Option<Vec<u8>> {
+        MemoryCache::get(self, key)
+    }
+
+    fn put(&self, key: &CacheKey, value: Vec<u8>) {
+        MemoryCache::put(self, key, value);
+    }
+}
@@ -0,0 +1,3 @@
+//! CSV export of the rows generated with `--csv`.
+
+pub use cornucopia_client_core::{write_csv, CsvError};
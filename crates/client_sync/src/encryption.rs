@@ -0,0 +1,6 @@
+//! Application-level encryption of sensitive columns. Fields declared encrypted with a `#`
+//! suffix, such as `--! insert_user (ssn#)`, are generated as [`Encrypted`] values, the
+//! ciphertexts stored in their `bytea` column, which are encrypted from and decrypted to
+//! their plaintext with a [`Codec`].
+
+pub use cornucopia_client_core::{Codec, Encrypted};
@@ -0,0 +1,83 @@
+//! Plan sampling of the read queries generated with `--explain`, which run a sample of
+//! their executions again under `EXPLAIN ANALYZE` on a connection of their own and hand
+//! the plan to a hook, to monitor plan regressions of each query.
+
+use std::sync::{
+    mpsc::{self, SyncSender},
+    Arc,
+};
+
+use cornucopia_client_core::{ExplainRequest, ExplainSampler, JsonPlan, EXPLAIN_PREFIX};
+use postgres::{types::Type, Client};
+
+pub use cornucopia_client_core::ExplainedQuery;
+
+/// Receives the plan of a sampled query execution.
+pub type ExplainHook = fn(&ExplainedQuery);
+
+/// Sampled executions waiting to be explained, beyond which samples are skipped.
+const QUEUE_LEN: usize = 16;
+
+/// Samples the plans of the statements it's attached to with their `explained` method.
+#[derive(Clone)]
+pub struct Explainer {
+    sampler: Arc<ExplainSampler>,
+    sender: SyncSender<ExplainRequest>,
+}
+
+impl Explainer {
+    /// Hands the plans of a `rate` fraction of the executions, between 0 and 1, to `hook`.
+    /// Sampled executions run again under `EXPLAIN ANALYZE`, in a read-only transaction
+    /// rolled back, on `client`, owned by a background thread so that queries don't wait
+    /// for their plan. Samples are skipped while the thread is busy.
+    pub fn spawn(mut client: Client, rate: f64, hook: ExplainHook) -> Self {
+        let (sender, receiver) = mpsc::sync_channel::<ExplainRequest>(QUEUE_LEN);
+        std::thread::spawn(move || {
+            for request in receiver {
+                explain(&mut client, &request, hook);
+            }
+        });
+        let sampler = ExplainSampler::new();
+        sampler.set_rate(rate);
+        Self {
+            sampler: Arc::new(sampler),
+            sender,
+        }
+    }
+
+    /// Whether the next execution is sampled.
+    pub(crate) fn sample(&self) -> bool {
+        self.sampler.sample()
+    }
+
+    /// Queues the execution of the query `id` binding `params`, encoded as `types`.
+    pub(crate) fn send(
+        &self,
+        id: &'static str,
+        sql: &str,
+        types: &[Type],
+        params: &[&(dyn postgres::types::ToSql + Sync)],
+    ) {
+        if let Some(request) = ExplainRequest::new(id, sql, types, params) {
+            // The thread is busy, skip this sample
+            self.sender.try_send(request).ok();
+        }
+    }
+}
+
+/// Hands the plan of `request` to `hook`. Failing to explain the query is ignored.
+fn explain(client: &mut Client, request: &ExplainRequest, hook: ExplainHook) {
+    let Ok(mut transaction) = client.build_transaction().read_only(true).start() else {
+        return;
+    };
+    let sql = format!("{EXPLAIN_PREFIX}{}", request.sql);
+    if let Ok(row) = transaction.query_one(&sql, &request.params()) {
+        if let Ok(JsonPlan(plan)) = row.try_get(0) {
+            hook(&ExplainedQuery {
+                id: request.id,
+                sql: &request.sql,
+                plan: &plan,
+            });
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use postgres::GenericClient;
+
+pub use cornucopia_client_core::{IntrospectionReport, QueryInfo, QueryReport};
+
+/// Prepares every query of the generated `registry` against the database, reporting
+/// which ones don't match its current schema.
+pub fn report<C: GenericClient>(client: &mut C, queries: &[QueryInfo]) -> IntrospectionReport {
+    IntrospectionReport {
+        queries: queries
+            .iter()
+            .map(|query| QueryReport {
+                query: *query,
+                error: prepare(client, query).err().map(|e| e.to_string()),
+            })
+            .collect(),
+    }
+}
+
+/// Prepares a query with its search path, restoring the previous one afterwards.
+fn prepare<C: GenericClient>(client: &mut C, query: &QueryInfo) -> Result<(), postgres::Error> {
+    let Some(search_path) = query.search_path else {
+        return client.prepare(query.sql).map(drop);
+    };
+    let previous: String = client
+        .query_one("SELECT current_setting('search_path')", &[])?
+        .get(0);
+    client.execute(
+        "SELECT set_config('search_path', $1, false)",
+        &[&search_path],
+    )?;
+    let prepared = client.prepare(query.sql).map(drop);
+    client.execute("SELECT set_config('search_path', $1, false)", &[&previous])?;
+    prepared
+}
@@ -0,0 +1,83 @@
+use std::fmt::Display;
+
+use postgres::{fallible_iterator::FallibleIterator, Client, GenericClient, Notification};
+
+use cornucopia_client_core::serde::Serialize;
+
+pub use cornucopia_client_core::Channel;
+
+/// Error returned while sending or receiving typed notifications.
+#[derive(Debug)]
+pub enum Error {
+    /// The connection to the database failed.
+    Db(postgres::Error),
+    /// A payload didn't match the type of its channel.
+    Decode(serde_json::Error),
+    /// A payload couldn't be encoded.
+    Encode(serde_json::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Db(e) => write!(f, "couldn't receive notification: {e}"),
+            Error::Decode(e) => write!(f, "couldn't decode notification payload: {e}"),
+            Error::Encode(e) => write!(f, "couldn't encode notification payload: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Db(e) => Some(e),
+            Error::Decode(e) | Error::Encode(e) => Some(e),
+        }
+    }
+}
+
+/// Subscribes `client` to the notifications of `C`.
+pub fn listen<C: Channel, G: GenericClient>(client: &mut G) -> Result<(), postgres::Error> {
+    client.batch_execute(&cornucopia_client_core::listen_sql::<C>())
+}
+
+/// Sends `payload` on the channel of `C`, delivered to its listeners once the current
+/// transaction commits.
+pub fn notify<C: Channel, G: GenericClient>(
+    client: &mut G,
+    payload: &C::Payload,
+) -> Result<(), Error>
+where
+    C::Payload: Serialize,
+{
+    let payload = cornucopia_client_core::encode::<C>(payload).map_err(Error::Encode)?;
+    client
+        .execute(cornucopia_client_core::NOTIFY_SQL, &[&C::NAME, &payload])
+        .map_err(Error::Db)?;
+    Ok(())
+}
+
+/// Decodes `notification`, returning `None` if it wasn't sent on the channel of `C`.
+pub fn decode<C: Channel>(
+    notification: &Notification,
+) -> Option<Result<C::Payload, serde_json::Error>> {
+    cornucopia_client_core::decode::<C>(notification.channel(), notification.payload())
+}
+
+/// Blocks waiting for notifications, yielding the decoded payloads of `C` and
+/// skipping the notifications sent on other channels.
+pub fn payloads<C: Channel>(
+    client: &mut Client,
+) -> impl Iterator<Item = Result<C::Payload, Error>> + '_ {
+    std::iter::from_fn(move || loop {
+        match client.notifications().blocking_iter().next() {
+            Ok(Some(notification)) => {
+                if let Some(payload) = decode::<C>(&notification) {
+                    return Some(payload.map_err(Error::Decode));
+                }
+            }
+            Ok(None) => return None,
+            Err(e) => return Some(Err(Error::Db(e))),
+        }
+    })
+}
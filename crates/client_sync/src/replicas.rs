@@ -0,0 +1,59 @@
+//! Read-your-writes consistency for applications routing reads to replicas. Generated
+//! mutating queries have a `bind_tracked` variant capturing the WAL position of the
+//! primary after their execution, which [`wait_for_lsn`] waits for on a replica.
+
+use std::time::{Duration, Instant};
+
+use cornucopia_client_core::{CURRENT_LSN_SQL, REPLAYED_LSN_SQL};
+use postgres::GenericClient;
+
+pub use cornucopia_client_core::ConsistencyToken;
+pub use postgres::types::PgLsn;
+
+/// Delay between two replay position checks of [`wait_for_lsn`].
+pub const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Records the current WAL position of the primary `client` in `token`.
+///
+/// Use it after mutating queries returning rows, which have no `bind_tracked` variant.
+/// Inside a transaction, the position of its commit isn't known yet: its writes are
+/// recorded as pending until this is called again once it is committed.
+pub fn capture<C: GenericClient>(
+    client: &mut C,
+    token: &mut ConsistencyToken,
+) -> Result<PgLsn, postgres::Error> {
+    let row = client.query_one(CURRENT_LSN_SQL, &[])?;
+    let lsn = row.get(0);
+    if row.get(1) {
+        token.observe_uncommitted();
+    } else {
+        token.observe(lsn);
+    }
+    Ok(lsn)
+}
+
+/// Waits until the replica `client` has replayed the writes recorded in `token`,
+/// returning `false` if it didn't catch up within `timeout`. Returns immediately when
+/// `client` is a primary, or with `false` if `token` has pending writes.
+pub fn wait_for_lsn<C: GenericClient>(
+    client: &mut C,
+    token: &ConsistencyToken,
+    timeout: Duration,
+) -> Result<bool, postgres::Error> {
+    if token.is_pending() {
+        return Ok(false);
+    }
+    let Some(lsn) = token.lsn() else {
+        return Ok(true);
+    };
+    let deadline = Instant::now() + timeout;
+    loop {
+        if client.query_one(REPLAYED_LSN_SQL, &[&lsn])?.get(0) {
+            return Ok(true);
+        }
+        if Instant::now() >= deadline {
+            return Ok(false);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
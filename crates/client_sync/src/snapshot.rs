@@ -0,0 +1,38 @@
+//! Point-in-time reads across several queries, for reports that must stay consistent
+//! while the database changes. A [`Snapshot`] exported by a transaction kept open is read
+//! by the generated `bind_at` functions of queries annotated with `--! snapshot`, each in
+//! its own transaction on another connection.
+
+use cornucopia_client_core::{EXPORT_SNAPSHOT_SQL, SNAPSHOT_TRANSACTION_SQL};
+use postgres::{Client, Error, Transaction};
+
+/// Snapshot of the database exported by a read-only transaction, valid until released.
+pub struct Snapshot<'a> {
+    transaction: Transaction<'a>,
+    id: String,
+}
+
+impl<'a> Snapshot<'a> {
+    /// Opens a read-only repeatable read transaction on `client` and exports its snapshot.
+    pub fn export(client: &'a mut Client) -> Result<Self, Error> {
+        let mut transaction = client.transaction()?;
+        transaction.batch_execute(SNAPSHOT_TRANSACTION_SQL)?;
+        let id = transaction.query_one(EXPORT_SNAPSHOT_SQL, &[])?.get(0);
+        Ok(Self { transaction, id })
+    }
+
+    /// Id of the snapshot, as given to `bind_at` functions.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Transaction exporting the snapshot, whose queries also read as of the snapshot.
+    pub fn transaction(&mut self) -> &mut Transaction<'a> {
+        &mut self.transaction
+    }
+
+    /// Ends the exporting transaction, after which the snapshot can't be read anymore.
+    pub fn release(self) -> Result<(), Error> {
+        self.transaction.commit()
+    }
+}
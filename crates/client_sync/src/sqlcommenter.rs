@@ -0,0 +1,34 @@
+use std::cell::RefCell;
+
+pub use cornucopia_client_core::SqlComment;
+
+thread_local! {
+    static SQL_COMMENT: RefCell<Option<SqlComment>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` in a scope where every query executed by generated code
+/// is tagged with `comment` using the sqlcommenter format.
+///
+/// Tagged statements are prepared on each execution instead of being cached,
+/// since their text changes with the comment.
+pub fn with_sql_comment<T>(comment: SqlComment, f: impl FnOnce() -> T) -> T {
+    /// Restores the previous comment, even when `f` panics
+    struct Restore(Option<SqlComment>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SQL_COMMENT.with(|it| *it.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(SQL_COMMENT.with(|it| it.replace(Some(comment))));
+    f()
+}
+
+/// Returns `query` tagged with the comment of the current thread, if any.
+pub(crate) fn tagged(query: &str) -> Option<String> {
+    SQL_COMMENT.with(|it| {
+        it.borrow()
+            .as_ref()
+            .and_then(|comment| comment.append_to(query))
+    })
+}
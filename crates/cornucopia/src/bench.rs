@@ -0,0 +1,145 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use postgres::{Client, SimpleQueryMessage};
+
+use crate::{
+    fixtures::{execute_sql, Fixtures},
+    prepare_queries::Preparation,
+};
+
+use self::error::Error;
+
+/// Latency and row count statistics of a benchmarked query.
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    /// Benchmarked query, as `module::query`
+    pub query: String,
+    pub iterations: usize,
+    /// Smallest and largest number of rows returned or affected by an iteration
+    pub rows: (u64, u64),
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+}
+
+impl Display for BenchReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.;
+        writeln!(f, "{} ({} iterations)", self.query, self.iterations)?;
+        match self.rows {
+            (min, max) if min == max => writeln!(f, "  rows: {min}")?,
+            (min, max) => writeln!(f, "  rows: {min}-{max}")?,
+        }
+        writeln!(f, "  p50:  {:.3} ms", ms(self.p50))?;
+        writeln!(f, "  p95:  {:.3} ms", ms(self.p95))?;
+        write!(f, "  p99:  {:.3} ms", ms(self.p99))
+    }
+}
+
+/// Executes `query` (as `module::query`) `iterations` times with its `fixtures` parameter
+/// values, each iteration inside a savepoint that is rolled back afterward.
+pub(crate) fn bench(
+    client: &mut Client,
+    preparation: &Preparation,
+    fixtures: Option<&Fixtures>,
+    query: &str,
+    iterations: usize,
+) -> Result<BenchReport, crate::Error> {
+    let Some((module, prepared)) = preparation.find_query(query) else {
+        return Err(Error::UnknownQuery {
+            query: query.to_string(),
+        }
+        .into());
+    };
+    let args = match fixtures {
+        Some(fixtures) => fixtures.arguments(module, prepared)?,
+        None if prepared.param.is_some() => {
+            return Err(Error::MissingFixtures {
+                query: query.to_string(),
+            }
+            .into())
+        }
+        None => Vec::new(),
+    };
+
+    let execute_err = |err| Error::Execute {
+        query: query.to_string(),
+        err,
+    };
+    let mut transaction = client.transaction().map_err(execute_err)?;
+    module
+        .set_local_search_path(&mut transaction)
+        .map_err(execute_err)?;
+    transaction
+        .batch_execute(&format!("PREPARE cornucopia_bench AS {}", prepared.sql))
+        .map_err(execute_err)?;
+    let execute = execute_sql("cornucopia_bench", &args);
+    // At least one iteration is needed to compute percentiles
+    let iterations = iterations.max(1);
+    let mut latencies = Vec::with_capacity(iterations);
+    let mut rows = (u64::MAX, 0);
+    let mut run = || -> Result<(), postgres::Error> {
+        for _ in 0..iterations {
+            // Each iteration is rolled back, so that writes don't conflict with each other
+            let mut iteration = transaction.transaction()?;
+            let start = Instant::now();
+            let messages = iteration.simple_query(&execute)?;
+            latencies.push(start.elapsed());
+            iteration.rollback()?;
+            for message in messages {
+                if let SimpleQueryMessage::CommandComplete(count) = message {
+                    rows = (rows.0.min(count), rows.1.max(count));
+                }
+            }
+        }
+        Ok(())
+    };
+    let result = run().and_then(|()| transaction.rollback());
+    // Prepared statements outlive rolled back transactions, even failed ones
+    client
+        .batch_execute("DEALLOCATE cornucopia_bench")
+        .map_err(execute_err)?;
+    result.map_err(execute_err)?;
+
+    latencies.sort_unstable();
+    // Nearest-rank percentile
+    let percentile = |p: usize| latencies[(p * latencies.len()).div_ceil(100).max(1) - 1];
+    Ok(BenchReport {
+        query: query.to_string(),
+        iterations,
+        rows,
+        p50: percentile(50),
+        p95: percentile(95),
+        p99: percentile(99),
+    })
+}
+
+pub(crate) mod error {
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("No query named `{query}`")]
+        #[diagnostic(
+            code(cornucopia::bench::unknown_query),
+            help("queries are named `module::query`")
+        )]
+        UnknownQuery { query: String },
+        #[error("Query `{query}` has parameters but no fixtures were given")]
+        #[diagnostic(
+            code(cornucopia::bench::missing_fixtures),
+            help("provide its parameter values with `--fixtures`")
+        )]
+        MissingFixtures { query: String },
+        #[error("Couldn't run query `{query}`: {err:#}")]
+        #[diagnostic(
+            code(cornucopia::bench::execute),
+            help("check that the fixture values match the types of the query's parameters")
+        )]
+        Execute { query: String, err: postgres::Error },
+    }
+}
@@ -0,0 +1,468 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    conn,
+    container::{self, ClusterSettings, ManagedContainer},
+    error::Error,
+    generate_live, generate_managed_as,
+    manifest::crate_name,
+    CodegenSettings, Config,
+};
+
+use self::error::Error as BuilderError;
+
+/// Where queries are prepared during generation.
+enum Database {
+    /// A live database managed by you
+    Live(String),
+    /// A container managed by cornucopia, with `podman` instead of `docker` if set
+    Managed {
+        podman: bool,
+        app_url: Option<String>,
+    },
+}
+
+/// Builder of a code generation, designed to be run from a `build.rs` script.
+///
+/// ```no_run
+/// // build.rs
+/// fn main() -> Result<(), cornucopia::Error> {
+///     cornucopia::generate()
+///         .migrations_path("migrations")
+///         .destination("src/cornucopia.rs")
+///         .run()?;
+///     Ok(())
+/// }
+/// ```
+pub struct Generator {
+    queries_path: PathBuf,
+    schema_files: Vec<PathBuf>,
+    migrations_path: Option<PathBuf>,
+    destination: PathBuf,
+    database: Database,
+    settings: CodegenSettings,
+    rerun_if_changed: bool,
+    keep_on_failure: bool,
+    cluster_settings: ClusterSettings,
+}
+
+/// Starts building a code generation with the same defaults as the CLI: asynchronous
+/// code is generated from the queries of `queries/` into `src/cornucopia.rs`, using a
+/// container managed by cornucopia.
+pub fn generate() -> Generator {
+    Generator {
+        queries_path: PathBuf::from("queries/"),
+        schema_files: Vec::new(),
+        migrations_path: None,
+        destination: PathBuf::from("src/cornucopia.rs"),
+        database: Database::Managed {
+            podman: false,
+            app_url: None,
+        },
+        settings: CodegenSettings::default(),
+        rerun_if_changed: true,
+        keep_on_failure: false,
+        cluster_settings: ClusterSettings::default(),
+    }
+}
+
+impl Generator {
+    /// Folder containing the queries.
+    pub fn queries_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.queries_path = path.into();
+        self
+    }
+
+    /// Adds a SQL file to the database schema, loaded into the managed container after
+    /// the previously added ones.
+    pub fn schema_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.schema_files.push(path.into());
+        self
+    }
+
+    /// Folder of migrations loaded into the managed container after the schema files.
+    /// Every `.sql` file found in it is loaded in version order, followed by flyway's
+    /// repeatable `R__` migrations, except the scripts reverting migrations, such as
+    /// Diesel's `down.sql`, sqlx's `.down.sql` and flyway's undo scripts. A sqitch project
+    /// loads its deploy scripts in the order of its `sqitch.plan` instead.
+    pub fn migrations_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.migrations_path = Some(path.into());
+        self
+    }
+
+    /// Destination file of the generated code.
+    pub fn destination(mut self, path: impl Into<PathBuf>) -> Self {
+        self.destination = path.into();
+        self
+    }
+
+    /// Writes the generated code into the `mod.rs` of the `dir` folder, with the `types`
+    /// module and each query module in their own file, instead of a single destination file.
+    pub fn destination_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.destination = dir.into().join("mod.rs");
+        self.settings.split_modules = true;
+        self
+    }
+
+    /// Writes the generated code as a publishable crate in the `dir` folder: a `src/lib.rs`
+    /// with the `types` module and each query module in their own file, and a `Cargo.toml`
+    /// depending on exactly the crates and features the code uses. The crate is named after
+    /// the folder.
+    pub fn destination_crate(mut self, dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        self.destination = dir.join("src").join("lib.rs");
+        self.settings.split_modules = true;
+        self.settings.crate_name = Some(crate_name(&dir));
+        self
+    }
+
+    /// Inherits the dependencies of the crate written by [`Self::destination_crate`] from
+    /// the `[workspace.dependencies]` of its workspace, which then sets their versions.
+    pub fn workspace_dependencies(mut self, workspace_dependencies: bool) -> Self {
+        self.settings.workspace_dependencies = workspace_dependencies;
+        self
+    }
+
+    /// Generate synchronous code. Both flavours are generated if asynchronous code is
+    /// also requested.
+    pub fn sync(mut self, gen_sync: bool) -> Self {
+        self.settings.gen_sync = gen_sync;
+        self
+    }
+
+    /// Generate asynchronous code, the default.
+    pub fn r#async(mut self, gen_async: bool) -> Self {
+        self.settings.gen_async = gen_async;
+        self
+    }
+
+    /// Replaces every code generation setting.
+    pub fn settings(mut self, settings: CodegenSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Applies a configuration, typically read from a `cornucopia.toml` file shared with the
+    /// CLI. Its code generation settings replace the current ones, and its schema files are
+    /// added to the previously added ones.
+    ///
+    /// ```no_run
+    /// // build.rs
+    /// fn main() -> Result<(), cornucopia::Error> {
+    ///     let config = cornucopia::Config::load("cornucopia.toml")?;
+    ///     cornucopia::generate().config(config).run()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn config(mut self, config: Config) -> Self {
+        self.settings = config.settings();
+        self.cluster_settings = config.cluster_settings();
+        if config.destination.is_some()
+            || config.destination_dir.is_some()
+            || config.destination_crate.is_some()
+        {
+            self.destination = config.destination();
+        }
+        if let Some(path) = config.queries_path {
+            self.queries_path = path;
+        }
+        self.schema_files.extend(config.schema_files);
+        if let (Database::Managed { podman, .. }, Some(config_podman)) =
+            (&mut self.database, config.podman)
+        {
+            *podman = config_podman;
+        }
+        self
+    }
+
+    /// Prepares the queries against your own database at `url` rather than a container
+    /// managed by cornucopia. Schema files and migrations are then ignored.
+    pub fn live(mut self, url: impl Into<String>) -> Self {
+        self.database = Database::Live(url.into());
+        self
+    }
+
+    /// Prepares the queries in a container managed by cornucopia, the default. The
+    /// container is run with `podman` instead of `docker` if set, and queries are prepared
+    /// through `app_url`, typically as your application's role, if some is given.
+    pub fn managed(mut self, podman: bool, app_url: Option<String>) -> Self {
+        self.database = Database::Managed { podman, app_url };
+        self
+    }
+
+    /// Print the `cargo:rerun-if-changed` lines rerunning the build script when a query,
+    /// schema file or migration changes, the default.
+    pub fn rerun_if_changed(mut self, rerun_if_changed: bool) -> Self {
+        self.rerun_if_changed = rerun_if_changed;
+        self
+    }
+
+    /// Keep the managed container running when the generation fails, so that it can be
+    /// debugged with `psql`.
+    pub fn keep_on_failure(mut self, keep_on_failure: bool) -> Self {
+        self.keep_on_failure = keep_on_failure;
+        self
+    }
+
+    /// Initializes the managed container's database cluster with `settings`, such as the
+    /// locale of production.
+    pub fn cluster_settings(mut self, settings: ClusterSettings) -> Self {
+        self.cluster_settings = settings;
+        self
+    }
+
+    /// Generates the code and writes it to its destination, returning it.
+    pub fn run(self) -> Result<String, Error> {
+        if self.rerun_if_changed {
+            println!("cargo:rerun-if-changed={}", self.queries_path.display());
+            for path in self.schema_files.iter().chain(&self.migrations_path) {
+                println!("cargo:rerun-if-changed={}", path.display());
+            }
+        }
+        let mut schema_files = self.schema_files;
+        if let Some(migrations_path) = &self.migrations_path {
+            schema_files.extend(migration_files(migrations_path)?);
+        }
+        match self.database {
+            Database::Live(url) => {
+                let mut client = conn::from_url(&url)?;
+                generate_live(
+                    &mut client,
+                    self.queries_path.as_path(),
+                    Some(self.destination.as_path()),
+                    self.settings,
+                )
+            }
+            Database::Managed { podman, app_url } => generate_managed_as(
+                self.queries_path.as_path(),
+                &schema_files
+                    .iter()
+                    .map(PathBuf::as_path)
+                    .collect::<Vec<_>>(),
+                Some(self.destination.as_path()),
+                &ManagedContainer {
+                    podman,
+                    cluster: self.cluster_settings,
+                },
+                app_url.as_deref(),
+                self.settings,
+            )
+            .map_err(|err| container::cleanup_after_failure(podman, self.keep_on_failure, err)),
+        }
+    }
+}
+
+/// Lists the SQL files of a migrations folder, ordered by version, or the deploy scripts
+/// of a sqitch project.
+pub(crate) fn migration_files(migrations_path: &Path) -> Result<Vec<PathBuf>, BuilderError> {
+    if migrations_path.join("sqitch.conf").is_file()
+        || migrations_path.join("sqitch.plan").is_file()
+    {
+        return sqitch_files(migrations_path);
+    }
+    let mut files = Vec::new();
+    let mut pending = vec![migrations_path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = std::fs::read_dir(&dir).map_err(|err| BuilderError {
+            path: dir.clone(),
+            err,
+        })?;
+        for entry in entries {
+            let path = entry
+                .map_err(|err| BuilderError {
+                    path: dir.clone(),
+                    err,
+                })?
+                .path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "sql") && !is_revert(&path) {
+                files.push(path);
+            }
+        }
+    }
+    // Flyway's repeatable migrations run after the versioned ones
+    files.sort_by_cached_key(|path| {
+        let name = migration_name(migrations_path, path).unwrap_or_default();
+        (
+            name.starts_with("R__"),
+            migration_version(&name),
+            path.clone(),
+        )
+    });
+    Ok(files)
+}
+
+/// Deploy scripts of the changes of a sqitch project, in the order of its plan. The
+/// earlier occurrences of a reworked change deploy the script named after the tag
+/// following them, such as `deploy/change@v1.0.sql`, and the last one `deploy/change.sql`.
+///
+/// The plan and deploy folder are found in the project's `top_dir`, unless `sqitch.conf`
+/// or the `%top_dir` and `%deploy_dir` pragmas of the plan point elsewhere.
+fn sqitch_files(project_path: &Path) -> Result<Vec<PathBuf>, BuilderError> {
+    let read = |path: &Path| {
+        std::fs::read_to_string(path).map_err(|err| BuilderError {
+            path: path.to_path_buf(),
+            err,
+        })
+    };
+    let conf_path = project_path.join("sqitch.conf");
+    let conf = if conf_path.is_file() {
+        sqitch_conf(&read(&conf_path)?)
+    } else {
+        Vec::new()
+    };
+    let conf_value = |key: &str| {
+        conf.iter()
+            .find(|(section, it, _)| section == "engine \"pg\"" && it == key)
+            .or_else(|| {
+                conf.iter()
+                    .find(|(section, it, _)| section == "core" && it == key)
+            })
+            .map(|(_, _, value)| project_path.join(value))
+    };
+    let top_dir = conf_value("top_dir");
+    let plan_path = conf_value("plan_file").unwrap_or_else(|| {
+        top_dir
+            .as_deref()
+            .unwrap_or(project_path)
+            .join("sqitch.plan")
+    });
+    let plan = read(&plan_path)?;
+
+    // The pragmas of the plan apply unless `sqitch.conf` overrides them
+    let pragma = |key: &str| {
+        plan.lines().find_map(|line| {
+            let (name, value) = line.trim().strip_prefix('%')?.split_once('=')?;
+            (name.trim() == key).then(|| project_path.join(value.trim()))
+        })
+    };
+    let top_dir = top_dir
+        .or_else(|| pragma("top_dir"))
+        .unwrap_or_else(|| project_path.to_path_buf());
+    let deploy_dir = conf_value("deploy_dir")
+        .or_else(|| pragma("deploy_dir"))
+        .unwrap_or_else(|| top_dir.join("deploy"));
+
+    // Changes and tags, in the order of the plan
+    let mut steps: Vec<(bool, &str)> = Vec::new();
+    for line in plan.lines().map(str::trim) {
+        // Pragmas and comments don't declare changes
+        if line.is_empty() || line.starts_with(['%', '#']) {
+            continue;
+        }
+        let name = line.split_whitespace().next().unwrap_or(line);
+        match name.strip_prefix('@') {
+            Some(tag) => steps.push((true, tag)),
+            None => steps.push((false, name)),
+        }
+    }
+    let mut files = Vec::new();
+    for (i, &(is_tag, change)) in steps.iter().enumerate() {
+        if is_tag {
+            continue;
+        }
+        let later = &steps[i + 1..];
+        let rework = later.iter().position(|&step| step == (false, change));
+        let file = match rework {
+            None => deploy_dir.join(format!("{change}.sql")),
+            Some(rework) => {
+                // Sqitch uses the first of the tags preceding the rework with a script
+                let tagged: Vec<PathBuf> = later[..rework]
+                    .iter()
+                    .filter(|(is_tag, _)| *is_tag)
+                    .map(|(_, tag)| deploy_dir.join(format!("{change}@{tag}.sql")))
+                    .collect();
+                tagged
+                    .iter()
+                    .find(|path| path.is_file())
+                    .or(tagged.first())
+                    .cloned()
+                    .unwrap_or_else(|| deploy_dir.join(format!("{change}.sql")))
+            }
+        };
+        files.push(file);
+    }
+    Ok(files)
+}
+
+/// `(section, key, value)` entries of a `sqitch.conf` file, written in git's config format.
+fn sqitch_conf(conf: &str) -> Vec<(String, String, String)> {
+    let mut entries = Vec::new();
+    let mut section = String::new();
+    for line in conf.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|it| it.strip_suffix(']')) {
+            section = name.trim().to_string();
+        } else if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim().trim_matches('"');
+            entries.push((section.clone(), key.trim().to_string(), value.to_string()));
+        }
+    }
+    entries
+}
+
+/// Whether `path` reverts a migration rather than applying it, such as the `down.sql` of
+/// Diesel, the `.down.sql` of sqlx's reversible migrations or flyway's `U1__init.sql` undo.
+fn is_revert(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let is_undo = name
+        .strip_prefix('U')
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()) && rest.contains("__"));
+    name == "down.sql" || name.ends_with(".down.sql") || is_undo
+}
+
+/// Name of the file or folder of the migrations folder holding the migration at `path`.
+fn migration_name(migrations_path: &Path, path: &Path) -> Option<String> {
+    let name = path
+        .strip_prefix(migrations_path)
+        .ok()?
+        .components()
+        .next()?
+        .as_os_str()
+        .to_str()?;
+    Some(name.to_string())
+}
+
+/// Version leading the name of a migration, such as `[1, 2]` for flyway's `V1.2__init.sql`
+/// or `[20230101]` for sqlx's `20230101_init.sql`.
+fn migration_version(name: &str) -> Option<Vec<u64>> {
+    let name = name.strip_prefix('V').unwrap_or(name);
+    let mut version = Vec::new();
+    let mut rest = name;
+    loop {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if digits == 0 {
+            break;
+        }
+        version.push(rest[..digits].parse().ok()?);
+        rest = &rest[digits..];
+        // Flyway separates the parts of versions by `.` or `_`, and the description by `__`
+        match rest.strip_prefix(['.', '_']) {
+            Some(next) if !rest.starts_with("__") => rest = next,
+            _ => break,
+        }
+    }
+    (!version.is_empty()).then_some(version)
+}
+
+pub(crate) mod error {
+    use std::path::PathBuf;
+
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    #[error("Couldn't read migrations folder `{path}`: ({err})")]
+    #[diagnostic(
+        code(cornucopia::builder::migrations),
+        help("check the path given to `migrations_path`, relative to the crate's root")
+    )]
+    pub struct Error {
+        pub(crate) path: PathBuf,
+        pub(crate) err: std::io::Error,
+    }
+}
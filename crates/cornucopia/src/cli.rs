@@ -1,22 +1,59 @@
-use std::path::PathBuf;
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
 
-use crate::{conn, container, error::Error, generate_live, generate_managed, CodegenSettings};
+use crate::{
+    bench_live, bench_managed, check_generated_live, check_generated_managed, check_live,
+    check_managed_as, check_managed_versions,
+    compat::error::Error as CompatError,
+    compat_report,
+    config::{error::Error as ConfigError, Config, CONFIG_FILE},
+    conn,
+    container::{self, ManagedContainer},
+    db_shell, diff_types, dump_types_live, dump_types_managed,
+    error::{Error, WriteStdoutError},
+    export_live, export_managed, export_sql_live, export_sql_managed, generate_live,
+    generate_managed_as,
+    import::import,
+    is_valid_statement_prefix, new_query, schema_diagram_live, schema_diagram_managed,
+    type_dump::error::Error as TypeDumpError,
+    watch::{stop_signal, watch_live, watch_managed},
+    DecimalCrate, DiagramFormat, ExportFormat, ParamSyntax, TimeCrate,
+};
 
 /// Command line interface to interact with Cornucopia SQL.
 #[derive(Parser, Debug)]
 #[clap(version)]
 struct Args {
+    /// Configuration file providing defaults for these flags [default: cornucopia.toml, if it exists]
+    #[clap(long)]
+    config: Option<PathBuf>,
+    /// Profile of the configuration file overriding its other keys, such as `ci` for `[profile.ci]`
+    #[clap(long)]
+    profile: Option<String>,
     /// Use `podman` instead of `docker`
     #[clap(short, long)]
     podman: bool,
-    /// Folder containing the queries
-    #[clap(short, long, default_value = "queries/")]
-    queries_path: PathBuf,
-    /// Destination folder for generated modules
-    #[clap(short, long, default_value = "src/cornucopia.rs")]
-    destination: PathBuf,
+    /// Folder containing the queries [default: queries/]
+    #[clap(short, long)]
+    queries_path: Option<PathBuf>,
+    /// Destination folder for generated modules [default: src/cornucopia.rs]
+    #[clap(short, long)]
+    destination: Option<PathBuf>,
+    /// Destination folder of a `mod.rs`, written with `types.rs` and one file per query module
+    #[clap(long, conflicts_with = "destination")]
+    destination_dir: Option<PathBuf>,
+    /// Destination folder of a publishable crate, written with its `Cargo.toml`, a
+    /// `src/lib.rs` and one file per module
+    #[clap(long, conflicts_with_all = ["destination", "destination_dir"])]
+    destination_crate: Option<PathBuf>,
+    /// Inherit the dependencies of the destination crate from its workspace
+    #[clap(long, requires = "destination_crate")]
+    workspace_dependencies: bool,
     #[clap(subcommand)]
     action: Action,
     /// Generate synchronous rust code
@@ -25,21 +62,277 @@ struct Args {
     /// Generate asynchronous rust code
     #[clap(long)]
     r#async: bool,
-    /// Derive serde's `Serialize` trait for generated types.
+    /// Derive serde's `Serialize` and `Deserialize` traits for generated rows and types.
     #[clap(long)]
     serialize: bool,
+    /// Generate a deadpool `create_pool` helper (async only)
+    #[clap(long)]
+    pool: bool,
+    /// Tag prepared statements with this prefix so they can be attributed to your application
+    #[clap(long, value_parser = parse_statement_prefix)]
+    statement_prefix: Option<String>,
+    /// Syntax used to write bind parameters in your queries [default: colon]
+    #[clap(long, value_enum)]
+    param_syntax: Option<ParamSyntax>,
+    /// Generate an ignored smoke test per query, run by `cargo test -- --ignored` against `DATABASE_URL` inside a rolled-back transaction
+    #[clap(long)]
+    tests: bool,
+    /// Generate a registry of every query (requires the client's `introspection` feature)
+    #[clap(long)]
+    registry: bool,
+    /// Generate variants of queries on partitioned tables that target a single partition
+    #[clap(long)]
+    partitions: bool,
+    /// Strip comments and collapse whitespace in the SQL embedded in generated code
+    #[clap(long)]
+    normalize_sql: bool,
+    /// Write the SQL of each statement into sidecar files in this directory, relative to the destination
+    #[clap(long)]
+    external_sql: Option<String>,
+    /// Resolve statements from a compressed table in release builds (requires the client's `obfuscated-sql` feature)
+    #[clap(long)]
+    obfuscate_sql: bool,
+    /// Generate read-your-writes helpers for reads routed to replicas (requires the client's `replicas` feature)
+    #[clap(long)]
+    replicas: bool,
+    /// Generate `to_map`/`from_map` conversions between rows or params and JSON value maps
+    #[clap(long)]
+    maps: bool,
+    /// Generate CSV export helpers for rows, whose columns must all be scalars (requires the client's `csv` feature)
+    #[clap(long)]
+    csv: bool,
+    /// Generate Arrow record batch conversions for rows, whose columns must all be primitives (requires the client's `arrow` feature)
+    #[clap(long)]
+    arrow: bool,
+    /// Generate sqlx `Type`, `Encode` and `Decode` impls for custom types (requires the client's `sqlx` feature)
+    #[clap(long)]
+    sqlx: bool,
+    /// Generate Diesel SQL types and `ToSql`/`FromSql` impls for custom enums (requires the client's `diesel` feature)
+    #[clap(long)]
+    diesel: bool,
+    /// Sample the plans of read queries for a hook (requires the client's `explain` feature)
+    #[clap(long)]
+    explain: bool,
+    /// Generate a cache per query module holding its prepared statements, to reuse them across calls
+    #[clap(long)]
+    statement_cache: bool,
+    /// Generate a `Queries` struct per query module, binding each of its queries to a client
+    #[clap(long)]
+    facade: bool,
+    /// Generate the version of the schema queries were generated against, as an OpenMetrics gauge
+    #[clap(long)]
+    schema_info: bool,
+    /// Generate criterion benchmarks decoding each non-Copy row and composite type (requires a dependency on `criterion`)
+    #[clap(long)]
+    benches: bool,
+    /// Infer the nullability of columns selected straight from a table from their `NOT NULL` constraint
+    #[clap(long)]
+    infer_nullability: bool,
+    /// Skip the params structs of queries and their impls, leaving the query functions' `bind`
+    #[clap(long)]
+    no_params_structs: bool,
+    /// Only generate the params structs of queries with more parameters than this threshold
+    #[clap(long)]
+    params_struct_threshold: Option<usize>,
+    /// Generate read-through cached variants of query structs (requires the client's `cache` feature)
+    #[clap(long)]
+    cache: bool,
+    /// Generate functions checking values against the numeric `CHECK` constraints of domains
+    #[clap(long)]
+    domain_checks: bool,
+    /// Generate a `validate` method on params structs checking the length of text written to `varchar(n)` columns
+    #[clap(long)]
+    text_limits: bool,
+    /// Template schema of a schema-per-tenant database, whose queries get a constructor taking a tenant's schema
+    #[clap(long)]
+    tenant_schema: Option<String>,
+    /// Comma-separated schemas the queries of modules without a `--@ schema` annotation are prepared with
+    #[clap(long, value_delimiter = ',')]
+    search_path: Option<Vec<String>>,
+    /// Comma-separated traits every row struct derives, such as `Hash,Eq`
+    #[clap(long, value_delimiter = ',')]
+    row_derives: Option<Vec<String>>,
+    /// Crate the date and time types are mapped to [default: time]
+    #[clap(long, value_enum)]
+    time_crate: Option<TimeCrate>,
+    /// Crate the `numeric` type is mapped to [default: rust_decimal]
+    #[clap(long, value_enum)]
+    decimal_crate: Option<DecimalCrate>,
+    /// Comma-separated lints allowed at the top of the generated file, replacing the default per-module allows
+    #[clap(long, value_delimiter = ',')]
+    lint_allows: Option<Vec<String>>,
+    /// Forbid unsafe code at the top of the generated file, which then can't be `include!`d
+    #[clap(long)]
+    forbid_unsafe: bool,
+    /// Keep the managed container running when a command fails, to debug it with `psql`
+    #[clap(long)]
+    keep_on_failure: bool,
+    /// Report breaking changes of the generated API compared to this previously generated file
+    #[clap(long)]
+    compat_report: Option<PathBuf>,
 }
 
 #[derive(Debug, Subcommand)]
 enum Action {
     /// Generate your modules against your own db
     Live {
-        /// Postgres url to the database
-        url: String,
+        /// Postgres url to the database, preferably as your application's role [default: the configuration's `url`]
+        url: Option<String>,
+        /// Regenerate your modules every time a query file changes
+        #[clap(long)]
+        watch: bool,
     },
     /// Generate your modules against schema files
+    #[clap(args_conflicts_with_subcommands = true)]
     Schema {
-        /// SQL files containing the database schema
+        #[clap(subcommand)]
+        action: Option<SchemaAction>,
+        /// SQL files containing the database schema, loaded as the container's superuser. Folders of migrations, such as flyway's or a sqitch project, are loaded in deploy order
+        schema_files: Vec<PathBuf>,
+        /// Postgres url to the managed container used to prepare queries as your application's role
+        #[clap(long)]
+        app_url: Option<String>,
+        /// Regenerate your modules every time a query or schema file changes, keeping the container alive
+        #[clap(long)]
+        watch: bool,
+    },
+    /// Check your queries for errors without generating any code
+    Check {
+        /// Postgres url to your own database. A managed container is used otherwise
+        #[clap(long)]
+        url: Option<String>,
+        /// Parameter fixtures to check and run inside a rolled-back transaction
+        #[clap(long)]
+        fixtures: Option<PathBuf>,
+        /// Postgres url to the managed container used to check queries as your application's role
+        #[clap(long)]
+        app_url: Option<String>,
+        /// Comma-separated PostgreSQL versions to check against, each in its own managed container
+        #[clap(long, value_delimiter = ',', conflicts_with = "url")]
+        pg_versions: Vec<String>,
+        /// Also fail if the code written at the destination differs from the code generated from the queries
+        #[clap(long)]
+        generated: bool,
+        /// SQL files containing the database schema (managed container only)
+        schema_files: Vec<PathBuf>,
+    },
+    /// Benchmark a query, reporting its latency percentiles
+    Bench {
+        /// Query to benchmark, as `module::query`
+        query: String,
+        /// Number of times the query is executed
+        #[clap(long, default_value_t = 100)]
+        iterations: usize,
+        /// Parameter fixtures providing the query's parameter values
+        #[clap(long)]
+        fixtures: Option<PathBuf>,
+        /// Postgres url to your own database. A managed container is used otherwise
+        #[clap(long)]
+        url: Option<String>,
+        /// SQL files containing the database schema (managed container only)
+        schema_files: Vec<PathBuf>,
+    },
+    /// Export the rows of a query to a file
+    Export {
+        /// Query to export, as `module::query`
+        #[clap(long)]
+        query: String,
+        /// File format of the export
+        #[clap(long, value_enum, default_value_t)]
+        format: ExportFormat,
+        /// Destination of the exported file
+        #[clap(long)]
+        out: PathBuf,
+        /// Parameter fixtures providing the query's parameter values
+        #[clap(long)]
+        fixtures: Option<PathBuf>,
+        /// Postgres url to your own database. A managed container is used otherwise
+        #[clap(long)]
+        url: Option<String>,
+        /// SQL files containing the database schema (managed container only)
+        schema_files: Vec<PathBuf>,
+    },
+    /// Export every query into a single SQL file for review
+    ExportSql {
+        /// Destination of the SQL file
+        out: PathBuf,
+        /// Postgres url to your own database. A managed container is used otherwise
+        #[clap(long)]
+        url: Option<String>,
+        /// SQL files containing the database schema (managed container only)
+        schema_files: Vec<PathBuf>,
+    },
+    /// Inspect the custom types used by your queries and their Rust mapping
+    Types {
+        #[clap(subcommand)]
+        action: TypesAction,
+    },
+    /// Work with the database managed by cornucopia
+    Db {
+        #[clap(subcommand)]
+        action: DbAction,
+    },
+    /// Interactively add an annotated query stub to your query files
+    NewQuery,
+    /// Import a folder of plain SQL files (one statement each) into an annotated query file
+    Import {
+        /// Folder containing the plain SQL files
+        dir: PathBuf,
+        /// Name of the query file to import into. Defaults to the folder's name
+        #[clap(long)]
+        module: Option<String>,
+    },
+    /// Print a shell completion script to stdout
+    Completions {
+        /// Shell to generate the completion script for
+        shell: Shell,
+    },
+    /// Print the man page to stdout
+    Man,
+}
+
+#[derive(Debug, Subcommand)]
+enum TypesAction {
+    /// Print every custom type with its schema and Rust mapping
+    Dump {
+        /// Postgres url to your own database. A managed container is used otherwise
+        #[clap(long)]
+        url: Option<String>,
+        /// SQL files containing the database schema (managed container only)
+        schema_files: Vec<PathBuf>,
+    },
+    /// Print the custom types that changed since a previous dump
+    Diff {
+        /// File containing the output of a previous `types dump`
+        previous: PathBuf,
+        /// Postgres url to your own database. A managed container is used otherwise
+        #[clap(long)]
+        url: Option<String>,
+        /// SQL files containing the database schema (managed container only)
+        schema_files: Vec<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum SchemaAction {
+    /// Print an entity-relationship diagram of the tables and their foreign keys
+    Diagram {
+        /// Output format of the diagram
+        #[clap(long, value_enum, default_value_t)]
+        format: DiagramFormat,
+        /// Postgres url to your own database. A managed container is used otherwise
+        #[clap(long)]
+        url: Option<String>,
+        /// SQL files containing the database schema (managed container only)
+        schema_files: Vec<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum DbAction {
+    /// Open a psql session in the managed container, with the same schema as during generation
+    Shell {
+        /// SQL files containing the database schema, loaded as the container's superuser. Folders of migrations, such as flyway's or a sqitch project, are loaded in deploy order
         schema_files: Vec<PathBuf>,
     },
 }
@@ -47,39 +340,492 @@ enum Action {
 // Main entrypoint of the CLI. Parses the args and calls the appropriate routines.
 pub fn run() -> Result<(), Error> {
     let Args {
+        config: config_path,
+        profile,
         podman,
         queries_path,
         destination,
+        destination_dir,
+        destination_crate,
+        workspace_dependencies,
         action,
         sync,
         r#async,
         serialize,
+        pool,
+        statement_prefix,
+        param_syntax,
+        tests,
+        registry,
+        partitions,
+        normalize_sql,
+        external_sql,
+        obfuscate_sql,
+        replicas,
+        maps,
+        csv,
+        arrow,
+        sqlx,
+        diesel,
+        explain,
+        statement_cache,
+        facade,
+        schema_info,
+        benches,
+        infer_nullability,
+        no_params_structs,
+        params_struct_threshold,
+        cache,
+        domain_checks,
+        text_limits,
+        tenant_schema,
+        search_path,
+        row_derives,
+        time_crate,
+        decimal_crate,
+        lint_allows,
+        forbid_unsafe,
+        keep_on_failure,
+        compat_report: previous_path,
     } = Args::parse();
 
-    let settings = CodegenSettings {
-        gen_async: r#async || !sync,
-        gen_sync: sync,
-        derive_ser: serialize,
+    // Flags take precedence over the configuration file
+    let file_config = match config_path {
+        Some(path) => Config::load_profile(path, profile.as_deref())?,
+        // Selecting a profile requires a configuration file
+        None if Path::new(CONFIG_FILE).is_file() || profile.is_some() => {
+            Config::load_profile(CONFIG_FILE, profile.as_deref())?
+        }
+        None => Config::default(),
     };
+    // Flags can only enable switches, leaving unset ones to the configuration file
+    let flag = |set: bool| set.then_some(true);
+    let config = file_config.merge(Config {
+        podman: flag(podman),
+        queries_path,
+        destination,
+        destination_dir,
+        destination_crate,
+        workspace_dependencies: flag(workspace_dependencies),
+        schema_files: Vec::new(),
+        sync: flag(sync),
+        r#async: flag(r#async),
+        serialize: flag(serialize),
+        pool: flag(pool),
+        statement_prefix,
+        param_syntax,
+        tests: flag(tests),
+        registry: flag(registry),
+        partitions: flag(partitions),
+        normalize_sql: flag(normalize_sql),
+        external_sql,
+        obfuscate_sql: flag(obfuscate_sql),
+        replicas: flag(replicas),
+        maps: flag(maps),
+        csv: flag(csv),
+        arrow: flag(arrow),
+        sqlx: flag(sqlx),
+        diesel: flag(diesel),
+        explain: flag(explain),
+        statement_cache: flag(statement_cache),
+        facade: flag(facade),
+        schema_info: flag(schema_info),
+        benches: flag(benches),
+        infer_nullability: flag(infer_nullability),
+        no_params_structs: flag(no_params_structs),
+        params_struct_threshold,
+        cache: flag(cache),
+        domain_checks: flag(domain_checks),
+        text_limits: flag(text_limits),
+        tenant_schema,
+        search_path,
+        row_derives,
+        time_crate,
+        decimal_crate,
+        // `--lint-allows ""` allows no lint at all
+        lint_allows,
+        forbid_unsafe: flag(forbid_unsafe),
+        url: None,
+        app_url: None,
+        locale: None,
+        encoding: None,
+        timezone: None,
+    });
+    let settings = config.settings();
+    // Deadpool only serves asynchronous clients
+    if settings.gen_pool && !settings.gen_async {
+        return Err(ConfigError::PoolWithoutAsync.into());
+    }
+    let (podman, queries_path, destination) = (
+        config.podman.unwrap_or_default(),
+        config.queries_path(),
+        config.destination(),
+    );
+    let managed = ManagedContainer {
+        podman,
+        cluster: config.cluster_settings(),
+    };
+    // Schema files given to a command replace those of the configuration
+    let schema_files_or_config = |schema_files: Vec<PathBuf>| {
+        if schema_files.is_empty() {
+            config.schema_files.clone()
+        } else {
+            schema_files
+        }
+    };
+    // So do urls
+    let url_or_config = |url: Option<String>| url.or_else(|| config.url.clone());
+    let app_url_or_config = |app_url: Option<String>| app_url.or_else(|| config.app_url.clone());
+
+    // Read the previous generation before it gets overwritten
+    let previous_code = previous_path
+        .map(|path| std::fs::read_to_string(&path).map_err(|err| CompatError::Read { path, err }))
+        .transpose()?;
 
     match action {
-        Action::Live { url } => {
+        Action::Live { url, watch: true } => {
+            let url = url_or_config(url).ok_or(ConfigError::MissingUrl)?;
+            let mut client = conn::from_url(&url)?;
+            watch_live(
+                &mut client,
+                &queries_path,
+                &destination,
+                &settings,
+                previous_code.as_deref(),
+                &*stop_signal()?,
+            )?;
+        }
+        Action::Live { url, watch: false } => {
+            let url = url_or_config(url).ok_or(ConfigError::MissingUrl)?;
             let mut client = conn::from_url(&url)?;
-            generate_live(&mut client, &queries_path, Some(&destination), settings)?;
+            let code = generate_live(&mut client, &queries_path, Some(&destination), settings)?;
+            if let Some(previous_code) = previous_code {
+                compat_report(&previous_code, &code)?;
+            }
+        }
+        Action::Schema {
+            action:
+                Some(SchemaAction::Diagram {
+                    format,
+                    url,
+                    schema_files,
+                }),
+            ..
+        } => {
+            let diagram = if let Some(url) = url_or_config(url) {
+                let mut client = conn::from_url(&url)?;
+                schema_diagram_live(&mut client, format)?
+            } else {
+                match schema_diagram_managed(
+                    &schema_files_or_config(schema_files),
+                    &managed,
+                    format,
+                ) {
+                    Ok(diagram) => diagram,
+                    Err(e) => {
+                        return Err(container::cleanup_after_failure(podman, keep_on_failure, e));
+                    }
+                }
+            };
+            print!("{diagram}");
         }
-        Action::Schema { schema_files } => {
+        Action::Schema {
+            action: None,
+            schema_files,
+            app_url,
+            watch: true,
+        } => {
+            let app_url = app_url_or_config(app_url);
+            if let Err(e) = watch_managed(
+                &queries_path,
+                &schema_files_or_config(schema_files),
+                &destination,
+                &managed,
+                app_url.as_deref(),
+                &settings,
+                previous_code.as_deref(),
+            ) {
+                return Err(container::cleanup_after_failure(podman, keep_on_failure, e));
+            }
+        }
+        Action::Schema {
+            action: None,
+            schema_files,
+            app_url,
+            watch: false,
+        } => {
+            let app_url = app_url_or_config(app_url);
             // Run the generate command. If the command is unsuccessful, cleanup Cornucopia's container
-            if let Err(e) = generate_managed(
+            match generate_managed_as(
                 queries_path,
-                &schema_files,
+                &schema_files_or_config(schema_files),
                 Some(destination),
-                podman,
+                &managed,
+                app_url.as_deref(),
                 settings,
             ) {
-                container::cleanup(podman).ok();
-                return Err(e);
+                Ok(code) => {
+                    if let Some(previous_code) = previous_code {
+                        compat_report(&previous_code, &code)?;
+                    }
+                }
+                Err(e) => {
+                    return Err(container::cleanup_after_failure(podman, keep_on_failure, e));
+                }
+            }
+        }
+        Action::Check {
+            url,
+            fixtures,
+            app_url,
+            pg_versions,
+            generated,
+            schema_files,
+        } => {
+            let schema_files = schema_files_or_config(schema_files);
+            let app_url = app_url_or_config(app_url);
+            // Checking against several versions requires managed containers
+            let url = if pg_versions.is_empty() {
+                url_or_config(url)
+            } else {
+                url
+            };
+            if let Some(url) = url {
+                let mut client = conn::from_url(&url)?;
+                check_live(&mut client, &queries_path, fixtures.as_ref(), &settings)?;
+                if generated {
+                    check_generated_live(&mut client, &queries_path, &destination, settings)?;
+                }
+            } else {
+                if !pg_versions.is_empty() {
+                    check_managed_versions(
+                        queries_path.clone(),
+                        &schema_files,
+                        fixtures,
+                        &managed,
+                        app_url.as_deref(),
+                        &settings,
+                        &pg_versions,
+                        keep_on_failure,
+                    )?;
+                } else if let Err(e) = check_managed_as(
+                    queries_path.clone(),
+                    &schema_files,
+                    fixtures,
+                    &managed,
+                    app_url.as_deref(),
+                    &settings,
+                ) {
+                    return Err(container::cleanup_after_failure(podman, keep_on_failure, e));
+                }
+                // Queries are free of errors, so differences can only come from drift
+                if generated {
+                    if let Err(e) = check_generated_managed(
+                        queries_path,
+                        &schema_files,
+                        destination,
+                        &managed,
+                        app_url.as_deref(),
+                        settings,
+                    ) {
+                        return Err(container::cleanup_after_failure(podman, keep_on_failure, e));
+                    }
+                }
+            }
+        }
+        Action::Bench {
+            query,
+            iterations,
+            fixtures,
+            url,
+            schema_files,
+        } => {
+            let report = if let Some(url) = url_or_config(url) {
+                let mut client = conn::from_url(&url)?;
+                bench_live(
+                    &mut client,
+                    &queries_path,
+                    fixtures.as_ref(),
+                    &query,
+                    iterations,
+                    &settings,
+                )?
+            } else {
+                match bench_managed(
+                    queries_path,
+                    &schema_files_or_config(schema_files),
+                    fixtures,
+                    &query,
+                    iterations,
+                    &managed,
+                    &settings,
+                ) {
+                    Ok(report) => report,
+                    Err(e) => {
+                        return Err(container::cleanup_after_failure(podman, keep_on_failure, e));
+                    }
+                }
+            };
+            println!("{report}");
+        }
+        Action::Export {
+            query,
+            format,
+            out,
+            fixtures,
+            url,
+            schema_files,
+        } => {
+            let nb_rows = if let Some(url) = url_or_config(url) {
+                let mut client = conn::from_url(&url)?;
+                export_live(
+                    &mut client,
+                    &queries_path,
+                    fixtures.as_ref(),
+                    &query,
+                    format,
+                    &out,
+                    &settings,
+                )?
+            } else {
+                match export_managed(
+                    queries_path,
+                    &schema_files_or_config(schema_files),
+                    fixtures,
+                    &query,
+                    format,
+                    out.clone(),
+                    &managed,
+                    &settings,
+                ) {
+                    Ok(nb_rows) => nb_rows,
+                    Err(e) => {
+                        return Err(container::cleanup_after_failure(podman, keep_on_failure, e));
+                    }
+                }
+            };
+            println!("Exported {nb_rows} row(s) to `{}`", out.display());
+        }
+        Action::ExportSql {
+            out,
+            url,
+            schema_files,
+        } => {
+            if let Some(url) = url_or_config(url) {
+                let mut client = conn::from_url(&url)?;
+                export_sql_live(&mut client, &queries_path, Some(&out), &settings)?;
+            } else if let Err(e) = export_sql_managed(
+                queries_path,
+                &schema_files_or_config(schema_files),
+                Some(out),
+                &managed,
+                &settings,
+            ) {
+                return Err(container::cleanup_after_failure(podman, keep_on_failure, e));
+            }
+        }
+        Action::Types { action } => {
+            let (previous_path, url, schema_files) = match action {
+                TypesAction::Dump { url, schema_files } => (None, url, schema_files),
+                TypesAction::Diff {
+                    previous,
+                    url,
+                    schema_files,
+                } => (Some(previous), url, schema_files),
+            };
+            // Read the previous dump before starting any container
+            let previous = previous_path
+                .map(|path| {
+                    std::fs::read_to_string(&path).map_err(|err| TypeDumpError { path, err })
+                })
+                .transpose()?;
+            let dump = if let Some(url) = url_or_config(url) {
+                let mut client = conn::from_url(&url)?;
+                dump_types_live(&mut client, &queries_path, &settings)?
+            } else {
+                match dump_types_managed(
+                    queries_path,
+                    &schema_files_or_config(schema_files),
+                    &managed,
+                    &settings,
+                ) {
+                    Ok(dump) => dump,
+                    Err(e) => {
+                        return Err(container::cleanup_after_failure(podman, keep_on_failure, e));
+                    }
+                }
+            };
+            match previous {
+                None => print!("{dump}"),
+                Some(previous) => {
+                    let changes = diff_types(&previous, &dump);
+                    if changes.is_empty() {
+                        println!("No custom type changed");
+                    }
+                    for change in changes {
+                        println!("{change}");
+                    }
+                }
             }
         }
+        Action::Db {
+            action: DbAction::Shell { schema_files },
+        } => {
+            if let Err(e) = db_shell(&schema_files_or_config(schema_files), &managed) {
+                return Err(container::cleanup_after_failure(podman, keep_on_failure, e));
+            }
+        }
+        Action::NewQuery => new_query(
+            &queries_path,
+            &mut std::io::stdin().lock(),
+            &mut std::io::stdout(),
+        )?,
+        Action::Import { dir, module } => {
+            let (destination, skipped) = import(&dir, &queries_path, module.as_deref())?;
+            for path in skipped {
+                eprintln!(
+                    "Skipped `{}`: cornucopia queries must contain a single statement",
+                    path.display()
+                );
+            }
+            println!("Imported queries into `{}`", destination.display());
+        }
+        Action::Completions { shell } => write_completions(shell, &mut std::io::stdout())?,
+        Action::Man => write_man_page(&mut std::io::stdout())?,
     };
     Ok(())
 }
+
+/// Parses a `--statement-prefix`, written in the SQL comment tagging statements.
+fn parse_statement_prefix(prefix: &str) -> Result<String, String> {
+    if is_valid_statement_prefix(prefix) {
+        Ok(prefix.to_string())
+    } else {
+        Err("expected ASCII letters, digits, `_`, `.`, `:` and `-` only".to_string())
+    }
+}
+
+/// Writes the completion script of the CLI for `shell` to `out`.
+pub fn write_completions(shell: Shell, out: &mut impl Write) -> Result<(), Error> {
+    let mut cmd = Args::command();
+    let name = cmd.get_name().to_string();
+    // `generate` panics on write errors, so the script is written to `out` in one go
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut cmd, name, &mut script);
+    out.write_all(&script).map_err(|err| WriteStdoutError {
+        what: "completion script",
+        err,
+    })?;
+    Ok(())
+}
+
+/// Writes the man page of the CLI to `out`.
+pub fn write_man_page(out: &mut impl Write) -> Result<(), Error> {
+    clap_mangen::Man::new(Args::command())
+        .render(out)
+        .map_err(|err| WriteStdoutError {
+            what: "man page",
+            err,
+        })?;
+    Ok(())
+}
@@ -2,13 +2,21 @@ use core::str;
 use std::fmt::{Display, Write};
 
 use codegen_template::code;
+use heck::ToShoutySnakeCase;
 use indexmap::IndexMap;
+use postgres_types::{Kind, Type};
 
 use crate::{
+    decode_benches::{Benchmark, BenchmarkTarget},
+    domain_checks::Domain,
+    lexer::{is_mutating, normalize, schema_references},
+    manifest::manifest,
+    parser::{ChannelAnnotation, ChannelPayload},
     prepare_queries::{
-        Ident, Preparation, PreparedContent, PreparedField, PreparedItem, PreparedModule,
-        PreparedQuery, PreparedType,
+        Ident, PartitionedSql, Preparation, PreparedBatch, PreparedCdcTable, PreparedContent,
+        PreparedField, PreparedItem, PreparedModule, PreparedQuery, PreparedScript, PreparedType,
     },
+    type_registrar::{CornucopiaType, BIG_DECIMAL},
     CodegenSettings,
 };
 
@@ -19,6 +27,20 @@ pub struct GenCtx {
     pub is_async: bool,
     // Should serializable struct
     pub gen_derive: bool,
+    // Should generate map conversions
+    pub gen_maps: bool,
+    // Should generate CSV export helpers
+    pub gen_csv: bool,
+    // Should generate Arrow record batch conversions
+    pub gen_arrow: bool,
+    // Should generate sqlx type bridges for custom types
+    pub gen_sqlx: bool,
+    // Should generate Diesel type bridges for custom enums
+    pub gen_diesel: bool,
+    // Should generate read-through cached queries
+    pub gen_cache: bool,
+    // Depth of the query module being generated
+    pub module_depth: u8,
 }
 
 impl GenCtx {
@@ -27,11 +49,41 @@ impl GenCtx {
             depth,
             is_async,
             gen_derive,
+            gen_maps: false,
+            gen_csv: false,
+            gen_arrow: false,
+            gen_sqlx: false,
+            gen_diesel: false,
+            gen_cache: false,
+            module_depth: 2,
         }
     }
 
+    /// Context generating the conversions and type bridges enabled by `settings`.
+    pub fn with_conversions(mut self, settings: &CodegenSettings) -> Self {
+        self.gen_maps = settings.gen_maps;
+        self.gen_csv = settings.gen_csv;
+        self.gen_arrow = settings.gen_arrow;
+        self.gen_sqlx = settings.gen_sqlx;
+        self.gen_diesel = settings.gen_diesel;
+        self.gen_cache = settings.gen_cache;
+        self
+    }
+
+    /// Context of a query module nested in the modules of `nesting` subdirectories.
+    pub fn nested(mut self, nesting: u8) -> Self {
+        self.depth += nesting;
+        self.module_depth += nesting;
+        self
+    }
+
+    /// Path of an item of the query module being generated.
+    pub fn module_path(&self, name: impl Display) -> String {
+        self.path(self.depth - self.module_depth, name)
+    }
+
     pub fn path(&self, depth: u8, name: impl Display) -> String {
-        let depth = std::iter::repeat("super::").take(depth as usize);
+        let depth = std::iter::repeat_n("super::", depth as usize);
         code!($($depth)$name)
     }
 
@@ -42,6 +94,14 @@ impl GenCtx {
             "cornucopia_sync"
         }
     }
+    /// Bound of the clients copying rows and running batches of statements.
+    pub fn bulk_client(&self) -> &'static str {
+        if self.is_async {
+            "cornucopia_async::GenericClientExt"
+        } else {
+            "GenericClient"
+        }
+    }
 }
 
 impl PreparedField {
@@ -89,6 +149,11 @@ impl PreparedField {
         )
     }
 
+    /// Doc comment of the field, from the comment of the column it is read from.
+    pub fn doc_comment(&self) -> String {
+        doc_comment(self.doc.as_deref())
+    }
+
     pub fn owning_assign(&self) -> String {
         let call = self.owning_call(None);
         if call == self.ident.rs {
@@ -304,7 +369,32 @@ fn composite_fromsql(
     );
 }
 
-fn gen_params_struct(w: &mut impl Write, params: &PreparedItem, ctx: &GenCtx) {
+/// Is a params struct generated for `params`, as they can be skipped entirely or up to a
+/// number of fields. The rows copied in by `--! copy` queries are always params structs.
+fn has_params_struct(
+    params: &PreparedItem,
+    module: &PreparedModule,
+    settings: &CodegenSettings,
+) -> bool {
+    let is_copied = module.queries.values().any(|query| {
+        query.copy.is_some()
+            && query.param.as_ref().is_some_and(|(idx, _)| {
+                module.params.get_index(*idx).unwrap().1.name == params.name
+            })
+    });
+    is_copied
+        || settings.gen_params_structs
+            && settings
+                .params_struct_threshold
+                .is_none_or(|threshold| params.fields.len() > threshold)
+}
+
+fn gen_params_struct(
+    w: &mut impl Write,
+    params: &PreparedItem,
+    ctx: &GenCtx,
+    settings: &CodegenSettings,
+) {
     let PreparedItem {
         name,
         fields,
@@ -316,23 +406,198 @@ fn gen_params_struct(w: &mut impl Write, params: &PreparedItem, ctx: &GenCtx) {
     if *is_named {
         let traits = &mut Vec::new();
 
-        let copy = if *is_copy { "Clone,Copy," } else { "" };
         let lifetime = if *is_ref { "'a," } else { "" };
         let fields_ty = fields
             .iter()
             .map(|p| p.param_ergo_ty(traits, ctx))
             .collect::<Vec<_>>();
         let fields_name = fields.iter().map(|p| &p.ident.rs);
+        let fields_doc = fields.iter().map(PreparedField::doc_comment);
         let traits_idx = (1..=traits.len()).map(idx_char);
+        let is_redacted = fields.iter().any(|p| p.is_sensitive);
+        let derive = if is_redacted {
+            if *is_copy {
+                "#[derive(Clone,Copy)]"
+            } else {
+                ""
+            }
+        } else if *is_copy {
+            "#[derive(Clone,Copy,Debug)]"
+        } else {
+            "#[derive(Debug)]"
+        };
+        let doc = sourced_doc_comment(params.doc.as_deref(), &params.source);
         code!(w =>
-            #[derive($copy Debug)]
+            $doc
+            $derive
             pub struct $name<$lifetime $($traits_idx: $traits,)> {
-                $(pub $fields_name: $fields_ty,)
+                $($fields_doc pub $fields_name: $fields_ty,)
             }
         );
+        if is_redacted {
+            let traits_idx = (1..=traits.len()).map(idx_char);
+            let traits_idx_use = traits_idx.clone();
+            let traits = traits.iter();
+            let generics = code!(<$lifetime $($traits_idx: $traits + std::fmt::Debug,)>);
+            let args = code!(<$lifetime $($traits_idx_use,)>);
+            redacted_debug(w, &name.value, &generics, &args, fields);
+        }
+        if settings.gen_text_limits && fields.iter().any(|p| p.max_len.is_some()) {
+            let traits_idx = (1..=traits.len()).map(idx_char);
+            let traits_idx_use = traits_idx.clone();
+            let traits = traits.iter();
+            let client_name = ctx.client_name();
+            let checks = fields.iter().filter_map(|p| {
+                p.max_len.map(|max_len| {
+                    let name = &p.ident.rs;
+                    format!("{client_name}::check_text_len(\"{name}\", &self.{name}, {max_len})?;")
+                })
+            });
+            code!(w =>
+                impl<$lifetime $($traits_idx: $traits,)> $name<$lifetime $($traits_idx_use,)> {
+                    /// Checks that the text fields fit in the `varchar(n)` columns they are
+                    /// written to, without a round trip to the database.
+                    pub fn validate(&self) -> Result<(), $client_name::TextTooLong> {
+                        $($checks)
+                        Ok(())
+                    }
+                }
+            );
+        }
+        // Borrowed composites have no owned counterpart to deserialize into
+        if ctx.gen_maps && !is_ref {
+            let owned = &mut Vec::new();
+            for field in fields {
+                field.ty.own_generics(field.is_inner_nullable, owned, ctx);
+            }
+            let traits_idx = (1..=traits.len()).map(idx_char);
+            let traits_idx_use = traits_idx.clone();
+            let traits = traits.iter();
+            let to_map = to_map_fn(fields);
+            let from_map = from_map_fn(fields);
+            code!(w =>
+                impl<$($traits_idx: $traits + serde::Serialize,)> $name<$($traits_idx_use,)> {
+                    $to_map
+                }
+                impl $name<$($owned,)> {
+                    $from_map
+                }
+            );
+        }
     }
 }
 
+/// Doc comment lines of an item or field documented by a database comment.
+fn doc_comment(doc: Option<&str>) -> String {
+    doc.map_or_else(String::new, |doc| {
+        doc.lines()
+            .map(|line| match line.trim_end() {
+                "" => "///\n".to_string(),
+                line => format!("/// {line}\n"),
+            })
+            .collect()
+    })
+}
+
+/// Doc comment lines of an item generated from a query file, ending with the `path:line`
+/// location of its declaration so that errors in generated code lead back to the SQL.
+fn sourced_doc_comment(doc: Option<&str>, source: &str) -> String {
+    let doc = doc.map_or_else(String::new, |doc| format!("{doc}\n\n"));
+    doc_comment(Some(&format!("{doc}Source: `{source}`")))
+}
+
+/// Generates Arrow conversions for rows, whose fields all have a direct Arrow equivalent
+/// once validated.
+fn gen_record_batch(w: &mut impl Write, name: &str, fields: &[PreparedField], ctx: &GenCtx) {
+    let arrow_tys: Vec<_> = fields
+        .iter()
+        .map(|p| p.ty.arrow_ty().expect("validated Arrow column"))
+        .collect();
+    let client_name = ctx.client_name();
+    let schema_fields = fields.iter().zip(&arrow_tys).map(|(p, (_, data_ty))| {
+        format!(
+            "{client_name}::arrow::schema::Field::new(\"{}\", {client_name}::arrow::schema::DataType::{data_ty}, {})",
+            p.ident.db, p.is_nullable
+        )
+    });
+    let columns = fields.iter().zip(&arrow_tys).map(|(p, (array, _))| {
+        let field = &p.ident.rs;
+        let value = match (*array, p.is_nullable) {
+            ("StringArray" | "BinaryArray", true) => format!("row.{field}.as_deref()"),
+            ("StringArray", false) => format!("row.{field}.as_str()"),
+            ("BinaryArray", false) => format!("row.{field}.as_slice()"),
+            _ => format!("row.{field}"),
+        };
+        format!("std::sync::Arc::new({client_name}::arrow::array::{array}::from(rows.iter().map(|row| {value}).collect::<Vec<_>>())) as {client_name}::arrow::array::ArrayRef")
+    });
+    code!(w =>
+        impl $name {
+            pub fn arrow_schema() -> $client_name::arrow::schema::SchemaRef {
+                std::sync::Arc::new($client_name::arrow::schema::Schema::new(vec![$($schema_fields,)]))
+            }
+            pub fn to_record_batch<'a>(rows: impl IntoIterator<Item = &'a Self>) -> Result<$client_name::arrow::array::RecordBatch, $client_name::arrow::schema::ArrowError> {
+                let rows: Vec<&Self> = rows.into_iter().collect();
+                $client_name::arrow::array::RecordBatch::try_new(Self::arrow_schema(), vec![$($columns,)])
+            }
+        }
+    );
+}
+
+/// Generates a `to_map` method converting every field to a JSON value, keyed by its name.
+fn to_map_fn(fields: &[PreparedField]) -> String {
+    let nb_fields = fields.len();
+    let fields_original_name = fields.iter().map(|p| &p.ident.db);
+    let fields_name = fields.iter().map(|p| &p.ident.rs);
+    code!(
+        pub fn to_map(&self) -> Result<std::collections::HashMap<String, serde_json::Value>, serde_json::Error> {
+            let mut map = std::collections::HashMap::with_capacity($nb_fields);
+            $(map.insert("$fields_original_name".to_string(), serde_json::to_value(&self.$fields_name)?);)
+            Ok(map)
+        }
+    )
+}
+
+/// Generates a `from_map` constructor reading every field from a JSON value, keyed by its
+/// name. Missing keys are read as `null`.
+fn from_map_fn(fields: &[PreparedField]) -> String {
+    let fields_original_name = fields.iter().map(|p| &p.ident.db);
+    let fields_name = fields.iter().map(|p| &p.ident.rs);
+    code!(
+        pub fn from_map(mut map: std::collections::HashMap<String, serde_json::Value>) -> Result<Self, serde_json::Error> {
+            Ok(Self {
+                $($fields_name: serde_json::from_value(map.remove("$fields_original_name").unwrap_or(serde_json::Value::Null))?,)
+            })
+        }
+    )
+}
+
+/// Generates a `Debug` implementation printing `***` in place of sensitive fields.
+fn redacted_debug(
+    w: &mut impl Write,
+    name: &str,
+    generics: &str,
+    args: &str,
+    fields: &[PreparedField],
+) {
+    let fields_label = fields.iter().map(|p| p.ident.rs.trim_start_matches("r#"));
+    let fields_value = fields.iter().map(|p| {
+        if p.is_sensitive {
+            "&format_args!(\"***\")".to_string()
+        } else {
+            format!("&self.{}", p.ident.rs)
+        }
+    });
+    code!(w =>
+        impl $generics std::fmt::Debug for $name $args {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("$name")
+                    $(.field("$fields_label", $fields_value))
+                    .finish()
+            }
+        }
+    );
+}
+
 fn gen_row_structs(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
     let PreparedItem {
         name,
@@ -346,17 +611,66 @@ fn gen_row_structs(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
         let fields_name = fields.iter().map(|p| &p.ident.rs);
         let fields_ty = fields.iter().map(|p| p.own_struct(ctx));
         let copy = if *is_copy { "Copy" } else { "" };
-        let ser_str = if ctx.gen_derive {
+        // CSV records can't nest arrays, composites or JSON values, as validated
+        let gen_csv = ctx.gen_csv;
+        // Cached rows are deserialized back from the cache
+        let ser_str = if ctx.gen_derive || row.is_serialized || ctx.gen_cache {
+            "serde::Serialize,serde::Deserialize,"
+        } else if gen_csv {
             "serde::Serialize,"
         } else {
             ""
         };
+        let fields_doc = fields.iter().map(PreparedField::doc_comment);
+        let is_redacted = fields.iter().any(|p| p.is_sensitive);
+        let debug = if is_redacted { "" } else { "Debug," };
+        // Requested derives, besides the default ones and serde's derived above
+        let derives: String = row
+            .derives
+            .iter()
+            .filter(|it| {
+                !it.starts_with("serde::")
+                    && !["Debug", "Clone", "PartialEq", "Copy"].contains(&it.as_str())
+            })
+            .map(|it| format!("{it},"))
+            .collect();
+        let doc = sourced_doc_comment(row.doc.as_deref(), &row.source);
         code!(w =>
-            #[derive($ser_str Debug, Clone, PartialEq,$copy)]
+            $doc
+            #[derive($ser_str $debug $derives Clone, PartialEq,$copy)]
             pub struct $name {
-                $(pub $fields_name : $fields_ty,)
+                $($fields_doc pub $fields_name : $fields_ty,)
             }
         );
+        if is_redacted {
+            redacted_debug(w, &name.value, "", "", fields);
+        }
+        if ctx.gen_maps {
+            let to_map = to_map_fn(fields);
+            let from_map = from_map_fn(fields);
+            code!(w =>
+                impl $name {
+                    $to_map
+                    $from_map
+                }
+            );
+        }
+        if ctx.gen_arrow {
+            gen_record_batch(w, &name.value, fields, ctx);
+        }
+        if gen_csv {
+            let client_name = ctx.client_name();
+            let nb_fields = fields.len();
+            let fields_original_name = fields.iter().map(|p| &p.ident.db);
+            code!(w =>
+                impl $name {
+                    pub const CSV_HEADER: [&'static str; $nb_fields] = [$("$fields_original_name",)];
+                    pub fn write_csv<'a, W: std::io::Write>(rows: impl IntoIterator<Item = &'a Self>, w: W) -> Result<(), $client_name::csv::CsvError> {
+                        $client_name::csv::write_csv(&Self::CSV_HEADER, rows, w)
+                    }
+                }
+            );
+        }
 
         if !is_copy {
             let fields_name = fields.iter().map(|p| &p.ident.rs);
@@ -375,10 +689,55 @@ fn gen_row_structs(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
                 }
             );
         };
+        gen_from_pg_row(w, row, ctx);
+    }
+}
+
+/// Implements the client's `FromPgRow` for the row struct, and its borrowed struct if any.
+fn gen_from_pg_row(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
+    let PreparedItem {
+        name,
+        fields,
+        is_copy,
+        ..
+    } = row;
+    let client_name = ctx.client_name();
+    let backend = if ctx.is_async {
+        "tokio_postgres"
+    } else {
+        "postgres"
+    };
+    let fields_name = fields.iter().map(|p| &p.ident.rs);
+    let fields_original_name = fields.iter().map(|p| &p.ident.db);
+    if *is_copy {
+        code!(w =>
+            impl<'a> $client_name::FromPgRow<'a> for $name {
+                fn from_row(row: &'a $backend::Row) -> Result<Self, $backend::Error> {
+                    Ok(Self {
+                        $($fields_name: row.try_get("$fields_original_name")?,)
+                    })
+                }
+            }
+        );
+    } else {
+        code!(w =>
+            impl<'a> $client_name::FromPgRow<'a> for ${name}Borrowed<'a> {
+                fn from_row(row: &'a $backend::Row) -> Result<Self, $backend::Error> {
+                    Ok(Self {
+                        $($fields_name: row.try_get("$fields_original_name")?,)
+                    })
+                }
+            }
+            impl<'a> $client_name::FromPgRow<'a> for $name {
+                fn from_row(row: &'a $backend::Row) -> Result<Self, $backend::Error> {
+                    <${name}Borrowed<'a> as $client_name::FromPgRow<'a>>::from_row(row).map(Into::into)
+                }
+            }
+        );
     }
 }
 
-fn gen_row_query(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
+fn gen_row_query(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx, settings: &CodegenSettings) {
     let PreparedItem {
         name,
         fields,
@@ -420,27 +779,52 @@ fn gen_row_query(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
     } else {
         fields[0].brw_ty(false, ctx)
     };
+    let explain = if settings.gen_explain {
+        format!("self.stmt.explain_sample(self.client, &self.params){fn_await};")
+    } else {
+        String::new()
+    };
+    // Cached results are keyed by the id of the query binding the statement
+    let (id_field, id_map) = if settings.gen_cache {
+        ("id: &'static str,", "id: self.id,")
+    } else {
+        ("", "")
+    };
+    // Cache keys don't identify the mapper, so mapped queries are marked to not be cached
+    let (mapped_param, mapped_generic, mapped_arg, mapped) = if settings.gen_cache {
+        (
+            ", const MAPPED: bool = false",
+            ", const MAPPED: bool",
+            ", MAPPED",
+            ", true",
+        )
+    } else {
+        ("", "", "", "")
+    };
 
     code!(w =>
-    pub struct ${name}Query<'a, C: GenericClient, T, const N: usize> {
+    pub struct ${name}Query<'a, C: GenericClient, T, const N: usize $mapped_param> {
         client: &'a $client_mut C,
         params: [&'a (dyn postgres_types::ToSql + Sync); N],
         stmt: &'a mut $client::private::Stmt,
         extractor: fn(&$backend::Row) -> $row_struct,
         mapper: fn($row_struct) -> T,
+        $id_field
     }
-    impl<'a, C, T:'a, const N: usize> ${name}Query<'a, C, T, N> where C: GenericClient {
-        pub fn map<R>(self, mapper: fn($row_struct) -> R) -> ${name}Query<'a,C,R,N> {
+    impl<'a, C, T:'a, const N: usize $mapped_generic> ${name}Query<'a, C, T, N $mapped_arg> where C: GenericClient {
+        pub fn map<R>(self, mapper: fn($row_struct) -> R) -> ${name}Query<'a,C,R,N $mapped> {
             ${name}Query {
                 client: self.client,
                 params: self.params,
                 stmt: self.stmt,
                 extractor: self.extractor,
                 mapper,
+                $id_map
             }
         }
 
         pub $fn_async fn one(self) -> Result<T, $backend::Error> {
+            $explain
             let stmt = self.stmt.prepare(self.client)$fn_await?;
             let row = self.client.query_one(stmt, &self.params)$fn_await?;
             Ok((self.mapper)((self.extractor)(&row)))
@@ -451,6 +835,7 @@ fn gen_row_query(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
         }
 
         pub $fn_async fn opt(self) -> Result<Option<T>, $backend::Error> {
+            $explain
             let stmt = self.stmt.prepare(self.client)$fn_await?;
             Ok(self
                 .client
@@ -462,6 +847,7 @@ fn gen_row_query(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
         pub $fn_async fn iter(
             self,
         ) -> Result<impl $raw_type<Item = Result<T, $backend::Error>> + 'a, $backend::Error> {
+            $explain
             let stmt = self.stmt.prepare(self.client)$fn_await?;
             let it = self
                 .client
@@ -473,19 +859,326 @@ fn gen_row_query(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
             Ok(it)
         }
     });
+    if settings.gen_cache {
+        gen_cached_query(w, &name.value, ctx);
+    }
+}
+
+/// Generates the read-through cached counterpart of a query struct, fetching its rows
+/// from a cache before the database.
+fn gen_cached_query<W: Write>(w: &mut W, name: &str, ctx: &GenCtx) {
+    // Synchronous queries are run lazily from a closure, asynchronous ones as a future
+    let (fn_async, fn_await, backend, client, run) = if ctx.is_async {
+        ("async", ".await", "tokio_postgres", "cornucopia_async", "")
+    } else {
+        ("", "", "postgres", "cornucopia_sync", "|| ")
+    };
+    let methods = ["one", "opt", "all"].map(|method| {
+        let output = match method {
+            "one" => "T",
+            "opt" => "Option<T>",
+            _ => "Vec<T>",
+        };
+        move |w: &mut W| {
+            code!(w =>
+                pub $fn_async fn $method(self) -> Result<$output, $backend::Error> {
+                    let query = self.query;
+                    let key = $client::private::cache_key(query.client, query.stmt, query.id, "$method", &query.params)$fn_await?;
+                    $client::private::read_through(self.cache, key, $run query.$method())$fn_await
+                }
+            )
+        }
+    });
+    code!(w =>
+        pub struct ${name}CachedQuery<'a, C: GenericClient, T, const N: usize> {
+            query: ${name}Query<'a, C, T, N>,
+            cache: &'a dyn $client::cache::QueryCache,
+        }
+        impl<'a, C, T:'a, const N: usize> ${name}Query<'a, C, T, N> where C: GenericClient {
+            /// Fetches the rows through `cache`, the database being only queried on a miss.
+            /// Mapped queries can't be cached, as their key doesn't identify the mapper:
+            /// map the fetched rows instead.
+            pub fn cached(self, cache: &'a dyn $client::cache::QueryCache) -> ${name}CachedQuery<'a, C, T, N> {
+                ${name}CachedQuery { query: self, cache }
+            }
+        }
+        impl<'a, C, T: 'a + $client::cache::Cacheable, const N: usize> ${name}CachedQuery<'a, C, T, N> where C: GenericClient {
+            $($!methods)
+        }
+    );
 }
 
 pub fn idx_char(idx: usize) -> String {
     format!("T{idx}")
 }
 
-fn gen_query_fn<W: Write>(w: &mut W, module: &PreparedModule, query: &PreparedQuery, ctx: &GenCtx) {
+/// Statement generated for a query or a batch, embedded in the generated code.
+#[derive(Debug, Clone, Copy)]
+struct GenStmt<'a> {
+    module: &'a PreparedModule,
+    /// Query or batch the statement belongs to
+    ident: &'a Ident,
+    hint: Option<&'a str>,
+    variant: StmtVariant<'a>,
+    sql: &'a str,
+}
+
+/// Variant of a query's statement, generated along its main statement.
+#[derive(Debug, Clone, Copy)]
+enum StmtVariant<'a> {
+    Main,
+    /// Statement targeting the named partition
+    Partition(&'a str),
+    /// Statement fetching a page of rows
+    Pages,
+    /// `COPY` statement of the query
+    Copy,
+}
+
+impl<'a> GenStmt<'a> {
+    fn query(
+        module: &'a PreparedModule,
+        query: &'a PreparedQuery,
+        variant: StmtVariant<'a>,
+        sql: &'a str,
+    ) -> Self {
+        Self {
+            module,
+            ident: &query.ident,
+            hint: query.hint.as_deref(),
+            variant,
+            sql,
+        }
+    }
+
+    fn batch(module: &'a PreparedModule, batch: &'a PreparedBatch) -> Self {
+        Self {
+            module,
+            ident: &batch.ident,
+            hint: None,
+            variant: StmtVariant::Main,
+            sql: &batch.sql,
+        }
+    }
+}
+
+/// SQL of a generated statement, tagged with the statement prefix if any.
+fn stmt_text(stmt: &GenStmt, statement_prefix: Option<&str>) -> String {
+    let sql = if let Some(prefix) = statement_prefix {
+        // The prefix is validated and names can't hold a `/`, so the tag can't end or open a
+        // comment
+        format!(
+            "/* {prefix}:{}.{} */ {}",
+            stmt.module.info.qualified_name(),
+            stmt.ident.db,
+            stmt.sql
+        )
+    } else {
+        stmt.sql.to_string()
+    };
+    // `pg_hint_plan` only reads hints from the first comment
+    match stmt.hint {
+        Some(hint) => format!("/*+ {hint} */ {sql}"),
+        None => sql,
+    }
+}
+
+/// Name of the sidecar file of a statement, without extension. Variants other than
+/// partitions are suffixed after a dot, which no query name can clash with.
+fn stmt_file_name(stmt: &GenStmt) -> String {
+    let name = stmt.ident.rs.trim_start_matches("r#");
+    match stmt.variant {
+        StmtVariant::Main => name.to_string(),
+        StmtVariant::Partition(partition) => {
+            format!("{name}_in_{}", Ident::new(partition.to_string()).rs)
+        }
+        StmtVariant::Pages => format!("{name}.pages"),
+        StmtVariant::Copy => format!("{name}.copy"),
+    }
+}
+
+/// Rust expression of the SQL of a generated statement: either an escaped string literal,
+/// or the inclusion of its sidecar file when SQL is externalized.
+fn stmt_sql(stmt: &GenStmt, settings: &CodegenSettings) -> String {
+    if let Some(dir) = &settings.external_sql {
+        // Split query modules are written below the destination, in their subdirectories
+        let up = if settings.split_modules {
+            "../".repeat(stmt.module.info.dirs.len() + 1)
+        } else {
+            String::new()
+        };
+        format!(
+            "include_str!(\"{up}{dir}/{}/{}.sql\")",
+            stmt.module.info.module_path().join("/"),
+            stmt_file_name(stmt)
+        )
+    } else {
+        let sql = stmt_text(stmt, settings.statement_prefix.as_deref());
+        // Rust string format escaping, so that the statement matches its sidecar file
+        format!("\"{}\"", sql.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+/// Every generated statement: those of queries, with their variants, and of batches.
+fn statements(preparation: &Preparation) -> impl Iterator<Item = GenStmt<'_>> {
+    preparation.modules.iter().flat_map(|module| {
+        let queries = module.queries.values().flat_map(move |query| {
+            let partitions = query.partitioned.iter().flat_map(move |partitioned| {
+                module.partitions[&partitioned.table]
+                    .iter()
+                    .zip(&partitioned.sqls)
+                    .map(move |(partition, sql)| {
+                        GenStmt::query(module, query, StmtVariant::Partition(partition), sql)
+                    })
+            });
+            let pages = query
+                .pagination
+                .iter()
+                .map(move |it| GenStmt::query(module, query, StmtVariant::Pages, &it.sql));
+            let copy = query
+                .copy
+                .iter()
+                .map(move |sql| GenStmt::query(module, query, StmtVariant::Copy, sql));
+            std::iter::once(GenStmt::query(module, query, StmtVariant::Main, &query.sql))
+                .chain(partitions)
+                .chain(pages)
+                .chain(copy)
+        });
+        let batches = module
+            .batches
+            .iter()
+            .map(move |batch| GenStmt::batch(module, batch));
+        queries.chain(batches)
+    })
+}
+
+/// Sidecar files of every generated statement, with their paths relative to the generated file.
+fn sql_files(preparation: &Preparation, settings: &CodegenSettings) -> Vec<(String, String)> {
+    let Some(dir) = &settings.external_sql else {
+        return Vec::new();
+    };
+    statements(preparation)
+        .map(|stmt| {
+            let path = format!(
+                "{dir}/{}/{}.sql",
+                stmt.module.info.module_path().join("/"),
+                stmt_file_name(&stmt)
+            );
+            let sql = stmt_text(&stmt, settings.statement_prefix.as_deref());
+            (path, sql)
+        })
+        .collect()
+}
+
+/// Hashed name of a statement, resolving its SQL in obfuscated builds (64-bit FNV-1a).
+fn stmt_hash(stmt: &GenStmt) -> u64 {
+    let name = format!(
+        "{}::{}",
+        stmt.module.info.qualified_name(),
+        stmt_file_name(stmt)
+    );
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Rust expression of the SQL of a generated statement, resolved from the compressed
+/// SQL table in release builds when SQL is obfuscated.
+fn stmt_expr(stmt: &GenStmt, ctx: &GenCtx, settings: &CodegenSettings) -> String {
+    let sql = stmt_sql(stmt, settings);
+    if settings.obfuscate_sql {
+        let table = ctx.path(ctx.depth, "SQL_TABLE");
+        let hash = stmt_hash(stmt);
+        format!("cornucopia_sql!({table}, {hash:#018x}, {sql})")
+    } else {
+        sql
+    }
+}
+
+/// Xorshift keystream masking compressed SQL tables, as small tables are stored
+/// uncompressed by deflate. Must match the one of `SqlTable`.
+fn sql_table_keystream() -> impl Iterator<Item = u8> {
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    std::iter::repeat_with(move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state as u8
+    })
+}
+
+/// Generates the compressed table of every statement's SQL, and the macro resolving
+/// statements from it in release builds while keeping plain SQL in debug builds.
+fn gen_sql_table(w: &mut String, preparation: &Preparation, settings: &CodegenSettings) {
+    let client = if settings.gen_sync {
+        "cornucopia_sync"
+    } else {
+        "cornucopia_async"
+    };
+    let mut table = Vec::new();
+    for stmt in statements(preparation) {
+        let sql = stmt_text(&stmt, settings.statement_prefix.as_deref());
+        let len = u32::try_from(sql.len()).expect("statement is smaller than 4GiB");
+        table.extend_from_slice(&stmt_hash(&stmt).to_le_bytes());
+        table.extend_from_slice(&len.to_le_bytes());
+        table.extend_from_slice(sql.as_bytes());
+    }
+    let compressed: String = miniz_oxide::deflate::compress_to_vec(&table, 9)
+        .iter()
+        .zip(sql_table_keystream())
+        .map(|(byte, key)| format!("\\x{:02x}", byte ^ key))
+        .collect();
+    // `code!` has no escape for `$`
+    let dollar = "$";
+    code!(w =>
+        #[cfg(not(debug_assertions))]
+        static SQL_TABLE: $client::private::SqlTable = $client::private::SqlTable::new(b"$compressed");
+        #[cfg(debug_assertions)]
+        macro_rules! cornucopia_sql {
+            (${dollar}table:path, ${dollar}hash:literal, ${dollar}sql:expr) => {
+                ${dollar}sql
+            };
+        }
+        #[cfg(not(debug_assertions))]
+        macro_rules! cornucopia_sql {
+            (${dollar}table:path, ${dollar}hash:literal, ${dollar}sql:expr) => {
+                ${dollar}table.get(${dollar}hash)
+            };
+        }
+    );
+}
+
+fn gen_query_fn<W: Write>(
+    w: &mut W,
+    module: &PreparedModule,
+    query: &PreparedQuery,
+    ctx: &GenCtx,
+    settings: &CodegenSettings,
+) {
     let PreparedQuery {
         ident,
         row,
-        sql,
+        sql: _,
         param,
+        superseded_by,
+        partitioned,
+        transaction,
+        max_concurrency,
+        copy,
+        snapshot,
+        pagination,
+        doc,
+        hint: _,
+        source,
     } = query;
+    let copy = copy.as_ref().map(|sql| {
+        stmt_expr(
+            &GenStmt::query(module, query, StmtVariant::Copy, sql),
+            ctx,
+            settings,
+        )
+    });
+    let bulk_client = ctx.bulk_client();
 
     let (client_mut, fn_async, fn_await, backend, client) = if ctx.is_async {
         ("", "async", ".await", "tokio_postgres", "cornucopia_async")
@@ -546,6 +1239,11 @@ fn gen_query_fn<W: Write>(w: &mut W, module: &PreparedModule, query: &PreparedQu
                     field.owning_call(Some("it")),
                 )
             };
+            let cache_id = if settings.gen_cache {
+                format!("id: \"{}.{}\",", module.info.qualified_name(), ident.db)
+            } else {
+                String::new()
+            };
             code!(w =>
                 pub fn bind<'a, C: GenericClient,$($traits_idx: $traits,)>(&'a mut self, client: &'a $client_mut C, $($params_name: &'a $params_ty,) ) -> ${row_name}Query<'a,C, $row_struct_name, $nb_params> {
                     ${row_name}Query {
@@ -554,9 +1252,39 @@ fn gen_query_fn<W: Write>(w: &mut W, module: &PreparedModule, query: &PreparedQu
                         stmt: &mut self.0,
                         extractor: |row| { $!extractor },
                         mapper: |it| { $mapper },
+                        $cache_id
                     }
                 }
             );
+            if let Some(copy) = &copy {
+                let (iter, wrap, iterator) = if ctx.is_async {
+                    (
+                        format!("futures::Stream<Item = Result<{row_struct_name}, tokio_postgres::Error>>"),
+                        "tokio_postgres::binary_copy::BinaryCopyOutStream",
+                        "",
+                    )
+                } else {
+                    (
+                        format!("Iterator<Item = Result<{row_struct_name}, postgres::Error>> + 'a"),
+                        "postgres::binary_copy::BinaryCopyOutIter",
+                        ".iterator()",
+                    )
+                };
+                code!(w =>
+                    /// Copies the rows of the query out in bulk, decoded from `COPY`'s binary format.
+                    pub $fn_async fn copy_out<'a, C: $bulk_client>(&'a mut self, client: &'a $client_mut C) -> Result<impl $iter, $backend::Error> {
+                        let stmt = self.0.prepare(client)$fn_await?;
+                        let types: Vec<_> = stmt.columns().iter().map(|c| c.type_().clone()).collect();
+                        let reader = client.copy_out($copy)$fn_await?;
+                        Ok($wrap::new(reader, &types)$iterator.map(|row| {
+                            row.map(|row| {
+                                let it = $!extractor;
+                                $mapper
+                            })
+                        }))
+                    }
+                );
+            }
         } else {
             // Execute fn
             let params_wrap = order.iter().map(|idx| {
@@ -569,49 +1297,377 @@ fn gen_query_fn<W: Write>(w: &mut W, module: &PreparedModule, query: &PreparedQu
                     client.execute(stmt, &[ $($params_wrap,) ])$fn_await
                 }
             );
-        }
-    };
-    // Gen statement struct
-    {
-        let sql = sql.replace('"', "\\\""); // Rust string format escaping
-        let name = &ident.rs;
-        code!(w =>
-            pub fn $name() -> ${struct_name}Stmt {
-                ${struct_name}Stmt($client::private::Stmt::new("$sql"))
-            }
-            pub struct ${struct_name}Stmt($client::private::Stmt);
-            impl ${struct_name}Stmt {
-                $!lazy_impl
+            if settings.gen_replicas && is_mutating(&query.sql) {
+                let traits_idx = (1..=traits.len()).map(idx_char);
+                let params_name = order.iter().map(|idx| &param_field[*idx].ident.rs);
+                // Execute inline rather than through `bind`, whose borrow of `client` would
+                // last for `'a` when parameters are borrowed custom types
+                let params_wrap = order.iter().map(|idx| {
+                    let p = &param_field[*idx];
+                    p.ty.sql_wrapped(&p.ident.rs, ctx)
+                });
+                code!(w =>
+                    /// Executes the statement, then records the WAL position of the primary in `token`.
+                    /// Inside a transaction, capture it again once committed.
+                    pub $fn_async fn bind_tracked<'a, C: GenericClient,$($traits_idx: $traits,)>(&'a mut self, client: &'a $client_mut C, token: &mut $client::replicas::ConsistencyToken, $($params_name: &'a $params_ty,)) -> Result<u64, $backend::Error> {
+                        let stmt = self.0.prepare(client)$fn_await?;
+                        let rows = client.execute(stmt, &[ $($params_wrap,) ])$fn_await?;
+                        $client::replicas::capture(client, token)$fn_await?;
+                        Ok(rows)
+                    }
+                );
             }
-        );
-    }
-
-    // Param impl
-    if let Some(param) = param {
-        if param.is_named {
-            let param_path = &param.path(ctx);
-            let lifetime = if param.is_copy || !param.is_ref {
-                ""
-            } else {
-                "'a,"
-            };
-            if let Some((idx, _)) = row {
-                let prepared_row = &module.rows.get_index(*idx).unwrap().1;
-                let query_row_struct = if prepared_row.is_named {
-                    prepared_row.path(ctx)
+            if let (Some(copy), Some(param)) = (&copy, param) {
+                let traits_idx = (1..=traits.len()).map(idx_char);
+                let item = if param.is_named {
+                    let lifetime = if param.is_copy || !param.is_ref {
+                        ""
+                    } else {
+                        "'a,"
+                    };
+                    let traits_idx = (1..=traits.len()).map(idx_char);
+                    let path = param.path(ctx);
+                    code!($path<$lifetime $($traits_idx,)>)
                 } else {
-                    prepared_row.fields[0].own_struct(ctx)
+                    params_ty[0].clone()
+                };
+                let bindings = order.iter().map(|idx| {
+                    let name = &param_field[*idx].ident.rs;
+                    let field = if param.is_named {
+                        format!("&row.{name}")
+                    } else {
+                        "&row".to_string()
+                    };
+                    move |w: &mut W| code!(w => let $name = $field;)
+                });
+                let params_wrap = order.iter().map(|idx| {
+                    let p = &param_field[*idx];
+                    p.ty.sql_wrapped(&p.ident.rs, ctx)
+                });
+                let (writer, pin, as_mut) = if ctx.is_async {
+                    (
+                        "tokio_postgres::binary_copy::BinaryCopyInWriter",
+                        "let mut writer = std::pin::pin!(writer);",
+                        ".as_mut()",
+                    )
+                } else {
+                    ("postgres::binary_copy::BinaryCopyInWriter", "", "")
                 };
-                let name = &module.rows.get_index(*idx).unwrap().1.name;
-                let nb_params = param_field.len();
                 code!(w =>
-                    impl <'a, C: GenericClient,$($traits_idx: $traits,)> $client::Params<'a, $param_path<$lifetime $($traits_idx,)>, ${name}Query<'a, C, $query_row_struct, $nb_params>, C> for ${struct_name}Stmt {
-                        fn params(&'a mut self, client: &'a $client_mut C, params: &'a $param_path<$lifetime $($traits_idx,)>) -> ${name}Query<'a, C, $query_row_struct, $nb_params> {
-                            self.bind(client, $(&params.$params_name,))
+                    /// Copies `rows` in bulk, encoded in `COPY`'s binary format, returning the number of copied rows.
+                    pub $fn_async fn copy_in<'a, C: $bulk_client,$($traits_idx: $traits,)>(&'a mut self, client: &'a $client_mut C, rows: impl IntoIterator<Item = $item>) -> Result<u64, $backend::Error> {
+                        let stmt = self.0.prepare(client)$fn_await?;
+                        let types = stmt.params().to_vec();
+                        let sink = client.copy_in($copy)$fn_await?;
+                        let $client_mut writer = $writer::new(sink, &types);
+                        $pin
+                        for row in rows {
+                            $($!bindings)
+                            writer$as_mut.write(&[ $($params_wrap,) ])$fn_await?;
                         }
+                        writer$as_mut.finish()$fn_await
                     }
                 );
-            } else {
+            }
+        }
+    };
+    let isolated_impl = |w: &mut W| {
+        let Some(setup) = transaction else {
+            return;
+        };
+        // Rust string format escaping
+        let setup_str = setup.replace('\\', "\\\\").replace('"', "\\\"");
+        let (client_ty, generic, transaction_mut, transaction_ref) = if ctx.is_async {
+            ("tokio_postgres::Client", "", "", "&transaction")
+        } else {
+            ("C", "C: GenericClient,", "mut", "&mut transaction")
+        };
+        let traits_idx = (1..=traits.len()).map(idx_char);
+        let params_name = order.iter().map(|idx| &param_field[*idx].ident.rs);
+        let params_call = params_name.clone();
+        let output = script_output_ty(module, query, ctx);
+        let collect = if row.is_some() {
+            format!(".all(){fn_await}")
+        } else {
+            fn_await.to_string()
+        };
+        code!(w =>
+            /// Retries the transactions of `bind_isolated` failing with a serialization failure
+            /// or a deadlock as decided by `hook`, instead of returning those errors as is.
+            #[must_use]
+            pub fn retried(mut self, hook: $client::transaction::RetryHook) -> Self {
+                self.0 = self.0.retried(hook);
+                self
+            }
+            /// Runs the statement in its own transaction set up with `$setup`, retried on
+            /// serialization failures as decided by the hook given to `retried`.
+            pub $fn_async fn bind_isolated<'a, $generic $($traits_idx: $traits,)>(&'a mut self, client: &'a mut $client_ty, $($params_name: &'a $params_ty,)) -> Result<$output, $backend::Error> {
+                let mut attempts = 0;
+                loop {
+                    attempts += 1;
+                    let $transaction_mut transaction = client.transaction()$fn_await?;
+                    transaction.batch_execute("$setup_str")$fn_await?;
+                    let result = self.bind($transaction_ref, $($params_call,))$collect;
+                    let result = match result {
+                        Ok(it) => transaction.commit()$fn_await.map(|()| it),
+                        Err(err) => Err(err),
+                    };
+                    match result {
+                        Err(err) if self.0.should_retry(attempts, &err) => {}
+                        result => return result,
+                    }
+                }
+            }
+        );
+    };
+    let snapshot_impl = |w: &mut W| {
+        if !snapshot {
+            return;
+        }
+        let (client_ty, generic, transaction_mut, transaction_ref) = if ctx.is_async {
+            ("tokio_postgres::Client", "", "", "&transaction")
+        } else {
+            ("C", "C: GenericClient,", "mut", "&mut transaction")
+        };
+        let traits_idx = (1..=traits.len()).map(idx_char);
+        let params_name = order.iter().map(|idx| &param_field[*idx].ident.rs);
+        let params_call = params_name.clone();
+        let output = script_output_ty(module, query, ctx);
+        code!(w =>
+            /// Runs the query in its own read-only transaction, reading rows as of `snapshot`,
+            /// the id of a snapshot exported by a transaction still open.
+            pub $fn_async fn bind_at<'a, $generic $($traits_idx: $traits,)>(&'a mut self, client: &'a mut $client_ty, snapshot: &str, $($params_name: &'a $params_ty,)) -> Result<$output, $backend::Error> {
+                let $transaction_mut transaction = client.transaction()$fn_await?;
+                transaction.batch_execute(&$client::private::set_snapshot_sql(snapshot))$fn_await?;
+                let rows = self.bind($transaction_ref, $($params_call,)).all()$fn_await?;
+                transaction.commit()$fn_await?;
+                Ok(rows)
+            }
+        );
+    };
+    let limited_impl = |w: &mut W| {
+        let (Some(max), true) = (max_concurrency, ctx.is_async) else {
+            return;
+        };
+        let limit = ctx.path(
+            ctx.depth,
+            concurrency_limit_path(&module.info.qualified_name(), &ident.rs),
+        );
+        let traits_idx = (1..=traits.len()).map(idx_char);
+        let params_name = order.iter().map(|idx| &param_field[*idx].ident.rs);
+        let params_call = params_name.clone();
+        let output = script_output_ty(module, query, ctx);
+        let collect = if row.is_some() {
+            ".all().await"
+        } else {
+            ".await"
+        };
+        code!(w =>
+            /// Runs the statement once fewer than $max executions of it are running.
+            pub async fn bind_limited<'a, C: GenericClient, $($traits_idx: $traits,)>(&'a mut self, client: &'a C, $($params_name: &'a $params_ty,)) -> Result<$output, tokio_postgres::Error> {
+                let _permit = $limit.acquire().await;
+                self.bind(client, $($params_call,))$collect
+            }
+        );
+    };
+    // Gen statement struct
+    {
+        let sql = stmt_expr(
+            &GenStmt::query(module, query, StmtVariant::Main, &query.sql),
+            ctx,
+            settings,
+        );
+        let name = &ident.rs;
+        // Sampled executions run again under `EXPLAIN ANALYZE`, which must not write twice
+        let explained = if settings.gen_explain && row.is_some() && !is_mutating(&query.sql) {
+            format!(
+                ".explained(\"{}.{}\")",
+                module.info.qualified_name(),
+                ident.db
+            )
+        } else {
+            String::new()
+        };
+        let deprecated = superseded_by
+            .as_ref()
+            .map(|newer| format!("#[deprecated(note = \"use `{newer}` instead\")]"))
+            .unwrap_or_default();
+        let explained_impl = |w: &mut W| {
+            if explained.is_empty() {
+                return;
+            }
+            code!(w =>
+                /// Samples the plans of this statement's executions with `explainer`.
+                #[must_use]
+                pub fn explained(mut self, explainer: &$client::explain::Explainer) -> Self {
+                    self.0 = self.0.explained_by(explainer);
+                    self
+                }
+            );
+        };
+        // The query's comment followed by its SQL and where it's declared
+        let fn_doc = doc
+            .as_ref()
+            .map_or_else(String::new, |doc| format!("{doc}\n\n"));
+        let fn_doc = doc_comment(Some(&format!(
+            "{fn_doc}```sql\n{}\n```\n\nSource: `{source}`",
+            query.sql
+        )));
+        let tenant_fn = |w: &mut W| {
+            let Some(template) = &settings.tenant_schema else {
+                return;
+            };
+            // References are located in the statement as embedded, prefix and hint included
+            let text = stmt_text(
+                &GenStmt::query(module, query, StmtVariant::Main, &query.sql),
+                settings.statement_prefix.as_deref(),
+            );
+            let references = schema_references(&text, template);
+            if references.is_empty() {
+                return;
+            }
+            let references = references
+                .iter()
+                .map(|it| format!("{}..{}", it.start, it.end))
+                .collect::<Vec<_>>()
+                .join(", ");
+            code!(w =>
+                /// Runs the query against the `schema` of a tenant instead of `$template`.
+                $deprecated
+                pub fn ${name}_for(schema: &$client::tenancy::TenantSchema) -> ${struct_name}Stmt {
+                    ${struct_name}Stmt($client::private::Stmt::new($sql)$explained.in_schema(&[$references], schema))
+                }
+            );
+        };
+        let pages_fn = |w: &mut W| {
+            let (Some(pagination), Some((idx, index))) = (pagination, row) else {
+                return;
+            };
+            let item = module.rows.get_index(*idx).unwrap().1;
+            let (row_struct_name, extract, mapper) = if item.is_named {
+                let path = item.path(ctx);
+                let post = if item.is_copy { "" } else { "Borrowed" };
+                let fields_name = item.fields.iter().map(|p| &p.ident.rs);
+                let fields_idx = (0..item.fields.len()).map(|i| index[i]);
+                (
+                    path.clone(),
+                    code!($path$post { $($fields_name: row.get($fields_idx),) }),
+                    code!(<$path>::from(it)),
+                )
+            } else {
+                let field = &item.fields[0];
+                let brw = field.brw_ty(false, ctx);
+                (
+                    field.own_struct(ctx),
+                    format!("{{ let it: {brw} = row.get(0); it }}"),
+                    field.owning_call(Some("it")),
+                )
+            };
+            let traits_idx = (1..=traits.len()).map(idx_char);
+            let params_name = order.iter().map(|idx| &param_field[*idx].ident.rs);
+            let params_wrap = order.iter().map(|idx| {
+                let p = &param_field[*idx];
+                p.ty.sql_wrapped(&p.ident.rs, ctx)
+            });
+            let cursor_ty = pagination.cursor.own_struct(ctx);
+            let cursor_name = &pagination.cursor.ident.db;
+            let column = pagination.column;
+            let sql = stmt_expr(
+                &GenStmt::query(module, query, StmtVariant::Pages, &pagination.sql),
+                ctx,
+                settings,
+            );
+            code!(w =>
+                /// Fetches the rows of the query by pages, in `$cursor_name` order.
+                $deprecated
+                pub fn ${name}_pages() -> ${struct_name}PagesStmt {
+                    ${struct_name}PagesStmt($client::private::Stmt::new($sql))
+                }
+                pub struct ${struct_name}PagesStmt($client::private::Stmt);
+                impl ${struct_name}PagesStmt {
+                    /// Fetches up to `limit` rows following `cursor`, the next cursor of the
+                    /// previous page or `None` for the first page.
+                    pub $fn_async fn page<'a, C: GenericClient, $($traits_idx: $traits,)>(&'a mut self, client: &'a $client_mut C, $($params_name: &'a $params_ty,) limit: u32, cursor: Option<$cursor_ty>) -> Result<$client::Page<$row_struct_name, $cursor_ty>, $backend::Error> {
+                        let stmt = self.0.prepare(client)$fn_await?;
+                        let rows = client.query(stmt, &[$($params_wrap,) &cursor, &(i64::from(limit) + 1)])$fn_await?;
+                        let rows = rows.iter().map(|row| {
+                            let it = $extract;
+                            ($mapper, row.get($column))
+                        });
+                        Ok($client::Page::new(rows.collect(), limit))
+                    }
+                }
+            );
+        };
+        code!(w =>
+            $fn_doc
+            $deprecated
+            pub fn $name() -> ${struct_name}Stmt {
+                ${struct_name}Stmt($client::private::Stmt::new($sql)$explained)
+            }
+            $!tenant_fn
+            $!pages_fn
+            pub struct ${struct_name}Stmt($client::private::Stmt);
+            impl ${struct_name}Stmt {
+                $!explained_impl
+                $!lazy_impl
+                $!isolated_impl
+                $!snapshot_impl
+                $!limited_impl
+            }
+        );
+        if let Some(PartitionedSql { table, sqls }) = partitioned {
+            let partition = ctx.module_path(partition_enum_name(table));
+            let arms = module.partitions[table]
+                .iter()
+                .zip(sqls)
+                .map(|(variant, sql)| {
+                    let sql = stmt_expr(
+                        &GenStmt::query(module, query, StmtVariant::Partition(variant), sql),
+                        ctx,
+                        settings,
+                    );
+                    let variant = Ident::new(variant.clone()).type_ident();
+                    let partition = &partition;
+                    move |w: &mut W| code!(w => $partition::$variant => $sql,)
+                });
+            code!(w =>
+                $deprecated
+                pub fn ${name}_in(partition: $partition) -> ${struct_name}Stmt {
+                    ${struct_name}Stmt($client::private::Stmt::new(match partition {
+                        $($!arms)
+                    })$explained)
+                }
+            );
+        }
+    }
+
+    // Param impl
+    if let Some(param) = param.filter(|param| has_params_struct(param, module, settings)) {
+        if param.is_named {
+            let param_path = &param.path(ctx);
+            let lifetime = if param.is_copy || !param.is_ref {
+                ""
+            } else {
+                "'a,"
+            };
+            if let Some((idx, _)) = row {
+                let prepared_row = &module.rows.get_index(*idx).unwrap().1;
+                let query_row_struct = if prepared_row.is_named {
+                    prepared_row.path(ctx)
+                } else {
+                    prepared_row.fields[0].own_struct(ctx)
+                };
+                let name = &module.rows.get_index(*idx).unwrap().1.name;
+                let nb_params = param_field.len();
+                code!(w =>
+                    impl <'a, C: GenericClient,$($traits_idx: $traits,)> $client::Params<'a, $param_path<$lifetime $($traits_idx,)>, ${name}Query<'a, C, $query_row_struct, $nb_params>, C> for ${struct_name}Stmt {
+                        fn params(&'a mut self, client: &'a $client_mut C, params: &'a $param_path<$lifetime $($traits_idx,)>) -> ${name}Query<'a, C, $query_row_struct, $nb_params> {
+                            self.bind(client, $(&params.$params_name,))
+                        }
+                    }
+                );
+            } else {
                 let (send_sync, pre_ty, post_ty_lf, pre, post) = if ctx.is_async {
                     (
                         "+ Send + Sync",
@@ -630,6 +1686,49 @@ fn gen_query_fn<W: Write>(w: &mut W, module: &PreparedModule, query: &PreparedQu
                         }
                     }
                 );
+                let fn_name = &ident.rs;
+                let allow_deprecated = if superseded_by.is_some() {
+                    "#[allow(deprecated)]"
+                } else {
+                    ""
+                };
+                // Execute inline rather than through `bind`, whose borrow of the statement
+                // would last for `'a` when parameters are borrowed custom types
+                let bindings = order.iter().map(|idx| {
+                    let name = &param_field[*idx].ident.rs;
+                    move |w: &mut W| code!(w => let $name = &self.$name;)
+                });
+                let params_wrap = order.iter().map(|idx| {
+                    let p = &param_field[*idx];
+                    p.ty.sql_wrapped(&p.ident.rs, ctx)
+                });
+                if ctx.is_async {
+                    code!(w =>
+                        impl <'a, C: GenericClient $send_sync, $($traits_idx: $traits,)> $client::Execute<'a, C> for $param_path<$lifetime $($traits_idx,)> {
+                            $allow_deprecated
+                            fn execute(&'a self, client: &'a C) -> $pre_ty<u64, $backend::Error>$post_ty_lf {
+                                Box::pin(async move {
+                                    $($!bindings)
+                                    let mut stmt = $fn_name();
+                                    let stmt = stmt.0.prepare(client).await?;
+                                    client.execute(stmt, &[ $($params_wrap,) ]).await
+                                })
+                            }
+                        }
+                    );
+                } else {
+                    code!(w =>
+                        impl <'a, C: GenericClient, $($traits_idx: $traits,)> $client::Execute<C> for $param_path<$lifetime $($traits_idx,)> {
+                            $allow_deprecated
+                            fn execute(&self, client: &mut C) -> Result<u64, $backend::Error> {
+                                $($!bindings)
+                                let mut stmt = $fn_name();
+                                let stmt = stmt.0.prepare(client)?;
+                                client.execute(stmt, &[ $($params_wrap,) ])
+                            }
+                        }
+                    );
+                }
             }
         }
     }
@@ -644,9 +1743,14 @@ fn gen_custom_type(w: &mut impl Write, schema: &str, prepared: &PreparedType, ct
         is_copy,
         is_params,
         name,
+        doc,
     } = prepared;
+    w.write_str(&doc_comment(doc.as_deref())).unwrap();
     let copy = if *is_copy { "Copy," } else { "" };
-    let ser_str = if ctx.gen_derive {
+    // Map conversions, cached rows and CSV export go through serde
+    let ser_str = if ctx.gen_derive || ctx.gen_maps || ctx.gen_cache {
+        "serde::Serialize,serde::Deserialize,"
+    } else if ctx.gen_csv {
         "serde::Serialize,"
     } else {
         ""
@@ -654,26 +1758,39 @@ fn gen_custom_type(w: &mut impl Write, schema: &str, prepared: &PreparedType, ct
     match content {
         PreparedContent::Enum(variants) => {
             let variants_ident = variants.iter().map(|v| &v.rs);
+            let (diesel_derive, diesel_attr) = if ctx.gen_diesel {
+                (
+                    "diesel::expression::AsExpression, diesel::deserialize::FromSqlRow,",
+                    format!("#[diesel(sql_type = {struct_name}Sql)]"),
+                )
+            } else {
+                ("", String::new())
+            };
             code!(w =>
-                #[derive($ser_str Debug, Clone, Copy, PartialEq, Eq)]
+                #[derive($ser_str $diesel_derive Debug, Clone, Copy, PartialEq, Eq)]
+                $diesel_attr
                 #[allow(non_camel_case_types)]
                 pub enum $struct_name {
                     $($variants_ident,)
                 }
             );
             enum_sql(w, name, struct_name, variants);
+            if ctx.gen_diesel {
+                diesel_enum(w, schema, name, struct_name, variants);
+            }
         }
         PreparedContent::Composite(fields) => {
             let fields_original_name = fields.iter().map(|p| &p.ident.db);
             let fields_name = fields.iter().map(|p| &p.ident.rs);
             {
                 let fields_ty = fields.iter().map(|p| p.own_struct(ctx));
+                let fields_doc = fields.iter().map(PreparedField::doc_comment);
                 code!(w =>
                     #[derive($ser_str Debug,postgres_types::FromSql,$copy Clone, PartialEq)]
                     #[postgres(name = "$name")]
                     pub struct $struct_name {
                         $(
-                            #[postgres(name = "$fields_original_name")]
+                            $fields_doc #[postgres(name = "$fields_original_name")]
                             pub $fields_name: $fields_ty,
                         )
                     }
@@ -716,19 +1833,222 @@ fn gen_custom_type(w: &mut impl Write, schema: &str, prepared: &PreparedType, ct
             }
         }
     }
+    if ctx.gen_sqlx {
+        sqlx_bridge(w, schema, prepared, ctx);
+    }
+}
+
+/// Generates a Diesel SQL type for an enum, named after it with a `Sql` suffix, and the
+/// `ToSql`/`FromSql` impls converting the enum from and to it.
+fn diesel_enum(w: &mut impl Write, schema: &str, name: &str, enum_name: &str, variants: &[Ident]) {
+    let enum_names = std::iter::repeat(enum_name);
+    let db_variants_ident = variants.iter().map(|v| &v.db);
+    let rs_variants_ident = variants.iter().map(|v| &v.rs);
+    let enum_names_from = enum_names.clone();
+    let db_variants_from = db_variants_ident.clone();
+    let rs_variants_from = rs_variants_ident.clone();
+    code!(w =>
+        #[derive(Debug, Clone, Copy, Default, diesel::sql_types::SqlType, diesel::query_builder::QueryId)]
+        #[diesel(postgres_type(name = "$name", schema = "$schema"))]
+        pub struct ${enum_name}Sql;
+        impl diesel::serialize::ToSql<${enum_name}Sql, diesel::pg::Pg> for $enum_name {
+            fn to_sql<'b>(&'b self, out: &mut diesel::serialize::Output<'b, '_, diesel::pg::Pg>) -> diesel::serialize::Result {
+                let s = match *self {
+                    $($enum_names::$rs_variants_ident => "$db_variants_ident",)
+                };
+                std::io::Write::write_all(out, s.as_bytes())?;
+                Ok(diesel::serialize::IsNull::No)
+            }
+        }
+        impl diesel::deserialize::FromSql<${enum_name}Sql, diesel::pg::Pg> for $enum_name {
+            fn from_sql(value: diesel::pg::PgValue<'_>) -> diesel::deserialize::Result<Self> {
+                match value.as_bytes() {
+                    $(b"$db_variants_from" => Ok($enum_names_from::$rs_variants_from),)
+                    s => Err(format!("invalid variant `{}` of `$name`", String::from_utf8_lossy(s)).into()),
+                }
+            }
+        }
+    );
+}
+
+/// Name of a custom type as displayed by postgres' `regtype`, which sqlx compares type
+/// names with. Types of the `public` schema are expected to be in the search path.
+fn regtype_name(schema: &str, name: &str) -> String {
+    let quote = |ident: &str| {
+        let is_plain = ident.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+            && ident
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if is_plain {
+            ident.to_string()
+        } else {
+            format!("\"{}\"", ident.replace('"', "\"\""))
+        }
+    };
+    if schema == "public" {
+        quote(name)
+    } else {
+        format!("{}.{}", quote(schema), quote(name))
+    }
+}
+
+/// Generates sqlx `Type`, `Encode` and `Decode` impls for an enum, or for a composite whose
+/// fields all have a sqlx equivalent without extra sqlx features.
+fn sqlx_bridge(w: &mut impl Write, schema: &str, prepared: &PreparedType, ctx: &GenCtx) {
+    let PreparedType {
+        struct_name,
+        content,
+        name,
+        ..
+    } = prepared;
+    let client = ctx.client_name();
+    let type_name = format!("{:?}", regtype_name(schema, name));
+    match content {
+        PreparedContent::Enum(variants) => {
+            let enum_names = std::iter::repeat(struct_name);
+            let db_variants_ident = variants.iter().map(|v| &v.db);
+            let rs_variants_ident = variants.iter().map(|v| &v.rs);
+            let enum_names_decode = enum_names.clone();
+            let db_variants_decode = db_variants_ident.clone();
+            let rs_variants_decode = rs_variants_ident.clone();
+            code!(w =>
+                impl<'r> $client::sqlx::Decode<'r, $client::sqlx::Postgres> for $struct_name {
+                    fn decode(value: $client::sqlx::postgres::PgValueRef<'r>) -> Result<Self, $client::sqlx::error::BoxDynError> {
+                        match <&str as $client::sqlx::Decode<$client::sqlx::Postgres>>::decode(value)? {
+                            $("$db_variants_decode" => Ok($enum_names_decode::$rs_variants_decode),)
+                            s => Err(format!("invalid variant `{s}` of `$name`").into()),
+                        }
+                    }
+                }
+                impl $client::sqlx::Encode<'_, $client::sqlx::Postgres> for $struct_name {
+                    fn encode_by_ref(&self, buf: &mut $client::sqlx::postgres::PgArgumentBuffer) -> Result<$client::sqlx::encode::IsNull, $client::sqlx::error::BoxDynError> {
+                        let s = match *self {
+                            $($enum_names::$rs_variants_ident => "$db_variants_ident",)
+                        };
+                        <&str as $client::sqlx::Encode<$client::sqlx::Postgres>>::encode(s, buf)
+                    }
+                }
+            );
+        }
+        PreparedContent::Composite(fields) => {
+            if !fields.iter().all(|p| p.ty.is_sqlx_compatible()) {
+                return;
+            }
+            let fields_name = fields.iter().map(|p| &p.ident.rs);
+            let fields_decode = fields_name.clone();
+            let fields_build = fields_name.clone();
+            let fields_ty = fields.iter().map(|p| p.own_struct(ctx));
+            code!(w =>
+                impl<'r> $client::sqlx::Decode<'r, $client::sqlx::Postgres> for $struct_name {
+                    fn decode(value: $client::sqlx::postgres::PgValueRef<'r>) -> Result<Self, $client::sqlx::error::BoxDynError> {
+                        let mut decoder = $client::sqlx::postgres::types::PgRecordDecoder::new(value)?;
+                        $(let $fields_decode = decoder.try_decode::<$fields_ty>()?;)
+                        Ok(Self { $($fields_build,) })
+                    }
+                }
+                impl $client::sqlx::Encode<'_, $client::sqlx::Postgres> for $struct_name {
+                    fn encode_by_ref(&self, buf: &mut $client::sqlx::postgres::PgArgumentBuffer) -> Result<$client::sqlx::encode::IsNull, $client::sqlx::error::BoxDynError> {
+                        let mut encoder = $client::sqlx::postgres::types::PgRecordEncoder::new(buf);
+                        $(encoder.encode(&self.$fields_name)?;)
+                        encoder.finish();
+                        Ok($client::sqlx::encode::IsNull::No)
+                    }
+                }
+            );
+        }
+    }
+    code!(w =>
+        impl $client::sqlx::Type<$client::sqlx::Postgres> for $struct_name {
+            fn type_info() -> $client::sqlx::postgres::PgTypeInfo {
+                $client::sqlx::postgres::PgTypeInfo::with_name($type_name)
+            }
+        }
+        impl $client::sqlx::postgres::PgHasArrayType for $struct_name {
+            fn array_type_info() -> $client::sqlx::postgres::PgTypeInfo {
+                $client::sqlx::postgres::PgTypeInfo::array_of($type_name)
+            }
+        }
+    );
+}
+
+/// Orders `0..len` so that every index comes after its dependencies, keeping the
+/// original order otherwise. Dependency cycles are broken in the original order.
+fn topological_order(len: usize, dependencies: impl Fn(usize) -> Vec<usize>) -> Vec<usize> {
+    fn visit(
+        idx: usize,
+        dependencies: &impl Fn(usize) -> Vec<usize>,
+        visited: &mut [bool],
+        order: &mut Vec<usize>,
+    ) {
+        if visited[idx] {
+            return;
+        }
+        visited[idx] = true;
+        for dependency in dependencies(idx) {
+            visit(dependency, dependencies, visited, order);
+        }
+        order.push(idx);
+    }
+
+    let mut visited = vec![false; len];
+    let mut order = Vec::with_capacity(len);
+    for idx in 0..len {
+        visit(idx, &dependencies, &mut visited, &mut order);
+    }
+    order
+}
+
+/// Schemas and names of the custom types a custom type is built from.
+fn type_dependencies(ty: &PreparedType) -> Vec<(&str, &str)> {
+    match &ty.content {
+        PreparedContent::Enum(_) => Vec::new(),
+        PreparedContent::Composite(fields) => fields
+            .iter()
+            .filter_map(|field| field.ty.custom_dependency())
+            .collect(),
+    }
 }
 
 fn gen_type_modules<W: Write>(
     w: &mut W,
     prepared: &IndexMap<String, Vec<PreparedType>>,
+    domains: &IndexMap<String, Vec<Domain>>,
     ctx: &GenCtx,
 ) {
-    let modules = prepared.iter().map(|(schema, types)| {
+    // Define schemas and types after the ones they depend on, so that generated code
+    // reads top-down even when composites reference types of other schemas
+    let schema_order = topological_order(prepared.len(), |idx| {
+        let (schema, types) = prepared.get_index(idx).unwrap();
+        types
+            .iter()
+            .flat_map(type_dependencies)
+            .filter(|(dep_schema, _)| dep_schema != schema)
+            .filter_map(|(dep_schema, _)| prepared.get_index_of(dep_schema))
+            .collect()
+    });
+    let modules = schema_order.into_iter().map(|idx| {
+        let (schema, types) = prepared.get_index(idx).unwrap();
+        let type_order = topological_order(types.len(), |idx| {
+            type_dependencies(&types[idx])
+                .into_iter()
+                .filter(|(dep_schema, _)| dep_schema == schema)
+                .filter_map(|(_, dep_name)| types.iter().position(|ty| ty.name == dep_name))
+                .collect()
+        });
         move |w: &mut W| {
             let lazy = |w: &mut W| {
-                for ty in types {
-                    gen_custom_type(w, schema, ty, ctx)
+                // Diesel's derives refer to the crate by name
+                let has_enums = types
+                    .iter()
+                    .any(|ty| matches!(ty.content, PreparedContent::Enum(_)));
+                if ctx.gen_diesel && has_enums {
+                    let client_name = ctx.client_name();
+                    code!(w => use $client_name::diesel;);
                 }
+                for idx in type_order {
+                    gen_custom_type(w, schema, &types[idx], ctx)
+                }
+                gen_domain_checks(w, domains.get(schema));
             };
 
             code!(w =>
@@ -737,35 +2057,1280 @@ fn gen_type_modules<W: Write>(
             });
         }
     });
+    // Schemas only holding constrained domains
+    let domain_modules = domains
+        .iter()
+        .filter(|(schema, _)| !prepared.contains_key(*schema))
+        .map(|(schema, domains)| {
+            move |w: &mut W| {
+                let lazy = |w: &mut W| gen_domain_checks(w, Some(domains));
+                code!(w =>
+                pub mod $schema {
+                    $!lazy
+                });
+            }
+        });
+    code!(w => $($!modules) $($!domain_modules));
+}
+
+/// Generates a function checking values against the constraints of each domain, for the
+/// domains whose constraints all compare their value to a number.
+fn gen_domain_checks<W: Write>(w: &mut W, domains: Option<&Vec<Domain>>) {
+    for domain in domains.into_iter().flatten() {
+        let Some(check) = domain.check_expr() else {
+            continue;
+        };
+        let name = &domain.ident.rs;
+        let db_name = &domain.ident.db;
+        let ty = domain.rust_name;
+        let checks_doc = domain
+            .checks
+            .iter()
+            .map(|check| format!("/// - `{check}`\n"));
+        code!(w =>
+            /// Checks `value` against the constraints of the `$db_name` domain, without a
+            /// round trip to the database:
+            $($checks_doc)
+            pub fn check_$name(value: $ty) -> bool {
+                $check
+            }
+        );
+    }
+}
+
+/// Generates the module `name` with its `content`, either inline or, when modules are
+/// split, in its own file at `path` relative to the destination.
+fn gen_module(
+    w: &mut String,
+    files: &mut Vec<(String, String)>,
+    path: Option<String>,
+    attrs: &str,
+    name: &str,
+    content: impl FnOnce(&mut String),
+) {
+    match path {
+        Some(path) => {
+            let mut file = GENERATED_HEADER.to_string();
+            content(&mut file);
+            files.push((path, file));
+            code!(w => $attrs pub mod $name;);
+        }
+        None => code!(w =>
+            $attrs
+            pub mod $name {
+                $!content
+            }
+        ),
+    }
+}
+
+/// Nests the code of each module in the modules of its subdirectories, given modules sorted
+/// so that those of a subdirectory follow each other.
+fn nest_modules<'a>(modules: impl IntoIterator<Item = (&'a [String], String)>) -> String {
+    let mut w = String::new();
+    let mut open: &[String] = &[];
+    for (dirs, code) in modules {
+        let common = open.iter().zip(dirs).take_while(|(a, b)| a == b).count();
+        for _ in common..open.len() {
+            w.push('}');
+        }
+        for dir in &dirs[common..] {
+            write!(w, "pub mod {dir} {{").unwrap();
+        }
+        w.push_str(&code);
+        open = dirs;
+    }
+    for _ in open {
+        w.push('}');
+    }
+    w
+}
+
+/// Generates a deadpool-based `create_pool` helper configured from a typed settings struct.
+fn gen_pool_module(w: &mut impl Write, allows: &str) {
     code!(w =>
-        #[allow(clippy::all, clippy::pedantic)]
-        #[allow(unused_variables)]
-        #[allow(unused_imports)]
-        #[allow(dead_code)]
-        pub mod types {
-            $($!modules)
+        $allows
+        pub mod pool {
+            /// Whether TLS is used when connecting to the database.
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            pub enum TlsMode {
+                Disable,
+                Prefer,
+                Require,
+            }
+
+            /// Whether SCRAM channel binding is used when authenticating.
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            pub enum ChannelBinding {
+                Disable,
+                Prefer,
+                Require,
+            }
+
+            /// Settings used by `create_pool` to build a connection pool.
+            #[derive(Debug, Clone)]
+            pub struct PoolSettings {
+                pub host: String,
+                pub port: u16,
+                pub user: String,
+                pub password: Option<String>,
+                pub dbname: String,
+                pub tls_mode: TlsMode,
+                pub channel_binding: ChannelBinding,
+                pub pool_size: usize,
+            }
+
+            impl Default for PoolSettings {
+                fn default() -> Self {
+                    Self {
+                        host: String::from("localhost"),
+                        port: 5432,
+                        user: String::from("postgres"),
+                        password: None,
+                        dbname: String::from("postgres"),
+                        tls_mode: TlsMode::Prefer,
+                        channel_binding: ChannelBinding::Prefer,
+                        pool_size: 16,
+                    }
+                }
+            }
+
+            /// Creates a pool of connections compatible with the generated queries.
+            ///
+            /// The `tls` connector is only used when `tls_mode` is not `TlsMode::Disable`,
+            /// in which case `tokio_postgres::NoTls` can be passed.
+            pub fn create_pool<T>(
+                settings: &PoolSettings,
+                tls: T,
+            ) -> Result<deadpool_postgres::Pool, deadpool_postgres::CreatePoolError>
+            where
+                T: tokio_postgres::tls::MakeTlsConnect<tokio_postgres::Socket> + Clone + Sync + Send + 'static,
+                T::Stream: Sync + Send,
+                T::TlsConnect: Sync + Send,
+                <T::TlsConnect as tokio_postgres::tls::TlsConnect<tokio_postgres::Socket>>::Future: Send,
+            {
+                let mut cfg = deadpool_postgres::Config::new();
+                cfg.host = Some(settings.host.clone());
+                cfg.port = Some(settings.port);
+                cfg.user = Some(settings.user.clone());
+                cfg.password = settings.password.clone();
+                cfg.dbname = Some(settings.dbname.clone());
+                cfg.ssl_mode = Some(match settings.tls_mode {
+                    TlsMode::Disable => deadpool_postgres::SslMode::Disable,
+                    TlsMode::Prefer => deadpool_postgres::SslMode::Prefer,
+                    TlsMode::Require => deadpool_postgres::SslMode::Require,
+                });
+                cfg.channel_binding = Some(match settings.channel_binding {
+                    ChannelBinding::Disable => deadpool_postgres::ChannelBinding::Disable,
+                    ChannelBinding::Prefer => deadpool_postgres::ChannelBinding::Prefer,
+                    ChannelBinding::Require => deadpool_postgres::ChannelBinding::Require,
+                });
+                cfg.pool = Some(deadpool_postgres::PoolConfig::new(settings.pool_size));
+                cfg.create_pool(Some(deadpool_postgres::Runtime::Tokio1), tls)
+            }
         }
     );
 }
 
-pub(crate) fn generate(preparation: Preparation, settings: CodegenSettings) -> String {
-    let mut buff = "// This file was generated with `cornucopia`. Do not modify.\n\n".to_string();
-    let w = &mut buff;
-    // Generate database type
-    gen_type_modules(
-        w,
-        &preparation.types,
-        &GenCtx::new(1, settings.gen_async, settings.derive_ser),
-    );
-    // Generate queries
-    let query_modules = preparation.modules.iter().map(|module| {
-        move |w: &mut String| {
-            let name = &module.info.name;
-            let ctx = GenCtx::new(2, settings.gen_async, settings.derive_ser);
-            let params_string = module
-                .params
-                .values()
-                .map(|params| |w: &mut String| gen_params_struct(w, params,  &ctx));
+/// Type of the output of a script's query: its rows, or the number of affected rows.
+fn script_output_ty(module: &PreparedModule, query: &PreparedQuery, ctx: &GenCtx) -> String {
+    match &query.row {
+        Some((idx, _)) => {
+            let row = module.rows.get_index(*idx).unwrap().1;
+            let ty = if row.is_named {
+                row.path(ctx)
+            } else {
+                row.fields[0].own_struct(ctx)
+            };
+            format!("Vec<{ty}>")
+        }
+        None => "u64".to_string(),
+    }
+}
+
+/// Generates the struct holding the output of each query of a script.
+fn gen_script_output(
+    w: &mut String,
+    module: &PreparedModule,
+    script: &PreparedScript,
+    ctx: &GenCtx,
+) {
+    let name = script.ident.type_ident();
+    let queries = script.queries.iter().map(|idx| &module.queries[idx.value]);
+    let fields_name = queries.clone().map(|query| &query.ident.rs);
+    let fields_ty = queries.map(|query| script_output_ty(module, query, ctx));
+    let ser_str = if ctx.gen_derive {
+        "serde::Serialize,serde::Deserialize,"
+    } else {
+        ""
+    };
+    code!(w =>
+        #[derive($ser_str Debug, Clone, PartialEq)]
+        pub struct ${name}Output {
+            $(pub $fields_name: $fields_ty,)
+        }
+    );
+}
+
+/// Generates a function running the statements of a batch in a single round trip.
+fn gen_batch_fn(
+    w: &mut String,
+    module: &PreparedModule,
+    batch: &PreparedBatch,
+    ctx: &GenCtx,
+    settings: &CodegenSettings,
+) {
+    let (fn_async, fn_await, backend, client_mut) = if ctx.is_async {
+        ("async", ".await", "tokio_postgres", "")
+    } else {
+        ("", "", "postgres", "mut")
+    };
+    let name = &batch.ident.rs;
+    let bulk_client = ctx.bulk_client();
+    let sql = stmt_expr(&GenStmt::batch(module, batch), ctx, settings);
+    code!(w =>
+        /// Runs the statements of the batch in a single round trip. They run in an implicit
+        /// transaction, unless the batch controls its own transactions.
+        pub $fn_async fn $name<C: $bulk_client>(client: &$client_mut C) -> Result<(), $backend::Error> {
+            client.batch_execute($sql)$fn_await
+        }
+    );
+}
+
+/// Parameters of `query`, in bind order.
+fn query_params<'a>(
+    module: &'a PreparedModule,
+    query: &'a PreparedQuery,
+) -> impl Iterator<Item = &'a PreparedField> + Clone + 'a {
+    query.param.iter().flat_map(|(idx, order)| {
+        let fields = &module.params.get_index(*idx).unwrap().1.fields;
+        order.iter().map(|idx| &fields[*idx])
+    })
+}
+
+/// Generates a holder of every statement of a module, so that each is only prepared once
+/// per connection.
+fn gen_stmt_cache(w: &mut String, module: &PreparedModule) {
+    if module.queries.is_empty() {
+        return;
+    }
+    let fields_name = module.queries.values().map(|query| &query.ident.rs);
+    let fields_ty = module
+        .queries
+        .values()
+        .map(|query| format!("{}Stmt", query.ident.type_ident()));
+    let fields_init = fields_name.clone();
+    let fields_fn = fields_name.clone();
+    code!(w =>
+        /// Every statement of this module, prepared on first use and reused afterwards.
+        /// Prepared statements belong to a connection, keep one cache per connection.
+        pub struct StmtCache {
+            $(pub $fields_name: $fields_ty,)
+        }
+        impl StmtCache {
+            #[allow(deprecated)]
+            pub fn new() -> Self {
+                Self {
+                    $($fields_init: $fields_fn(),)
+                }
+            }
+        }
+        impl Default for StmtCache {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    );
+}
+
+/// Generates a facade binding every query of a module to a client, each method preparing its
+/// statement on first use and reusing it afterwards.
+fn gen_queries_facade(w: &mut String, module: &PreparedModule, ctx: &GenCtx) {
+    if module.queries.is_empty() {
+        return;
+    }
+    let (client_mut, fn_async, fn_await, backend, client) = if ctx.is_async {
+        ("", "async", ".await", "tokio_postgres", "self.client")
+    } else {
+        ("mut", "", "", "postgres", "&mut *self.client")
+    };
+    // Statements are held in a tuple, as fields named after the queries could clash with `client`
+    let stmts_ty = module
+        .queries
+        .values()
+        .map(|query| format!("{}Stmt", query.ident.type_ident()));
+    let stmts_init = module.queries.values().map(|query| &query.ident.rs);
+    let methods = module.queries.values().enumerate().map(|(i, query)| {
+        move |w: &mut String| {
+            let name = &query.ident.rs;
+            let params: Vec<_> = query_params(module, query).collect();
+            let traits = &mut Vec::new();
+            let params_ty: Vec<_> = params
+                .iter()
+                .map(|field| field.param_ergo_ty(traits, ctx))
+                .collect();
+            let params_name = params.iter().map(|field| &field.ident.rs);
+            let params_call = params_name.clone();
+            let traits_idx = (1..=traits.len()).map(idx_char);
+            let deprecated = query
+                .superseded_by
+                .as_ref()
+                .map(|newer| format!("#[deprecated(note = \"use `{newer}` instead\")]"))
+                .unwrap_or_default();
+            if let Some((idx, _)) = query.row {
+                let row = module.rows.get_index(idx).unwrap().1;
+                let row_struct = if row.is_named {
+                    row.path(ctx)
+                } else {
+                    row.fields[0].own_struct(ctx)
+                };
+                let row_name = &row.name;
+                let nb_params = params.len();
+                code!(w =>
+                    $deprecated
+                    pub fn $name<'a, $($traits_idx: $traits,)>(&'a mut self, $($params_name: &'a $params_ty,)) -> ${row_name}Query<'a, C, $row_struct, $nb_params> {
+                        self.stmts.$i.bind($client, $($params_call,))
+                    }
+                );
+            } else {
+                code!(w =>
+                    $deprecated
+                    pub $fn_async fn $name<'a, $($traits_idx: $traits,)>(&'a mut self, $($params_name: &'a $params_ty,)) -> Result<u64, $backend::Error> {
+                        self.stmts.$i.bind($client, $($params_call,))$fn_await
+                    }
+                );
+            }
+        }
+    });
+    code!(w =>
+        /// Every query of this module bound to a client, to pass around a single object
+        /// instead of importing each query function.
+        pub struct Queries<'c, C: GenericClient> {
+            client: &'c $client_mut C,
+            stmts: ($($stmts_ty,)),
+        }
+        impl<'c, C: GenericClient> Queries<'c, C> {
+            #[allow(deprecated)]
+            pub fn new(client: &'c $client_mut C) -> Self {
+                Self {
+                    client,
+                    stmts: ($($stmts_init(),)),
+                }
+            }
+            $($!methods)
+        }
+    );
+}
+
+/// Generates the function running the queries of a script in a single transaction.
+/// Parameters with the same name are shared by the script's queries.
+fn gen_script_fn(w: &mut String, module: &PreparedModule, script: &PreparedScript, ctx: &GenCtx) {
+    let (fn_async, fn_await, backend, client_ty, generic, transaction_mut, transaction_ref) =
+        if ctx.is_async {
+            (
+                "async",
+                ".await",
+                "tokio_postgres",
+                "tokio_postgres::Client",
+                "",
+                "",
+                "&transaction",
+            )
+        } else {
+            (
+                "",
+                "",
+                "postgres",
+                "C",
+                "C: GenericClient,",
+                "mut",
+                "&mut transaction",
+            )
+        };
+    let queries: Vec<_> = script
+        .queries
+        .iter()
+        .map(|idx| &module.queries[idx.value])
+        .collect();
+    let mut params: Vec<&PreparedField> = Vec::new();
+    for field in queries.iter().flat_map(|query| query_params(module, query)) {
+        if !params.iter().any(|it| it.ident.db == field.ident.db) {
+            params.push(field);
+        }
+    }
+    let traits = &mut Vec::new();
+    let params_ty: Vec<_> = params
+        .iter()
+        .map(|field| field.param_ergo_ty(traits, ctx))
+        .collect();
+    let params_name = params.iter().map(|field| &field.ident.rs);
+    let traits_idx = (1..=traits.len()).map(idx_char);
+    let steps = queries.iter().map(|query| {
+        move |w: &mut String| {
+            let name = &query.ident.rs;
+            let args = query_params(module, query).map(|field| &field.ident.rs);
+            let collect = if query.row.is_some() {
+                format!(".all(){fn_await}")
+            } else {
+                fn_await.to_string()
+            };
+            code!(w => let $name = $name().bind($transaction_ref, $($args,))$collect?;)
+        }
+    });
+    let fields_name = queries.iter().map(|query| &query.ident.rs);
+    let fn_name = &script.ident.rs;
+    let output = ctx.module_path(format!("{}Output", script.ident.type_ident()));
+    code!(w =>
+        pub $fn_async fn $fn_name<$generic $($traits_idx: $traits,)>(client: &mut $client_ty, $($params_name: &$params_ty,)) -> Result<$output, $backend::Error> {
+            let $transaction_mut transaction = client.transaction()$fn_await?;
+            $($!steps)
+            transaction.commit()$fn_await?;
+            Ok($output { $($fields_name,) })
+        }
+    );
+}
+
+fn partition_enum_name(table: &str) -> String {
+    format!("{}Partition", Ident::new(table.to_string()).type_ident())
+}
+
+/// Generates the enumeration of the partitions of a partitioned table, enumerated at generation time.
+fn gen_partition_enum(w: &mut String, table: &str, partitions: &[String]) {
+    let enum_name = partition_enum_name(table);
+    let variants: Vec<_> = partitions
+        .iter()
+        .map(|partition| Ident::new(partition.clone()).type_ident())
+        .collect();
+    let (all, arms) = (variants.iter(), variants.iter());
+    let names = partitions
+        .iter()
+        .map(|partition| partition.replace('\\', "\\\\").replace('"', "\\\""));
+    let nb_partitions = partitions.len();
+    code!(w =>
+        /// Partitions of the `$table` table.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $enum_name {
+            $($variants,)
+        }
+        impl $enum_name {
+            /// Every partition, in catalog order.
+            pub const ALL: [Self; $nb_partitions] = [$(Self::$all,)];
+            /// Name of the partition, schema-qualified if it is not in the search path.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Self::$arms => "$names",)
+                }
+            }
+        }
+    );
+}
+
+/// Generates the marker type of a `LISTEN`/`NOTIFY` channel, and its payload struct
+/// when its fields are declared inline.
+fn gen_channel(w: &mut String, channel: &ChannelAnnotation, settings: &CodegenSettings) {
+    let client = if settings.gen_sync {
+        "cornucopia_sync"
+    } else {
+        "cornucopia_async"
+    };
+    let name = &channel.name.value;
+    let struct_name = Ident::new(name.clone()).type_ident();
+    let payload = match &channel.payload {
+        ChannelPayload::Path(path) => path.value.clone(),
+        ChannelPayload::Fields(fields) => {
+            let payload = format!("{struct_name}Payload");
+            let fields = fields.iter().map(|field| {
+                let ident = Ident::new(field.name.value.clone());
+                let ty = if field.nullable {
+                    format!("Option<{}>", field.ty.value)
+                } else {
+                    field.ty.value.clone()
+                };
+                move |w: &mut String| {
+                    let rename = if ident.rs == ident.db {
+                        String::new()
+                    } else {
+                        format!("#[serde(rename = \"{}\")]", ident.db)
+                    };
+                    let default = if field.nullable {
+                        "#[serde(default)]"
+                    } else {
+                        ""
+                    };
+                    let rs = &ident.rs;
+                    code!(w => $rename $default pub $rs: $ty,)
+                }
+            });
+            code!(w =>
+                #[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq)]
+                pub struct $payload {
+                    $($!fields)
+                }
+            );
+            payload
+        }
+    };
+    code!(w =>
+        /// Notifications sent on the `$name` channel.
+        pub struct ${struct_name}Channel;
+        impl $client::notifications::Channel for ${struct_name}Channel {
+            const NAME: &'static str = "$name";
+            type Payload = $payload;
+        }
+    );
+}
+
+/// Generates the typed `listen_*` and `notify_*` helpers of a `LISTEN`/`NOTIFY` channel.
+fn gen_channel_fns(w: &mut String, channel: &ChannelAnnotation, ctx: &GenCtx) {
+    let client = ctx.client_name();
+    let name = &channel.name.value;
+    let ident = Ident::new(name.clone());
+    let fn_name = ident.rs.trim_start_matches("r#");
+    let channel_ty = ctx.module_path(format!("{}Channel", ident.type_ident()));
+    let payload = match &channel.payload {
+        ChannelPayload::Path(path) => path.value.clone(),
+        ChannelPayload::Fields(_) => ctx.module_path(format!("{}Payload", ident.type_ident())),
+    };
+    // A payload type declared by path may not implement `Serialize`, so the bound is
+    // deferred to the callers of `notify_*` instead of breaking the generated module.
+    let (notify_generics, notify_payload, notify_bounds) = match &channel.payload {
+        ChannelPayload::Path(_) => (
+            ", P".to_string(),
+            "P".to_string(),
+            format!("where {channel_ty}: {client}::notifications::Channel<Payload = P>, P: serde::Serialize,"),
+        ),
+        ChannelPayload::Fields(_) => (String::new(), payload.clone(), String::new()),
+    };
+    if ctx.is_async {
+        code!(w =>
+            /// Subscribes to the `$name` channel, then yields its payloads received through
+            /// `messages`, the messages polled from the connection of `client`.
+            pub async fn listen_$fn_name<C: GenericClient, S>(client: &C, messages: S) -> Result<impl futures::Stream<Item = Result<$payload, $client::notifications::Error>>, tokio_postgres::Error>
+            where
+                S: futures::Stream<Item = Result<tokio_postgres::AsyncMessage, tokio_postgres::Error>>,
+            {
+                $client::notifications::listen::<$channel_ty, C>(client).await?;
+                Ok($client::notifications::payloads::<$channel_ty, S>(messages))
+            }
+            /// Sends `payload` on the `$name` channel.
+            pub async fn notify_$fn_name<C: GenericClient $notify_generics>(client: &C, payload: &$notify_payload) -> Result<(), $client::notifications::Error> $notify_bounds {
+                $client::notifications::notify::<$channel_ty, C>(client, payload).await
+            }
+        );
+    } else {
+        code!(w =>
+            /// Subscribes to the `$name` channel, then blocks waiting for its payloads.
+            pub fn listen_$fn_name(client: &mut postgres::Client) -> Result<impl Iterator<Item = Result<$payload, $client::notifications::Error>> + '_, postgres::Error> {
+                $client::notifications::listen::<$channel_ty, _>(client)?;
+                Ok($client::notifications::payloads::<$channel_ty>(client))
+            }
+            /// Sends `payload` on the `$name` channel.
+            pub fn notify_$fn_name<C: GenericClient $notify_generics>(client: &mut C, payload: &$notify_payload) -> Result<(), $client::notifications::Error> $notify_bounds {
+                $client::notifications::notify::<$channel_ty, C>(client, payload)
+            }
+        );
+    }
+}
+
+/// Generates the record struct of a CDC table, decoded from logical replication changes.
+fn gen_cdc_table(w: &mut String, table: &PreparedCdcTable, settings: &CodegenSettings) {
+    let client = if settings.gen_sync {
+        "cornucopia_sync"
+    } else {
+        "cornucopia_async"
+    };
+    let PreparedCdcTable {
+        schema,
+        table,
+        struct_name,
+        fields,
+    } = table;
+    let ser_str = if settings.derive_ser {
+        "serde::Serialize,"
+    } else {
+        ""
+    };
+    let fields = fields.iter().map(|field| {
+        move |w: &mut String| {
+            let rename = if field.ident.rs == field.ident.db {
+                String::new()
+            } else {
+                format!("#[serde(rename = \"{}\")]", field.ident.db)
+            };
+            let rs = &field.ident.rs;
+            let ty = if field.is_nullable {
+                format!("Option<{}>", field.rust_ty)
+            } else {
+                field.rust_ty.to_string()
+            };
+            code!(w => $rename pub $rs: $ty,)
+        }
+    });
+    code!(w =>
+        /// A row of the `$schema.$table` table, as decoded from logical replication changes.
+        #[derive(serde::Deserialize, $ser_str Debug, Clone, PartialEq)]
+        pub struct $struct_name {
+            $($!fields)
+        }
+        impl $client::cdc::Table for $struct_name {
+            const SCHEMA: &'static str = "$schema";
+            const NAME: &'static str = "$table";
+        }
+    );
+}
+
+/// Generates a registry listing every generated query, for introspection purposes.
+fn gen_registry_module(w: &mut String, preparation: &Preparation, settings: &CodegenSettings) {
+    let allows = module_allows(settings, "#[allow(clippy::all, clippy::pedantic)]");
+    let client = if settings.gen_sync {
+        "cornucopia_sync"
+    } else {
+        "cornucopia_async"
+    };
+    let queries = preparation.modules.iter().flat_map(|module| {
+        module.queries.values().map(move |query| {
+            let module_name = module.info.qualified_name();
+            let name = &query.ident.db;
+            let sql = stmt_sql(
+                &GenStmt::query(module, query, StmtVariant::Main, &query.sql),
+                settings,
+            );
+            let search_path = match &module.search_path {
+                Some(search_path) => format!("Some({search_path:?})"),
+                None => "None".to_string(),
+            };
+            move |w: &mut String| {
+                code!(w => $client::introspection::QueryInfo { module: "$module_name", name: "$name", sql: $sql, search_path: $search_path },)
+            }
+        })
+    });
+    code!(w =>
+        $allows
+        pub mod registry {
+            /// Every query generated by cornucopia, in declaration order.
+            pub const QUERIES: &[$client::introspection::QueryInfo] = &[
+                $($!queries)
+            ];
+        }
+    );
+}
+
+/// Path of the concurrency limit of a query, relative to the root of the generated code.
+fn concurrency_limit_path(module_name: &str, query_name: &str) -> String {
+    format!(
+        "concurrency::{module_name}::{}",
+        query_name.to_shouty_snake_case()
+    )
+}
+
+/// Generates a registry holding the concurrency limit of every query annotated with
+/// `--! max_concurrency`, for async code.
+fn gen_concurrency_module(w: &mut String, preparation: &Preparation) {
+    let modules = preparation.modules.iter().filter_map(|module| {
+        let limits: Vec<_> = module
+            .queries
+            .values()
+            .filter_map(|query| {
+                query
+                    .max_concurrency
+                    .map(|max| (query.ident.rs.to_shouty_snake_case(), max))
+            })
+            .collect();
+        if limits.is_empty() {
+            return None;
+        }
+        let name = &module.info.name;
+        let statics = limits.iter().map(|(name, max)| {
+            move |w: &mut String| {
+                code!(w => pub static $name: cornucopia_async::concurrency::ConcurrencyLimit = cornucopia_async::concurrency::ConcurrencyLimit::new($max);)
+            }
+        });
+        let code = &mut String::new();
+        code!(code =>
+            pub mod $name {
+                $($!statics)
+            }
+        );
+        Some((module.info.dirs.as_slice(), std::mem::take(code)))
+    });
+    let modules: Vec<_> = modules.collect();
+    if modules.is_empty() {
+        return;
+    }
+    let modules = nest_modules(modules);
+    code!(w =>
+        /// Concurrency limits of the queries annotated with `--! max_concurrency`.
+        pub mod concurrency {
+            $modules
+        }
+    );
+}
+
+/// Expression building `ty` at runtime, as its decoding code expects it. The OIDs of
+/// custom types, which differ between databases, are left out.
+fn type_expr(ty: &Type) -> String {
+    if Type::from_oid(ty.oid()).is_some() {
+        return format!("postgres_types::Type::from_oid({}).unwrap()", ty.oid());
+    }
+    let kind = match ty.kind() {
+        Kind::Enum(variants) => {
+            let variants: Vec<_> = variants.iter().map(|v| format!("{v:?}.into()")).collect();
+            format!("postgres_types::Kind::Enum(vec![{}])", variants.join(", "))
+        }
+        Kind::Array(inner) => format!("postgres_types::Kind::Array({})", type_expr(inner)),
+        Kind::Domain(inner) => format!("postgres_types::Kind::Domain({})", type_expr(inner)),
+        Kind::Range(inner) => format!("postgres_types::Kind::Range({})", type_expr(inner)),
+        Kind::Composite(fields) => {
+            let fields: Vec<_> = fields
+                .iter()
+                .map(|f| {
+                    format!(
+                        "postgres_types::Field::new({:?}.into(), {})",
+                        f.name(),
+                        type_expr(f.type_())
+                    )
+                })
+                .collect();
+            format!(
+                "postgres_types::Kind::Composite(vec![{}])",
+                fields.join(", ")
+            )
+        }
+        _ => "postgres_types::Kind::Simple".to_string(),
+    };
+    format!(
+        "postgres_types::Type::new({:?}.into(), 0, {kind}, {:?}.into())",
+        ty.name(),
+        ty.schema()
+    )
+}
+
+/// Generates criterion benchmarks decoding each canned payload into the borrowed struct
+/// of its type, then into the owned one as queries do.
+fn gen_benches_module(w: &mut String, benchmarks: &[Benchmark]) {
+    let benches = benchmarks.iter().map(|Benchmark { path, target }| {
+        move |w: &mut String| {
+            let (types, payloads, borrowed) = match target {
+                BenchmarkTarget::Composite(payload) => (
+                    vec![type_expr(&payload.ty)],
+                    vec![&payload.bytes],
+                    format!(
+                        "<super::{path}Borrowed as postgres_types::FromSql>::from_sql(&types[0], std::hint::black_box(payloads[0])).unwrap()"
+                    ),
+                ),
+                BenchmarkTarget::Row(columns) => {
+                    let fields: Vec<_> = columns
+                        .iter()
+                        .enumerate()
+                        .map(|(i, (name, _))| {
+                            format!("{name}: postgres_types::FromSql::from_sql_nullable(&types[{i}], Some(std::hint::black_box(payloads[{i}]))).unwrap()")
+                        })
+                        .collect();
+                    (
+                        columns.iter().map(|(_, payload)| type_expr(&payload.ty)).collect(),
+                        columns.iter().map(|(_, payload)| &payload.bytes).collect(),
+                        format!("super::{path}Borrowed {{ {} }}", fields.join(", ")),
+                    )
+                }
+            };
+            let nb_payloads = payloads.len();
+            let payloads = payloads.iter().map(|bytes| format!("&{bytes:?}"));
+            let borrowed_name = format!("\"{path}/borrowed\"");
+            let owned_name = format!("\"{path}/owned\"");
+            code!(w =>
+                {
+                    let types = [$($types,)];
+                    let payloads: [&[u8]; $nb_payloads] = [$($payloads,)];
+                    let len: usize = payloads.iter().map(|it| it.len()).sum();
+                    group.throughput(criterion::Throughput::Bytes(len as u64));
+                    group.bench_function($borrowed_name, |b| b.iter(|| $borrowed));
+                    group.bench_function($owned_name, |b| b.iter(|| super::$path::from($borrowed)));
+                }
+            );
+        }
+    });
+    code!(w =>
+        /// Decode benchmarks of the non-Copy rows and composite types, against payloads canned
+        /// from the database at generation time. Run them from a criterion benchmark:
+        ///
+        /// ```ignore
+        /// criterion::criterion_group!(benches, cornucopia::benches::decode);
+        /// criterion::criterion_main!(benches);
+        /// ```
+        pub mod benches {
+            /// Measures the throughput of decoding each row and composite type into its
+            /// borrowed struct, and into its owned struct through the borrowed one.
+            pub fn decode(c: &mut criterion::Criterion) {
+                let mut group = c.benchmark_group("decode");
+                $($!benches)
+                group.finish();
+            }
+        }
+    );
+}
+
+/// Generates the search path the queries of a module were prepared with.
+fn gen_search_path(w: &mut String, search_path: &str) {
+    let search_path = format!("{search_path:?}");
+    code!(w =>
+        /// Search path the queries of this module were prepared with. Connections running them
+        /// must use it, for instance with `SET search_path TO` followed by it.
+        pub const SEARCH_PATH: &str = $search_path;
+    );
+}
+
+fn gen_schema_info_module(w: &mut String, version: &str, settings: &CodegenSettings) {
+    let allows = module_allows(settings, "#[allow(clippy::all, clippy::pedantic)]");
+    let label = version
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    let line = format!("{:?}", format!("db_schema_info{{version=\"{label}\"}} 1"));
+    let version = format!("{version:?}");
+    code!(w =>
+        $allows
+        pub mod schema_info {
+            /// Version of the database schema this code was generated against.
+            pub const SCHEMA_VERSION: &str = $version;
+
+            /// OpenMetrics sample exposing `SCHEMA_VERSION` as an info gauge.
+            pub fn openmetrics() -> &'static str {
+                $line
+            }
+        }
+    );
+}
+
+/// Type and value of a smoke test parameter, if we know how to build one.
+fn test_value(
+    ty: &CornucopiaType,
+    is_inner_nullable: bool,
+    types: &IndexMap<String, Vec<PreparedType>>,
+) -> Option<(String, String)> {
+    Some(match ty {
+        CornucopiaType::Simple {
+            pg_ty, rust_name, ..
+        } => {
+            let value = match *pg_ty {
+                Type::TEXT | Type::VARCHAR => return Some(("&str".into(), "\"\"".into())),
+                Type::BYTEA => "Vec::new()",
+                Type::JSON | Type::JSONB => "serde_json::Value::Null",
+                // Every chrono type defaults to the Unix epoch
+                _ if rust_name.starts_with("chrono::") => "Default::default()",
+                // Parameters are given as the client's `Numeric` wrapper
+                _ if *rust_name == BIG_DECIMAL => return Some(("_".into(), "Default::default()".into())),
+                Type::TIMESTAMP => "time::PrimitiveDateTime::new(time::Date::from_ordinal_date(1970, 1).unwrap(), time::Time::MIDNIGHT)",
+                Type::TIMESTAMPTZ => "time::OffsetDateTime::UNIX_EPOCH",
+                Type::DATE => "time::Date::from_ordinal_date(1970, 1).unwrap()",
+                Type::TIME => "time::Time::MIDNIGHT",
+                Type::INET => "std::net::IpAddr::V4(std::net::Ipv4Addr::UNSPECIFIED)",
+                Type::MACADDR => "eui48::MacAddress::nil()",
+                _ => "Default::default()",
+            };
+            ((*rust_name).to_string(), value.to_string())
+        }
+        CornucopiaType::Array { inner } => {
+            let (inner, _) = test_value(inner, false, types)?;
+            let inner = if is_inner_nullable {
+                format!("Option<{inner}>")
+            } else {
+                inner
+            };
+            (format!("Vec<{inner}>"), "Vec::new()".to_string())
+        }
+        CornucopiaType::Domain { inner, .. } => test_value(inner, false, types)?,
+        // Ranges and encrypted values come from the client crate and overriding types from
+        // yours, we don't try to build them
+        CornucopiaType::Range { .. }
+        | CornucopiaType::Override { .. }
+        | CornucopiaType::Encrypted { .. } => return None,
+        CornucopiaType::Custom {
+            pg_ty, struct_name, ..
+        } => {
+            let schema = pg_ty.schema();
+            let ty = types
+                .get(schema)?
+                .iter()
+                .find(|it| it.name == pg_ty.name())?;
+            match &ty.content {
+                PreparedContent::Enum(variants) => {
+                    let path = format!("super::types::{schema}::{struct_name}");
+                    let value = format!("{path}::{}", variants.first()?.rs);
+                    (path, value)
+                }
+                // Composites may contain anything, we don't try to build them
+                PreparedContent::Composite(_) => return None,
+            }
+        }
+    })
+}
+
+/// Generates one smoke test per query, running it against `DATABASE_URL` inside a
+/// rolled-back transaction. Queries with parameters we can't build are skipped, and errors
+/// caused by the placeholder values violating the schema's constraints are ignored. The tests
+/// are ignored by default, so they are run with `cargo test -- --ignored`.
+///
+/// A stress test, sized by `CORNUCOPIA_STRESS_PARALLELISM`, also runs every query
+/// from that many concurrent transactions sharing a small pool of connections, in alternating
+/// orders, so that queries deadlocking with each other are caught.
+fn gen_tests_module(w: &mut String, preparation: &Preparation, settings: &CodegenSettings) {
+    let allows = module_allows(settings, "#[allow(clippy::all, clippy::pedantic)]");
+    let is_async = !settings.gen_sync;
+    let flavor = if settings.gen_sync && settings.gen_async {
+        if is_async {
+            "async_::"
+        } else {
+            "sync::"
+        }
+    } else {
+        ""
+    };
+    let (attribute, fn_async, fn_await, client_mut, connect, transaction_ty, error_ty) = if is_async
+    {
+        (
+            "#[tokio::test]",
+            "async",
+            ".await",
+            "",
+            "let (mut client, connection) = tokio_postgres::connect(&url, tokio_postgres::NoTls).await.unwrap();
+            tokio::spawn(connection);",
+            "&tokio_postgres::Transaction<'_>",
+
+            "tokio_postgres::Error",
+        )
+    } else {
+        (
+            "#[test]",
+            "",
+            "",
+            "mut",
+            "let mut client = postgres::Client::connect(&url, postgres::NoTls).unwrap();",
+            "&mut postgres::Transaction<'_>",
+            "postgres::Error",
+        )
+    };
+    let tests: Vec<_> = preparation
+        .modules
+        .iter()
+        .flat_map(|module| {
+            module.queries.values().filter_map(move |query| {
+                let params: Vec<_> = match &query.param {
+                    Some((idx, order)) => {
+                        let fields = &module.params.get_index(*idx).unwrap().1.fields;
+                        order
+                            .iter()
+                            .map(|idx| {
+                                let field = &fields[*idx];
+                                let (ty, value) = test_value(
+                                    &field.ty,
+                                    field.is_inner_nullable,
+                                    &preparation.types,
+                                )?;
+                                Some(if field.is_nullable {
+                                    (format!("Option<{ty}>"), format!("Some({value})"))
+                                } else {
+                                    (ty, value)
+                                })
+                            })
+                            .collect::<Option<_>>()?
+                    }
+                    None => Vec::new(),
+                };
+                let test_name = format!(
+                    "{}_{}",
+                    module.info.module_path().join("_"),
+                    query.ident.rs.trim_start_matches("r#")
+                );
+                Some((module, query, params, test_name))
+            })
+        })
+        .collect();
+    let runs = tests.iter().map(|(module, query, params, test_name)| {
+        move |w: &mut String| {
+            let module_name = module.info.qualified_name();
+            let query_name = &query.ident.rs;
+            let params_name = (0..params.len()).map(|i| format!("p{i}"));
+            let args = params_name.clone();
+            let params_ty = params.iter().map(|(ty, _)| ty);
+            let params_value = params.iter().map(|(_, value)| value);
+            let run = if query.row.is_some() { ".all()" } else { "" };
+            let deprecated = if query.superseded_by.is_some() {
+                "#[allow(deprecated)]"
+            } else {
+                ""
+            };
+            // Undone once the query ran, so that it doesn't leak into the next stress test query
+            let (set_search_path, reset_search_path) = match &module.search_path {
+                Some(search_path) => (
+                    format!(
+                        "transaction.batch_execute({:?}){fn_await}.unwrap();",
+                        format!("SET LOCAL search_path TO {search_path}")
+                    ),
+                    format!(
+                        "transaction.batch_execute(\"RESET search_path\"){fn_await}.unwrap();"
+                    ),
+                ),
+                None => (String::new(), String::new()),
+            };
+            code!(w =>
+                $deprecated
+                $fn_async fn run_$test_name(transaction: $transaction_ty) {
+                    $(let $params_name: $params_ty = $params_value;)
+                    transaction.batch_execute("SAVEPOINT smoke")$fn_await.unwrap();
+                    $set_search_path
+                    let result = super::queries::$module_name::$flavor$query_name()
+                        .bind(transaction, $(&$args,))
+                        $run$fn_await;
+                    match result {
+                        Ok(_) => {
+                            $reset_search_path
+                            transaction.batch_execute("RELEASE SAVEPOINT smoke")$fn_await.unwrap();
+                        }
+                        Err(err) if is_data_error(&err) => {
+                            transaction.batch_execute("ROLLBACK TO SAVEPOINT smoke")$fn_await.unwrap();
+                        }
+                        Err(err) => panic!("{err}"),
+                    }
+                }
+
+                $attribute
+                #[ignore = "requires DATABASE_URL"]
+                $fn_async fn $test_name() {
+                    let url = database_url();
+                    $connect
+                    let $client_mut transaction = client.transaction()$fn_await.unwrap();
+                    run_$test_name(&$client_mut transaction)$fn_await;
+                    transaction.rollback()$fn_await.unwrap();
+                }
+            );
+        }
+    });
+    let forward = tests.iter().map(|(.., test_name)| test_name);
+    let backward = forward.clone().rev();
+    let stress = move |w: &mut String| {
+        if is_async {
+            code!(w =>
+                #[tokio::test]
+                #[ignore = "requires DATABASE_URL and CORNUCOPIA_STRESS_PARALLELISM"]
+                async fn stress() {
+                    let (url, parallelism) = (database_url(), stress_parallelism());
+                    let (release, acquire) = tokio::sync::mpsc::channel(STRESS_POOL_SIZE);
+                    for _ in 0..STRESS_POOL_SIZE {
+                        let (client, connection) = tokio_postgres::connect(&url, tokio_postgres::NoTls).await.unwrap();
+                        tokio::spawn(connection);
+                        release.send(client).await.unwrap();
+                    }
+                    let acquire = std::sync::Arc::new(tokio::sync::Mutex::new(acquire));
+                    let workers: Vec<_> = (0..parallelism)
+                        .map(|worker| {
+                            let (acquire, release) = (acquire.clone(), release.clone());
+                            tokio::spawn(async move {
+                                let mut client = tokio::time::timeout(STRESS_TIMEOUT, async {
+                                    acquire.lock().await.recv().await.unwrap()
+                                })
+                                .await
+                                .expect("connection pool exhausted, queries may be deadlocked");
+                                let transaction = client.transaction().await.unwrap();
+                                if worker % 2 == 0 {
+                                    $(run_$forward(&transaction).await;)
+                                } else {
+                                    $(run_$backward(&transaction).await;)
+                                }
+                                transaction.rollback().await.unwrap();
+                                release.send(client).await.unwrap();
+                            })
+                        })
+                        .collect();
+                    for worker in workers {
+                        worker.await.unwrap();
+                    }
+                }
+            );
+        } else {
+            code!(w =>
+                #[test]
+                #[ignore = "requires DATABASE_URL and CORNUCOPIA_STRESS_PARALLELISM"]
+                fn stress() {
+                    let (url, parallelism) = (database_url(), stress_parallelism());
+                    let (release, acquire) = std::sync::mpsc::sync_channel(STRESS_POOL_SIZE);
+                    for _ in 0..STRESS_POOL_SIZE {
+                        let client = postgres::Client::connect(&url, postgres::NoTls).unwrap();
+                        release.send(client).unwrap();
+                    }
+                    let acquire = std::sync::Mutex::new(acquire);
+                    std::thread::scope(|scope| {
+                        for worker in 0..parallelism {
+                            let (acquire, release) = (&acquire, release.clone());
+                            scope.spawn(move || {
+                                let mut client = acquire
+                                    .lock()
+                                    .unwrap()
+                                    .recv_timeout(STRESS_TIMEOUT)
+                                    .expect("connection pool exhausted, queries may be deadlocked");
+                                let mut transaction = client.transaction().unwrap();
+                                if worker % 2 == 0 {
+                                    $(run_$forward(&mut transaction);)
+                                } else {
+                                    $(run_$backward(&mut transaction);)
+                                }
+                                transaction.rollback().unwrap();
+                                release.send(client).unwrap();
+                            });
+                        }
+                    });
+                }
+            );
+        }
+    };
+    code!(w =>
+        #[cfg(test)]
+        $allows
+        mod generated_tests {
+            /// Number of connections shared by the stress test workers
+            const STRESS_POOL_SIZE: usize = 2;
+            /// Maximum time a stress test worker waits for a connection
+            const STRESS_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+            fn database_url() -> String {
+                std::env::var("DATABASE_URL")
+                    .expect("DATABASE_URL must be set to run the cornucopia generated tests")
+            }
+
+            /// Placeholder values can't satisfy the constraints of the schema, so data
+            /// exceptions and integrity constraint violations don't fail the tests.
+            fn is_data_error(err: &$error_ty) -> bool {
+                err.code()
+                    .is_some_and(|code| matches!(&code.code()[..2], "22" | "23"))
+            }
+
+            fn stress_parallelism() -> usize {
+                std::env::var("CORNUCOPIA_STRESS_PARALLELISM")
+                    .expect("CORNUCOPIA_STRESS_PARALLELISM must be set to run the cornucopia stress test")
+                    .parse()
+                    .expect("CORNUCOPIA_STRESS_PARALLELISM must be a number")
+            }
+
+            $($!runs)
+            $!stress
+        }
+    );
+}
+
+/// Lints allowed by default in the types and queries modules.
+const QUERIES_ALLOWS: &str = "#[allow(clippy::all, clippy::pedantic)] #[allow(unused_variables)] #[allow(unused_imports)] #[allow(dead_code)]";
+
+/// Module level `#[allow]` attributes, dropped when lints are allowed for the whole file.
+fn module_allows<'a>(settings: &CodegenSettings, attrs: &'a str) -> &'a str {
+    if settings.lint_allows.is_some() {
+        ""
+    } else {
+        attrs
+    }
+}
+
+/// Header of every generated file.
+const GENERATED_HEADER: &str = "// This file was generated with `cornucopia`. Do not modify.\n\n";
+
+/// Generates the code of every prepared module, returning it along with the sidecar SQL files
+/// it includes, if any.
+pub(crate) fn generate(
+    mut preparation: Preparation,
+    settings: &CodegenSettings,
+) -> (String, Vec<(String, String)>) {
+    // Rows derive the traits of the settings too, serde's ones making them serializable
+    for row in preparation
+        .modules
+        .iter_mut()
+        .flat_map(|module| module.rows.values_mut())
+    {
+        for derive in &settings.row_derives {
+            if !row.derives.contains(derive) {
+                row.derives.push(derive.clone());
+            }
+        }
+        row.is_serialized |= row.derives.iter().any(|it| it.starts_with("serde::"));
+    }
+    if settings.normalize_sql {
+        for query in preparation
+            .modules
+            .iter_mut()
+            .flat_map(|module| module.queries.values_mut())
+        {
+            query.sql = normalize(&query.sql);
+            if let Some(partitioned) = &mut query.partitioned {
+                for sql in &mut partitioned.sqls {
+                    *sql = normalize(sql);
+                }
+            }
+            if let Some(pagination) = &mut query.pagination {
+                pagination.sql = normalize(&pagination.sql);
+            }
+            if let Some(copy) = &mut query.copy {
+                *copy = normalize(copy);
+            }
+        }
+        for batch in preparation
+            .modules
+            .iter_mut()
+            .flat_map(|module| &mut module.batches)
+        {
+            batch.sql = normalize(&batch.sql);
+        }
+    }
+    let mut buff = GENERATED_HEADER.to_string();
+    let mut files = Vec::new();
+    let w = &mut buff;
+    if settings.forbid_unsafe {
+        code!(w => #![forbid(unsafe_code)]);
+    }
+    if let Some(lints) = settings
+        .lint_allows
+        .as_deref()
+        .filter(|lints| !lints.is_empty())
+    {
+        let lints = lints.iter();
+        code!(w => #![allow($($lints,))]);
+    }
+    // Generate obfuscated SQL table
+    if settings.obfuscate_sql {
+        gen_sql_table(w, &preparation, settings);
+    }
+    // Generate pool helper
+    if settings.gen_pool && settings.gen_async {
+        gen_pool_module(
+            w,
+            module_allows(
+                settings,
+                "#[allow(clippy::all, clippy::pedantic)] #[allow(dead_code)]",
+            ),
+        );
+    }
+    // Generate database type, serializable as soon as one row is as they may be its fields
+    let derive_types = settings.derive_ser
+        || preparation
+            .modules
+            .iter()
+            .any(|module| module.rows.values().any(|row| row.is_serialized));
+    let types_ctx = GenCtx::new(1, settings.gen_async, derive_types).with_conversions(settings);
+    gen_module(
+        w,
+        &mut files,
+        settings.split_modules.then(|| "types.rs".to_string()),
+        module_allows(settings, QUERIES_ALLOWS),
+        "types",
+        |w| gen_type_modules(w, &preparation.types, &preparation.domains, &types_ctx),
+    );
+    // Generate queries
+    let query_modules = preparation.modules.iter().map(|module| {
+        move |w: &mut String| {
+            // Modules of query files in subdirectories are nested in the modules of these
+            let nesting = module.info.dirs.len() as u8;
+            let ctx = GenCtx::new(2, settings.gen_async, settings.derive_ser).with_conversions(settings).nested(nesting);
+            let params_string = module
+                .params
+                .values()
+                .filter(|params| has_params_struct(params, module, settings))
+                .map(|params| |w: &mut String| gen_params_struct(w, params, &ctx, settings));
             let rows_struct_string = module
                 .rows
                 .values()
@@ -774,7 +3339,7 @@ pub(crate) fn generate(preparation: Preparation, settings: CodegenSettings) -> S
             let sync_specific = |w: &mut String| {
                 let gen_specific = |depth: u8, is_async: bool| {
                     move |w: &mut String| {
-                        let ctx = GenCtx::new(depth, is_async, settings.derive_ser);
+                        let ctx = GenCtx::new(depth, is_async, settings.derive_ser).with_conversions(settings).nested(nesting);
                         let import = if is_async {
                             "use futures::{StreamExt, TryStreamExt};use futures; use cornucopia_async::GenericClient;"
                         } else {
@@ -783,14 +3348,49 @@ pub(crate) fn generate(preparation: Preparation, settings: CodegenSettings) -> S
                         let rows_query_string = module
                             .rows
                             .values()
-                            .map(|row| |w: &mut String| gen_row_query(w, row, &ctx));
+                            .map(|row| |w: &mut String| gen_row_query(w, row, &ctx, settings));
                         let queries_string = module.queries.values().map(|query| {
-                            |w: &mut String| gen_query_fn(w, module, query, &ctx)
+                            |w: &mut String| {
+                                gen_query_fn(
+                                    w,
+                                    module,
+                                    query,
+                                    &ctx,
+                                    settings,
+                                )
+                            }
                         });
+                        let scripts_string = module
+                            .scripts
+                            .iter()
+                            .map(|script| |w: &mut String| gen_script_fn(w, module, script, &ctx));
+                        let batches_string = module
+                            .batches
+                            .iter()
+                            .map(|batch| |w: &mut String| gen_batch_fn(w, module, batch, &ctx, settings));
+                        let channels_string = module
+                            .channels
+                            .iter()
+                            .map(|channel| |w: &mut String| gen_channel_fns(w, channel, &ctx));
+                        let stmt_cache = |w: &mut String| {
+                            if settings.gen_statement_cache {
+                                gen_stmt_cache(w, module);
+                            }
+                        };
+                        let facade = |w: &mut String| {
+                            if settings.gen_facade {
+                                gen_queries_facade(w, module, &ctx);
+                            }
+                        };
                         code!(w =>
                             $import
                             $($!rows_query_string)
                             $($!queries_string)
+                            $($!scripts_string)
+                            $($!batches_string)
+                            $($!channels_string)
+                            $!stmt_cache
+                            $!facade
                         )
                     }
                 };
@@ -818,23 +3418,92 @@ pub(crate) fn generate(preparation: Preparation, settings: CodegenSettings) -> S
                 }
             };
 
-            code!(w =>
-                pub mod $name {
-                    $($!params_string)
-                    $($!rows_struct_string)
-                    $!sync_specific
+            let channels = module
+                .channels
+                .iter()
+                .map(|channel| |w: &mut String| gen_channel(w, channel, settings));
+
+            let cdc_tables = module
+                .cdc_tables
+                .iter()
+                .map(|table| |w: &mut String| gen_cdc_table(w, table, settings));
+
+            let scripts_output = module
+                .scripts
+                .iter()
+                .map(|script| |w: &mut String| gen_script_output(w, module, script, &ctx));
+
+            let partitions = module
+                .partitions
+                .iter()
+                .map(|(table, partitions)| |w: &mut String| gen_partition_enum(w, table, partitions));
+
+            let search_path = |w: &mut String| {
+                if let Some(search_path) = &module.search_path {
+                    gen_search_path(w, search_path);
                 }
+            };
+
+            code!(w =>
+                $!search_path
+                $($!params_string)
+                $($!rows_struct_string)
+                $($!channels)
+                $($!cdc_tables)
+                $($!partitions)
+                $($!scripts_output)
+                $!sync_specific
             );
         }
     });
+    let mut queries = Vec::new();
+    for (module, content) in preparation.modules.iter().zip(query_modules) {
+        let name = &module.info.name;
+        let path = settings.split_modules.then(|| {
+            let dirs: String = module
+                .info
+                .dirs
+                .iter()
+                .map(|dir| format!("{dir}/"))
+                .collect();
+            format!("queries/{dirs}{}.rs", name.trim_start_matches("r#"))
+        });
+        let mut code = String::new();
+        gen_module(&mut code, &mut files, path, "", name, content);
+        queries.push((module.info.dirs.as_slice(), code));
+    }
+    let queries = nest_modules(queries);
+    let allows = module_allows(settings, QUERIES_ALLOWS);
     code!(w =>
-        #[allow(clippy::all, clippy::pedantic)]
-        #[allow(unused_variables)]
-        #[allow(unused_imports)]
-        #[allow(dead_code)]
+        $allows
         pub mod queries {
-            $($!query_modules)
+            $queries
         }
     );
-    buff
+    // Generate query registry
+    if settings.gen_registry {
+        gen_registry_module(w, &preparation, settings);
+    }
+    // Generate concurrency limits
+    if settings.gen_async {
+        gen_concurrency_module(w, &preparation);
+    }
+    // Generate schema version
+    if let Some(version) = &preparation.schema_version {
+        gen_schema_info_module(w, version, settings);
+    }
+    // Generate decode benchmarks
+    if settings.gen_benches {
+        gen_benches_module(w, &preparation.benchmarks);
+    }
+    // Generate smoke tests
+    if settings.gen_tests {
+        gen_tests_module(w, &preparation, settings);
+    }
+    files.extend(sql_files(&preparation, settings));
+    if let Some(name) = &settings.crate_name {
+        let manifest = manifest(name, &preparation, settings);
+        files.push(("../Cargo.toml".to_string(), manifest));
+    }
+    (buff, files)
 }
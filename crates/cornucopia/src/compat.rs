@@ -0,0 +1,257 @@
+use std::collections::BTreeMap;
+
+use quote::ToTokens;
+use syn::{FnArg, ImplItem, Item, Pat, ReturnType, Visibility};
+
+use self::error::Error;
+
+/// Public surface of a generated file, keyed by item path.
+#[derive(Default)]
+struct Api {
+    /// Functions and methods with their signature
+    fns: BTreeMap<String, Signature>,
+    /// Structs with their fields and types
+    structs: BTreeMap<String, Vec<(String, String)>>,
+    /// Enums with their variants
+    enums: BTreeMap<String, Vec<String>>,
+}
+
+/// Parameters of a function, in order and with their types, and its return type.
+struct Signature {
+    params: Vec<(String, String)>,
+    output: String,
+}
+
+impl Api {
+    fn parse(code: &str) -> Result<Self, syn::Error> {
+        let file = syn::parse_file(code)?;
+        let mut api = Self::default();
+        api.visit(&file.items, "");
+        Ok(api)
+    }
+
+    fn visit(&mut self, items: &[Item], path: &str) {
+        let path_of = |name: &dyn std::fmt::Display| {
+            if path.is_empty() {
+                name.to_string()
+            } else {
+                format!("{path}::{name}")
+            }
+        };
+        for item in items {
+            match item {
+                Item::Mod(it) if is_pub(&it.vis) => {
+                    if let Some((_, items)) = &it.content {
+                        self.visit(items, &path_of(&it.ident));
+                    }
+                }
+                Item::Fn(it) if is_pub(&it.vis) => {
+                    self.fns.insert(path_of(&it.sig.ident), signature(&it.sig));
+                }
+                Item::Struct(it) if is_pub(&it.vis) => {
+                    let fields = it
+                        .fields
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, field)| is_pub(&field.vis))
+                        .map(|(i, field)| {
+                            let name = field
+                                .ident
+                                .as_ref()
+                                .map_or_else(|| i.to_string(), ToString::to_string);
+                            (name, tokens(&field.ty))
+                        })
+                        .collect();
+                    self.structs.insert(path_of(&it.ident), fields);
+                }
+                Item::Enum(it) if is_pub(&it.vis) => {
+                    let variants = it.variants.iter().map(|v| v.ident.to_string()).collect();
+                    self.enums.insert(path_of(&it.ident), variants);
+                }
+                Item::Impl(it) if it.trait_.is_none() => {
+                    let ty = tokens(&it.self_ty);
+                    for item in &it.items {
+                        if let ImplItem::Fn(method) = item {
+                            if is_pub(&method.vis) {
+                                let name = path_of(&format!("{ty}::{}", method.sig.ident));
+                                self.fns.insert(name, signature(&method.sig));
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn is_pub(vis: &Visibility) -> bool {
+    matches!(vis, Visibility::Public(_))
+}
+
+/// Renders tokens in a readable and formatting-independent way.
+fn tokens(it: &impl ToTokens) -> String {
+    let mut rendered = it.to_token_stream().to_string();
+    for (from, to) in [
+        (" < >", ""),
+        (" :: ", "::"),
+        (":: ", "::"),
+        ("& ", "&"),
+        (" <", "<"),
+        ("< ", "<"),
+        (" >", ">"),
+        (" ,", ","),
+        ("fn (", "fn("),
+        ("( ", "("),
+        (" )", ")"),
+    ] {
+        rendered = rendered.replace(from, to);
+    }
+    rendered
+}
+
+fn signature(sig: &syn::Signature) -> Signature {
+    let params = sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(arg) => match &*arg.pat {
+                Pat::Ident(pat) => Some((pat.ident.to_string(), tokens(&arg.ty))),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+    let output = match &sig.output {
+        ReturnType::Default => String::from("()"),
+        ReturnType::Type(_, ty) => tokens(ty),
+    };
+    Signature { params, output }
+}
+
+/// Lists the changes between `old` and `new` fields that break existing code.
+/// New fields are only breaking when `additions_break` is set, and moved fields when
+/// `positional` is set, such as the parameters of a function.
+fn compare_fields(
+    changes: &mut Vec<String>,
+    path: &str,
+    kind: &str,
+    old: &[(String, String)],
+    new: &[(String, String)],
+    additions_break: bool,
+    positional: bool,
+) {
+    for (old_idx, (name, old_ty)) in old.iter().enumerate() {
+        let Some((new_idx, (_, new_ty))) = new.iter().enumerate().find(|(_, (n, _))| n == name)
+        else {
+            changes.push(format!("removed {kind} `{name}` from `{path}`"));
+            continue;
+        };
+        if new_ty != old_ty {
+            changes.push(format!(
+                "changed type of {kind} `{name}` in `{path}` from `{old_ty}` to `{new_ty}`"
+            ));
+        }
+        if positional && new_idx != old_idx {
+            changes.push(format!(
+                "moved {kind} `{name}` of `{path}` from position {} to {}",
+                old_idx + 1,
+                new_idx + 1
+            ));
+        }
+    }
+    if additions_break {
+        for (name, _) in new {
+            if !old.iter().any(|(n, _)| n == name) {
+                changes.push(format!("new required {kind} `{name}` in `{path}`"));
+            }
+        }
+    }
+}
+
+/// Compares the API of the `old` and `new` generated code, returning the list of breaking changes.
+pub(crate) fn breaking_changes(old: &str, new: &str) -> Result<Vec<String>, Error> {
+    let old = Api::parse(old).map_err(|err| Error::Parse { err })?;
+    let new = Api::parse(new).expect("generated code is valid Rust");
+    let mut changes = Vec::new();
+
+    for (path, old_sig) in &old.fns {
+        match new.fns.get(path) {
+            None => changes.push(format!("removed function `{path}`")),
+            Some(new_sig) => {
+                compare_fields(
+                    &mut changes,
+                    path,
+                    "parameter",
+                    &old_sig.params,
+                    &new_sig.params,
+                    true,
+                    true,
+                );
+                if new_sig.output != old_sig.output {
+                    changes.push(format!(
+                        "changed return type of `{path}` from `{}` to `{}`",
+                        old_sig.output, new_sig.output
+                    ));
+                }
+            }
+        }
+    }
+    for (path, old_fields) in &old.structs {
+        match new.structs.get(path) {
+            None => changes.push(format!("removed struct `{path}`")),
+            Some(new_fields) => {
+                // New fields only force callers to change the structs they build themselves
+                let is_params = path.ends_with("Params");
+                compare_fields(
+                    &mut changes,
+                    path,
+                    "field",
+                    old_fields,
+                    new_fields,
+                    is_params,
+                    false,
+                );
+            }
+        }
+    }
+    for (path, old_variants) in &old.enums {
+        match new.enums.get(path) {
+            None => changes.push(format!("removed enum `{path}`")),
+            Some(new_variants) => {
+                for variant in old_variants {
+                    if !new_variants.contains(variant) {
+                        changes.push(format!("removed variant `{variant}` from `{path}`"));
+                    }
+                }
+            }
+        }
+    }
+    Ok(changes)
+}
+
+pub(crate) mod error {
+    use std::path::PathBuf;
+
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("Couldn't read previously generated file `{path}`: ({err})")]
+        #[diagnostic(
+            code(cornucopia::compat::read),
+            help("check that the path given to `--compat-report` points to a previously generated file")
+        )]
+        Read { path: PathBuf, err: std::io::Error },
+        #[error("Couldn't parse previously generated file: {err}")]
+        #[diagnostic(
+            code(cornucopia::compat::parse),
+            help("the previous file must be Rust code generated by cornucopia")
+        )]
+        Parse { err: syn::Error },
+        #[error("Found {} breaking change(s) in the generated API", changes.len())]
+        #[diagnostic(code(cornucopia::compat::breaking), help("- {}", changes.join("\n- ")))]
+        Breaking { changes: Vec<String> },
+    }
+}
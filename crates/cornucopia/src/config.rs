@@ -0,0 +1,360 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::{
+    container::ClusterSettings, manifest::crate_name, CodegenSettings, DecimalCrate, ParamSyntax,
+    TimeCrate,
+};
+
+use self::error::Error;
+
+/// Name of the configuration file read from the current directory by the CLI.
+pub(crate) const CONFIG_FILE: &str = "cornucopia.toml";
+
+/// Settings shared by every invocation, typically read from a `cornucopia.toml` file so
+/// that they don't have to be passed each time. Keys are named after the CLI's flags:
+///
+/// ```toml
+/// queries-path = "queries/"
+/// destination = "src/cornucopia.rs"
+/// schema-files = ["schema.sql"]
+/// sync = true
+/// serialize = true
+/// param-syntax = "dollar"
+/// ```
+///
+/// Paths are relative to the current directory. Unset keys keep the CLI's defaults. The
+/// `locale`, `encoding` and `timezone` of the managed container's database can only be set
+/// here, see [`ClusterSettings`].
+///
+/// Named profiles override any of these keys, so that a single file serves every
+/// environment. They are selected with `--profile`, see [`Config::load_profile`]:
+///
+/// ```toml
+/// schema-files = ["schema.sql"]
+///
+/// [profile.ci]
+/// podman = true
+///
+/// [profile.staging]
+/// url = "postgres://app@staging/app"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    pub podman: Option<bool>,
+    pub queries_path: Option<PathBuf>,
+    pub destination: Option<PathBuf>,
+    /// Folder of a `mod.rs` written with the `types` module and each query module in their
+    /// own file, replacing `destination`
+    pub destination_dir: Option<PathBuf>,
+    /// Folder of a publishable crate whose `src/lib.rs` is written with the `types` module
+    /// and each query module in their own file, along with its `Cargo.toml`, replacing
+    /// `destination`
+    pub destination_crate: Option<PathBuf>,
+    /// Inherit the dependencies of the destination crate from its workspace
+    pub workspace_dependencies: Option<bool>,
+    /// SQL files containing the database schema, loaded into the managed container, or
+    /// folders of migrations loaded in deploy order
+    pub schema_files: Vec<PathBuf>,
+    pub sync: Option<bool>,
+    pub r#async: Option<bool>,
+    pub serialize: Option<bool>,
+    pub pool: Option<bool>,
+    pub statement_prefix: Option<String>,
+    pub param_syntax: Option<ParamSyntax>,
+    pub tests: Option<bool>,
+    pub registry: Option<bool>,
+    pub partitions: Option<bool>,
+    pub normalize_sql: Option<bool>,
+    pub external_sql: Option<String>,
+    pub obfuscate_sql: Option<bool>,
+    pub replicas: Option<bool>,
+    pub maps: Option<bool>,
+    pub csv: Option<bool>,
+    pub arrow: Option<bool>,
+    pub sqlx: Option<bool>,
+    pub diesel: Option<bool>,
+    pub explain: Option<bool>,
+    pub statement_cache: Option<bool>,
+    pub facade: Option<bool>,
+    pub schema_info: Option<bool>,
+    pub benches: Option<bool>,
+    pub infer_nullability: Option<bool>,
+    pub no_params_structs: Option<bool>,
+    pub params_struct_threshold: Option<usize>,
+    pub cache: Option<bool>,
+    pub domain_checks: Option<bool>,
+    pub text_limits: Option<bool>,
+    pub tenant_schema: Option<String>,
+    pub search_path: Option<Vec<String>>,
+    /// Traits every row struct derives, such as `Hash`
+    pub row_derives: Option<Vec<String>>,
+    pub time_crate: Option<TimeCrate>,
+    pub decimal_crate: Option<DecimalCrate>,
+    pub lint_allows: Option<Vec<String>>,
+    pub forbid_unsafe: Option<bool>,
+    /// Postgres url to your own database, used by the commands taking a `--url`
+    pub url: Option<String>,
+    /// Postgres url to the managed container as your application's role, used by the
+    /// commands taking an `--app-url`
+    pub app_url: Option<String>,
+    pub locale: Option<String>,
+    pub encoding: Option<String>,
+    pub timezone: Option<String>,
+}
+
+impl Config {
+    /// Reads a configuration file, ignoring its profiles.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::load_profile(path, None)
+    }
+
+    /// Reads a configuration file, whose `[profile.<name>]` table overrides the other keys
+    /// if some `profile` is given. Unlike flags, profiles can disable switches.
+    pub fn load_profile(path: impl AsRef<Path>, profile: Option<&str>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|err| Error::Read {
+            path: path.to_owned(),
+            err,
+        })?;
+        let parse_err = |err| Error::Parse {
+            path: path.to_owned(),
+            err,
+        };
+        let mut table: toml::Table = toml::from_str(&content).map_err(parse_err)?;
+        let profiles = table.remove("profile");
+        if let Some(name) = profile {
+            let overrides = profiles
+                .as_ref()
+                .and_then(|profiles| profiles.get(name))
+                .and_then(toml::Value::as_table)
+                .ok_or_else(|| Error::UnknownProfile {
+                    path: path.to_owned(),
+                    profile: name.to_string(),
+                })?;
+            merge_tables(&mut table, overrides.clone());
+        }
+        toml::Value::Table(table).try_into().map_err(parse_err)
+    }
+
+    /// Overrides this configuration with the keys `other` sets, such as the flags of a CLI
+    /// invocation.
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        // A destination file, folder and crate override each other
+        let (destination, destination_dir, destination_crate) = if other.destination.is_some()
+            || other.destination_dir.is_some()
+            || other.destination_crate.is_some()
+        {
+            (
+                other.destination,
+                other.destination_dir,
+                other.destination_crate,
+            )
+        } else {
+            (
+                self.destination,
+                self.destination_dir,
+                self.destination_crate,
+            )
+        };
+        Self {
+            podman: other.podman.or(self.podman),
+            queries_path: other.queries_path.or(self.queries_path),
+            destination,
+            destination_dir,
+            destination_crate,
+            workspace_dependencies: other.workspace_dependencies.or(self.workspace_dependencies),
+            schema_files: if other.schema_files.is_empty() {
+                self.schema_files
+            } else {
+                other.schema_files
+            },
+            sync: other.sync.or(self.sync),
+            r#async: other.r#async.or(self.r#async),
+            serialize: other.serialize.or(self.serialize),
+            pool: other.pool.or(self.pool),
+            statement_prefix: other.statement_prefix.or(self.statement_prefix),
+            param_syntax: other.param_syntax.or(self.param_syntax),
+            tests: other.tests.or(self.tests),
+            registry: other.registry.or(self.registry),
+            partitions: other.partitions.or(self.partitions),
+            normalize_sql: other.normalize_sql.or(self.normalize_sql),
+            external_sql: other.external_sql.or(self.external_sql),
+            obfuscate_sql: other.obfuscate_sql.or(self.obfuscate_sql),
+            replicas: other.replicas.or(self.replicas),
+            maps: other.maps.or(self.maps),
+            csv: other.csv.or(self.csv),
+            arrow: other.arrow.or(self.arrow),
+            sqlx: other.sqlx.or(self.sqlx),
+            diesel: other.diesel.or(self.diesel),
+            explain: other.explain.or(self.explain),
+            statement_cache: other.statement_cache.or(self.statement_cache),
+            facade: other.facade.or(self.facade),
+            schema_info: other.schema_info.or(self.schema_info),
+            benches: other.benches.or(self.benches),
+            infer_nullability: other.infer_nullability.or(self.infer_nullability),
+            no_params_structs: other.no_params_structs.or(self.no_params_structs),
+            params_struct_threshold: other
+                .params_struct_threshold
+                .or(self.params_struct_threshold),
+            cache: other.cache.or(self.cache),
+            domain_checks: other.domain_checks.or(self.domain_checks),
+            text_limits: other.text_limits.or(self.text_limits),
+            tenant_schema: other.tenant_schema.or(self.tenant_schema),
+            search_path: other.search_path.or(self.search_path),
+            row_derives: other.row_derives.or(self.row_derives),
+            time_crate: other.time_crate.or(self.time_crate),
+            decimal_crate: other.decimal_crate.or(self.decimal_crate),
+            lint_allows: other.lint_allows.or(self.lint_allows),
+            forbid_unsafe: other.forbid_unsafe.or(self.forbid_unsafe),
+            url: other.url.or(self.url),
+            app_url: other.app_url.or(self.app_url),
+            locale: other.locale.or(self.locale),
+            encoding: other.encoding.or(self.encoding),
+            timezone: other.timezone.or(self.timezone),
+        }
+    }
+
+    /// Folder containing the queries, `queries/` by default.
+    pub fn queries_path(&self) -> PathBuf {
+        self.queries_path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("queries/"))
+    }
+
+    /// Destination file of the generated code, `src/cornucopia.rs` by default, the
+    /// `mod.rs` of the destination folder or the `src/lib.rs` of the destination crate.
+    pub fn destination(&self) -> PathBuf {
+        match (
+            &self.destination_crate,
+            &self.destination_dir,
+            &self.destination,
+        ) {
+            (Some(dir), _, _) => dir.join("src").join("lib.rs"),
+            (None, Some(dir), _) => dir.join("mod.rs"),
+            (None, None, Some(destination)) => destination.clone(),
+            (None, None, None) => PathBuf::from("src/cornucopia.rs"),
+        }
+    }
+
+    /// Settings the managed container's database cluster is initialized with.
+    pub fn cluster_settings(&self) -> ClusterSettings {
+        ClusterSettings {
+            locale: self.locale.clone(),
+            encoding: self.encoding.clone(),
+            timezone: self.timezone.clone(),
+        }
+    }
+
+    /// Code generation settings. Asynchronous code is generated unless only synchronous
+    /// code is requested.
+    pub fn settings(&self) -> CodegenSettings {
+        CodegenSettings {
+            gen_async: self.r#async.unwrap_or_default() || !self.sync.unwrap_or_default(),
+            gen_sync: self.sync.unwrap_or_default(),
+            derive_ser: self.serialize.unwrap_or_default(),
+            gen_pool: self.pool.unwrap_or_default(),
+            statement_prefix: self.statement_prefix.clone(),
+            param_syntax: self.param_syntax.unwrap_or_default(),
+            gen_tests: self.tests.unwrap_or_default(),
+            gen_registry: self.registry.unwrap_or_default(),
+            gen_partitions: self.partitions.unwrap_or_default(),
+            normalize_sql: self.normalize_sql.unwrap_or_default(),
+            external_sql: self.external_sql.clone(),
+            split_modules: self.destination_dir.is_some() || self.destination_crate.is_some(),
+            crate_name: self.destination_crate.as_deref().map(crate_name),
+            workspace_dependencies: self.workspace_dependencies.unwrap_or_default(),
+            obfuscate_sql: self.obfuscate_sql.unwrap_or_default(),
+            gen_replicas: self.replicas.unwrap_or_default(),
+            gen_maps: self.maps.unwrap_or_default(),
+            gen_csv: self.csv.unwrap_or_default(),
+            gen_arrow: self.arrow.unwrap_or_default(),
+            gen_sqlx: self.sqlx.unwrap_or_default(),
+            gen_diesel: self.diesel.unwrap_or_default(),
+            gen_explain: self.explain.unwrap_or_default(),
+            gen_statement_cache: self.statement_cache.unwrap_or_default(),
+            gen_facade: self.facade.unwrap_or_default(),
+            gen_schema_info: self.schema_info.unwrap_or_default(),
+            gen_benches: self.benches.unwrap_or_default(),
+            infer_nullability: self.infer_nullability.unwrap_or_default(),
+            gen_params_structs: !self.no_params_structs.unwrap_or_default(),
+            params_struct_threshold: self.params_struct_threshold,
+            gen_cache: self.cache.unwrap_or_default(),
+            gen_domain_checks: self.domain_checks.unwrap_or_default(),
+            gen_text_limits: self.text_limits.unwrap_or_default(),
+            tenant_schema: self.tenant_schema.clone(),
+            search_path: self.search_path.clone().unwrap_or_default(),
+            row_derives: self.row_derives.clone().unwrap_or_default(),
+            time_crate: self.time_crate.unwrap_or_default(),
+            decimal_crate: self.decimal_crate.unwrap_or_default(),
+            // An empty lint allows no lint at all
+            lint_allows: self.lint_allows.as_ref().map(|lints| {
+                lints
+                    .iter()
+                    .filter(|lint| !lint.is_empty())
+                    .cloned()
+                    .collect()
+            }),
+            forbid_unsafe: self.forbid_unsafe.unwrap_or_default(),
+        }
+    }
+}
+
+/// Overrides the keys of `table` with those of `overrides`, recursing into the tables both
+/// define so that overriding one of their keys keeps the others.
+fn merge_tables(table: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (table.get_mut(&key), value) {
+            (Some(toml::Value::Table(table)), toml::Value::Table(overrides)) => {
+                merge_tables(table, overrides);
+            }
+            (_, value) => {
+                table.insert(key, value);
+            }
+        }
+    }
+}
+
+pub(crate) mod error {
+    use std::path::PathBuf;
+
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("Couldn't read configuration file `{path}`: ({err})")]
+        #[diagnostic(
+            code(cornucopia::config::read),
+            help("check the path given to `--config`")
+        )]
+        Read { path: PathBuf, err: std::io::Error },
+        #[error("Couldn't parse configuration file `{path}`: {err}")]
+        #[diagnostic(
+            code(cornucopia::config::parse),
+            help("keys are named after the CLI's flags, such as `queries-path` or `sync`")
+        )]
+        Parse { path: PathBuf, err: toml::de::Error },
+        #[error("Configuration file `{path}` has no `{profile}` profile")]
+        #[diagnostic(
+            code(cornucopia::config::unknown_profile),
+            help("profiles are declared as `[profile.<name>]` tables")
+        )]
+        UnknownProfile { path: PathBuf, profile: String },
+        #[error("No database url given")]
+        #[diagnostic(
+            code(cornucopia::config::missing_url),
+            help("pass the url of your database, or set the `url` key of the configuration")
+        )]
+        MissingUrl,
+        #[error("A connection pool can't be generated for synchronous code only")]
+        #[diagnostic(
+            code(cornucopia::config::pool_without_async),
+            help("also generate asynchronous code with `--async`, or drop `--pool`")
+        )]
+        PoolWithoutAsync,
+    }
+}
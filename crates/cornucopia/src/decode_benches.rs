@@ -0,0 +1,241 @@
+use postgres::Client;
+use postgres_types::{FromSql, Kind, Type};
+
+use crate::{
+    prepare_queries::{Preparation, PreparedContent},
+    type_registrar::CornucopiaType,
+};
+
+use self::error::Error;
+
+/// Binary payload of a sample value, as sent by PostgreSQL
+#[derive(Debug, Clone)]
+pub(crate) struct Payload {
+    pub(crate) ty: Type,
+    pub(crate) bytes: Vec<u8>,
+}
+
+/// A non-Copy row or composite type whose decoding is benchmarked
+#[derive(Debug, Clone)]
+pub(crate) struct Benchmark {
+    /// Path of the owned struct from the root of the generated code
+    pub(crate) path: String,
+    pub(crate) target: BenchmarkTarget,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum BenchmarkTarget {
+    /// A composite type, decoded from a single value
+    Composite(Payload),
+    /// A row, decoded column by column
+    Row(Vec<(String, Payload)>),
+}
+
+/// Raw binary value of any type
+struct Raw(Vec<u8>);
+
+impl<'a> FromSql<'a> for Raw {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        Ok(Raw(raw.to_vec()))
+    }
+
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+}
+
+/// Cans a binary payload for each non-Copy named row and composite type, by asking the
+/// database to encode a sample value. Types we can't build a sample of, such as ranges,
+/// overridden types or domains rejecting our sample, are skipped.
+pub(crate) fn prepare_benchmarks(
+    client: &mut Client,
+    preparation: &Preparation,
+) -> Result<Vec<Benchmark>, Error> {
+    let mut benchmarks = Vec::new();
+    for (schema, types) in &preparation.types {
+        for ty in types.iter().filter(|it| !it.is_copy) {
+            let PreparedContent::Composite(fields) = &ty.content else {
+                continue;
+            };
+            let Some(samples) = fields
+                .iter()
+                .map(|field| field_sample(&field.ty))
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+            let sample = format!(
+                "ROW({})::{}",
+                samples.join(", "),
+                qualified_name(schema, &ty.name)
+            );
+            if let Some(payload) = payload(client, &sample)? {
+                benchmarks.push(Benchmark {
+                    path: format!("types::{schema}::{}", ty.struct_name),
+                    target: BenchmarkTarget::Composite(payload),
+                });
+            }
+        }
+    }
+    for module in &preparation.modules {
+        'rows: for row in module.rows.values() {
+            if !row.is_named || row.is_copy {
+                continue;
+            }
+            let mut columns = Vec::new();
+            for field in &row.fields {
+                let Some(sample) = field_sample(&field.ty) else {
+                    continue 'rows;
+                };
+                let Some(payload) = payload(client, &sample)? else {
+                    continue 'rows;
+                };
+                columns.push((field.ident.rs.clone(), payload));
+            }
+            benchmarks.push(Benchmark {
+                path: format!(
+                    "queries::{}::{}",
+                    module.info.qualified_name(),
+                    row.name.value
+                ),
+                target: BenchmarkTarget::Row(columns),
+            });
+        }
+    }
+    Ok(benchmarks)
+}
+
+/// Asks the database to encode `sample`, returning `None` if it is rejected.
+fn payload(client: &mut Client, sample: &str) -> Result<Option<Payload>, Error> {
+    let stmt = match client.prepare(&format!("SELECT {sample}")) {
+        Ok(stmt) => stmt,
+        Err(e) if e.code().is_some() => return Ok(None),
+        Err(e) => return Err(Error(e)),
+    };
+    let ty = stmt.columns()[0].type_().clone();
+    match client.query_one(&stmt, &[]) {
+        Ok(row) => Ok(row.get::<_, Option<Raw>>(0).map(|Raw(mut bytes)| {
+            erase_oids(&ty, &mut bytes);
+            Payload { ty, bytes }
+        })),
+        // Such as a domain's check constraint
+        Err(e) if e.code().is_some() => Ok(None),
+        Err(e) => Err(Error(e)),
+    }
+}
+
+/// Zeroes the OIDs of the custom types nested in a binary value, which differ between
+/// databases and are ignored by the decoders, so that the generated code only depends on
+/// the schema.
+fn erase_oids(ty: &Type, bytes: &mut [u8]) {
+    fn erase(ty: &Type, bytes: &mut [u8]) {
+        if Type::from_oid(ty.oid()).is_none() {
+            bytes.fill(0);
+        }
+    }
+    fn len(bytes: &[u8]) -> usize {
+        i32::from_be_bytes(bytes[..4].try_into().unwrap()).max(0) as usize
+    }
+    match ty.kind() {
+        Kind::Composite(fields) => {
+            let mut pos = 4;
+            for field in fields {
+                erase(field.type_(), &mut bytes[pos..pos + 4]);
+                let field_len = len(&bytes[pos + 4..]);
+                pos += 8;
+                erase_oids(field.type_(), &mut bytes[pos..pos + field_len]);
+                pos += field_len;
+            }
+        }
+        Kind::Array(inner) => {
+            let dimensions = len(bytes);
+            erase(inner, &mut bytes[8..12]);
+            let mut pos = 12 + dimensions * 8;
+            while pos < bytes.len() {
+                let value_len = len(&bytes[pos..]);
+                pos += 4;
+                erase_oids(inner, &mut bytes[pos..pos + value_len]);
+                pos += value_len;
+            }
+        }
+        Kind::Domain(inner) => erase_oids(inner, bytes),
+        _ => {}
+    }
+}
+
+/// SQL expression of a sample value of `ty`, if we know how to build one.
+fn field_sample(ty: &CornucopiaType) -> Option<String> {
+    match ty {
+        CornucopiaType::Array { inner } => {
+            let inner = field_sample(inner)?;
+            Some(format!("ARRAY[{inner}, {inner}]"))
+        }
+        CornucopiaType::Simple { pg_ty, .. }
+        | CornucopiaType::Domain { pg_ty, .. }
+        | CornucopiaType::Custom { pg_ty, .. } => sample(pg_ty),
+        CornucopiaType::Range { .. }
+        | CornucopiaType::Override { .. }
+        | CornucopiaType::Encrypted { .. } => None,
+    }
+}
+
+fn sample(ty: &Type) -> Option<String> {
+    let value = match ty.kind() {
+        Kind::Simple => match *ty {
+            Type::BOOL => "true",
+            Type::CHAR => "'c'",
+            Type::INT2 | Type::INT4 | Type::INT8 => "42",
+            Type::FLOAT4 | Type::FLOAT8 | Type::NUMERIC => "4.2",
+            Type::TEXT | Type::VARCHAR => "'cornucopia'",
+            Type::BYTEA => "'\\x636f726e75636f706961'",
+            Type::JSON | Type::JSONB => "'{\"cornucopia\": [1, 2, 3]}'",
+            Type::TIMESTAMP | Type::TIMESTAMPTZ => "'2000-01-01 12:00:00'",
+            Type::DATE => "'2000-01-01'",
+            Type::TIME => "'12:00:00'",
+            Type::UUID => "'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11'",
+            Type::INET => "'127.0.0.1'",
+            Type::MACADDR => "'08:00:2b:01:02:03'",
+            _ => return None,
+        }
+        .to_string(),
+        Kind::Enum(variants) => format!("'{}'", variants.first()?.replace('\'', "''")),
+        Kind::Array(inner) => {
+            let inner = sample(inner)?;
+            format!("ARRAY[{inner}, {inner}]")
+        }
+        Kind::Domain(inner) => sample(inner)?,
+        Kind::Composite(fields) => {
+            let fields = fields
+                .iter()
+                .map(|field| sample(field.type_()))
+                .collect::<Option<Vec<_>>>()?;
+            format!("ROW({})", fields.join(", "))
+        }
+        _ => return None,
+    };
+    Some(format!(
+        "({value})::{}",
+        qualified_name(ty.schema(), ty.name())
+    ))
+}
+
+fn qualified_name(schema: &str, name: &str) -> String {
+    format!(
+        "\"{}\".\"{}\"",
+        schema.replace('"', "\"\""),
+        name.replace('"', "\"\"")
+    )
+}
+
+pub(crate) mod error {
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    #[error("Couldn't encode the benchmark payloads: {0:#}")]
+    #[diagnostic(
+        code(cornucopia::decode_benches),
+        help("check that the database is still reachable and that the benchmarked queries' rows can be selected")
+    )]
+    pub struct Error(pub(crate) postgres::Error);
+}
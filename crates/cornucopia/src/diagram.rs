@@ -0,0 +1,266 @@
+use std::fmt::Write;
+
+use postgres::Client;
+
+use self::error::Error;
+
+/// Output format of an entity-relationship diagram.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum DiagramFormat {
+    /// Graphviz DOT, rendered with `dot -Tsvg`
+    Dot,
+    /// Mermaid `erDiagram`, rendered by GitHub and most documentation tools
+    #[default]
+    Mermaid,
+}
+
+/// Columns of every user table, in declaration order.
+const COLUMNS_SQL: &str = "SELECT n.nspname, c.relname, a.attname,
+    format_type(a.atttypid, a.atttypmod), a.attnotnull,
+    EXISTS (
+        SELECT FROM pg_constraint k
+        WHERE k.conrelid = c.oid AND k.contype = 'p' AND a.attnum = ANY(k.conkey)
+    )
+FROM pg_class c
+JOIN pg_namespace n ON n.oid = c.relnamespace
+JOIN pg_attribute a ON a.attrelid = c.oid
+WHERE c.relkind IN ('r', 'p') AND NOT c.relispartition
+    AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+    AND n.nspname NOT LIKE 'pg_toast%'
+    AND a.attnum > 0 AND NOT a.attisdropped
+ORDER BY n.nspname, c.relname, a.attnum";
+
+/// Foreign keys between user tables, with their columns in key order.
+const FOREIGN_KEYS_SQL: &str = "SELECT k.conname,
+    fn.nspname, f.relname,
+    ARRAY(
+        SELECT a.attname FROM unnest(k.conkey) WITH ORDINALITY u(attnum, i)
+        JOIN pg_attribute a ON a.attrelid = k.conrelid AND a.attnum = u.attnum ORDER BY u.i
+    ),
+    tn.nspname, t.relname,
+    ARRAY(
+        SELECT a.attname FROM unnest(k.confkey) WITH ORDINALITY u(attnum, i)
+        JOIN pg_attribute a ON a.attrelid = k.confrelid AND a.attnum = u.attnum ORDER BY u.i
+    ),
+    EXISTS (
+        SELECT FROM pg_attribute a
+        WHERE a.attrelid = k.conrelid AND a.attnum = ANY(k.conkey) AND NOT a.attnotnull
+    )
+FROM pg_constraint k
+JOIN pg_class f ON f.oid = k.conrelid
+JOIN pg_namespace fn ON fn.oid = f.relnamespace
+JOIN pg_class t ON t.oid = k.confrelid
+JOIN pg_namespace tn ON tn.oid = t.relnamespace
+WHERE k.contype = 'f' AND NOT f.relispartition
+    AND fn.nspname NOT IN ('pg_catalog', 'information_schema')
+ORDER BY fn.nspname, f.relname, k.conname";
+
+struct Table {
+    schema: String,
+    name: String,
+    columns: Vec<Column>,
+}
+
+impl Table {
+    /// Name of the table, qualified by its schema outside of `public`.
+    fn display_name(&self) -> String {
+        if self.schema == "public" {
+            self.name.clone()
+        } else {
+            format!("{}.{}", self.schema, self.name)
+        }
+    }
+}
+
+struct Column {
+    name: String,
+    ty: String,
+    not_null: bool,
+    primary_key: bool,
+    foreign_key: bool,
+}
+
+struct ForeignKey {
+    name: String,
+    from: (String, String),
+    from_columns: Vec<String>,
+    to: (String, String),
+    to_columns: Vec<String>,
+    /// Rows may reference nothing through a nullable column
+    is_optional: bool,
+}
+
+/// Introspects the tables and foreign keys of the database, skipping partitions and
+/// system schemas, and renders them as an entity-relationship diagram in `format`.
+pub(crate) fn diagram(client: &mut Client, format: DiagramFormat) -> Result<String, Error> {
+    let mut tables: Vec<Table> = Vec::new();
+    for row in client.query(COLUMNS_SQL, &[]).map_err(Error)? {
+        let (schema, name): (String, String) = (row.get(0), row.get(1));
+        let column = Column {
+            name: row.get(2),
+            ty: row.get(3),
+            not_null: row.get(4),
+            primary_key: row.get(5),
+            foreign_key: false,
+        };
+        match tables.last_mut() {
+            Some(table) if table.schema == schema && table.name == name => {
+                table.columns.push(column);
+            }
+            _ => tables.push(Table {
+                schema,
+                name,
+                columns: vec![column],
+            }),
+        }
+    }
+    let foreign_keys: Vec<_> = client
+        .query(FOREIGN_KEYS_SQL, &[])
+        .map_err(Error)?
+        .into_iter()
+        .map(|row| ForeignKey {
+            name: row.get(0),
+            from: (row.get(1), row.get(2)),
+            from_columns: row.get(3),
+            to: (row.get(4), row.get(5)),
+            to_columns: row.get(6),
+            is_optional: row.get(7),
+        })
+        .collect();
+    for fk in &foreign_keys {
+        if let Some(table) = tables
+            .iter_mut()
+            .find(|t| t.schema == fk.from.0 && t.name == fk.from.1)
+        {
+            for column in &mut table.columns {
+                column.foreign_key |= fk.from_columns.contains(&column.name);
+            }
+        }
+    }
+    Ok(match format {
+        DiagramFormat::Dot => dot(&tables, &foreign_keys),
+        DiagramFormat::Mermaid => mermaid(&tables, &foreign_keys),
+    })
+}
+
+fn dot(tables: &[Table], foreign_keys: &[ForeignKey]) -> String {
+    fn escape(s: &str) -> String {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+    // Quoted strings, used for identifiers and plain labels, escape quotes and backslashes
+    fn quoted(s: &str) -> String {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+    let mut w = String::new();
+    w.push_str("digraph schema {\n    rankdir=LR;\n    node [shape=plaintext];\n");
+    for table in tables {
+        let _ = write!(
+            w,
+            "    {} [label=<<table border=\"0\" cellborder=\"1\" cellspacing=\"0\">\
+            <tr><td bgcolor=\"lightgrey\"><b>{}</b></td></tr>",
+            quoted(&format!("{}.{}", table.schema, table.name)),
+            escape(&table.display_name())
+        );
+        for column in &table.columns {
+            let keys = match (column.primary_key, column.foreign_key) {
+                (true, true) => " PK FK",
+                (true, false) => " PK",
+                (false, true) => " FK",
+                (false, false) => "",
+            };
+            let nullable = if column.not_null { "" } else { "?" };
+            let _ = write!(
+                w,
+                "<tr><td port=\"{}\" align=\"left\">{}: {}{nullable}{keys}</td></tr>",
+                escape(&column.name),
+                escape(&column.name),
+                escape(&column.ty)
+            );
+        }
+        w.push_str("</table>>];\n");
+    }
+    for fk in foreign_keys {
+        let _ = writeln!(
+            w,
+            "    {}:{} -> {}:{} [label={}{}];",
+            quoted(&format!("{}.{}", fk.from.0, fk.from.1)),
+            quoted(&fk.from_columns[0]),
+            quoted(&format!("{}.{}", fk.to.0, fk.to.1)),
+            quoted(&fk.to_columns[0]),
+            quoted(&fk.name),
+            if fk.is_optional { ", style=dashed" } else { "" }
+        );
+    }
+    w.push_str("}\n");
+    w
+}
+
+fn mermaid(tables: &[Table], foreign_keys: &[ForeignKey]) -> String {
+    // Mermaid only accepts a single word for names and types
+    fn word(s: &str) -> String {
+        s.chars()
+            .map(|c| {
+                if c.is_alphanumeric() || "_-[]()".contains(c) {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+    fn entity(schema: &str, name: &str) -> String {
+        if schema == "public" {
+            word(name)
+        } else {
+            word(&format!("{schema}_{name}"))
+        }
+    }
+    let mut w = String::from("erDiagram\n");
+    for table in tables {
+        let _ = writeln!(w, "    {} {{", entity(&table.schema, &table.name));
+        for column in &table.columns {
+            let keys = match (column.primary_key, column.foreign_key) {
+                (true, true) => " PK, FK",
+                (true, false) => " PK",
+                (false, true) => " FK",
+                (false, false) => "",
+            };
+            let nullable = if column.not_null { "" } else { " \"nullable\"" };
+            let _ = writeln!(
+                w,
+                "        {} {}{keys}{nullable}",
+                word(&column.ty),
+                word(&column.name)
+            );
+        }
+        w.push_str("    }\n");
+    }
+    for fk in foreign_keys {
+        let cardinality = if fk.is_optional { "|o--o{" } else { "||--o{" };
+        let _ = writeln!(
+            w,
+            "    {} {cardinality} {} : \"{}\"",
+            entity(&fk.to.0, &fk.to.1),
+            entity(&fk.from.0, &fk.from.1),
+            // Quoted labels can't contain quotes, which are written as an entity code
+            fk.name.replace('"', "#quot;")
+        );
+    }
+    w
+}
+
+pub(crate) mod error {
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    #[error("Couldn't introspect the database schema: {0:#}")]
+    #[diagnostic(
+        code(cornucopia::diagram),
+        help("the role used to connect needs to read the system catalogs")
+    )]
+    pub struct Error(pub(crate) postgres::Error);
+}
@@ -0,0 +1,232 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use indexmap::IndexMap;
+use postgres::Client;
+
+use crate::{
+    prepare_queries::{Ident, Preparation, PreparedContent, PreparedField},
+    type_registrar::CornucopiaType,
+};
+
+use self::error::Error;
+
+/// A domain with `CHECK` constraints, used by a field
+#[derive(Debug, Clone)]
+pub(crate) struct Domain {
+    pub(crate) ident: Ident,
+    /// Rust type of the values checked by the domain
+    pub(crate) rust_name: &'static str,
+    /// Definition of each constraint, as printed by `pg_get_constraintdef`
+    pub(crate) checks: Vec<String>,
+}
+
+impl Domain {
+    /// Rust expression checking a `value` binding against every constraint of the domain,
+    /// if they all compare it to a number.
+    pub(crate) fn check_expr(&self) -> Option<String> {
+        let cast = match self.rust_name {
+            "i8" | "i16" | "i32" | "i64" => "i64",
+            "f32" | "f64" => "f64",
+            _ => return None,
+        };
+        let mut comparisons = Vec::new();
+        for check in &self.checks {
+            let expr = check.strip_prefix("CHECK ")?;
+            for (op, literal) in comparisons_of(expr)? {
+                let literal = match cast {
+                    "i64" => literal.parse::<i64>().ok()?.to_string(),
+                    _ => format!("{:?}", literal.parse::<f64>().ok()?),
+                };
+                comparisons.push(format!("{cast}::from(value) {op} {literal}"));
+            }
+        }
+        Some(comparisons.join(" && "))
+    }
+}
+
+/// Documents the params, row and composite type fields of a domain type with the domain's
+/// `CHECK` constraints, returning the constrained domains grouped by schema. Columns of a
+/// domain type are described with its base type, so only the fields of composite types and
+/// params can be of a domain type.
+pub(crate) fn prepare_domains(
+    client: &mut Client,
+    preparation: &mut Preparation,
+) -> Result<IndexMap<String, Vec<Domain>>, Error> {
+    let mut checks = HashMap::new();
+    let mut domains: IndexMap<String, Vec<Domain>> = IndexMap::new();
+    let fields = preparation
+        .modules
+        .iter_mut()
+        .flat_map(|module| module.params.values_mut().chain(module.rows.values_mut()))
+        .flat_map(|row| row.fields.iter_mut())
+        .chain(
+            preparation
+                .types
+                .values_mut()
+                .flatten()
+                .filter_map(|ty| match &mut ty.content {
+                    PreparedContent::Composite(fields) => Some(fields),
+                    PreparedContent::Enum(_) => None,
+                })
+                .flatten(),
+        );
+    for field in fields {
+        let ty = field.ty.clone();
+        let CornucopiaType::Domain { pg_ty, inner } = ty.as_ref() else {
+            continue;
+        };
+        let field_checks = match checks.entry(pg_ty.oid()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let rows = client
+                    .query(
+                        "SELECT pg_get_constraintdef(oid) FROM pg_constraint
+                    WHERE contypid = $1 AND contype = 'c' ORDER BY conname",
+                        &[&pg_ty.oid()],
+                    )
+                    .map_err(Error)?;
+                let domain_checks: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+                if !domain_checks.is_empty() {
+                    let rust_name = match inner.as_ref() {
+                        CornucopiaType::Simple { rust_name, .. } => *rust_name,
+                        _ => "",
+                    };
+                    domains
+                        .entry(pg_ty.schema().to_string())
+                        .or_default()
+                        .push(Domain {
+                            ident: Ident::new(pg_ty.name().to_string()),
+                            rust_name,
+                            checks: domain_checks.clone(),
+                        });
+                }
+                entry.insert(domain_checks)
+            }
+        };
+        document(field, pg_ty.name(), field_checks);
+    }
+    Ok(domains)
+}
+
+/// Appends the constraints of the field's `domain` to its doc comment.
+fn document(field: &mut PreparedField, domain: &str, checks: &[String]) {
+    if checks.is_empty() {
+        return;
+    }
+    let mut doc = field
+        .doc
+        .take()
+        .map_or_else(String::new, |doc| doc + "\n\n");
+    doc.push_str(&format!("Checked by the `{domain}` domain:"));
+    for check in checks {
+        doc.push_str(&format!("\n- `{check}`"));
+    }
+    field.doc = Some(doc);
+}
+
+/// Comparisons of `VALUE` to a number joined by `AND` in `expr`, as `(operator, number)`.
+fn comparisons_of(expr: &str) -> Option<Vec<(&'static str, &str)>> {
+    let expr = strip_parens(expr);
+    if let Some(operands) = split_and(expr) {
+        let mut comparisons = Vec::new();
+        for operand in operands {
+            comparisons.extend(comparisons_of(operand)?);
+        }
+        return Some(comparisons);
+    }
+    let rest = expr.strip_prefix("VALUE ")?;
+    let (op, literal) = rest.split_once(' ')?;
+    let op = match op {
+        ">" => ">",
+        ">=" => ">=",
+        "<" => "<",
+        "<=" => "<=",
+        "=" => "==",
+        "<>" => "!=",
+        _ => return None,
+    };
+    Some(vec![(op, number_literal(literal)?)])
+}
+
+/// Strips the parentheses wrapping the whole of `expr`.
+fn strip_parens(mut expr: &str) -> &str {
+    while let Some(inner) = expr.strip_prefix('(').and_then(|it| it.strip_suffix(')')) {
+        // `(a) AND (b)` isn't wrapped as a whole
+        let mut depth = 0;
+        let balanced = inner.chars().all(|c| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            depth >= 0
+        });
+        if !balanced {
+            break;
+        }
+        expr = inner;
+    }
+    expr
+}
+
+/// Operands of `expr` if it is a conjunction, `None` otherwise.
+fn split_and(expr: &str) -> Option<Vec<&str>> {
+    let mut operands = Vec::new();
+    let (mut depth, mut start, mut in_literal) = (0, 0, false);
+    for (idx, c) in expr.char_indices() {
+        match c {
+            '\'' => in_literal = !in_literal,
+            _ if in_literal => {}
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ if depth == 0 && expr[idx..].starts_with(" AND ") => {
+                operands.push(&expr[start..idx]);
+                start = idx + " AND ".len();
+            }
+            _ => {}
+        }
+    }
+    if operands.is_empty() {
+        None
+    } else {
+        operands.push(&expr[start..]);
+        Some(operands)
+    }
+}
+
+/// Number of a literal such as `0`, `(0.5)::double precision` or `'-5'::integer`.
+fn number_literal(mut literal: &str) -> Option<&str> {
+    loop {
+        let stripped = strip_parens(literal);
+        let stripped = match stripped.rfind("::") {
+            // Casts of numbers to numeric types
+            Some(idx) if !stripped[idx..].contains([')', '\'']) => &stripped[..idx],
+            _ => stripped,
+        };
+        let stripped = stripped
+            .strip_prefix('\'')
+            .and_then(|it| it.strip_suffix('\''))
+            .unwrap_or(stripped);
+        if stripped == literal {
+            break;
+        }
+        literal = stripped;
+    }
+    literal
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '-' | '.' | 'e' | 'E' | '+'))
+        .then_some(literal)
+}
+
+pub(crate) mod error {
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    #[error("Couldn't read the constraints of domains: {0:#}")]
+    #[diagnostic(
+        code(cornucopia::domain_checks),
+        help("check that the database is still reachable and that your user can read `pg_constraint`")
+    )]
+    pub struct Error(pub(crate) postgres::Error);
+}
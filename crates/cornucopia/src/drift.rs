@@ -0,0 +1,134 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use similar::TextDiff;
+
+use self::error::Error;
+
+/// Checks that the code written at `destination`, and its module and sidecar SQL files,
+/// match the freshly generated `code` and `files`. Rust files that differ are compared
+/// again once the generated code is formatted with `rustfmt`, as generated files are
+/// commonly formatted before being committed, and the lines that still differ reported.
+pub(crate) fn check_drift(
+    destination: &Path,
+    code: &str,
+    files: &[(String, String)],
+) -> Result<(), Error> {
+    let dir = destination.parent().unwrap_or(Path::new(""));
+    let outdated: Vec<_> = std::iter::once((destination.to_path_buf(), code))
+        .chain(
+            files
+                .iter()
+                .map(|(path, content)| (dir.join(path), content.as_str())),
+        )
+        .filter_map(|(path, content)| drift(path, content))
+        .collect();
+    if outdated.is_empty() {
+        Ok(())
+    } else {
+        Err(Error { outdated })
+    }
+}
+
+/// Generated file differing from the file written at its path.
+#[derive(Debug)]
+pub(crate) struct Drift {
+    pub(crate) path: PathBuf,
+    /// Lines that differ, `None` if the file is missing
+    pub(crate) diff: Option<String>,
+}
+
+/// Most lines of a drifted file shown in a report.
+const MAX_DIFF_LINES: usize = 40;
+
+fn drift(path: PathBuf, generated: &str) -> Option<Drift> {
+    let Ok(written) = std::fs::read_to_string(&path) else {
+        return Some(Drift { path, diff: None });
+    };
+    if written == generated {
+        return None;
+    }
+    let formatted = path
+        .extension()
+        .is_some_and(|ext| ext == "rs")
+        .then(|| rustfmt(generated))
+        .flatten();
+    if formatted.as_deref() == Some(written.as_str()) {
+        return None;
+    }
+    // Lines are compared with the generated code as it was written, formatted or not
+    let diff = std::iter::once(generated)
+        .chain(formatted.as_deref())
+        .map(|generated| TextDiff::from_lines(written.as_str(), generated))
+        .max_by(|a, b| a.ratio().total_cmp(&b.ratio()))?
+        .unified_diff()
+        .context_radius(2)
+        .header("written", "generated")
+        .to_string();
+    let nb_lines = diff.lines().count();
+    let mut diff: String = diff
+        .lines()
+        .take(MAX_DIFF_LINES)
+        .map(|line| format!("    {line}\n"))
+        .collect();
+    if nb_lines > MAX_DIFF_LINES {
+        diff.push_str(&format!(
+            "    ... {} more line(s)\n",
+            nb_lines - MAX_DIFF_LINES
+        ));
+    }
+    Some(Drift {
+        path,
+        diff: Some(diff),
+    })
+}
+
+/// Formats `code` with `rustfmt`, `None` if it isn't installed or fails.
+fn rustfmt(code: &str) -> Option<String> {
+    let mut child = Command::new("rustfmt")
+        .args(["--edition", "2021"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    child.stdin.take()?.write_all(code.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8(output.stdout).ok())
+        .flatten()
+}
+
+/// Lists drifted files in a report, one per line followed by the lines that differ.
+fn display(outdated: &[Drift]) -> String {
+    outdated
+        .iter()
+        .map(|drift| match &drift.diff {
+            Some(diff) => format!("{}\n{diff}", drift.path.display()),
+            None => format!("{} (missing)\n", drift.path.display()),
+        })
+        .collect::<Vec<_>>()
+        .join("- ")
+}
+
+pub(crate) mod error {
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    use super::Drift;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    #[error("Found {} outdated or missing generated file(s):\n- {}", outdated.len(), super::display(outdated))]
+    #[diagnostic(
+        code(cornucopia::drift),
+        help("regenerate the code and commit it along with the queries and migrations")
+    )]
+    pub struct Error {
+        pub(crate) outdated: Vec<Drift>,
+    }
+}
@@ -1,15 +1,22 @@
 use std::path::PathBuf;
 
-use miette::{Diagnostic, GraphicalReportHandler, GraphicalTheme};
+use miette::{Diagnostic, GraphicalReportHandler, GraphicalTheme, NamedSource, SourceSpan};
 use thiserror::Error as ThisError;
 
 /// Enumeration of all the errors reported by Cornucopia.
+///
+/// Every error has a stable code, such as `cornucopia::conn`, and most of them a hint on
+/// how to fix it, both available through [`Diagnostic`].
 #[derive(Debug, ThisError, Diagnostic)]
 #[error(transparent)]
 #[diagnostic(transparent)]
 pub enum Error {
     /// An error while trying to connect to a database.
     Connection(#[from] crate::conn::error::Error),
+    /// An error while reading a configuration file.
+    Config(#[from] crate::config::error::Error),
+    /// An error while listing the migrations of a code generation.
+    Migrations(#[from] crate::builder::error::Error),
     /// An error while trying to read PostgreSQL query files.
     ReadQueries(#[from] crate::read_queries::error::Error),
     /// An error while trying to parse PostgreSQL query files.
@@ -18,15 +25,81 @@ pub enum Error {
     ValidateQueries(#[from] Box<crate::validation::error::Error>),
     /// An error while manipulating a container managed by Cornucopia.
     Container(#[from] crate::container::error::Error),
+    /// A failure of a command run against the container managed by Cornucopia, with the
+    /// tail of its logs.
+    ContainerFailure(crate::container::error::Failure),
     /// An error while trying to prepare PostgreSQL queries.
     PrepareQueries(#[from] crate::prepare_queries::error::Error),
     /// An error while reading PostgreSQL schema files.
     LoadSchema(#[from] crate::load_schema::error::Error),
+    /// An error while creating a test database.
+    TestDatabase(#[from] crate::test_database::error::Error),
     /// An error while trying to write the generated code to its destination file.
     WriteCodeGenFile(#[from] WriteOutputError),
+    /// An error while writing the completion script or man page of the CLI.
+    WriteStdout(#[from] WriteStdoutError),
+    /// Generated code that isn't valid Rust, due to a bug in Cornucopia.
+    InvalidCode(#[from] InvalidCodeError),
+    /// A statement prefix that can't be written in the comment tagging statements.
+    InvalidStatementPrefix(#[from] InvalidStatementPrefixError),
+    /// An error while adding a new query stub.
+    NewQuery(#[from] crate::new_query::error::Error),
+    /// An error while importing plain SQL files.
+    Import(#[from] crate::import::error::Error),
+    /// An error while comparing the generated API with a previous generation.
+    Compat(#[from] crate::compat::error::Error),
+    /// Generated files that don't match the queries they were generated from.
+    Drift(#[from] crate::drift::error::Error),
+    /// An error while loading or running parameter fixtures.
+    Fixtures(#[from] crate::fixtures::error::Error),
+    /// An error while benchmarking a query.
+    Bench(#[from] crate::bench::error::Error),
+    /// An error while exporting the rows of a query.
+    Export(#[from] crate::export::error::Error),
+    /// An error while preparing the partition variants of PostgreSQL queries.
+    Partitions(#[from] crate::partitions::error::Error),
+    /// An error while reading the version of the database schema.
+    SchemaInfo(#[from] crate::schema_info::error::Error),
+    /// An error while encoding the payloads of the decode benchmarks.
+    Benchmarks(#[from] crate::decode_benches::error::Error),
+    /// An error while reading the constraints of domains.
+    DomainChecks(#[from] crate::domain_checks::error::Error),
+    /// An error while introspecting the database schema for a diagram.
+    Diagram(#[from] crate::diagram::error::Error),
+    /// An error while reading a previous dump of the custom types.
+    TypeDump(#[from] crate::type_dump::error::Error),
+    /// An error while watching queries and schema files for changes.
+    Watch(#[from] crate::watch::error::Error),
+    /// Errors found while checking PostgreSQL query files.
+    Check(#[from] CheckError),
+    /// Failures of PostgreSQL query files checked against several PostgreSQL versions.
+    CheckVersions(#[from] CheckVersionsError),
 }
 
 impl Error {
+    /// Whether the database failed, rather than cornucopia rejecting your query files,
+    /// in which case the logs of the managed container are worth reading.
+    pub(crate) fn is_database_failure(&self) -> bool {
+        match self {
+            Self::Connection(_)
+            | Self::Container(_)
+            | Self::LoadSchema(_)
+            | Self::PrepareQueries(crate::prepare_queries::error::Error::Db { .. })
+            | Self::Partitions(_)
+            | Self::SchemaInfo(_)
+            | Self::Benchmarks(_)
+            | Self::DomainChecks(_)
+            | Self::Diagram(_)
+            | Self::Watch(crate::watch::error::Error::Reset(_)) => true,
+            Self::ContainerFailure(failure) => failure.err.is_database_failure(),
+            Self::Check(CheckError { errors }) => errors.iter().any(Self::is_database_failure),
+            Self::CheckVersions(CheckVersionsError { failures }) => {
+                failures.iter().any(|it| it.err.is_database_failure())
+            }
+            _ => false,
+        }
+    }
+
     #[must_use]
     pub fn report(self) -> String {
         let mut buff = String::new();
@@ -40,7 +113,97 @@ impl Error {
 
 #[derive(Debug, ThisError, Diagnostic)]
 #[error("Could not write your queries to destination file `{file_path}`: ({err})")]
+#[diagnostic(
+    code(cornucopia::write_output),
+    help("check that the destination's folder exists and is writable")
+)]
 pub struct WriteOutputError {
     pub(crate) file_path: PathBuf,
     pub(crate) err: std::io::Error,
 }
+
+#[derive(Debug, ThisError, Diagnostic)]
+#[error("Could not write the {what}: ({err})")]
+#[diagnostic(
+    code(cornucopia::write_stdout),
+    help("check that the output wasn't closed early, for instance by the end of a pipe")
+)]
+pub struct WriteStdoutError {
+    pub(crate) what: &'static str,
+    pub(crate) err: std::io::Error,
+}
+
+#[derive(Debug, ThisError, Diagnostic)]
+#[error("Generated code is not valid Rust at line {line}, column {column}: {msg}")]
+#[diagnostic(code(cornucopia::invalid_code), help("{help}"))]
+pub struct InvalidCodeError {
+    pub(crate) msg: String,
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+    /// Chunk of generated code surrounding the error
+    #[source_code]
+    pub(crate) src: NamedSource,
+    #[label("error occurs near this location")]
+    pub(crate) err_span: SourceSpan,
+    pub(crate) help: String,
+}
+
+#[derive(Debug, ThisError, Diagnostic)]
+#[error("Invalid statement prefix `{prefix}`")]
+#[diagnostic(
+    code(cornucopia::statement_prefix),
+    help("use ASCII letters, digits, `_`, `.`, `:` and `-` only")
+)]
+pub struct InvalidStatementPrefixError {
+    pub(crate) prefix: String,
+}
+
+#[derive(Debug, ThisError, Diagnostic)]
+#[error("Found {} error(s) in your queries", errors.len())]
+#[diagnostic(
+    code(cornucopia::check),
+    help("fix the errors listed below, each with its own hint")
+)]
+pub struct CheckError {
+    #[related]
+    pub(crate) errors: Vec<Error>,
+}
+
+#[derive(Debug, ThisError, Diagnostic)]
+#[error(
+    "Your queries failed on PostgreSQL {}",
+    failures.iter().map(|it| it.version.as_str()).collect::<Vec<_>>().join(", ")
+)]
+#[diagnostic(
+    code(cornucopia::check_versions),
+    help("fix the failures listed below, or drop the versions you don't support from `--pg-versions`")
+)]
+pub struct CheckVersionsError {
+    #[related]
+    pub(crate) failures: Vec<VersionFailure>,
+}
+
+/// Failure of a check against one PostgreSQL version.
+#[derive(Debug, ThisError, Diagnostic)]
+#[error("PostgreSQL {version}")]
+#[diagnostic(code(cornucopia::check_versions::version))]
+pub struct VersionFailure {
+    pub(crate) version: String,
+    #[source]
+    #[diagnostic_source]
+    pub(crate) err: Error,
+    /// Help of `err`, such as the logs of the version's container, which isn't rendered
+    /// along with its message
+    #[help]
+    help: Option<String>,
+}
+
+impl VersionFailure {
+    pub(crate) fn new(version: String, err: Error) -> Self {
+        Self {
+            version,
+            help: err.help().map(|help| help.to_string()),
+            err,
+        }
+    }
+}
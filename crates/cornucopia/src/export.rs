@@ -0,0 +1,196 @@
+use std::{fs::File, path::Path, sync::Arc};
+
+use arrow_array::{
+    ArrayRef, BooleanArray, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array,
+    RecordBatch, StringArray,
+};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+use postgres::{types::Type, Client, SimpleQueryMessage};
+
+use crate::{
+    fixtures::{execute_sql, Fixtures},
+    prepare_queries::Preparation,
+};
+
+use self::error::Error;
+
+/// File format of exported query results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// Apache Parquet
+    #[default]
+    Parquet,
+}
+
+/// Executes `query` (as `module::query`) with its `fixtures` parameter values, inside a
+/// transaction that is rolled back afterward, then writes its rows to `out` in `format`.
+/// Returns the number of exported rows.
+pub(crate) fn export(
+    client: &mut Client,
+    preparation: &Preparation,
+    fixtures: Option<&Fixtures>,
+    query: &str,
+    format: ExportFormat,
+    out: &Path,
+) -> Result<usize, crate::Error> {
+    let Some((module, prepared)) = preparation.find_query(query) else {
+        return Err(Error::UnknownQuery {
+            query: query.to_string(),
+        }
+        .into());
+    };
+    let args = match fixtures {
+        Some(fixtures) => fixtures.arguments(module, prepared)?,
+        None if prepared.param.is_some() => {
+            return Err(Error::MissingFixtures {
+                query: query.to_string(),
+            }
+            .into())
+        }
+        None => Vec::new(),
+    };
+
+    let execute_err = |err| Error::Execute {
+        query: query.to_string(),
+        err,
+    };
+    let mut transaction = client.transaction().map_err(execute_err)?;
+    module
+        .set_local_search_path(&mut transaction)
+        .map_err(execute_err)?;
+    let columns = transaction
+        .prepare(&prepared.sql)
+        .map_err(execute_err)?
+        .columns()
+        .iter()
+        .map(|column| (column.name().to_string(), column.type_().clone()))
+        .collect::<Vec<_>>();
+    transaction
+        .batch_execute(&format!("PREPARE cornucopia_export AS {}", prepared.sql))
+        .map_err(execute_err)?;
+    // Rows are read as text, which every type supports
+    let messages = transaction
+        .simple_query(&execute_sql("cornucopia_export", &args))
+        .and_then(|messages| transaction.rollback().map(|()| messages));
+    // Prepared statements outlive rolled back transactions, even failed ones
+    client
+        .batch_execute("DEALLOCATE cornucopia_export")
+        .map_err(execute_err)?;
+    let messages = messages.map_err(execute_err)?;
+
+    let mut values = vec![Vec::new(); columns.len()];
+    for message in messages {
+        if let SimpleQueryMessage::Row(row) = message {
+            for (i, column) in values.iter_mut().enumerate() {
+                column.push(row.get(i).map(str::to_string));
+            }
+        }
+    }
+    let nb_rows = values.first().map_or(0, Vec::len);
+    let batch = record_batch(&columns, values).map_err(|err| Error::Write {
+        path: out.to_owned(),
+        err: err.into(),
+    })?;
+
+    match format {
+        ExportFormat::Parquet => {
+            let file = File::create(out).map_err(|err| Error::Write {
+                path: out.to_owned(),
+                err: err.into(),
+            })?;
+            let write_err = |err| Error::Write {
+                path: out.to_owned(),
+                err,
+            };
+            let mut writer = ArrowWriter::try_new(file, batch.schema(), None).map_err(write_err)?;
+            writer.write(&batch).map_err(write_err)?;
+            writer.close().map_err(write_err)?;
+        }
+    }
+    Ok(nb_rows)
+}
+
+/// Builds a record batch from the text representation of each column. Booleans and
+/// numbers keep their type, other columns are exported as strings.
+fn record_batch(
+    columns: &[(String, Type)],
+    values: Vec<Vec<Option<String>>>,
+) -> Result<RecordBatch, arrow_schema::ArrowError> {
+    // PostgreSQL's text output of these types always parses
+    fn parse<T: std::str::FromStr>(values: Vec<Option<String>>) -> Vec<Option<T>> {
+        values
+            .into_iter()
+            .map(|value| value.map(|value| value.parse().ok().expect("valid text output")))
+            .collect()
+    }
+
+    let mut fields = Vec::with_capacity(columns.len());
+    let mut arrays: Vec<ArrayRef> = Vec::with_capacity(columns.len());
+    for ((name, ty), values) in columns.iter().zip(values) {
+        let (data_ty, array): (_, ArrayRef) = match *ty {
+            Type::BOOL => (
+                DataType::Boolean,
+                Arc::new(BooleanArray::from(
+                    values
+                        .into_iter()
+                        .map(|value| value.map(|value| value == "t"))
+                        .collect::<Vec<_>>(),
+                )),
+            ),
+            Type::INT2 => (DataType::Int16, Arc::new(Int16Array::from(parse(values)))),
+            Type::INT4 => (DataType::Int32, Arc::new(Int32Array::from(parse(values)))),
+            Type::INT8 => (DataType::Int64, Arc::new(Int64Array::from(parse(values)))),
+            Type::FLOAT4 => (
+                DataType::Float32,
+                Arc::new(Float32Array::from(parse(values))),
+            ),
+            Type::FLOAT8 => (
+                DataType::Float64,
+                Arc::new(Float64Array::from(parse(values))),
+            ),
+            _ => (DataType::Utf8, Arc::new(StringArray::from(values))),
+        };
+        fields.push(Field::new(name, data_ty, true));
+        arrays.push(array);
+    }
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)
+}
+
+pub(crate) mod error {
+    use std::path::PathBuf;
+
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("No query named `{query}`")]
+        #[diagnostic(
+            code(cornucopia::export::unknown_query),
+            help("queries are named `module::query`")
+        )]
+        UnknownQuery { query: String },
+        #[error("Query `{query}` has parameters but no fixtures were given")]
+        #[diagnostic(
+            code(cornucopia::export::missing_fixtures),
+            help("provide its parameter values with `--fixtures`")
+        )]
+        MissingFixtures { query: String },
+        #[error("Couldn't run query `{query}`: {err:#}")]
+        #[diagnostic(
+            code(cornucopia::export::execute),
+            help("check that the fixture values match the types of the query's parameters")
+        )]
+        Execute { query: String, err: postgres::Error },
+        #[error("Couldn't write export file `{path}`: {err}")]
+        #[diagnostic(
+            code(cornucopia::export::write),
+            help("check that the destination folder exists and is writable")
+        )]
+        Write {
+            path: PathBuf,
+            err: parquet::errors::ParquetError,
+        },
+    }
+}
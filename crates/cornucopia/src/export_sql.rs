@@ -0,0 +1,37 @@
+use std::fmt::Write;
+
+use crate::prepare_queries::Preparation;
+
+/// Renders every prepared query into a single SQL file, each preceded by a header
+/// naming its Rust function and describing its numbered parameters.
+pub(crate) fn export_sql(preparation: &Preparation) -> String {
+    let mut out = String::from(
+        "-- This file was generated with `cornucopia`. Do not modify.\n\
+         -- It contains every SQL statement the application can run.\n",
+    );
+    for module in &preparation.modules {
+        for query in module.queries.values() {
+            let module_name = module.info.qualified_name();
+            writeln!(out).unwrap();
+            writeln!(out, "-- {module_name}::{}", query.ident.db).unwrap();
+            writeln!(out, "-- Rust: queries::{module_name}::{}", query.ident.rs).unwrap();
+            if let Some((idx, order)) = &query.param {
+                let fields = &module.params.get_index(*idx).unwrap().1.fields;
+                for (i, idx) in order.iter().enumerate() {
+                    let field = &fields[*idx];
+                    let nullable = if field.is_nullable { ", nullable" } else { "" };
+                    writeln!(
+                        out,
+                        "--   ${} {} ({}{nullable})",
+                        i + 1,
+                        field.ident.db,
+                        field.ty.pg_ty()
+                    )
+                    .unwrap();
+                }
+            }
+            writeln!(out, "{};", query.sql.trim()).unwrap();
+        }
+    }
+    out
+}
@@ -0,0 +1,313 @@
+use std::path::{Path, PathBuf};
+
+use postgres::Client;
+use toml::{Table, Value};
+
+use crate::prepare_queries::{Preparation, PreparedModule, PreparedQuery};
+
+use self::error::Error;
+
+/// Example parameter values, keyed by `module::query` then by parameter name:
+///
+/// ```toml
+/// ["author::insert_author"]
+/// name = "Agatha Christie"
+/// country = "UK"
+/// ```
+///
+/// Nullable parameters can be omitted, in which case they are bound to `NULL`.
+pub(crate) struct Fixtures {
+    path: PathBuf,
+    queries: Table,
+}
+
+impl Fixtures {
+    pub(crate) fn load(path: &Path) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path).map_err(|err| Error::Read {
+            path: path.to_owned(),
+            err,
+        })?;
+        let queries = content.parse().map_err(|err| Error::Parse {
+            path: path.to_owned(),
+            err,
+        })?;
+        Ok(Self {
+            path: path.to_owned(),
+            queries,
+        })
+    }
+
+    /// Returns the fixture values of `query` as SQL literals, in bind order.
+    pub(crate) fn arguments(
+        &self,
+        module: &PreparedModule,
+        query: &PreparedQuery,
+    ) -> Result<Vec<String>, Error> {
+        let key = format!("{}::{}", module.info.qualified_name(), query.ident.db);
+        let values = match self.queries.get(&key) {
+            Some(Value::Table(values)) => Some(values),
+            Some(_) => {
+                return Err(Error::NotATable {
+                    path: self.path.clone(),
+                    key,
+                })
+            }
+            None => None,
+        };
+        let Some((idx, order)) = &query.param else {
+            return Ok(Vec::new());
+        };
+        let fields = &module.params.get_index(*idx).unwrap().1.fields;
+        order
+            .iter()
+            .map(|idx| {
+                let field = &fields[*idx];
+                match values.and_then(|values| values.get(&field.ident.db)) {
+                    Some(value) => literal(value).ok_or_else(|| Error::InvalidValue {
+                        key: key.clone(),
+                        param: field.ident.db.clone(),
+                    }),
+                    None if field.is_nullable => Ok("NULL".to_string()),
+                    None => Err(Error::MissingParam {
+                        path: self.path.clone(),
+                        key: key.clone(),
+                        param: field.ident.db.clone(),
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    /// Checks that every fixture targets an existing query and parameter, then runs each
+    /// fixture query inside a rolled-back transaction. Returns every error encountered.
+    pub(crate) fn check(&self, client: &mut Client, preparation: &Preparation) -> Vec<Error> {
+        let mut errors = Vec::new();
+        let mut runnable = Vec::new();
+        for (key, values) in &self.queries {
+            let found = key.rsplit_once("::").and_then(|(module, query)| {
+                let module = preparation
+                    .modules
+                    .iter()
+                    .find(|it| it.info.qualified_name() == module)?;
+                let query = module.queries.values().find(|it| it.ident.db == query)?;
+                Some((module, query))
+            });
+            let Some((module, query)) = found else {
+                errors.push(Error::UnknownQuery {
+                    path: self.path.clone(),
+                    key: key.clone(),
+                });
+                continue;
+            };
+            if let Value::Table(values) = values {
+                let fields = query
+                    .param
+                    .as_ref()
+                    .map(|(idx, _)| &module.params.get_index(*idx).unwrap().1.fields[..])
+                    .unwrap_or_default();
+                for param in values.keys() {
+                    if !fields.iter().any(|f| &f.ident.db == param) {
+                        errors.push(Error::UnknownParam {
+                            path: self.path.clone(),
+                            key: key.clone(),
+                            param: param.clone(),
+                        });
+                    }
+                }
+            }
+            match self.arguments(module, query) {
+                Ok(args) => runnable.push((key, module, query, args)),
+                Err(e) => errors.push(e),
+            }
+        }
+        if !errors.is_empty() {
+            return errors;
+        }
+
+        let mut transaction = match client.transaction() {
+            Ok(transaction) => transaction,
+            Err(err) => return vec![Error::Transaction(err)],
+        };
+        for (key, module, query, args) in runnable {
+            // Each fixture runs in its own savepoint so that a failure doesn't hide the next ones
+            let mut prepared = false;
+            let result = transaction.transaction().and_then(|mut savepoint| {
+                module.set_local_search_path(&mut savepoint)?;
+                savepoint.batch_execute(&format!("PREPARE cornucopia_fixture AS {}", query.sql))?;
+                prepared = true;
+                savepoint.batch_execute(&execute_sql("cornucopia_fixture", &args))
+            });
+            // Prepared statements outlive rolled back savepoints
+            let deallocated = if prepared {
+                transaction.batch_execute("DEALLOCATE cornucopia_fixture")
+            } else {
+                Ok(())
+            };
+            let result = result.and(deallocated);
+            if let Err(err) = result {
+                errors.push(Error::Execute {
+                    key: key.clone(),
+                    err,
+                });
+            }
+        }
+        errors
+    }
+}
+
+/// Builds the statement executing the prepared statement `name` with SQL literal `args`.
+/// Untyped literals are coerced to the statement's parameter types by PostgreSQL.
+pub(crate) fn execute_sql(name: &str, args: &[String]) -> String {
+    if args.is_empty() {
+        format!("EXECUTE {name}")
+    } else {
+        format!("EXECUTE {name}({})", args.join(", "))
+    }
+}
+
+/// Renders a fixture value as an SQL literal, returning `None` for unsupported values.
+fn literal(value: &Value) -> Option<String> {
+    Some(match value {
+        Value::String(s) => quote(s),
+        Value::Integer(i) => i.to_string(),
+        // Non-finite floats have no numeric literal, only an input syntax
+        Value::Float(f) if !f.is_finite() => format!("{}::float8", quote(&float_input(*f))),
+        Value::Float(f) => f.to_string(),
+        Value::Boolean(b) => b.to_string(),
+        Value::Datetime(d) => quote(&d.to_string()),
+        Value::Array(_) => quote(&array_literal(value)?),
+        Value::Table(_) => return None,
+    })
+}
+
+/// Renders a value using PostgreSQL's array input syntax (`{1,2,3}`).
+fn array_literal(value: &Value) -> Option<String> {
+    Some(match value {
+        Value::Array(items) => {
+            let mut out = String::from("{");
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&array_literal(item)?);
+            }
+            out.push('}');
+            out
+        }
+        Value::String(s) => {
+            let mut out = String::from("\"");
+            for c in s.chars() {
+                if matches!(c, '"' | '\\') {
+                    out.push('\\');
+                }
+                out.push(c);
+            }
+            out.push('"');
+            out
+        }
+        Value::Datetime(d) => format!("\"{d}\""),
+        Value::Float(f) => float_input(*f),
+        Value::Table(_) => return None,
+        other => literal(other)?,
+    })
+}
+
+/// Renders a float using PostgreSQL's `float8` input syntax, which spells out `NaN` and
+/// infinities.
+fn float_input(f: f64) -> String {
+    if f.is_nan() {
+        String::from("NaN")
+    } else if f == f64::INFINITY {
+        String::from("Infinity")
+    } else if f == f64::NEG_INFINITY {
+        String::from("-Infinity")
+    } else {
+        f.to_string()
+    }
+}
+
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('\'');
+    for c in s.chars() {
+        if c == '\'' {
+            out.push('\'');
+        }
+        out.push(c);
+    }
+    out.push('\'');
+    out
+}
+
+pub(crate) mod error {
+    use std::path::PathBuf;
+
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("Couldn't read fixtures file `{path}`: ({err})")]
+        #[diagnostic(
+            code(cornucopia::fixtures::read),
+            help("check the path given to `--fixtures`")
+        )]
+        Read { path: PathBuf, err: std::io::Error },
+        #[error("Couldn't parse fixtures file `{path}`: {err}")]
+        #[diagnostic(
+            code(cornucopia::fixtures::parse),
+            help("fixtures files are TOML tables keyed by `module::query`")
+        )]
+        Parse { path: PathBuf, err: toml::de::Error },
+        #[error("[{path}] : fixture `{key}` must be a table of parameter values")]
+        #[diagnostic(
+            code(cornucopia::fixtures::not_a_table),
+            help("write it as `[module::query]` followed by `param = value` lines")
+        )]
+        NotATable { path: PathBuf, key: String },
+        #[error("[{path}] : fixture `{key}` doesn't match any query")]
+        #[diagnostic(
+            code(cornucopia::fixtures::unknown_query),
+            help("fixtures are keyed by `module::query`")
+        )]
+        UnknownQuery { path: PathBuf, key: String },
+        #[error("[{path}] : fixture `{key}` has no parameter named `{param}`")]
+        #[diagnostic(
+            code(cornucopia::fixtures::unknown_param),
+            help("remove it or fix its name to match the query's parameters")
+        )]
+        UnknownParam {
+            path: PathBuf,
+            key: String,
+            param: String,
+        },
+        #[error("[{path}] : fixture `{key}` is missing non-nullable parameter `{param}`")]
+        #[diagnostic(
+            code(cornucopia::fixtures::missing_param),
+            help("add a value for it, or declare it nullable in the query with `{param}?`")
+        )]
+        MissingParam {
+            path: PathBuf,
+            key: String,
+            param: String,
+        },
+        #[error("Unsupported value for parameter `{param}` of fixture `{key}`")]
+        #[diagnostic(
+            code(cornucopia::fixtures::invalid_value),
+            help("use strings, numbers, booleans, datetimes or arrays of those")
+        )]
+        InvalidValue { key: String, param: String },
+        #[error("Couldn't run fixture `{key}`: {err:#}")]
+        #[diagnostic(
+            code(cornucopia::fixtures::execute),
+            help("check that the fixture values match the types of the query's parameters")
+        )]
+        Execute { key: String, err: postgres::Error },
+        #[error("Couldn't open a transaction to run fixtures: {0:#}")]
+        #[diagnostic(
+            code(cornucopia::fixtures::transaction),
+            help("check that the database is still reachable and isn't in recovery")
+        )]
+        Transaction(postgres::Error),
+    }
+}
@@ -0,0 +1,257 @@
+use std::path::{Path, PathBuf};
+
+use heck::ToSnakeCase;
+use miette::NamedSource;
+
+use crate::{
+    lexer::{is_keyword, lex, InvalidParam, Token},
+    utils::append_query,
+};
+
+use self::error::Error;
+
+fn is_comparison(token: &Token) -> bool {
+    match token {
+        Token::Op(op) => matches!(op.as_str(), "=" | "<>" | "!=" | "<" | ">" | "<=" | ">="),
+        Token::Ident(kw, _) => ["LIKE", "ILIKE"].contains(&kw.to_ascii_uppercase().as_str()),
+        _ => false,
+    }
+}
+
+/// Infers a name for the parameter at `tokens[i]` from the surrounding SQL.
+fn infer_from_context(tokens: &[Token], i: usize) -> Option<String> {
+    let before = |n: usize| i.checked_sub(n).map(|idx| &tokens[idx]);
+    let after = |n: usize| tokens.get(i + n);
+    let ident = |token: Option<&Token>| match token {
+        Some(Token::Ident(ident, _)) => Some(ident.clone()),
+        _ => None,
+    };
+
+    // col = $1
+    if before(1).is_some_and(is_comparison) {
+        return ident(before(2));
+    }
+    // col = ANY($1)
+    if before(1) == Some(&Token::Punct('('))
+        && before(2).is_some_and(|t| is_keyword(t, "ANY"))
+        && before(3).is_some_and(is_comparison)
+    {
+        return ident(before(4));
+    }
+    // $1 = col
+    if after(1).is_some_and(is_comparison) {
+        return ident(after(2));
+    }
+    // LIMIT $1 / OFFSET $1
+    match before(1) {
+        Some(t) if is_keyword(t, "LIMIT") => Some("limit".into()),
+        Some(t) if is_keyword(t, "OFFSET") => Some("offset".into()),
+        _ => None,
+    }
+}
+
+/// Infers parameter names from `INSERT INTO table (cols) VALUES ($1, ...)` lists.
+fn infer_from_insert(tokens: &[Token], names: &mut [Option<String>]) {
+    let Some(values) = tokens.iter().position(|t| is_keyword(t, "VALUES")) else {
+        return;
+    };
+    // Collect the column list preceding VALUES
+    if values == 0 || tokens[values - 1] != Token::Punct(')') {
+        return;
+    }
+    let mut columns = Vec::new();
+    let mut idx = values - 1;
+    loop {
+        let Some(prev) = idx.checked_sub(1) else {
+            return;
+        };
+        idx = prev;
+        match &tokens[idx] {
+            Token::Ident(ident, _) => columns.push(ident.clone()),
+            Token::Punct(',') => {}
+            Token::Punct('(') => break,
+            _ => return,
+        }
+    }
+    columns.reverse();
+
+    // Match each row of values with the column list
+    let mut idx = values + 1;
+    while tokens.get(idx) == Some(&Token::Punct('(')) {
+        let mut depth = 0;
+        let mut item = Vec::new();
+        let mut col = 0;
+        idx += 1;
+        while let Some(token) = tokens.get(idx) {
+            idx += 1;
+            match token {
+                Token::Punct('(') => depth += 1,
+                Token::Punct(')') if depth == 0 => break,
+                Token::Punct(')') => depth -= 1,
+                Token::Punct(',') if depth == 0 => {
+                    assign_insert_item(&item, columns.get(col), names);
+                    item.clear();
+                    col += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            item.push(token.clone());
+        }
+        assign_insert_item(&item, columns.get(col), names);
+        if tokens.get(idx) != Some(&Token::Punct(',')) {
+            break;
+        }
+        idx += 1;
+    }
+}
+
+fn assign_insert_item(item: &[Token], column: Option<&String>, names: &mut [Option<String>]) {
+    if let ([Token::Param(n, _)], Some(column)) = (item, column) {
+        if let Some(name @ None) = names.get_mut(n - 1) {
+            *name = Some(column.clone());
+        }
+    }
+}
+
+/// Rewrites positional parameters (`$1`, `$2`, ...) into named cornucopia bind parameters,
+/// inferring their names from the surrounding SQL when possible.
+fn name_params(sql: &str) -> Result<String, InvalidParam> {
+    let tokens = lex(sql)?;
+    let nb_params = tokens
+        .iter()
+        .filter_map(|t| match t {
+            Token::Param(n, _) => Some(*n),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0);
+    let mut names: Vec<Option<String>> = vec![None; nb_params];
+    infer_from_insert(&tokens, &mut names);
+    for (i, token) in tokens.iter().enumerate() {
+        if let Token::Param(n, _) = token {
+            if names[n - 1].is_none() {
+                names[n - 1] = infer_from_context(&tokens, i);
+            }
+        }
+    }
+
+    // Sanitize names and make them unique
+    let mut unique: Vec<String> = Vec::with_capacity(names.len());
+    for (i, name) in names.into_iter().enumerate() {
+        let name = name
+            .map(|name| name.to_snake_case())
+            .filter(|name| {
+                name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                    && !name.starts_with(|c: char| c.is_ascii_digit())
+            })
+            .unwrap_or_else(|| format!("param{}", i + 1));
+        let name = if unique.contains(&name) {
+            format!("{name}{}", i + 1)
+        } else {
+            name
+        };
+        unique.push(name);
+    }
+
+    // Replace from the end so that byte ranges stay valid
+    let mut sql = sql.to_string();
+    for token in tokens.iter().rev() {
+        if let Token::Param(n, range) = token {
+            sql.replace_range(range.clone(), &format!(":{}", unique[n - 1]));
+        }
+    }
+    Ok(sql)
+}
+
+/// Imports the plain SQL files (one statement each) located in `dir` into the
+/// `module` query file of `queries_path`, rewriting positional parameters into
+/// named bind parameters. Returns the path of the query file and the skipped files.
+pub(crate) fn import(
+    dir: &Path,
+    queries_path: &Path,
+    module: Option<&str>,
+) -> Result<(PathBuf, Vec<PathBuf>), Error> {
+    let io_err = |path: &Path| {
+        let path = path.to_owned();
+        move |err| Error::Io { err, path }
+    };
+
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(io_err(dir))? {
+        let path = entry.map_err(io_err(dir))?.path();
+        if path.extension().is_some_and(|ext| ext == "sql") {
+            files.push(path);
+        }
+    }
+    files.sort();
+
+    let module = module.map_or_else(
+        || {
+            std::fs::canonicalize(dir)
+                .ok()
+                .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().to_snake_case()))
+                .unwrap_or_else(|| "imported".into())
+        },
+        str::to_string,
+    );
+    let destination = queries_path.join(format!("{module}.sql"));
+    std::fs::create_dir_all(queries_path).map_err(io_err(queries_path))?;
+
+    let mut skipped = Vec::new();
+    for path in files {
+        let content = std::fs::read_to_string(&path).map_err(io_err(&path))?;
+        let sql = content.trim().trim_end_matches(';').trim_end();
+        // Cornucopia queries are delimited by `;`, so we can only import single statements
+        if sql.contains(';') {
+            skipped.push(path);
+            continue;
+        }
+        let mut name = path
+            .file_stem()
+            .expect("is a file")
+            .to_string_lossy()
+            .to_snake_case();
+        if name.starts_with(|c: char| c.is_ascii_digit()) {
+            name.insert_str(0, "query_");
+        }
+        // `sql` starts where the content's leading whitespace ends
+        let offset = content.len() - content.trim_start().len();
+        let sql = name_params(sql).map_err(|InvalidParam(range)| Error::InvalidParam {
+            src: NamedSource::new(path.to_string_lossy(), content.clone()),
+            span: (offset + range.start, range.len()).into(),
+        })?;
+        let query = format!("--! {name}\n{sql};\n");
+        append_query(&destination, &query).map_err(io_err(&destination))?;
+    }
+
+    Ok((destination, skipped))
+}
+
+pub(crate) mod error {
+    use std::path::PathBuf;
+
+    use miette::{Diagnostic, NamedSource, SourceSpan};
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("Couldn't import `{path}`: {err:#}")]
+        #[diagnostic(
+            code(cornucopia::import),
+            help("check that the imported files exist and that the queries folder is writable")
+        )]
+        Io { err: std::io::Error, path: PathBuf },
+        #[error("Couldn't import a positional parameter PostgreSQL can't bind")]
+        #[diagnostic(
+            code(cornucopia::import::invalid_param),
+            help("parameters are numbered from `$1` to `$65535`")
+        )]
+        InvalidParam {
+            #[source_code]
+            src: NamedSource,
+            #[label("invalid parameter")]
+            span: SourceSpan,
+        },
+    }
+}
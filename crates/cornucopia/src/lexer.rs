@@ -0,0 +1,306 @@
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token {
+    /// Identifier or keyword (without its quotes), with its byte range in the source.
+    Ident(String, std::ops::Range<usize>),
+    /// Positional parameter `$n`, with its byte range in the source.
+    Param(usize, std::ops::Range<usize>),
+    Op(String),
+    Punct(char),
+    /// Literals, numbers and anything we don't care about.
+    Other,
+}
+
+/// Positional parameter whose number PostgreSQL can't bind, with its byte range in the source.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct InvalidParam(pub(crate) std::ops::Range<usize>);
+
+/// PostgreSQL binds at most this many parameters per statement.
+const MAX_PARAMS: usize = u16::MAX as usize;
+
+/// Minimal SQL lexer, just good enough to find parameters, identifiers and their context.
+pub(crate) fn lex(sql: &str) -> Result<Vec<Token>, InvalidParam> {
+    Ok(lex_with_offsets(sql)?
+        .into_iter()
+        .map(|(_, token)| token)
+        .collect())
+}
+
+/// Element of SQL spanning several bytes, scanned the same way by the lexer, the
+/// normalization and the translation of bind parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Scanned {
+    /// Line or block comment, block comments being nested.
+    Comment,
+    /// String literal, with backslash escapes if it is an `E'...'` string, or
+    /// dollar-quoted string.
+    Literal,
+    /// Quoted identifier, whose doubled quotes are escaped quotes.
+    QuotedIdent,
+    /// Unquoted identifier or keyword, which can hold `$` after its first character.
+    Word,
+}
+
+/// Kind and end of the element of `sql` starting at `start`, if any.
+pub(crate) fn scan(sql: &str, start: usize) -> Option<(Scanned, usize)> {
+    let bytes = sql.as_bytes();
+    let mut i = start + 1;
+    let scanned = match bytes[start] {
+        b'-' if bytes.get(i) == Some(&b'-') => {
+            while i < bytes.len() && bytes[i] != b'\n' {
+                i += 1;
+            }
+            Scanned::Comment
+        }
+        b'/' if bytes.get(i) == Some(&b'*') => {
+            let mut depth = 0;
+            i = start;
+            while i < bytes.len() {
+                if sql[i..].starts_with("/*") {
+                    depth += 1;
+                    i += 2;
+                } else if sql[i..].starts_with("*/") {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+            Scanned::Comment
+        }
+        b'\'' => {
+            // Backslash escapes are only allowed in `E'...'` strings
+            let escapes = start > 0
+                && matches!(bytes[start - 1], b'e' | b'E')
+                && !(start > 1 && is_word_byte(bytes[start - 2]));
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'\\' if escapes => i += 2,
+                    b'\'' if bytes.get(i + 1) == Some(&b'\'') => i += 2,
+                    b'\'' => {
+                        i += 1;
+                        break;
+                    }
+                    _ => i += 1,
+                }
+            }
+            Scanned::Literal
+        }
+        b'"' => {
+            while i < bytes.len() {
+                i += 1;
+                if bytes[i - 1] == b'"' {
+                    if bytes.get(i) == Some(&b'"') {
+                        i += 1;
+                    } else {
+                        break;
+                    }
+                }
+            }
+            Scanned::QuotedIdent
+        }
+        b'$' if bytes.get(i).is_some_and(|c| !c.is_ascii_digit()) => {
+            // Dollar quoted string, whose tag is an identifier
+            let mut tag_end = i;
+            while tag_end < bytes.len() && is_word_byte(bytes[tag_end]) && bytes[tag_end] != b'$' {
+                tag_end += 1;
+            }
+            if bytes.get(tag_end) != Some(&b'$') {
+                return None;
+            }
+            let tag = &sql[start..=tag_end];
+            i = sql[tag_end + 1..]
+                .find(tag)
+                .map_or(bytes.len(), |end| tag_end + 1 + end + tag.len());
+            Scanned::Literal
+        }
+        c if c.is_ascii_alphabetic() || c == b'_' => {
+            while i < bytes.len() && is_word_byte(bytes[i]) {
+                i += 1;
+            }
+            Scanned::Word
+        }
+        _ => return None,
+    };
+    Some((scanned, i.min(bytes.len())))
+}
+
+/// Whether `c` can continue an unquoted identifier.
+fn is_word_byte(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_' || c == b'$'
+}
+
+/// Tokens of `sql`, along with the byte offset each starts at.
+pub(crate) fn lex_with_offsets(sql: &str) -> Result<Vec<(usize, Token)>, InvalidParam> {
+    let bytes = sql.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        if let Some((scanned, end)) = scan(sql, start) {
+            i = end;
+            match scanned {
+                Scanned::Comment => {}
+                Scanned::Literal => tokens.push((start, Token::Other)),
+                Scanned::QuotedIdent => {
+                    let unquoted = sql[start + 1..end]
+                        .strip_suffix('"')
+                        .unwrap_or(&sql[start + 1..end]);
+                    tokens.push((
+                        start,
+                        Token::Ident(unquoted.replace("\"\"", "\""), start..end),
+                    ));
+                }
+                Scanned::Word => {
+                    tokens.push((start, Token::Ident(sql[start..end].to_string(), start..end)));
+                }
+            }
+            continue;
+        }
+        i += 1;
+        match c {
+            c if c.is_ascii_whitespace() => {}
+            b'$' if bytes.get(i).is_some_and(|c| (b'1'..=b'9').contains(c)) => {
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let n = sql[start + 1..i]
+                    .parse()
+                    .ok()
+                    .filter(|n| *n <= MAX_PARAMS)
+                    .ok_or(InvalidParam(start..i))?;
+                tokens.push((start, Token::Param(n, start..i)));
+            }
+            c if c.is_ascii_digit() => {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.') {
+                    i += 1;
+                }
+                tokens.push((start, Token::Other));
+            }
+            b'<' | b'>' | b'=' | b'!' | b'~' => {
+                while i < bytes.len() && matches!(bytes[i], b'<' | b'>' | b'=' | b'!' | b'~') {
+                    i += 1;
+                }
+                tokens.push((start, Token::Op(sql[start..i].to_string())));
+            }
+            _ => {
+                // Skip the remaining bytes of multi-byte characters
+                while i < bytes.len() && !sql.is_char_boundary(i) {
+                    i += 1;
+                }
+                tokens.push((start, Token::Punct(c as char)));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Statements of `sql`, split at the semicolons ending them outside of literals, quoted
+/// identifiers, comments and dollar-quoted strings.
+pub(crate) fn statements(sql: &str) -> Result<Vec<&str>, InvalidParam> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    for (offset, token) in lex_with_offsets(sql)? {
+        if token == Token::Punct(';') {
+            statements.push(&sql[start..offset]);
+            start = offset + 1;
+        }
+    }
+    statements.push(&sql[start..]);
+    statements.retain(|statement| !statement.trim().is_empty());
+    Ok(statements)
+}
+
+pub(crate) fn is_keyword(token: &Token, keyword: &str) -> bool {
+    matches!(token, Token::Ident(ident, _) if ident.eq_ignore_ascii_case(keyword))
+}
+
+/// Byte ranges of the references to the `template` schema in `sql`, such as `template` in
+/// `template.users` or `"template".users`. Literals and comments are skipped, and unquoted
+/// names are compared case-insensitively as PostgreSQL folds them.
+pub(crate) fn schema_references(sql: &str, template: &str) -> Vec<std::ops::Range<usize>> {
+    let Ok(tokens) = lex(sql) else {
+        return Vec::new();
+    };
+    tokens
+        .iter()
+        .zip(tokens.iter().skip(1))
+        .filter_map(|(token, next)| match token {
+            Token::Ident(name, range) if next == &Token::Punct('.') => {
+                let is_quoted = range.len() != name.len();
+                let is_template = if is_quoted {
+                    name == template
+                } else {
+                    name.eq_ignore_ascii_case(template)
+                };
+                is_template.then(|| range.clone())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether `sql` may have side effects, which is anything but a plain `SELECT`: data
+/// modifying statements or `WITH` clauses, `SELECT INTO`, row locks and sequence updates.
+/// Functions with side effects can't be told apart, and are left to a read-only transaction.
+pub(crate) fn is_mutating(sql: &str) -> bool {
+    // Postgres rejects such queries anyway, assume the worst
+    let Ok(tokens) = lex(sql) else {
+        return true;
+    };
+    let any_keyword = |token: &Token, keywords: &[&str]| {
+        keywords.iter().any(|keyword| is_keyword(token, keyword))
+    };
+    let reads = match tokens.first() {
+        Some(token) => any_keyword(token, &["SELECT", "WITH", "VALUES", "TABLE"]),
+        None => return false,
+    };
+    let side_effect = tokens.iter().enumerate().any(|(i, token)| {
+        let next = tokens.get(i + 1);
+        any_keyword(token, &["INSERT", "UPDATE", "DELETE", "MERGE", "INTO"])
+            // `FOR UPDATE`, `FOR NO KEY UPDATE`, `FOR SHARE` and `FOR KEY SHARE`
+            || is_keyword(token, "FOR")
+                && next.is_some_and(|it| any_keyword(it, &["UPDATE", "NO", "SHARE", "KEY"]))
+            || any_keyword(token, &["nextval", "setval"]) && next == Some(&Token::Punct('('))
+    });
+    !reads || side_effect
+}
+
+/// Strips comments and collapses whitespace into single spaces, leaving literals,
+/// quoted identifiers, dollar-quoted strings and planner hints (`/*+ ... */`, read by
+/// extensions such as `pg_hint_plan`) untouched.
+pub(crate) fn normalize(sql: &str) -> String {
+    let bytes = sql.as_bytes();
+    let mut out = String::with_capacity(sql.len());
+    let mut pending_space = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        if let Some((scanned, end)) = scan(sql, start) {
+            i = end;
+            if scanned == Scanned::Comment && !sql[start..].starts_with("/*+") {
+                pending_space = true;
+                continue;
+            }
+        } else if bytes[start].is_ascii_whitespace() {
+            i += 1;
+            pending_space = true;
+            continue;
+        } else {
+            i += 1;
+            // Skip the remaining bytes of multi-byte characters
+            while i < bytes.len() && !sql.is_char_boundary(i) {
+                i += 1;
+            }
+        }
+        if pending_space && !out.is_empty() {
+            out.push(' ');
+        }
+        pending_space = false;
+        out.push_str(&sql[start..i]);
+    }
+    out
+}
@@ -1,41 +1,345 @@
+mod bench;
+mod builder;
 mod cli;
 mod codegen;
+mod compat;
+mod config;
+mod decode_benches;
+mod diagram;
+mod domain_checks;
+mod drift;
 mod error;
+mod export;
+mod export_sql;
+mod fixtures;
+mod import;
+mod lexer;
 mod load_schema;
+mod manifest;
+mod new_query;
 mod parser;
+mod partitions;
 mod prepare_queries;
 mod read_queries;
+mod schema_info;
+mod test_database;
+mod text_limits;
+mod type_dump;
 mod type_registrar;
 mod utils;
 mod validation;
+mod watch;
 
 /// Helpers to establish connections to database instances.
 pub mod conn;
 /// High-level interfaces to work with Cornucopia's container manager.
 pub mod container;
 
-use std::path::Path;
+use std::{
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+};
 
+use miette::{NamedSource, SourceSpan};
 use postgres::Client;
 
 use codegen::generate as generate_internal;
-use error::WriteOutputError;
+use container::ManagedContainer;
+use decode_benches::prepare_benchmarks;
+use domain_checks::prepare_domains;
+use drift::check_drift;
+use error::{
+    CheckError, CheckVersionsError, InvalidCodeError, InvalidStatementPrefixError, VersionFailure,
+    WriteOutputError,
+};
+use fixtures::Fixtures;
 use parser::parse_query_module;
-use prepare_queries::prepare;
+use partitions::prepare_partitions;
+use prepare_queries::{check, prepare};
 use read_queries::read_query_modules;
+use schema_info::schema_version;
 
+pub use clap_complete::Shell;
 #[doc(hidden)]
 pub use cli::run;
+pub use cli::{write_completions, write_man_page};
 
+pub use bench::BenchReport;
+pub use builder::{generate, Generator};
+pub use config::Config;
+pub use diagram::DiagramFormat;
 pub use error::Error;
+pub use export::ExportFormat;
 pub use load_schema::load_schema;
+pub use test_database::TestDatabase;
+pub use watch::watch_live;
 
 /// Struct containing the settings for code generation.
-#[derive(Clone, Copy)]
+///
+/// Settings are added along with features, so build them from their defaults instead of
+/// listing every field, or through a [`Generator`] in a build script:
+///
+/// ```
+/// let settings = cornucopia::CodegenSettings {
+///     gen_sync: true,
+///     derive_ser: true,
+///     ..Default::default()
+/// };
+/// ```
+///
+/// # Compatibility
+///
+/// The settings aren't `Copy` anymore since they hold strings, such as the statement
+/// prefix: pass them by reference or clone them instead.
+#[derive(Clone)]
 pub struct CodegenSettings {
     pub gen_async: bool,
     pub gen_sync: bool,
     pub derive_ser: bool,
+    pub gen_pool: bool,
+    /// Prefix tagging each prepared statement, so that statements can be attributed
+    /// to an application in `pg_prepared_statements` and `pg_stat_activity`. Made of ASCII
+    /// letters, digits, `_`, `.`, `:` and `-`, as it is written in an SQL comment.
+    pub statement_prefix: Option<String>,
+    /// Syntax used to write bind parameters in query files.
+    pub param_syntax: ParamSyntax,
+    /// Generate a smoke test per query, run against `DATABASE_URL` by `cargo test -- --ignored`.
+    pub gen_tests: bool,
+    /// Generate a registry of every query, used by the clients' `introspection` feature.
+    pub gen_registry: bool,
+    /// Generate variants of the queries referencing a partitioned table that target one
+    /// of its partitions, enumerated from the catalog at generation time.
+    pub gen_partitions: bool,
+    /// Strip comments and collapse whitespace in the SQL embedded in generated code.
+    /// The original text is kept otherwise, which eases debugging.
+    pub normalize_sql: bool,
+    /// Directory, relative to the generated file, where the SQL of each statement is
+    /// written as a sidecar file included with `include_str!`, instead of inlined as a
+    /// string literal. Sidecar files are only written when a destination is given.
+    pub external_sql: Option<String>,
+    /// Write the `types` module and each query module into their own file, declared by the
+    /// generated file, so that large schemas don't produce a single huge file. Module files
+    /// are written relative to the destination, typically a `mod.rs`.
+    pub split_modules: bool,
+    /// Name of the publishable crate the generated code is written as, with a `Cargo.toml`
+    /// depending on exactly the crates and features the code uses, written next to the
+    /// `src` folder of the destination. Workspaces can build the generated code once and
+    /// share it across services.
+    pub crate_name: Option<String>,
+    /// Inherit the dependencies of the generated crate from the `[workspace.dependencies]`
+    /// of its workspace, which then sets their versions, instead of pinning the versions
+    /// cornucopia is tested with.
+    pub workspace_dependencies: bool,
+    /// Resolve statements from a compressed table by the hash of their name in release
+    /// builds, so that SQL can't be grepped from shipped binaries. Requires the client's
+    /// `obfuscated-sql` feature. The query registry still embeds plain SQL.
+    pub obfuscate_sql: bool,
+    /// Generate `bind_tracked` variants of mutating queries, recording the WAL position
+    /// of the primary so that reads routed to replicas can wait for it. Requires the
+    /// client's `replicas` feature.
+    pub gen_replicas: bool,
+    /// Generate `to_map` and `from_map` conversions between named rows or params and
+    /// `HashMap<String, serde_json::Value>`, keyed by column or parameter name. Custom
+    /// types derive `serde::Serialize` and `serde::Deserialize`.
+    pub gen_maps: bool,
+    /// Generate a `write_csv` function exporting rows with a header record. Every column
+    /// of a named row must be a scalar or an enum, or the queries are rejected. Requires
+    /// the client's `csv` feature.
+    pub gen_csv: bool,
+    /// Generate `arrow_schema` and `to_record_batch` functions converting rows into an
+    /// Arrow `RecordBatch`. Every column of a named row must be a boolean, number, string
+    /// or bytes, or the queries are rejected. Requires the client's `arrow` feature.
+    pub gen_arrow: bool,
+    /// Generate sqlx `Type`, `Encode` and `Decode` impls for custom enums, and for
+    /// composites whose fields are all booleans, numbers, strings, bytes or other bridged
+    /// types, so that they can be shared with sqlx. Requires the client's `sqlx` feature.
+    pub gen_sqlx: bool,
+    /// Generate a Diesel SQL type for each custom enum, named after the enum with a `Sql`
+    /// suffix, with the `ToSql` and `FromSql` impls sharing the enum with Diesel. Requires the
+    /// `diesel` feature of the client crate.
+    pub gen_diesel: bool,
+    /// Sample the plans of read queries attached to an `Explainer`, which runs a fraction
+    /// of their executions again under `EXPLAIN ANALYZE` on a connection of its own and
+    /// hands the plans to a hook. Requires the client's `explain` feature.
+    pub gen_explain: bool,
+    /// Generate a `StmtCache` per query module, holding each of its statements so that
+    /// repeated calls reuse them instead of preparing them again. Deadpool clients
+    /// already reuse statements through their own cache.
+    pub gen_statement_cache: bool,
+    /// Generate a `Queries` struct per query module, holding a client and exposing each
+    /// of the module's queries as a method, so that a single object can be passed around.
+    /// A query can't be named `new` then, as it would clash with the constructor.
+    pub gen_facade: bool,
+    /// Generate a `schema_info` module exposing the version of the schema the code was
+    /// generated against, as an OpenMetrics info gauge. The version is the latest
+    /// migration applied by refinery, sqlx or diesel, or a fingerprint of the schema.
+    pub gen_schema_info: bool,
+    /// Generate a `benches` module of criterion benchmarks decoding each non-Copy row and
+    /// composite type into its borrowed and owned structs, from binary payloads canned at
+    /// generation time. Requires a dependency on `criterion`.
+    pub gen_benches: bool,
+    /// Infer the nullability of the columns selected straight from a table from their
+    /// `NOT NULL` constraint, instead of annotating each nullable column. Columns listed
+    /// in a row's annotation keep their annotated nullability.
+    pub infer_nullability: bool,
+    /// Generate a params struct for the queries with named or several parameters, along
+    /// with its `params` and `execute` impls. Large query sets only using the query
+    /// functions' `bind` can skip them to trim the generated code.
+    pub gen_params_structs: bool,
+    /// Only generate the params structs of queries with more parameters than this
+    /// threshold, the others being bound with plain arguments.
+    pub params_struct_threshold: Option<usize>,
+    /// Generate a `cached` method on query structs, fetching rows through a read-through
+    /// `QueryCache` keyed by query id and hashed parameters. Rows and custom types derive
+    /// `serde::Serialize` and `serde::Deserialize` to be cached. Requires the client's
+    /// `cache` feature.
+    pub gen_cache: bool,
+    /// Generate a `check_` function per domain whose `CHECK` constraints all compare its
+    /// value to a number, validating parameters client-side before sending them. Fields of
+    /// a constrained domain are documented with its constraints either way.
+    pub gen_domain_checks: bool,
+    /// Generate a `validate` method on params structs, checking that the text fields written
+    /// to `varchar(n)` columns fit in them. Fields written to or read from such
+    /// columns are documented with their limit either way.
+    pub gen_text_limits: bool,
+    /// Template schema of a schema-per-tenant database. Queries referencing it, such as
+    /// `tenant_template.users`, get a `_for` constructor running them against the schema of
+    /// a tenant given at runtime, validated by the client's `TenantSchema`.
+    pub tenant_schema: Option<String>,
+    /// Search path the queries of modules without a `--@ schema` annotation are prepared
+    /// with, exposed as their `SEARCH_PATH` constant. Empty to use the session's own.
+    pub search_path: Vec<String>,
+    /// Traits every row struct derives on top of its default derives, such as `Hash` and
+    /// `Eq` so that rows can be put in sets. Rows declared by a query with a
+    /// `--! derive(...)` option also derive the listed traits.
+    pub row_derives: Vec<String>,
+    /// Crate the date and time types are mapped to.
+    pub time_crate: TimeCrate,
+    /// Crate the `numeric` type is mapped to.
+    pub decimal_crate: DecimalCrate,
+    /// Lints allowed by a `#![allow]` attribute at the top of the generated file, replacing
+    /// the `#[allow]` attributes of each generated module. Inner attributes can't be
+    /// `include!`d, the file must then be declared as a module or be a crate root.
+    pub lint_allows: Option<Vec<String>>,
+    /// Forbid unsafe code with a `#![forbid(unsafe_code)]` attribute at the top of the
+    /// generated file, which then can't be `include!`d either.
+    pub forbid_unsafe: bool,
+}
+
+impl CodegenSettings {
+    /// Checks the settings that their types don't, before generating anything.
+    fn check(&self) -> Result<(), InvalidStatementPrefixError> {
+        match &self.statement_prefix {
+            Some(prefix) if !is_valid_statement_prefix(prefix) => {
+                Err(InvalidStatementPrefixError {
+                    prefix: prefix.clone(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Whether `prefix` matches `[A-Za-z0-9_.:-]+`, so that it can't end or open the comment
+/// tagging statements.
+pub(crate) fn is_valid_statement_prefix(prefix: &str) -> bool {
+    !prefix.is_empty()
+        && prefix
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'.' | b':' | b'-'))
+}
+
+impl Default for CodegenSettings {
+    /// The CLI's defaults: asynchronous code with params structs, and nothing else.
+    fn default() -> Self {
+        Self {
+            gen_async: true,
+            gen_sync: false,
+            derive_ser: false,
+            gen_pool: false,
+            statement_prefix: None,
+            param_syntax: ParamSyntax::default(),
+            gen_tests: false,
+            gen_registry: false,
+            gen_partitions: false,
+            normalize_sql: false,
+            external_sql: None,
+            split_modules: false,
+            crate_name: None,
+            workspace_dependencies: false,
+            obfuscate_sql: false,
+            gen_replicas: false,
+            gen_maps: false,
+            gen_csv: false,
+            gen_arrow: false,
+            gen_sqlx: false,
+            gen_diesel: false,
+            gen_explain: false,
+            gen_statement_cache: false,
+            gen_facade: false,
+            gen_schema_info: false,
+            gen_benches: false,
+            infer_nullability: false,
+            gen_params_structs: true,
+            params_struct_threshold: None,
+            gen_cache: false,
+            gen_domain_checks: false,
+            gen_text_limits: false,
+            tenant_schema: None,
+            search_path: Vec::new(),
+            row_derives: Vec::new(),
+            time_crate: TimeCrate::default(),
+            decimal_crate: DecimalCrate::default(),
+            lint_allows: None,
+            forbid_unsafe: false,
+        }
+    }
+}
+
+/// Syntax used to write bind parameters in query files. Parameters written in
+/// any syntax other than [`ParamSyntax::Colon`] are translated to it while
+/// reading query files, easing the reuse of SQL written for other tools.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamSyntax {
+    /// `:name`, cornucopia's own syntax
+    #[default]
+    Colon,
+    /// `$name`
+    Dollar,
+    /// `@name`
+    At,
+    /// `%(name)s`, as used by Python's DB-API
+    Pyformat,
+}
+
+/// Crate the `timestamp`, `timestamptz`, `date` and `time` types are mapped to. The
+/// generated code requires the matching `with-time-0_3` or `with-chrono-0_4` feature of
+/// `postgres`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeCrate {
+    /// `time::PrimitiveDateTime`, `time::OffsetDateTime`, `time::Date` and `time::Time`
+    #[default]
+    Time,
+    /// `chrono::NaiveDateTime`, `chrono::DateTime<chrono::Utc>`, `chrono::NaiveDate` and
+    /// `chrono::NaiveTime`
+    Chrono,
+}
+
+/// Crate the `numeric` type is mapped to. `rust_decimal::Decimal` requires its
+/// `db-postgres` feature, `bigdecimal::BigDecimal` the client's `with-bigdecimal-0_4`
+/// feature, whose `Numeric` wrapper is the borrowed and params type of the fields.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DecimalCrate {
+    /// `rust_decimal::Decimal`, limited to 28 significant digits
+    #[default]
+    #[value(name = "rust_decimal")]
+    #[serde(rename = "rust_decimal")]
+    RustDecimal,
+    /// `bigdecimal::BigDecimal`, of arbitrary precision
+    Bigdecimal,
 }
 
 /// Generates Rust queries from PostgreSQL queries located at `queries_path`,
@@ -48,17 +352,22 @@ pub fn generate_live<P: AsRef<Path>>(
     destination: Option<P>,
     settings: CodegenSettings,
 ) -> Result<String, Error> {
+    settings.check()?;
     // Read
-    let modules = read_query_modules(queries_path.as_ref())?
+    let modules = read_query_modules(queries_path.as_ref(), settings.param_syntax)?
         .into_iter()
         .map(parse_query_module)
         .collect::<Result<_, parser::error::Error>>()?;
     // Generate
-    let prepared_modules = prepare(client, modules)?;
-    let generated_code = generate_internal(prepared_modules, settings);
+    let (generated_code, files) = generate_files(client, modules, &settings)?;
+    check_generated_files(
+        &generated_code,
+        &files,
+        destination.as_ref().map(AsRef::as_ref),
+    )?;
     // Write
     if let Some(d) = destination {
-        write_generated_code(d.as_ref(), &generated_code)?;
+        write_generated_code(d.as_ref(), &generated_code, &files)?;
     };
 
     Ok(generated_code)
@@ -78,30 +387,760 @@ pub fn generate_managed<P: AsRef<Path>>(
     podman: bool,
     settings: CodegenSettings,
 ) -> Result<String, Error> {
+    generate_managed_as(
+        queries_path,
+        schema_files,
+        destination,
+        &ManagedContainer {
+            podman,
+            ..Default::default()
+        },
+        None,
+        settings,
+    )
+}
+
+/// Generates Rust queries like [`generate_managed`]. The schema is loaded as the
+/// container's superuser. If some `app_url` is given, queries are then prepared through
+/// it, typically as your application's role, so that permission problems surface during
+/// generation.
+pub fn generate_managed_as<P: AsRef<Path>>(
+    queries_path: P,
+    schema_files: &[P],
+    destination: Option<P>,
+    managed: &ManagedContainer,
+    app_url: Option<&str>,
+    settings: CodegenSettings,
+) -> Result<String, Error> {
+    settings.check()?;
     // Read
-    let modules = read_query_modules(queries_path.as_ref())?
+    let modules = read_query_modules(queries_path.as_ref(), settings.param_syntax)?
         .into_iter()
         .map(parse_query_module)
         .collect::<Result<_, parser::error::Error>>()?;
-    container::setup(podman)?;
+    container::setup(managed)?;
     let mut client = conn::cornucopia_conn()?;
     load_schema(&mut client, schema_files)?;
-    let prepared_modules = prepare(&mut client, modules)?;
-    let generated_code = generate_internal(prepared_modules, settings);
-    container::cleanup(podman)?;
+    let mut client = conn::app_conn(client, app_url)?;
+    let (generated_code, files) = generate_files(&mut client, modules, &settings)?;
+    check_generated_files(
+        &generated_code,
+        &files,
+        destination.as_ref().map(AsRef::as_ref),
+    )?;
 
     if let Some(destination) = destination {
-        write_generated_code(destination.as_ref(), &generated_code)?;
+        write_generated_code(destination.as_ref(), &generated_code, &files)?;
     };
+    // Failures leave the container to the caller, which may keep it for debugging
+    container::cleanup(managed.podman)?;
 
     Ok(generated_code)
 }
 
-fn write_generated_code(destination: &Path, generated_code: &str) -> Result<(), Error> {
-    Ok(
-        std::fs::write(destination, generated_code).map_err(|err| WriteOutputError {
-            err,
-            file_path: destination.to_owned(),
-        })?,
+/// Checks that the code generated from the PostgreSQL queries located at `queries_path`,
+/// using a live database managed by you, matches the code written at `destination` and its
+/// module and sidecar SQL files, without writing anything. Formatting the generated files
+/// doesn't count as a difference. Code generation settings are set using the `settings`
+/// parameter.
+pub fn check_generated_live<P: AsRef<Path>>(
+    client: &mut Client,
+    queries_path: P,
+    destination: P,
+    settings: CodegenSettings,
+) -> Result<(), Error> {
+    settings.check()?;
+    let modules = read_query_modules(queries_path.as_ref(), settings.param_syntax)?
+        .into_iter()
+        .map(parse_query_module)
+        .collect::<Result<_, parser::error::Error>>()?;
+    let (generated_code, files) = generate_files(client, modules, &settings)?;
+    check_drift(destination.as_ref(), &generated_code, &files)?;
+    Ok(())
+}
+
+/// Checks that the code generated from the PostgreSQL queries located at `queries_path`,
+/// using a container managed by cornucopia, matches the code written at `destination` like
+/// [`check_generated_live`]. The database schema is created using `schema_files`.
+///
+/// The container is run by Docker, or by Podman if `managed.podman` is set, and its
+/// database cluster is initialized with `managed.cluster`.
+pub fn check_generated_managed<P: AsRef<Path>>(
+    queries_path: P,
+    schema_files: &[P],
+    destination: P,
+    managed: &ManagedContainer,
+    app_url: Option<&str>,
+    settings: CodegenSettings,
+) -> Result<(), Error> {
+    settings.check()?;
+    let modules = read_query_modules(queries_path.as_ref(), settings.param_syntax)?
+        .into_iter()
+        .map(parse_query_module)
+        .collect::<Result<_, parser::error::Error>>()?;
+    container::setup(managed)?;
+    let mut client = conn::cornucopia_conn()?;
+    load_schema(&mut client, schema_files)?;
+    let mut client = conn::app_conn(client, app_url)?;
+    let (generated_code, files) = generate_files(&mut client, modules, &settings)?;
+    check_drift(destination.as_ref(), &generated_code, &files)?;
+    container::cleanup(managed.podman)?;
+    Ok(())
+}
+
+/// Prepares the queries of `modules` and generates the code of the destination file along
+/// with its module and sidecar SQL files.
+fn generate_files(
+    client: &mut Client,
+    mut modules: Vec<parser::Module>,
+    settings: &CodegenSettings,
+) -> Result<(String, Vec<(String, String)>), Error> {
+    if !settings.search_path.is_empty() {
+        for module in modules.iter_mut().filter(|it| it.schemas.is_empty()) {
+            // The annotation has no source, its span is never reported
+            let span = SourceSpan::from((0, 0));
+            module.schemas.push(parser::SchemaAnnotation {
+                span,
+                schemas: settings
+                    .search_path
+                    .iter()
+                    .map(|schema| parser::Span {
+                        span,
+                        value: schema.clone(),
+                    })
+                    .collect(),
+            });
+        }
+    }
+    let mut prepared_modules = prepare(client, modules, settings)?;
+    if settings.gen_partitions {
+        prepare_partitions(client, &mut prepared_modules)?;
+    }
+    if settings.gen_schema_info {
+        prepared_modules.schema_version = Some(schema_version(client)?);
+    }
+    if settings.gen_benches {
+        prepared_modules.benchmarks = prepare_benchmarks(client, &prepared_modules)?;
+    }
+    // Fields of constrained domains are documented with their constraints either way
+    let domains = prepare_domains(client, &mut prepared_modules)?;
+    if settings.gen_domain_checks {
+        prepared_modules.domains = domains;
+    }
+    Ok(generate_internal(prepared_modules, settings))
+}
+
+/// Exports every PostgreSQL query located at `queries_path`, prepared against a live
+/// database managed by you, into a single reviewable SQL file. If some `destination` is
+/// given, the SQL will be written at that path. Bind parameters are written and types are
+/// mapped following `settings`.
+pub fn export_sql_live<P: AsRef<Path>>(
+    client: &mut Client,
+    queries_path: P,
+    destination: Option<P>,
+    settings: &CodegenSettings,
+) -> Result<String, Error> {
+    let modules = read_query_modules(queries_path.as_ref(), settings.param_syntax)?
+        .into_iter()
+        .map(parse_query_module)
+        .collect::<Result<_, parser::error::Error>>()?;
+    let preparation = prepare(client, modules, settings)?;
+    let sql = export_sql::export_sql(&preparation);
+    if let Some(destination) = destination {
+        write_generated_code(destination.as_ref(), &sql, &[])?;
+    };
+
+    Ok(sql)
+}
+
+/// Exports every PostgreSQL query located at `queries_path`, prepared using a container
+/// managed by cornucopia, into a single reviewable SQL file. The database schema is created
+/// using `schema_files`. If some `destination` is given, the SQL will be written at that
+/// path. Bind parameters are written and types are mapped following `settings`.
+///
+/// The container is run by Docker, or by Podman if `managed.podman` is set, and its
+/// database cluster is initialized with `managed.cluster`.
+pub fn export_sql_managed<P: AsRef<Path>>(
+    queries_path: P,
+    schema_files: &[P],
+    destination: Option<P>,
+    managed: &ManagedContainer,
+    settings: &CodegenSettings,
+) -> Result<String, Error> {
+    let modules = read_query_modules(queries_path.as_ref(), settings.param_syntax)?
+        .into_iter()
+        .map(parse_query_module)
+        .collect::<Result<_, parser::error::Error>>()?;
+    container::setup(managed)?;
+    let mut client = conn::cornucopia_conn()?;
+    load_schema(&mut client, schema_files)?;
+    let preparation = prepare(&mut client, modules, settings)?;
+    let sql = export_sql::export_sql(&preparation);
+
+    if let Some(destination) = destination {
+        write_generated_code(destination.as_ref(), &sql, &[])?;
+    };
+    container::cleanup(managed.podman)?;
+
+    Ok(sql)
+}
+
+/// Benchmarks `query` (as `module::query`) from the queries located at `queries_path`
+/// against a live database managed by you. The query is executed `iterations` times with
+/// the parameter values of the `fixtures` file, each time inside a transaction that is
+/// rolled back afterward. Bind parameters are written and types are mapped following
+/// `settings`.
+pub fn bench_live<P: AsRef<Path>>(
+    client: &mut Client,
+    queries_path: P,
+    fixtures: Option<P>,
+    query: &str,
+    iterations: usize,
+    settings: &CodegenSettings,
+) -> Result<BenchReport, Error> {
+    let fixtures = fixtures
+        .map(|path| Fixtures::load(path.as_ref()))
+        .transpose()?;
+    let modules = read_query_modules(queries_path.as_ref(), settings.param_syntax)?
+        .into_iter()
+        .map(parse_query_module)
+        .collect::<Result<_, parser::error::Error>>()?;
+    let preparation = prepare(client, modules, settings)?;
+    bench::bench(client, &preparation, fixtures.as_ref(), query, iterations)
+}
+
+/// Benchmarks `query` (as `module::query`) from the queries located at `queries_path` using
+/// a container managed by cornucopia. The database schema is created using `schema_files`.
+/// The query is executed `iterations` times with the parameter values of the `fixtures`
+/// file. Bind parameters are written and types are mapped following `settings`.
+///
+/// The container is run by Docker, or by Podman if `managed.podman` is set, and its
+/// database cluster is initialized with `managed.cluster`.
+pub fn bench_managed<P: AsRef<Path>>(
+    queries_path: P,
+    schema_files: &[P],
+    fixtures: Option<P>,
+    query: &str,
+    iterations: usize,
+    managed: &ManagedContainer,
+    settings: &CodegenSettings,
+) -> Result<BenchReport, Error> {
+    let fixtures = fixtures
+        .map(|path| Fixtures::load(path.as_ref()))
+        .transpose()?;
+    let modules = read_query_modules(queries_path.as_ref(), settings.param_syntax)?
+        .into_iter()
+        .map(parse_query_module)
+        .collect::<Result<_, parser::error::Error>>()?;
+    container::setup(managed)?;
+    let mut client = conn::cornucopia_conn()?;
+    load_schema(&mut client, schema_files)?;
+    let preparation = prepare(&mut client, modules, settings)?;
+    let report = bench::bench(
+        &mut client,
+        &preparation,
+        fixtures.as_ref(),
+        query,
+        iterations,
+    )?;
+    container::cleanup(managed.podman)?;
+
+    Ok(report)
+}
+
+/// Exports the rows of `query` (as `module::query`) from the queries located at
+/// `queries_path` to the `out` file in `format`, using a live database managed by you. The
+/// query is executed with the parameter values of the `fixtures` file, inside a transaction
+/// that is rolled back afterward. Bind parameters are written and types are mapped
+/// following `settings`. Returns the number of exported rows.
+pub fn export_live<P: AsRef<Path>>(
+    client: &mut Client,
+    queries_path: P,
+    fixtures: Option<P>,
+    query: &str,
+    format: ExportFormat,
+    out: P,
+    settings: &CodegenSettings,
+) -> Result<usize, Error> {
+    let fixtures = fixtures
+        .map(|path| Fixtures::load(path.as_ref()))
+        .transpose()?;
+    let modules = read_query_modules(queries_path.as_ref(), settings.param_syntax)?
+        .into_iter()
+        .map(parse_query_module)
+        .collect::<Result<_, parser::error::Error>>()?;
+    let preparation = prepare(client, modules, settings)?;
+    export::export(
+        client,
+        &preparation,
+        fixtures.as_ref(),
+        query,
+        format,
+        out.as_ref(),
     )
 }
+
+/// Exports the rows of `query` (as `module::query`) from the queries located at
+/// `queries_path` to the `out` file in `format`, using a container managed by cornucopia.
+/// The database schema is created using `schema_files`. The query is executed with the
+/// parameter values of the `fixtures` file. Bind parameters are written and types are
+/// mapped following `settings`. Returns the number of exported rows.
+///
+/// The container is run by Docker, or by Podman if `managed.podman` is set, and its
+/// database cluster is initialized with `managed.cluster`.
+#[allow(clippy::too_many_arguments)]
+pub fn export_managed<P: AsRef<Path>>(
+    queries_path: P,
+    schema_files: &[P],
+    fixtures: Option<P>,
+    query: &str,
+    format: ExportFormat,
+    out: P,
+    managed: &ManagedContainer,
+    settings: &CodegenSettings,
+) -> Result<usize, Error> {
+    let fixtures = fixtures
+        .map(|path| Fixtures::load(path.as_ref()))
+        .transpose()?;
+    let modules = read_query_modules(queries_path.as_ref(), settings.param_syntax)?
+        .into_iter()
+        .map(parse_query_module)
+        .collect::<Result<_, parser::error::Error>>()?;
+    container::setup(managed)?;
+    let mut client = conn::cornucopia_conn()?;
+    load_schema(&mut client, schema_files)?;
+    let preparation = prepare(&mut client, modules, settings)?;
+    let nb_rows = export::export(
+        &mut client,
+        &preparation,
+        fixtures.as_ref(),
+        query,
+        format,
+        out.as_ref(),
+    )?;
+    container::cleanup(managed.podman)?;
+
+    Ok(nb_rows)
+}
+
+/// Checks the PostgreSQL queries located at `queries_path` against a live database managed
+/// by you, without generating any code. Bind parameters are written and types are mapped
+/// following `settings`. Every error found is reported instead of stopping at the first
+/// one.
+///
+/// If some `fixtures` file is given, its parameter values are checked against the
+/// queries and each fixture query is run inside a rolled-back transaction.
+pub fn check_live<P: AsRef<Path>>(
+    client: &mut Client,
+    queries_path: P,
+    fixtures: Option<P>,
+    settings: &CodegenSettings,
+) -> Result<(), Error> {
+    let fixtures = fixtures
+        .map(|path| Fixtures::load(path.as_ref()))
+        .transpose()?;
+    let mut errors = Vec::new();
+    let modules = parse_all(queries_path.as_ref(), settings.param_syntax, &mut errors)?;
+    errors.extend(
+        check(client, modules, settings)
+            .into_iter()
+            .map(Error::from),
+    );
+    if let (Some(fixtures), true) = (&fixtures, errors.is_empty()) {
+        check_fixtures(
+            client,
+            queries_path.as_ref(),
+            settings,
+            fixtures,
+            &mut errors,
+        )?;
+    }
+    into_check_result(errors)
+}
+
+/// Checks the PostgreSQL queries located at `queries_path` using a container managed by
+/// cornucopia, without generating any code. The database schema is created using
+/// `schema_files` and bind parameters are written and types are mapped following
+/// `settings`. Every error found is reported instead of stopping at the first one.
+///
+/// The container is run by Docker, or by Podman if `managed.podman` is set, and its
+/// database cluster is initialized with `managed.cluster`.
+///
+/// If some `fixtures` file is given, its parameter values are checked against the
+/// queries and each fixture query is run inside a rolled-back transaction.
+pub fn check_managed<P: AsRef<Path>>(
+    queries_path: P,
+    schema_files: &[P],
+    fixtures: Option<P>,
+    managed: &ManagedContainer,
+    settings: &CodegenSettings,
+) -> Result<(), Error> {
+    check_managed_as(
+        queries_path,
+        schema_files,
+        fixtures,
+        managed,
+        None,
+        settings,
+    )
+}
+
+/// Checks the PostgreSQL queries located at `queries_path` like [`check_managed`]. The
+/// schema is loaded as the container's superuser. If some `app_url` is given, queries are
+/// then checked through it, typically as your application's role.
+pub fn check_managed_as<P: AsRef<Path>>(
+    queries_path: P,
+    schema_files: &[P],
+    fixtures: Option<P>,
+    managed: &ManagedContainer,
+    app_url: Option<&str>,
+    settings: &CodegenSettings,
+) -> Result<(), Error> {
+    let fixtures = fixtures
+        .map(|path| Fixtures::load(path.as_ref()))
+        .transpose()?;
+    check_container(
+        queries_path.as_ref(),
+        schema_files,
+        fixtures.as_ref(),
+        managed,
+        app_url,
+        settings,
+        "latest",
+    )
+}
+
+/// Checks the PostgreSQL queries located at `queries_path` like [`check_managed`], once
+/// per PostgreSQL version of `versions`, such as `13` or `16`, each in a container running
+/// the PostgreSQL image tagged with that version. Every version is checked, and the
+/// failures of each one are reported together, unless `keep_on_failure` is set: the check
+/// then stops at the first failing version, whose container is kept running to be
+/// inspected.
+#[allow(clippy::too_many_arguments)]
+pub fn check_managed_versions<P: AsRef<Path>>(
+    queries_path: P,
+    schema_files: &[P],
+    fixtures: Option<P>,
+    managed: &ManagedContainer,
+    app_url: Option<&str>,
+    settings: &CodegenSettings,
+    versions: &[String],
+    keep_on_failure: bool,
+) -> Result<(), Error> {
+    let fixtures = fixtures
+        .map(|path| Fixtures::load(path.as_ref()))
+        .transpose()?;
+    let mut failures = Vec::new();
+    for version in versions {
+        let result = check_container(
+            queries_path.as_ref(),
+            schema_files,
+            fixtures.as_ref(),
+            managed,
+            app_url,
+            settings,
+            version,
+        );
+        if let Err(err) = result {
+            // The next version needs the container's name and port
+            let err = container::cleanup_after_failure(managed.podman, keep_on_failure, err);
+            failures.push(VersionFailure::new(version.clone(), err));
+            if keep_on_failure {
+                break;
+            }
+        }
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(CheckVersionsError { failures }.into())
+    }
+}
+
+fn check_container<P: AsRef<Path>>(
+    queries_path: &Path,
+    schema_files: &[P],
+    fixtures: Option<&Fixtures>,
+    managed: &ManagedContainer,
+    app_url: Option<&str>,
+    settings: &CodegenSettings,
+    version: &str,
+) -> Result<(), Error> {
+    let mut errors = Vec::new();
+    let modules = parse_all(queries_path, settings.param_syntax, &mut errors)?;
+    container::setup_version(managed, version)?;
+    let mut client = conn::cornucopia_conn()?;
+    load_schema(&mut client, schema_files)?;
+    let mut client = conn::app_conn(client, app_url)?;
+    errors.extend(
+        check(&mut client, modules, settings)
+            .into_iter()
+            .map(Error::from),
+    );
+    if let (Some(fixtures), true) = (fixtures, errors.is_empty()) {
+        check_fixtures(&mut client, queries_path, settings, fixtures, &mut errors)?;
+    }
+    into_check_result(errors)?;
+    container::cleanup(managed.podman)?;
+    Ok(())
+}
+
+/// Dumps every custom type used by the PostgreSQL queries located at `queries_path`,
+/// prepared against a live database managed by you, with its Rust mapping. The dump is
+/// sorted by type name so that it can be committed and compared with [`diff_types`]. Bind
+/// parameters are written and types are mapped following `settings`.
+pub fn dump_types_live<P: AsRef<Path>>(
+    client: &mut Client,
+    queries_path: P,
+    settings: &CodegenSettings,
+) -> Result<String, Error> {
+    let modules = read_query_modules(queries_path.as_ref(), settings.param_syntax)?
+        .into_iter()
+        .map(parse_query_module)
+        .collect::<Result<_, parser::error::Error>>()?;
+    let preparation = prepare(client, modules, settings)?;
+    Ok(type_dump::dump_types(&preparation))
+}
+
+/// Dumps every custom type used by the PostgreSQL queries located at `queries_path`,
+/// prepared using a container managed by cornucopia, with its Rust mapping. The database
+/// schema is created using `schema_files`. Bind parameters are written and types are mapped
+/// following `settings`.
+///
+/// The container is run by Docker, or by Podman if `managed.podman` is set, and its
+/// database cluster is initialized with `managed.cluster`.
+pub fn dump_types_managed<P: AsRef<Path>>(
+    queries_path: P,
+    schema_files: &[P],
+    managed: &ManagedContainer,
+    settings: &CodegenSettings,
+) -> Result<String, Error> {
+    let modules = read_query_modules(queries_path.as_ref(), settings.param_syntax)?
+        .into_iter()
+        .map(parse_query_module)
+        .collect::<Result<_, parser::error::Error>>()?;
+    container::setup(managed)?;
+    let mut client = conn::cornucopia_conn()?;
+    load_schema(&mut client, schema_files)?;
+    let preparation = prepare(&mut client, modules, settings)?;
+    container::cleanup(managed.podman)?;
+
+    Ok(type_dump::dump_types(&preparation))
+}
+
+/// Interactively asks for a new query on `input`, prompting on `output`, and appends its
+/// annotated stub to the query file of its module in `queries_path`.
+pub fn new_query<P: AsRef<Path>>(
+    queries_path: P,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> Result<(), Error> {
+    Ok(new_query::new_query(queries_path.as_ref(), input, output)?)
+}
+
+/// Renders the tables and foreign keys of a live database managed by you as an
+/// entity-relationship diagram in `format`.
+pub fn schema_diagram_live(client: &mut Client, format: DiagramFormat) -> Result<String, Error> {
+    Ok(diagram::diagram(client, format)?)
+}
+
+/// Renders the tables and foreign keys of the database schema created using
+/// `schema_files`, in a container managed by cornucopia, as an entity-relationship
+/// diagram in `format`.
+///
+/// The container is run by Docker, or by Podman if `managed.podman` is set, and its
+/// database cluster is initialized with `managed.cluster`.
+pub fn schema_diagram_managed<P: AsRef<Path>>(
+    schema_files: &[P],
+    managed: &ManagedContainer,
+    format: DiagramFormat,
+) -> Result<String, Error> {
+    container::setup(managed)?;
+    let mut client = conn::cornucopia_conn()?;
+    load_schema(&mut client, schema_files)?;
+    let diagram = diagram::diagram(&mut client, format)?;
+    container::cleanup(managed.podman)?;
+    Ok(diagram)
+}
+
+/// Opens an interactive `psql` session in a container managed by cornucopia, whose
+/// database schema is created using `schema_files` as during generation. A container
+/// that is already running, such as one kept after a failure, is reused as it is.
+/// Otherwise, the container is started for the session and removed once it ends.
+///
+/// The container is run by Docker, or by Podman if `managed.podman` is set, and its
+/// database cluster is initialized with `managed.cluster`.
+pub fn db_shell<P: AsRef<Path>>(
+    schema_files: &[P],
+    managed: &ManagedContainer,
+) -> Result<(), Error> {
+    if container::is_running(managed.podman) {
+        println!("Reusing the running `cornucopia_postgres` container");
+        return Ok(container::psql(managed.podman)?);
+    }
+    container::setup(managed)?;
+    let mut client = conn::cornucopia_conn()?;
+    load_schema(&mut client, schema_files)?;
+    drop(client);
+    container::psql(managed.podman)?;
+    container::cleanup(managed.podman)?;
+    Ok(())
+}
+
+/// Compares a `current` type dump with a `previous` one, returning one line per added
+/// (`+`), removed (`-`) or changed (`~`) type, each changed type followed by its
+/// changed variants or fields.
+pub fn diff_types(previous: &str, current: &str) -> Vec<String> {
+    type_dump::diff_types(previous, current)
+}
+
+/// Compares the `new_code` generated by cornucopia with a previously generated `old_code`,
+/// returning an error listing every breaking change of the generated API, such as
+/// removed functions, changed field types or new required parameters.
+pub fn compat_report(old_code: &str, new_code: &str) -> Result<(), Error> {
+    let changes = compat::breaking_changes(old_code, new_code)?;
+    if changes.is_empty() {
+        Ok(())
+    } else {
+        Err(compat::error::Error::Breaking { changes }.into())
+    }
+}
+
+/// Reads and parses every query module, collecting parsing errors in `errors`.
+fn parse_all(
+    queries_path: &Path,
+    param_syntax: ParamSyntax,
+    errors: &mut Vec<Error>,
+) -> Result<Vec<parser::Module>, Error> {
+    let mut modules = Vec::new();
+    for info in read_query_modules(queries_path, param_syntax)? {
+        match parse_query_module(info) {
+            Ok(module) => modules.push(module),
+            Err(e) => errors.push(e.into()),
+        }
+    }
+    Ok(modules)
+}
+
+/// Checks parameter `fixtures` against the queries located at `queries_path`, which must
+/// be free of errors, collecting fixtures errors in `errors`.
+fn check_fixtures(
+    client: &mut Client,
+    queries_path: &Path,
+    settings: &CodegenSettings,
+    fixtures: &Fixtures,
+    errors: &mut Vec<Error>,
+) -> Result<(), Error> {
+    let modules = read_query_modules(queries_path, settings.param_syntax)?
+        .into_iter()
+        .map(parse_query_module)
+        .collect::<Result<_, parser::error::Error>>()?;
+    let preparation = prepare(client, modules, settings)?;
+    errors.extend(
+        fixtures
+            .check(client, &preparation)
+            .into_iter()
+            .map(Error::from),
+    );
+    Ok(())
+}
+
+fn into_check_result(errors: Vec<Error>) -> Result<(), Error> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(CheckError { errors }.into())
+    }
+}
+
+/// Checks that the generated code parses as Rust. Otherwise, the code is written next
+/// to the `destination`, with a `.raw` extension, to be attached to a bug report.
+fn check_generated_code(code: &str, destination: Option<&Path>) -> Result<(), Error> {
+    let Err(err) = syn::parse_file(code) else {
+        return Ok(());
+    };
+    let start = err.span().start();
+    // Lines are 1-indexed, columns are 0-indexed and counted in chars
+    let offset = code
+        .split_inclusive('\n')
+        .take(start.line.saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>();
+    let offset = offset
+        + code[offset..]
+            .chars()
+            .take(start.column)
+            .map(char::len_utf8)
+            .sum::<usize>();
+    // Generated code is mostly written on long lines, keep only the surroundings
+    let floor = |mut i: usize| {
+        while !code.is_char_boundary(i) {
+            i -= 1;
+        }
+        i
+    };
+    let chunk_start = floor(offset.saturating_sub(200));
+    let chunk_end = floor((offset + 200).min(code.len()));
+    let help = match destination {
+        Some(destination) => {
+            let mut raw = destination.as_os_str().to_owned();
+            raw.push(".raw");
+            let raw = PathBuf::from(raw);
+            std::fs::write(&raw, code).map_err(|err| WriteOutputError {
+                err,
+                file_path: raw.clone(),
+            })?;
+            format!(
+                "this is a bug in cornucopia, please report it with the generated code written to `{}`",
+                raw.display()
+            )
+        }
+        None => "this is a bug in cornucopia, please report it".to_string(),
+    };
+    Err(InvalidCodeError {
+        msg: err.to_string(),
+        line: start.line,
+        column: start.column + 1,
+        src: NamedSource::new("generated code", code[chunk_start..chunk_end].to_string()),
+        err_span: (offset - chunk_start..offset - chunk_start).into(),
+        help,
+    }
+    .into())
+}
+
+/// Checks the generated code and its module files, written relative to `destination`.
+fn check_generated_files(
+    code: &str,
+    files: &[(String, String)],
+    destination: Option<&Path>,
+) -> Result<(), Error> {
+    check_generated_code(code, destination)?;
+    for (path, code) in files.iter().filter(|(path, _)| path.ends_with(".rs")) {
+        let destination = destination.map(|it| it.parent().unwrap_or(Path::new("")).join(path));
+        check_generated_code(code, destination.as_deref())?;
+    }
+    Ok(())
+}
+
+/// Writes the generated code at `destination`, and its module and sidecar SQL files
+/// relative to it.
+fn write_generated_code(
+    destination: &Path,
+    generated_code: &str,
+    files: &[(String, String)],
+) -> Result<(), Error> {
+    let write = |path: &Path, content: &str| {
+        path.parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::write(path, content))
+            .map_err(|err| WriteOutputError {
+                err,
+                file_path: path.to_owned(),
+            })
+    };
+    let dir = destination.parent().unwrap_or(Path::new(""));
+    for (path, content) in files {
+        write(&dir.join(path), content)?;
+    }
+    Ok(write(destination, generated_code)?)
+}
@@ -3,16 +3,26 @@ use std::path::Path;
 use miette::NamedSource;
 use postgres::Client;
 
-use crate::utils::db_err;
+use crate::{builder::migration_files, utils::db_err};
 
 use self::error::Error;
 
 /// Loads PostgreSQL schemas into a database.
 ///
-/// Takes a list of file paths as parameter and loads them in their given order.
+/// Takes a list of file paths as parameter and loads them in their given order. A
+/// directory path loads the migrations of an external tool, such as a flyway `sql/`
+/// folder or a sqitch project, in the order they would be deployed.
 pub fn load_schema<P: AsRef<Path>>(client: &mut Client, paths: &[P]) -> Result<(), Error> {
     for path in paths {
         let path = path.as_ref();
+        if path.is_dir() {
+            let files = migration_files(path).map_err(|err| Error::Io {
+                path: err.path.to_string_lossy().to_string(),
+                err: err.err,
+            })?;
+            load_schema(client, &files)?;
+            continue;
+        }
         let sql = std::fs::read_to_string(path).map_err(|err| Error::Io {
             path: path.to_string_lossy().to_string(),
             err,
@@ -47,8 +57,13 @@ pub(crate) mod error {
     #[derive(Debug, ThisError, Diagnostic)]
     pub enum Error {
         #[error("Could not read schema `{path}`: ({err})")]
+        #[diagnostic(
+            code(cornucopia::load_schema::io),
+            help("check that every schema path exists, or that the migrations folder contains SQL files")
+        )]
         Io { path: String, err: std::io::Error },
         #[error("Could not execute schema: {msg}")]
+        #[diagnostic(code(cornucopia::load_schema::postgres))]
         Postgres {
             msg: String,
             #[source_code]
@@ -0,0 +1,324 @@
+use std::{collections::BTreeSet, path::Path};
+
+use crate::{
+    parser::ChannelPayload,
+    prepare_queries::{Preparation, PreparedContent},
+    type_registrar::CornucopiaType,
+    CodegenSettings,
+};
+
+/// Crate the generated code may refer to, depended upon as soon as it does
+struct Dependency {
+    name: &'static str,
+    /// Name the code refers to the crate with
+    path: &'static str,
+    version: &'static str,
+    default_features: bool,
+    features: &'static [&'static str],
+    /// Feature deriving serde's traits on the crate's types
+    serde: Option<&'static str>,
+}
+
+impl Dependency {
+    const fn new(name: &'static str, path: &'static str, version: &'static str) -> Self {
+        Self {
+            name,
+            path,
+            version,
+            default_features: true,
+            features: &[],
+            serde: None,
+        }
+    }
+}
+
+/// Dependencies of the generated code, besides the client and the driver, at the versions
+/// cornucopia's own generated code is tested with
+const DEPENDENCIES: [Dependency; 11] = [
+    Dependency {
+        features: &["derive"],
+        ..Dependency::new("postgres-types", "postgres_types", "0.2.4")
+    },
+    Dependency::new("futures", "futures", "0.3.25"),
+    Dependency::new("deadpool-postgres", "deadpool_postgres", "0.12.1"),
+    Dependency {
+        features: &["derive"],
+        ..Dependency::new("serde", "serde", "1.0.152")
+    },
+    Dependency {
+        features: &["raw_value"],
+        ..Dependency::new("serde_json", "serde_json", "1.0.91")
+    },
+    Dependency {
+        serde: Some("serde"),
+        ..Dependency::new("time", "time", "0.3.17")
+    },
+    Dependency {
+        serde: Some("serde"),
+        ..Dependency::new("chrono", "chrono", "0.4.23")
+    },
+    Dependency {
+        serde: Some("serde"),
+        ..Dependency::new("uuid", "uuid", "1.2.2")
+    },
+    Dependency {
+        serde: Some("serde"),
+        ..Dependency::new("eui48", "eui48", "1.1.0")
+    },
+    Dependency {
+        features: &["db-postgres"],
+        ..Dependency::new("rust_decimal", "rust_decimal", "1.28.0")
+    },
+    Dependency {
+        serde: Some("serde"),
+        ..Dependency::new("bigdecimal", "bigdecimal", "0.4.2")
+    },
+];
+
+/// Driver features enabling the conversions of a crate's types
+const DRIVER_FEATURES: [(&str, &str); 5] = [
+    ("serde_json", "with-serde_json-1"),
+    ("time", "with-time-0_3"),
+    ("chrono", "with-chrono-0_4"),
+    ("uuid", "with-uuid-1"),
+    ("eui48", "with-eui48-1"),
+];
+
+/// Client features only the asynchronous client has
+const ASYNC_FEATURES: [&str; 1] = ["concurrency"];
+
+/// Clients the generated code may use, synchronous then asynchronous, along with their
+/// driver and its version. The versions are checked against the workspace's clients.
+const CLIENTS: [(&str, &str, &str, &str); 2] = [
+    ("cornucopia_sync", "0.4.0", "postgres", "0.19.4"),
+    ("cornucopia_async", "0.6.0", "tokio-postgres", "0.7.7"),
+];
+
+/// Name of the crate written in `dir`, after the directory.
+pub(crate) fn crate_name(dir: &Path) -> String {
+    dir.file_name().map_or_else(
+        || "queries".to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
+/// Crates and features used by the generated code, resolved from the prepared queries
+/// and the settings they are generated with
+#[derive(Default)]
+struct Usage {
+    /// Paths of the crates referred to by the Rust types
+    crates: BTreeSet<String>,
+    /// Features of the client
+    features: BTreeSet<&'static str>,
+    /// Whether serde's traits are derived or required
+    serde: bool,
+}
+
+impl Usage {
+    fn new(preparation: &Preparation, settings: &CodegenSettings) -> Self {
+        let mut usage = Self::default();
+        let modules = &preparation.modules;
+        let items = modules
+            .iter()
+            .flat_map(|module| module.params.values().chain(module.rows.values()));
+        for item in items.clone() {
+            for field in &item.fields {
+                usage.visit_ty(&field.ty);
+            }
+        }
+        for ty in preparation.types.values().flatten() {
+            if let PreparedContent::Composite(fields) = &ty.content {
+                for field in fields {
+                    usage.visit_ty(&field.ty);
+                }
+            }
+        }
+        for module in modules {
+            for field in module.cdc_tables.iter().flat_map(|table| &table.fields) {
+                usage.visit_path(field.rust_ty);
+            }
+            for channel in &module.channels {
+                if let ChannelPayload::Path(path) = &channel.payload {
+                    usage.visit_path(&path.value);
+                }
+            }
+        }
+        let has_channels = modules.iter().any(|module| !module.channels.is_empty());
+        let has_cdc = modules.iter().any(|module| !module.cdc_tables.is_empty());
+        let has_limits = modules
+            .iter()
+            .flat_map(|module| module.queries.values())
+            .any(|query| query.max_concurrency.is_some());
+        usage.serde = settings.derive_ser
+            || settings.gen_maps
+            || settings.gen_csv
+            || has_channels
+            || has_cdc
+            || items.clone().any(|item| item.is_serialized);
+        for (feature, used) in [
+            ("arrow", settings.gen_arrow),
+            ("cache", settings.gen_cache),
+            ("cdc", has_cdc),
+            ("concurrency", settings.gen_async && has_limits),
+            ("csv", settings.gen_csv),
+            ("diesel", settings.gen_diesel),
+            ("explain", settings.gen_explain),
+            ("introspection", settings.gen_registry),
+            ("notifications", has_channels),
+            ("obfuscated-sql", settings.obfuscate_sql),
+            ("replicas", settings.gen_replicas),
+            ("serde", usage.serde),
+            ("sqlx", settings.gen_sqlx),
+            ("with-serde_json-1", usage.crates.contains("serde_json")),
+            ("with-bigdecimal-0_4", usage.crates.contains("bigdecimal")),
+        ] {
+            if used {
+                usage.features.insert(feature);
+            }
+        }
+        usage
+    }
+
+    fn visit_ty(&mut self, ty: &CornucopiaType) {
+        match ty {
+            CornucopiaType::Simple { rust_name, .. } => self.visit_path(rust_name),
+            CornucopiaType::Override { path, .. } => self.visit_path(path),
+            CornucopiaType::Array { inner }
+            | CornucopiaType::Domain { inner, .. }
+            | CornucopiaType::Range { inner, .. } => self.visit_ty(inner),
+            CornucopiaType::Custom { .. } | CornucopiaType::Encrypted { .. } => {}
+        }
+    }
+
+    /// Records the crate of the Rust type `path`, if it's a path into a crate.
+    fn visit_path(&mut self, path: &str) {
+        if let Some((krate, _)) = path.trim_start_matches("::").split_once("::") {
+            self.crates.insert(krate.to_string());
+        }
+    }
+}
+
+/// `Cargo.toml` of the crate `name` holding the code generated from `preparation` with
+/// `settings`, depending on exactly the crates and features the code uses.
+pub(crate) fn manifest(
+    name: &str,
+    preparation: &Preparation,
+    settings: &CodegenSettings,
+) -> String {
+    let usage = Usage::new(preparation, settings);
+    let workspace = settings.workspace_dependencies;
+    let mut dependencies = String::new();
+    let clients = [settings.gen_sync, settings.gen_async];
+    for ((client, version, driver, driver_version), _) in
+        CLIENTS.into_iter().zip(clients).filter(|(_, used)| *used)
+    {
+        let features: Vec<&str> = usage
+            .features
+            .iter()
+            .copied()
+            .filter(|feature| client == "cornucopia_async" || !ASYNC_FEATURES.contains(feature))
+            .collect();
+        write_dependency(
+            &mut dependencies,
+            client,
+            version,
+            true,
+            &features,
+            workspace,
+        );
+        let features: Vec<&str> = DRIVER_FEATURES
+            .iter()
+            .filter(|(path, _)| usage.crates.contains(*path))
+            .map(|(_, feature)| *feature)
+            .collect();
+        write_dependency(
+            &mut dependencies,
+            driver,
+            driver_version,
+            true,
+            &features,
+            workspace,
+        );
+    }
+    for dependency in &DEPENDENCIES {
+        let used = match dependency.path {
+            "postgres_types" => true,
+            "futures" => settings.gen_async,
+            "deadpool_postgres" => settings.gen_async && settings.gen_pool,
+            "serde" => usage.serde,
+            path => usage.crates.contains(path),
+        };
+        if !used {
+            continue;
+        }
+        let mut features = dependency.features.to_vec();
+        features.extend(dependency.serde.filter(|_| usage.serde));
+        write_dependency(
+            &mut dependencies,
+            dependency.name,
+            dependency.version,
+            dependency.default_features,
+            &features,
+            workspace,
+        );
+    }
+    // Smoke tests and benchmarks are the only users of these crates
+    let mut dev_dependencies = String::new();
+    if settings.gen_tests && !settings.gen_sync {
+        write_dependency(
+            &mut dev_dependencies,
+            "tokio",
+            "1.30.0",
+            true,
+            &["macros", "rt", "sync", "time"],
+            workspace,
+        );
+    }
+    if settings.gen_benches {
+        write_dependency(
+            &mut dev_dependencies,
+            "criterion",
+            "0.5.1",
+            true,
+            &[],
+            workspace,
+        );
+    }
+    let mut manifest = format!(
+        "# This file was generated with `cornucopia`. Do not modify.\n\n\
+        [package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+        [dependencies]\n{dependencies}"
+    );
+    if !dev_dependencies.is_empty() {
+        manifest.push_str(&format!("\n[dev-dependencies]\n{dev_dependencies}"));
+    }
+    manifest
+}
+
+/// Appends the dependency on `name` to a dependencies table. Dependencies inherited from
+/// the workspace get their version and default features from it.
+fn write_dependency(
+    table: &mut String,
+    name: &str,
+    version: &str,
+    default_features: bool,
+    features: &[&str],
+    workspace: bool,
+) {
+    if workspace {
+        table.push_str(&format!("{name} = {{ workspace = true"));
+    } else if default_features && features.is_empty() {
+        table.push_str(&format!("{name} = \"{version}\"\n"));
+        return;
+    } else {
+        table.push_str(&format!("{name} = {{ version = \"{version}\""));
+        if !default_features {
+            table.push_str(", default-features = false");
+        }
+    }
+    if !features.is_empty() {
+        let features: Vec<_> = features.iter().map(|it| format!("\"{it}\"")).collect();
+        table.push_str(&format!(", features = [{}]", features.join(", ")));
+    }
+    table.push_str(" }\n");
+}
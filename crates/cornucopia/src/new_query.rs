@@ -0,0 +1,213 @@
+use std::{
+    io::{BufRead, Write},
+    path::Path,
+};
+
+use crate::utils::append_query;
+
+use self::error::Error;
+
+/// A query stub, as described by the user.
+struct QueryStub {
+    name: String,
+    params: Vec<Field>,
+    /// `None` if the query does not return rows.
+    columns: Option<Vec<Field>>,
+}
+
+struct Field {
+    name: String,
+    nullable: bool,
+}
+
+impl QueryStub {
+    /// Renders the annotated stub, ready to be appended to a query file.
+    fn render(&self) -> String {
+        fn nullable(fields: &[Field]) -> Option<String> {
+            let names: Vec<_> = fields
+                .iter()
+                .filter(|f| f.nullable)
+                .map(|f| format!("{}?", f.name))
+                .collect();
+            (!names.is_empty()).then(|| format!("({})", names.join(", ")))
+        }
+
+        let mut annotation = format!("--! {}", self.name);
+        let params = nullable(&self.params);
+        let row = self.columns.as_deref().and_then(nullable);
+        if let Some(params) = &params {
+            annotation.push(' ');
+            annotation.push_str(params);
+        }
+        if let Some(row) = row {
+            if params.is_none() {
+                annotation.push_str(" ()");
+            }
+            annotation.push_str(" : ");
+            annotation.push_str(&row);
+        }
+
+        let names: Vec<_> = self.params.iter().map(|p| p.name.as_str()).collect();
+        let sql = match &self.columns {
+            Some(columns) => {
+                let selected = if columns.is_empty() {
+                    "*".to_string()
+                } else {
+                    columns
+                        .iter()
+                        .map(|c| c.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                let mut sql = format!("SELECT {selected}\nFROM table_name");
+                for (i, name) in names.iter().enumerate() {
+                    let keyword = if i == 0 { "WHERE" } else { "  AND" };
+                    sql.push_str(&format!("\n{keyword} {name} = :{name}"));
+                }
+                sql
+            }
+            None if names.is_empty() => "DELETE FROM table_name".to_string(),
+            None => format!(
+                "INSERT INTO table_name ({})\nVALUES ({})",
+                names.join(", "),
+                names
+                    .iter()
+                    .map(|n| format!(":{n}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+
+        format!("-- TODO: replace `table_name` with your own table\n{annotation}\n{sql};\n")
+    }
+}
+
+/// Interactively asks for a new query on `input` and appends its annotated stub to the
+/// right query file in `queries_path`.
+pub(crate) fn new_query(
+    queries_path: &Path,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> Result<(), Error> {
+    let module = ask_ident(input, output, "Module (query file name)")?;
+    let name = ask_ident(input, output, "Query name")?;
+    let params = ask_fields(
+        input,
+        output,
+        "Parameters (comma separated, suffix nullable ones with `?`)",
+    )?;
+    let returns_rows = ask(input, output, "Does it return rows? [Y/n]")?;
+    let columns = if matches!(returns_rows.as_str(), "" | "y" | "Y" | "yes") {
+        Some(ask_fields(
+            input,
+            output,
+            "Columns (comma separated, suffix nullable ones with `?`, empty for all)",
+        )?)
+    } else {
+        None
+    };
+
+    let stub = QueryStub {
+        name,
+        params,
+        columns,
+    }
+    .render();
+    let path = queries_path.join(format!("{module}.sql"));
+    std::fs::create_dir_all(queries_path)
+        .and_then(|_| append_query(&path, &stub))
+        .map_err(|err| Error {
+            err,
+            path: path.clone(),
+        })?;
+
+    writeln!(output, "\nAdded to `{}`:\n\n{stub}", path.display()).map_err(terminal_err)?;
+    Ok(())
+}
+
+fn terminal_err(err: std::io::Error) -> Error {
+    Error {
+        err,
+        path: "<terminal>".into(),
+    }
+}
+
+fn ask(input: &mut impl BufRead, output: &mut impl Write, question: &str) -> Result<String, Error> {
+    write!(output, "{question}: ").map_err(terminal_err)?;
+    output.flush().map_err(terminal_err)?;
+    let mut answer = String::new();
+    if input.read_line(&mut answer).map_err(terminal_err)? == 0 {
+        return Err(terminal_err(std::io::ErrorKind::UnexpectedEof.into()));
+    }
+    Ok(answer.trim().to_string())
+}
+
+fn is_ident(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn ask_ident(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    question: &str,
+) -> Result<String, Error> {
+    loop {
+        let answer = ask(input, output, question)?;
+        if is_ident(&answer) {
+            return Ok(answer);
+        }
+        writeln!(
+            output,
+            "`{answer}` is not a valid identifier, use only [a-zA-Z0-9_]"
+        )
+        .map_err(terminal_err)?;
+    }
+}
+
+fn ask_fields(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    question: &str,
+) -> Result<Vec<Field>, Error> {
+    'ask: loop {
+        let answer = ask(input, output, question)?;
+        let mut fields = Vec::new();
+        for field in answer.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+            let (name, nullable) = match field.strip_suffix('?') {
+                Some(name) => (name, true),
+                None => (field, false),
+            };
+            if !is_ident(name) {
+                writeln!(
+                    output,
+                    "`{name}` is not a valid identifier, use only [a-zA-Z0-9_]"
+                )
+                .map_err(terminal_err)?;
+                continue 'ask;
+            }
+            fields.push(Field {
+                name: name.to_string(),
+                nullable,
+            });
+        }
+        return Ok(fields);
+    }
+}
+
+pub(crate) mod error {
+    use std::path::PathBuf;
+
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    #[error("Couldn't write the new query to `{path}`: {err:#}")]
+    #[diagnostic(
+        code(cornucopia::new_query),
+        help("check that the queries folder is writable, or point to it with `--queries-path`")
+    )]
+    pub struct Error {
+        pub(crate) err: std::io::Error,
+        pub(crate) path: PathBuf,
+    }
+}
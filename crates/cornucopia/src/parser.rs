@@ -5,7 +5,10 @@ use error::Error;
 use heck::ToUpperCamelCase;
 use miette::SourceSpan;
 
-use crate::read_queries::ModuleInfo;
+use crate::{
+    lexer::{is_keyword, lex_with_offsets, statements, Token},
+    read_queries::ModuleInfo,
+};
 
 /// Th    if is data structure holds a value and the context in which it was parsed.
 /// This context is used for error reporting.
@@ -67,6 +70,24 @@ fn plain_ident() -> impl Parser<char, Span<String>, Error = Simple<char>> {
         })
 }
 
+/// A plain ident, optionally followed by a version suffix (`name@v2`)
+fn versioned_ident() -> impl Parser<char, Span<String>, Error = Simple<char>> {
+    plain_ident()
+        .then(
+            just("@v")
+                .ignore_then(filter(char::is_ascii_digit).repeated().at_least(1))
+                .collect::<String>()
+                .or_not(),
+        )
+        .map_with_span(|(name, version), span: Range<usize>| Span {
+            value: match version {
+                Some(version) => format!("{}@v{version}", name.value),
+                None => name.value,
+            },
+            span: span.into(),
+        })
+}
+
 fn quoted_ident() -> impl Parser<char, Span<String>, Error = Simple<char>> {
     none_of('"')
         .repeated()
@@ -83,6 +104,19 @@ fn ident() -> impl Parser<char, Span<String>, Error = Simple<char>> {
     plain_ident().or(quoted_ident())
 }
 
+/// A plain ident, optionally qualified by a schema (`schema.name`)
+fn qualified_ident() -> impl Parser<char, Span<String>, Error = Simple<char>> {
+    plain_ident()
+        .then(just('.').ignore_then(plain_ident()).or_not())
+        .map_with_span(|(first, second), span: Range<usize>| Span {
+            value: match second {
+                Some(second) => format!("{}.{}", first.value, second.value),
+                None => first.value,
+            },
+            span: span.into(),
+        })
+}
+
 fn ln() -> impl Parser<char, (), Error = Simple<char>> {
     just("\n").or(just("\n\r")).ignored()
 }
@@ -94,9 +128,9 @@ fn space() -> impl Parser<char, (), Error = Simple<char>> {
 }
 
 fn blank() -> impl Parser<char, (), Error = Simple<char>> {
-    // We want to escape valid SQL comment beginning with -- while not escaping our syntax --: or --!
+    // We want to escape valid SQL comment beginning with -- while not escaping our syntax --:, --!, --~, --%, --& or --@
     let comment = just("--")
-        .then(none_of(":!").rewind())
+        .then(none_of(":!~%&@").rewind())
         .then(none_of('\n').repeated());
     filter(|c: &char| c.is_whitespace())
         .ignored()
@@ -110,6 +144,9 @@ pub struct NullableIdent {
     pub name: Span<String>,
     pub nullable: bool,
     pub inner_nullable: bool,
+    pub sensitive: bool,
+    /// Whether the field is stored encrypted, as declared by a `#` suffix
+    pub encrypted: bool,
 }
 
 fn parse_nullable_ident() -> impl Parser<char, Vec<NullableIdent>, Error = Simple<char>> {
@@ -117,11 +154,17 @@ fn parse_nullable_ident() -> impl Parser<char, Vec<NullableIdent>, Error = Simpl
         .ignore_then(ident())
         .then(just('?').or_not())
         .then(just("[?]").or_not())
-        .map(|((name, null), inner_null)| NullableIdent {
-            name,
-            nullable: null.is_some(),
-            inner_nullable: inner_null.is_some(),
-        })
+        .then(just('*').or_not())
+        .then(just('#').or_not())
+        .map(
+            |((((name, null), inner_null), sensitive), encrypted)| NullableIdent {
+                name,
+                nullable: null.is_some(),
+                inner_nullable: inner_null.is_some(),
+                sensitive: sensitive.is_some(),
+                encrypted: encrypted.is_some(),
+            },
+        )
         .then_ignore(space())
         .separated_by(just(','))
         .allow_trailing()
@@ -145,14 +188,589 @@ impl TypeAnnotation {
     }
 }
 
+/// A PostgreSQL type mapped to an existing rust type instead of a generated one:
+/// `--: my_composite as crate::models::Thing`
+#[derive(Debug, Clone)]
+pub struct TypeOverride {
+    /// Name of the type, optionally qualified by its schema
+    pub name: Span<String>,
+    /// Path of the rust type, implementing `ToSql` and `FromSql`
+    pub path: Span<String>,
+}
+
+impl TypeOverride {
+    fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
+        just("--:")
+            .ignore_then(space())
+            .ignore_then(qualified_ident())
+            .then_ignore(space())
+            .then_ignore(just("as"))
+            .then_ignore(space())
+            .then(rust_type())
+            .then_ignore(space())
+            .map(|(name, path)| Self { name, path })
+    }
+
+    /// Does this override map `ty`, matching any schema if its name isn't qualified
+    pub(crate) fn matches(&self, ty: &postgres_types::Type) -> bool {
+        match self.name.value.split_once('.') {
+            Some((schema, name)) => ty.schema() == schema && ty.name() == name,
+            None => ty.name() == self.name.value,
+        }
+    }
+}
+
+/// A rust type, written as is in query files. Commas are allowed inside generic arguments.
+fn rust_type() -> impl Parser<char, Span<String>, Error = Simple<char>> {
+    let generics = recursive(|generics| {
+        just('<')
+            .chain(
+                generics
+                    .or(none_of("<>\n").map(|c| vec![c]))
+                    .repeated()
+                    .flatten(),
+            )
+            .chain(just('>'))
+    });
+    generics
+        .or(none_of(",()<>\n").map(|c| vec![c]))
+        .repeated()
+        .at_least(1)
+        .flatten()
+        .collect::<String>()
+        .map_with_span(|value, span: Range<usize>| Span {
+            value: value.trim_end().to_string(),
+            span: span.into(),
+        })
+}
+
+/// A field of an inline notification payload
+#[derive(Debug, Clone)]
+pub struct ChannelField {
+    pub name: Span<String>,
+    pub nullable: bool,
+    pub ty: Span<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ChannelPayload {
+    /// Path to an existing rust type
+    Path(Span<String>),
+    /// Inline list of fields, generating a payload struct
+    Fields(Vec<ChannelField>),
+}
+
+/// A `LISTEN`/`NOTIFY` channel with JSON payloads: `--~ channel (field: Type, nullable?: Type)`
+/// or `--~ channel path::to::Type`
+#[derive(Debug, Clone)]
+pub struct ChannelAnnotation {
+    pub name: Span<String>,
+    pub payload: ChannelPayload,
+}
+
+impl ChannelAnnotation {
+    fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
+        let field = space()
+            .ignore_then(ident())
+            .then(just('?').or_not())
+            .then_ignore(space())
+            .then_ignore(just(':'))
+            .then_ignore(space())
+            .then(rust_type())
+            .map(|((name, nullable), ty)| ChannelField {
+                name,
+                nullable: nullable.is_some(),
+                ty,
+            });
+        let fields = field
+            .separated_by(just(','))
+            .allow_trailing()
+            .delimited_by(just('('), just(')'))
+            .map(ChannelPayload::Fields);
+        just("--~")
+            .ignore_then(space())
+            .ignore_then(ident())
+            .then_ignore(space())
+            .then(fields.or(rust_type().map(ChannelPayload::Path)))
+            .then_ignore(space())
+            .map(|(name, payload)| Self { name, payload })
+    }
+}
+
+/// A table whose logical replication changes are decoded: `--% [schema.]table`. Only
+/// tables whose columns are all booleans, integers, floats or strings are supported.
+#[derive(Debug, Clone)]
+pub struct CdcAnnotation {
+    pub name: Span<String>,
+}
+
+impl CdcAnnotation {
+    fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
+        just("--%")
+            .ignore_then(space())
+            .ignore_then(qualified_ident())
+            .then_ignore(space())
+            .map(|name| Self { name })
+    }
+}
+
+/// An ordered list of queries executed in a single transaction: `--& script (query_a, query_b)`
+#[derive(Debug, Clone)]
+pub struct ScriptAnnotation {
+    pub name: Span<String>,
+    pub queries: Vec<Span<String>>,
+}
+
+impl ScriptAnnotation {
+    fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
+        just("--&")
+            .ignore_then(space())
+            .ignore_then(plain_ident())
+            .then_ignore(space())
+            .then(
+                space()
+                    .ignore_then(versioned_ident())
+                    .then_ignore(space())
+                    .separated_by(just(','))
+                    .allow_trailing()
+                    .at_least(1)
+                    .delimited_by(just('('), just(')')),
+            )
+            .then_ignore(space())
+            .map(|(name, queries)| Self { name, queries })
+    }
+}
+
+/// Search path the queries of a module are prepared with: `--@ schema inventory, public`
+#[derive(Debug, Clone)]
+pub struct SchemaAnnotation {
+    pub span: SourceSpan,
+    pub schemas: Vec<Span<String>>,
+}
+
+impl SchemaAnnotation {
+    fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
+        just("--@")
+            .ignore_then(space())
+            .ignore_then(just("schema"))
+            .ignore_then(space())
+            .ignore_then(
+                ident()
+                    .then_ignore(space())
+                    .separated_by(just(',').then_ignore(space()))
+                    .at_least(1),
+            )
+            .then_ignore(space())
+            .map_with_span(|schemas, span: Range<usize>| Self {
+                span: span.into(),
+                schemas,
+            })
+    }
+
+    /// Search path listing the schemas as quoted identifiers.
+    pub(crate) fn search_path(&self) -> String {
+        self.schemas
+            .iter()
+            .map(|schema| {
+                let simple = schema
+                    .value
+                    .starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+                    && schema.value.chars().all(|c| {
+                        c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '$'
+                    });
+                // Only identifiers which would be folded or rejected unquoted are quoted
+                if simple {
+                    schema.value.clone()
+                } else {
+                    format!("\"{}\"", schema.value.replace('"', "\"\""))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Characteristics of the transaction a query runs in, declared on the line following
+/// its annotation: `--! isolation serializable, read_only`
+#[derive(Debug, Clone)]
+pub struct TransactionAnnotation {
+    pub isolation: Span<String>,
+    pub modes: Vec<Span<String>>,
+}
+
+impl TransactionAnnotation {
+    const LEVELS: [&'static str; 4] = [
+        "serializable",
+        "repeatable_read",
+        "read_committed",
+        "read_uncommitted",
+    ];
+    const MODES: [&'static str; 4] = ["read_only", "read_write", "deferrable", "not_deferrable"];
+
+    /// Characteristics as expected by `SET TRANSACTION`
+    pub fn sql(&self) -> String {
+        let keywords = |it: &Span<String>| it.value.replace('_', " ").to_uppercase();
+        std::iter::once(format!("ISOLATION LEVEL {}", keywords(&self.isolation)))
+            .chain(self.modes.iter().map(keywords))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Statements setting up the transaction of a query, which may also be read-only, time
+    /// out or set planner parameters through its own annotations. `None` if the query needs
+    /// no transaction.
+    pub fn setup_sql(
+        transaction: Option<&Self>,
+        read_only: bool,
+        timeout: Option<u64>,
+        planner_settings: &[PlannerSetting],
+    ) -> Option<String> {
+        let mut characteristics: Vec<_> = transaction.map(Self::sql).into_iter().collect();
+        if read_only
+            && !transaction.is_some_and(|it| it.modes.iter().any(|mode| mode.value == "read_only"))
+        {
+            characteristics.push("READ ONLY".to_string());
+        }
+        let statements: Vec<_> = (!characteristics.is_empty())
+            .then(|| format!("SET TRANSACTION {}", characteristics.join(", ")))
+            .into_iter()
+            .chain(timeout.map(|ms| format!("SET LOCAL statement_timeout = {ms}")))
+            .chain(
+                planner_settings
+                    .iter()
+                    .map(|it| format!("SET LOCAL {} = {}", it.name.value, it.value)),
+            )
+            .collect();
+        (!statements.is_empty()).then(|| statements.join("; "))
+    }
+
+    fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
+        let keyword = |keywords: &'static [&'static str]| {
+            plain_ident().validate(move |it, span, emit| {
+                if !keywords.contains(&it.value.as_str()) {
+                    emit(Simple::custom(
+                        span,
+                        format!("expected one of {}", keywords.join(", ")),
+                    ));
+                }
+                it
+            })
+        };
+        just("--!")
+            .ignore_then(space())
+            .ignore_then(just("isolation"))
+            .ignore_then(space())
+            .ignore_then(keyword(&Self::LEVELS))
+            .then(
+                just(',')
+                    .ignore_then(space())
+                    .ignore_then(keyword(&Self::MODES))
+                    .then_ignore(space())
+                    .repeated(),
+            )
+            .then_ignore(space())
+            .map(|(isolation, modes)| Self { isolation, modes })
+    }
+}
+
+/// Options declared on the lines following a query annotation
+#[derive(Debug, Clone)]
+enum QueryOption {
+    Transaction(TransactionAnnotation),
+    /// `--! serialize`, deriving serde's `Serialize` and `Deserialize` on the query's row
+    Serialize,
+    /// `--! max_concurrency 4`, limiting how many executions of the query run at once
+    MaxConcurrency(Span<usize>),
+    /// `--! list ids, names`, binding parameters as lists usable in `IN (:ids)`
+    List(Vec<Span<String>>),
+    /// `--! copy`, copying rows in or out in bulk with `COPY`
+    Copy(SourceSpan),
+    /// `--! snapshot`, reading rows as of a snapshot exported by another transaction
+    Snapshot(SourceSpan),
+    /// `--! timeout 5s`, cancelling the query's transaction after a delay in milliseconds
+    Timeout(Span<u64>),
+    /// `--! read_only`, running the query in a read-only transaction
+    ReadOnly(SourceSpan),
+    /// `--! paginated id`, fetching rows by pages in the order of a cursor column
+    Paginated(Span<String>),
+    /// `--! hint SeqScan(book)`, prefixing the statement with a `pg_hint_plan` hint comment
+    Hint(String),
+    /// `--! set enable_seqscan = off`, setting a planner parameter in the query's transaction
+    Set(PlannerSetting),
+    /// `--! multi_exec`, returning the number of rows affected by each statement of a `WITH`
+    /// query
+    MultiExec(SourceSpan),
+    /// `--! derive(Hash, Eq)`, deriving more traits on the query's row
+    Derive(Vec<String>),
+}
+
+impl QueryOption {
+    fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
+        TransactionAnnotation::parser()
+            .map(Self::Transaction)
+            .or(just("--!")
+                .ignore_then(space())
+                .ignore_then(just("serialize"))
+                .ignore_then(space())
+                .to(Self::Serialize))
+            .or(just("--!")
+                .ignore_then(space())
+                .ignore_then(just("max_concurrency"))
+                .ignore_then(space())
+                .ignore_then(text::int(10))
+                // Out of range limits are reported by validation
+                .map_with_span(|it: String, span: Range<usize>| {
+                    Self::MaxConcurrency(Span {
+                        value: it.parse().unwrap_or(usize::MAX),
+                        span: span.into(),
+                    })
+                })
+                .then_ignore(space()))
+            .or(just("--!")
+                .ignore_then(space())
+                .ignore_then(just("list"))
+                .ignore_then(space())
+                .ignore_then(
+                    plain_ident()
+                        .then_ignore(space())
+                        .separated_by(just(',').then(space()))
+                        .at_least(1),
+                )
+                .map(Self::List))
+            .or(just("--!")
+                .ignore_then(space())
+                .ignore_then(just("copy"))
+                .map_with_span(|_, span: Range<usize>| Self::Copy(span.into()))
+                .then_ignore(space()))
+            .or(just("--!")
+                .ignore_then(space())
+                .ignore_then(just("snapshot"))
+                .map_with_span(|_, span: Range<usize>| Self::Snapshot(span.into()))
+                .then_ignore(space()))
+            .or(just("--!")
+                .ignore_then(space())
+                .ignore_then(just("timeout"))
+                .ignore_then(space())
+                .ignore_then(text::int(10))
+                .then(
+                    just("ms")
+                        .to(1)
+                        .or(just("s").to(1000))
+                        .or(just("min").to(60_000)),
+                )
+                .validate(|(it, unit): (String, u64), span, emit| {
+                    let timeout = it.parse::<u64>().unwrap_or(0).saturating_mul(unit);
+                    if timeout == 0 {
+                        emit(Simple::custom(
+                            span.clone(),
+                            "expected a positive timeout, such as `500ms`, `5s` or `1min`",
+                        ));
+                    }
+                    Self::Timeout(Span {
+                        value: timeout,
+                        span: span.into(),
+                    })
+                })
+                .then_ignore(space()))
+            .or(just("--!")
+                .ignore_then(space())
+                .ignore_then(just("read_only"))
+                .map_with_span(|_, span: Range<usize>| Self::ReadOnly(span.into()))
+                .then_ignore(space()))
+            .or(just("--!")
+                .ignore_then(space())
+                .ignore_then(just("paginated"))
+                .ignore_then(space())
+                .ignore_then(plain_ident())
+                .then_ignore(space())
+                .map(Self::Paginated))
+            .or(just("--!")
+                .ignore_then(space())
+                .ignore_then(just("hint"))
+                .ignore_then(filter(|c: &char| c.is_whitespace() && *c != '\n'))
+                .ignore_then(none_of('\n').repeated().collect::<String>())
+                .validate(|it, span, emit| {
+                    let hint = it.trim().to_string();
+                    if hint.is_empty() || hint.contains("*/") {
+                        emit(Simple::custom(
+                            span,
+                            "expected hints such as `SeqScan(book)`, which can't close the comment",
+                        ));
+                    }
+                    Self::Hint(hint)
+                }))
+            .or(PlannerSetting::parser().map(Self::Set))
+            .or(just("--!")
+                .ignore_then(space())
+                .ignore_then(just("multi_exec"))
+                .map_with_span(|_, span: Range<usize>| Self::MultiExec(span.into()))
+                .then_ignore(space()))
+            .or(just("--!")
+                .ignore_then(space())
+                .ignore_then(just("derive"))
+                .ignore_then(space())
+                .ignore_then(
+                    space()
+                        .ignore_then(
+                            filter(|c: &char| c.is_ascii_alphanumeric() || *c == '_' || *c == ':')
+                                .repeated()
+                                .at_least(1)
+                                .collect::<String>(),
+                        )
+                        .then_ignore(space())
+                        .separated_by(just(','))
+                        .at_least(1)
+                        .delimited_by(just('('), just(')')),
+                )
+                .then_ignore(space())
+                .map(Self::Derive))
+    }
+}
+
+/// A `--! set name = value` planner parameter, set locally to the isolated transaction of
+/// the query
+#[derive(Debug, Clone)]
+pub(crate) struct PlannerSetting {
+    /// Name of the parameter, possibly qualified by its extension
+    pub(crate) name: Span<String>,
+    pub(crate) value: String,
+}
+
+impl PlannerSetting {
+    fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
+        just("--!")
+            .ignore_then(space())
+            .ignore_then(just("set"))
+            .ignore_then(space())
+            .ignore_then(
+                filter(|c: &char| c.is_ascii_alphanumeric() || *c == '_' || *c == '.')
+                    .repeated()
+                    .at_least(1)
+                    .collect()
+                    .map_with_span(|value: String, span: Range<usize>| Span {
+                        value,
+                        span: span.into(),
+                    }),
+            )
+            .then_ignore(space())
+            .then_ignore(just('='))
+            .then_ignore(space())
+            .then(none_of('\n').repeated().collect::<String>())
+            .validate(|(name, value), span, emit| {
+                let value = value.trim().to_string();
+                if Self::literals()
+                    .then_ignore(end())
+                    .parse(value.as_str())
+                    .is_err()
+                {
+                    emit(Simple::custom(
+                        span,
+                        "expected a literal value, such as `off`, `-1`, `'64MB'` or a list of them",
+                    ));
+                }
+                Self { name, value }
+            })
+    }
+
+    /// Comma separated list of keywords, numbers or quoted strings, as accepted by `SET`, so
+    /// that values can't smuggle other statements
+    fn literals() -> impl Parser<char, (), Error = Simple<char>> {
+        let keyword = filter(|c: &char| c.is_ascii_alphabetic() || *c == '_')
+            .ignore_then(filter(|c: &char| c.is_ascii_alphanumeric() || *c == '_').repeated());
+        let number = one_of("+-")
+            .or_not()
+            .ignore_then(text::digits::<char, Simple<char>>(10))
+            .ignore_then(just('.').ignore_then(text::digits(10)).or_not());
+        // Quotes are doubled inside strings
+        let string = none_of('\'')
+            .ignored()
+            .or(just("''").ignored())
+            .repeated()
+            .delimited_by(just('\''), just('\''));
+        keyword
+            .ignored()
+            .or(number.ignored())
+            .or(string.ignored())
+            .separated_by(just(',').padded_by(just(' ').repeated()))
+            .at_least(1)
+            .ignored()
+    }
+}
+
+/// `COPY` statement of a `--! copy` query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CopySql {
+    /// Copies rows in, one per execution of an `INSERT` query
+    In(String),
+    /// Copies the rows returned by the query out
+    Out(String),
+}
+
+/// Statement of a `--! multi_exec` query, counting the rows affected by each statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MultiExecSql {
+    pub(crate) sql: String,
+    /// Names of the statements of the `WITH` clause, as PostgreSQL resolves them
+    pub(crate) names: Vec<String>,
+    /// Name of the statement wrapping the main statement, after its command
+    pub(crate) command: String,
+    /// Whether the rows of a `MERGE` are counted, which returns rows since PostgreSQL 17
+    pub(crate) counts_merge: bool,
+}
+
+/// Parameter of a `--! list` option
+#[derive(Debug)]
+pub(crate) struct ListParam {
+    pub(crate) name: Span<String>,
+    /// Whether every use of the parameter was rewritten to compare against an array
+    pub(crate) is_expanded: bool,
+}
+
+/// Replacement of `len` bytes at `start` of a query's SQL by `new_len` bytes, as the SQL
+/// read when it was made.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SqlEdit {
+    pub(crate) start: usize,
+    pub(crate) len: usize,
+    pub(crate) new_len: usize,
+}
+
 #[derive(Debug)]
 pub(crate) struct Query {
     pub(crate) name: Span<String>,
     pub(crate) param: QueryDataStruct,
     pub(crate) row: QueryDataStruct,
+    pub(crate) transaction: Option<TransactionAnnotation>,
+    pub(crate) serialize: bool,
+    pub(crate) max_concurrency: Option<Span<usize>>,
+    pub(crate) list_params: Vec<ListParam>,
+    /// Span of the `--! copy` option, if any
+    pub(crate) copy: Option<SourceSpan>,
+    /// Span of the `--! snapshot` option, if any
+    pub(crate) snapshot: Option<SourceSpan>,
+    /// Statement timeout of the query's transaction, in milliseconds
+    pub(crate) timeout: Option<Span<u64>>,
+    /// Span of the `--! read_only` option, if any
+    pub(crate) read_only: Option<SourceSpan>,
+    /// Cursor column of the `--! paginated` option, if any
+    pub(crate) paginated: Option<Span<String>>,
+    /// `pg_hint_plan` hints of the `--! hint` options, if any
+    pub(crate) hint: Option<String>,
+    /// Planner parameters of the `--! set` options
+    pub(crate) planner_settings: Vec<PlannerSetting>,
+    /// Span of the `--! multi_exec` option, if any
+    pub(crate) multi_exec: Option<SourceSpan>,
+    /// Traits the row derives on top of its default derives, from `--! derive` options
+    pub(crate) derives: Vec<String>,
+    /// Whether the query holds several statements, run together in a single batch
+    pub(crate) is_batch: bool,
     pub(crate) sql_span: SourceSpan,
     pub(crate) sql_str: String,
+    /// Edits made to the SQL as written to get `sql_str`, in order
+    pub(crate) sql_edits: Vec<SqlEdit>,
     pub(crate) bind_params: Vec<Span<String>>,
+    /// Text of the `--` comment lines directly preceding the query annotation, if any
+    pub(crate) doc: Option<String>,
 }
 
 impl Query {
@@ -210,14 +828,40 @@ impl Query {
             .allow_trailing()
     }
 
-    /// Parse sql query, normalizing named parameters
-    fn parse_sql_query(
-    ) -> impl Parser<char, (String, SourceSpan, Vec<Span<String>>), Error = Simple<char>> {
+    /// Offset in the SQL as written of `offset` in the SQL resulting from `edits`. Offsets
+    /// within an edit point at its start.
+    pub(crate) fn source_offset(edits: &[SqlEdit], offset: usize) -> usize {
+        edits.iter().rev().fold(offset, |offset, edit| {
+            if offset >= edit.start + edit.new_len {
+                offset + edit.len - edit.new_len
+            } else {
+                offset.min(edit.start)
+            }
+        })
+    }
+
+    /// Parse sql query, normalizing named parameters. A query holding several statements,
+    /// following each other until the next annotation, is a batch.
+    fn parse_sql_query() -> impl Parser<
+        char,
+        (String, SourceSpan, Vec<Span<String>>, Vec<SqlEdit>, bool),
+        Error = Simple<char>,
+    > {
+        let following = just(';')
+            .chain(filter(|c: &char| c.is_whitespace()).repeated())
+            .then_ignore(none_of("-;").rewind())
+            .chain(none_of(";").repeated());
         none_of(";")
             .repeated()
+            .then(following.repeated())
             .then_ignore(just(';'))
-            .collect::<String>()
-            .map_with_span(|mut sql_str, span: Range<usize>| {
+            .map(|(first, following)| {
+                let sql_str: String = first.into_iter().chain(following.concat()).collect();
+                // Semicolons of literals don't end statements
+                let is_batch = statements(&sql_str).is_ok_and(|it| it.len() > 1);
+                (sql_str, is_batch)
+            })
+            .map_with_span(|(mut sql_str, is_batch), span: Range<usize>| {
                 let bind_params: Vec<_> = Self::parse_bind().parse(sql_str.clone()).unwrap();
                 // Remove duplicate
                 let dedup_params: Vec<_> = bind_params
@@ -229,15 +873,225 @@ impl Query {
                     .rev()
                     .collect();
 
+                let mut edits = Vec::new();
                 for bind_param in bind_params.iter().rev() {
                     let index = dedup_params.iter().position(|bp| bp == bind_param).unwrap();
                     let start = bind_param.span.offset() - 1;
                     let end = start + bind_param.span.len();
-                    sql_str.replace_range(start..=end, &format!("${}", index + 1));
+                    let placeholder = format!("${}", index + 1);
+                    edits.push(SqlEdit {
+                        start,
+                        len: end + 1 - start,
+                        new_len: placeholder.len(),
+                    });
+                    sql_str.replace_range(start..=end, &placeholder);
+                }
+
+                (sql_str, span.into(), dedup_params, edits, is_batch)
+            })
+    }
+
+    /// Rewrites `IN ($n)` into `= ANY($n)` and `NOT IN ($n)` into `<> ALL($n)`, so that
+    /// the `n`th parameter is bound as a single array rather than a single value. Literals,
+    /// quoted identifiers, comments and dollar-quoted strings are left untouched. Returns
+    /// whether every use of the parameter was rewritten, recording the rewrites in `edits`.
+    fn expand_list_param(sql_str: &mut String, n: usize, edits: &mut Vec<SqlEdit>) -> bool {
+        // Invalid parameters are reported when preparing the query
+        let Ok(tokens) = lex_with_offsets(sql_str) else {
+            return true;
+        };
+        // Unquoted keyword, as quoted identifiers are lexed without their quotes
+        let is_bare_keyword = |token: Option<&(usize, Token)>, keyword: &str| {
+            matches!(token, Some((_, token @ Token::Ident(_, range)))
+                if range.len() == keyword.len() && is_keyword(token, keyword))
+        };
+        let placeholder = format!("${n}");
+        let mut rewrites = Vec::new();
+        for (i, window) in tokens.windows(4).enumerate() {
+            let [(start, _), (_, Token::Punct('(')), (_, Token::Param(param, _)), (end, Token::Punct(')'))] =
+                window
+            else {
+                continue;
+            };
+            if *param != n || !is_bare_keyword(window.first(), "IN") {
+                continue;
+            }
+            let (start, replacement) = match i.checked_sub(1).map(|i| &tokens[i]) {
+                not @ Some((start, _)) if is_bare_keyword(not, "NOT") => {
+                    (*start, format!("<> ALL({placeholder})"))
                 }
+                _ => (*start, format!("= ANY({placeholder})")),
+            };
+            rewrites.push((start..=*end, replacement));
+        }
+        let uses = tokens
+            .iter()
+            .filter(|(_, token)| matches!(token, Token::Param(param, _) if *param == n))
+            .count();
+        for (range, replacement) in rewrites.iter().rev() {
+            edits.push(SqlEdit {
+                start: *range.start(),
+                len: range.end() + 1 - range.start(),
+                new_len: replacement.len(),
+            });
+            sql_str.replace_range(range.clone(), replacement);
+        }
+        rewrites.len() == uses
+    }
+
+    /// `COPY` statement of a `--! copy` query with `nb_params` parameters, in binary
+    /// format. `INSERT INTO t (a, b) VALUES (:a, :b)` queries, binding each value to a
+    /// parameter in order, copy rows in. `INSERT` queries of any other form get `None`.
+    /// Other queries copy their rows out.
+    pub(crate) fn copy_sql(sql_str: &str, nb_params: usize) -> Option<CopySql> {
+        fn strip_keyword<'a>(sql: &'a str, keyword: &str) -> Option<&'a str> {
+            let sql = sql.trim_start();
+            let rest = sql.get(keyword.len()..)?;
+            (sql[..keyword.len()].eq_ignore_ascii_case(keyword)
+                && rest.starts_with(|c: char| c.is_whitespace()))
+            .then_some(rest)
+        }
 
-                (sql_str, span.into(), dedup_params)
+        let sql = sql_str.trim();
+        let Some(rest) = strip_keyword(sql, "INSERT") else {
+            return Some(CopySql::Out(format!(
+                "COPY ({sql}) TO STDOUT (FORMAT binary)"
+            )));
+        };
+        let target_and_values = strip_keyword(rest, "INTO")?;
+        let values_start = target_and_values.to_ascii_uppercase().rfind("VALUES")?;
+        let (target, values) = target_and_values.split_at(values_start);
+        let values: String = values["VALUES".len()..]
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        let params: Vec<_> = (1..=nb_params).map(|i| format!("${i}")).collect();
+        (!params.is_empty() && values == format!("({})", params.join(",")))
+            .then(|| CopySql::In(format!("COPY {} FROM STDIN (FORMAT binary)", target.trim())))
+    }
+
+    /// Statement of a `--! multi_exec` query, counting the rows affected by each
+    /// data-modifying statement of its `WITH` clause and by its main statement into a column
+    /// named after the statement, or after the main statement's command. Statements without
+    /// a `RETURNING` clause return a row per affected row. `None` unless the query is a
+    /// `WITH` clause followed by an `INSERT`, `UPDATE`, `DELETE` or `MERGE`.
+    pub(crate) fn multi_exec_sql(sql_str: &str) -> Option<MultiExecSql> {
+        const WRITES: [&str; 4] = ["INSERT", "UPDATE", "DELETE", "MERGE"];
+        // Invalid parameters are reported when preparing the query
+        let tokens = lex_with_offsets(sql_str).ok()?;
+        let writes = |token: &Token| WRITES.iter().any(|it| is_keyword(token, it));
+        let is_punct =
+            |i: usize, c: char| matches!(tokens.get(i), Some((_, Token::Punct(it))) if *it == c);
+        // Index of the parenthesis closing the one opened at `open`
+        let closing = |open: usize| {
+            let mut depth = 0;
+            for (i, (_, token)) in tokens.iter().enumerate().skip(open) {
+                match token {
+                    Token::Punct('(') => depth += 1,
+                    Token::Punct(')') if depth == 1 => return Some(i),
+                    Token::Punct(')') => depth -= 1,
+                    _ => {}
+                }
+            }
+            None
+        };
+        // Whether the statement of `tokens[start..end]` already returns rows
+        let returns = |start: usize, end: usize| {
+            let mut depth = 0;
+            tokens[start..end].iter().any(|(_, token)| {
+                match token {
+                    Token::Punct('(') => depth += 1,
+                    Token::Punct(')') => depth -= 1,
+                    _ => {}
+                }
+                depth == 0 && is_keyword(token, "RETURNING")
             })
+        };
+
+        if !is_keyword(&tokens.first()?.1, "WITH") {
+            return None;
+        }
+        let mut i = 1;
+        if tokens
+            .get(i)
+            .is_some_and(|(_, it)| is_keyword(it, "RECURSIVE"))
+        {
+            i += 1;
+        }
+        let mut sql = String::new();
+        let mut copied = 0;
+        // Name of the count column and reference to the counted statement
+        let mut counts = Vec::new();
+        let mut names = Vec::new();
+        let mut counts_merge = false;
+        loop {
+            let Some((_, Token::Ident(name, range))) = tokens.get(i) else {
+                return None;
+            };
+            // Unquoted names are folded to lower case
+            names.push(if range.len() == name.len() {
+                name.to_lowercase()
+            } else {
+                name.clone()
+            });
+            i += 1;
+            if is_punct(i, '(') {
+                i = closing(i)? + 1;
+            }
+            if !is_keyword(&tokens.get(i)?.1, "AS") {
+                return None;
+            }
+            i += 1;
+            while tokens
+                .get(i)
+                .is_some_and(|(_, it)| is_keyword(it, "NOT") || is_keyword(it, "MATERIALIZED"))
+            {
+                i += 1;
+            }
+            if !is_punct(i, '(') {
+                return None;
+            }
+            let close = closing(i)?;
+            if let Some((_, statement)) = tokens.get(i + 1).filter(|(_, it)| writes(it)) {
+                counts_merge |= is_keyword(statement, "MERGE");
+                if !returns(i + 1, close) {
+                    let end = tokens[close].0;
+                    sql.push_str(sql_str[copied..end].trim_end());
+                    sql.push_str(" RETURNING 1");
+                    copied = end;
+                }
+                counts.push((name.clone(), sql_str[range.clone()].to_string()));
+            }
+            i = close + 1;
+            if !is_punct(i, ',') {
+                break;
+            }
+            i += 1;
+        }
+        let (start, main) = tokens.get(i)?;
+        let command = WRITES
+            .iter()
+            .find(|it| is_keyword(main, it))?
+            .to_lowercase();
+        counts_merge |= command == "merge";
+        sql.push_str(sql_str[copied..*start].trim_end());
+        let mut main_sql = sql_str[*start..].trim_end().to_string();
+        if !returns(i, tokens.len()) {
+            main_sql.push_str(" RETURNING 1");
+        }
+        sql.push_str(&format!(",\n\"{command}\" AS ({main_sql})\nSELECT "));
+        counts.push((command.clone(), format!("\"{command}\"")));
+        let counts: Vec<_> = counts
+            .iter()
+            .map(|(name, statement)| format!("(SELECT count(*) FROM {statement}) AS \"{name}\""))
+            .collect();
+        sql.push_str(&counts.join(", "));
+        Some(MultiExecSql {
+            sql,
+            names,
+            command,
+            counts_merge,
+        })
     }
 
     fn parse_query_annotation(
@@ -245,7 +1099,7 @@ impl Query {
     {
         just("--!")
             .ignore_then(space())
-            .ignore_then(plain_ident())
+            .ignore_then(versioned_ident())
             .then_ignore(space())
             .then(QueryDataStruct::parser())
             .then_ignore(space())
@@ -262,18 +1116,104 @@ impl Query {
         Self::parse_query_annotation()
             .then_ignore(space())
             .then_ignore(ln())
+            .then(QueryOption::parser().then_ignore(ln()).repeated())
             .then(Self::parse_sql_query())
             .map(
-                |((name, param, row), (sql_str, sql_span, bind_params))| Self {
-                    name,
-                    param,
-                    row,
-                    sql_span,
-                    sql_str,
-                    bind_params,
+                |(
+                    ((name, param, row), options),
+                    (mut sql_str, sql_span, bind_params, mut sql_edits, is_batch),
+                )| {
+                    let mut transaction = None;
+                    let mut serialize = false;
+                    let mut max_concurrency = None;
+                    let mut list_params = Vec::new();
+                    let mut copy = None;
+                    let mut snapshot = None;
+                    let mut timeout = None;
+                    let mut read_only = None;
+                    let mut paginated = None;
+                    let mut hints = Vec::new();
+                    let mut planner_settings = Vec::new();
+                    let mut multi_exec = None;
+                    let mut derives = Vec::new();
+                    for option in options {
+                        match option {
+                            QueryOption::Transaction(it) => transaction = Some(it),
+                            QueryOption::Serialize => serialize = true,
+                            QueryOption::MaxConcurrency(it) => max_concurrency = Some(it),
+                            QueryOption::List(it) => list_params.extend(it),
+                            QueryOption::Copy(it) => copy = Some(it),
+                            QueryOption::Snapshot(it) => snapshot = Some(it),
+                            QueryOption::Timeout(it) => timeout = Some(it),
+                            QueryOption::ReadOnly(it) => read_only = Some(it),
+                            QueryOption::Paginated(it) => paginated = Some(it),
+                            QueryOption::Hint(it) => hints.push(it),
+                            QueryOption::Set(it) => planner_settings.push(it),
+                            QueryOption::MultiExec(it) => multi_exec = Some(it),
+                            QueryOption::Derive(it) => derives.extend(it),
+                        }
+                    }
+                    let list_params = list_params
+                        .into_iter()
+                        .map(|name| {
+                            let is_expanded = match bind_params.iter().position(|it| *it == name) {
+                                Some(i) => {
+                                    Self::expand_list_param(&mut sql_str, i + 1, &mut sql_edits)
+                                }
+                                // Unbound parameters are reported by validation
+                                None => true,
+                            };
+                            ListParam { name, is_expanded }
+                        })
+                        .collect();
+                    Self {
+                        name,
+                        param,
+                        row,
+                        transaction,
+                        serialize,
+                        max_concurrency,
+                        list_params,
+                        copy,
+                        snapshot,
+                        timeout,
+                        read_only,
+                        paginated,
+                        hint: (!hints.is_empty()).then(|| hints.join(" ")),
+                        planner_settings,
+                        multi_exec,
+                        derives,
+                        is_batch,
+                        sql_span,
+                        sql_str,
+                        sql_edits,
+                        bind_params,
+                        doc: None,
+                    }
                 },
             )
     }
+
+    /// Text of the `--` comment lines directly preceding the annotation of the query in
+    /// `info`, stripped of their `--` marker. Comments are skipped by the parser as blanks.
+    fn doc_comment(&self, info: &ModuleInfo) -> Option<String> {
+        let content = info.content.as_str();
+        let annotation_start = content[..info.byte_offset(self.name.span.offset())]
+            .rfind('\n')
+            .map_or(0, |idx| idx + 1);
+        let mut lines: Vec<&str> = content[..annotation_start]
+            .lines()
+            .rev()
+            .map_while(|line| {
+                let text = line.trim().strip_prefix("--")?;
+                // Cornucopia annotations aren't comments
+                (!text.starts_with([':', '!', '~', '%', '&', '@']))
+                    .then(|| text.strip_prefix(' ').unwrap_or(text).trim_end())
+            })
+            .collect();
+        lines.reverse();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
 }
 
 #[derive(Debug)]
@@ -357,20 +1297,35 @@ impl QueryDataStruct {
 #[derive(Debug)]
 enum Statement {
     Type(TypeAnnotation),
-    Query(Query),
+    TypeOverride(TypeOverride),
+    Query(Box<Query>),
+    Channel(ChannelAnnotation),
+    Cdc(CdcAnnotation),
+    Script(ScriptAnnotation),
+    Schema(SchemaAnnotation),
 }
 
 #[derive(Debug)]
 pub(crate) struct Module {
     pub(crate) info: ModuleInfo,
     pub(crate) types: Vec<TypeAnnotation>,
+    pub(crate) type_overrides: Vec<TypeOverride>,
     pub(crate) queries: Vec<Query>,
+    pub(crate) channels: Vec<ChannelAnnotation>,
+    pub(crate) cdc_tables: Vec<CdcAnnotation>,
+    pub(crate) scripts: Vec<ScriptAnnotation>,
+    pub(crate) schemas: Vec<SchemaAnnotation>,
 }
 
 pub(crate) fn parse_query_module(info: ModuleInfo) -> Result<Module, Error> {
-    match TypeAnnotation::parser()
-        .map(Statement::Type)
-        .or(Query::parser().map(Statement::Query))
+    match TypeOverride::parser()
+        .map(Statement::TypeOverride)
+        .or(TypeAnnotation::parser().map(Statement::Type))
+        .or(Query::parser().map(|it| Statement::Query(Box::new(it))))
+        .or(ChannelAnnotation::parser().map(Statement::Channel))
+        .or(CdcAnnotation::parser().map(Statement::Cdc))
+        .or(ScriptAnnotation::parser().map(Statement::Script))
+        .or(SchemaAnnotation::parser().map(Statement::Schema))
         .separated_by(blank())
         .allow_leading()
         .allow_trailing()
@@ -379,17 +1334,35 @@ pub(crate) fn parse_query_module(info: ModuleInfo) -> Result<Module, Error> {
     {
         Ok(statements) => {
             let mut types = Vec::new();
+            let mut type_overrides = Vec::new();
             let mut queries = Vec::new();
+            let mut channels = Vec::new();
+            let mut cdc_tables = Vec::new();
+            let mut scripts = Vec::new();
+            let mut schemas = Vec::new();
             for item in statements {
                 match item {
                     Statement::Type(it) => types.push(it),
-                    Statement::Query(it) => queries.push(it),
+                    Statement::TypeOverride(it) => type_overrides.push(it),
+                    Statement::Query(mut it) => {
+                        it.doc = it.doc_comment(&info);
+                        queries.push(*it);
+                    }
+                    Statement::Channel(it) => channels.push(it),
+                    Statement::Cdc(it) => cdc_tables.push(it),
+                    Statement::Script(it) => scripts.push(it),
+                    Statement::Schema(it) => schemas.push(it),
                 }
             }
             Ok(Module {
                 info,
                 types,
+                type_overrides,
                 queries,
+                channels,
+                cdc_tables,
+                scripts,
+                schemas,
             })
         }
         Err(e) => Err(Error {
@@ -406,6 +1379,7 @@ pub(crate) mod error {
 
     #[derive(Debug, ThisError, Diagnostic)]
     #[error("Couldn't parse queries")]
+    #[diagnostic(code(cornucopia::parser))]
     pub struct Error {
         #[source_code]
         pub src: NamedSource,
@@ -0,0 +1,170 @@
+use indexmap::IndexMap;
+use postgres::Client;
+
+use crate::{
+    lexer::{is_keyword, lex, Token},
+    prepare_queries::{PartitionedSql, Preparation},
+};
+
+use self::error::Error;
+
+/// Keywords directly followed by a table name
+const TABLE_KEYWORDS: [&str; 4] = ["FROM", "JOIN", "UPDATE", "INTO"];
+
+/// Keywords that can directly follow a table name without being its alias
+const CLAUSE_KEYWORDS: [&str; 30] = [
+    "CROSS",
+    "DEFAULT",
+    "EXCEPT",
+    "FETCH",
+    "FOR",
+    "FULL",
+    "GROUP",
+    "HAVING",
+    "INNER",
+    "INTERSECT",
+    "JOIN",
+    "LEFT",
+    "LIMIT",
+    "NATURAL",
+    "OFFSET",
+    "ON",
+    "ORDER",
+    "OUTER",
+    "OVERRIDING",
+    "RETURNING",
+    "RIGHT",
+    "SELECT",
+    "SET",
+    "TABLESAMPLE",
+    "UNION",
+    "USING",
+    "VALUES",
+    "WHERE",
+    "WINDOW",
+    "WITH",
+];
+
+/// Enumerates the partitions of every partitioned table of the search path, then
+/// prepares a variant of each query referencing a single partitioned table for each
+/// of its partitions, targeting that partition directly.
+pub(crate) fn prepare_partitions(
+    client: &mut Client,
+    preparation: &mut Preparation,
+) -> Result<(), Error> {
+    let rows = client
+        .query(
+            "SELECT parent.relname, child.oid::regclass::text
+            FROM pg_partitioned_table p
+            JOIN pg_class parent ON parent.oid = p.partrelid
+            JOIN pg_inherits i ON i.inhparent = parent.oid
+            JOIN pg_class child ON child.oid = i.inhrelid
+            WHERE pg_table_is_visible(parent.oid)
+            ORDER BY parent.relname, child.relname",
+            &[],
+        )
+        .map_err(Error::Catalog)?;
+    let mut tables: IndexMap<String, Vec<String>> = IndexMap::new();
+    for row in rows {
+        tables.entry(row.get(0)).or_default().push(row.get(1));
+    }
+
+    for module in &mut preparation.modules {
+        // Queries are prepared with the search path of their module
+        let mut transaction = client.transaction().map_err(Error::Catalog)?;
+        module
+            .set_local_search_path(&mut transaction)
+            .map_err(Error::Catalog)?;
+        for query in module.queries.values_mut() {
+            let mut referenced = tables
+                .iter()
+                .filter(|(table, _)| splice(&query.sql, table, table).is_some());
+            // Queries joining several partitioned tables are ambiguous
+            let (Some((table, partitions)), None) = (referenced.next(), referenced.next()) else {
+                continue;
+            };
+            let mut sqls = Vec::with_capacity(partitions.len());
+            for partition in partitions {
+                let sql = splice(&query.sql, table, partition).expect("table is referenced");
+                transaction.prepare(&sql).map_err(|err| Error::Prepare {
+                    query: format!("{}::{}", module.info.qualified_name(), query.ident.db),
+                    partition: partition.clone(),
+                    err,
+                })?;
+                sqls.push(sql);
+            }
+            query.partitioned = Some(PartitionedSql {
+                table: table.clone(),
+                sqls,
+            });
+            module
+                .partitions
+                .entry(table.clone())
+                .or_insert_with(|| partitions.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the references to `table` following `FROM`, `JOIN`, `UPDATE` or `INTO`
+/// in `sql` by `partition`, aliased as `table` so that qualified columns still resolve.
+/// Returns `None` if `sql` doesn't reference `table`.
+fn splice(sql: &str, table: &str, partition: &str) -> Option<String> {
+    // Prepared queries only bind valid parameters
+    let tokens = lex(sql).ok()?;
+    let mut spliced = sql.to_string();
+    let mut found = false;
+    // Replace from the end so that byte ranges stay valid
+    for (i, token) in tokens.iter().enumerate().rev() {
+        let Token::Ident(name, range) = token else {
+            continue;
+        };
+        let names_table = i.checked_sub(1).is_some_and(|prev| {
+            TABLE_KEYWORDS
+                .iter()
+                .any(|keyword| is_keyword(&tokens[prev], keyword))
+        });
+        let is_qualified = tokens.get(i + 1) == Some(&Token::Punct('.'));
+        if !names_table || is_qualified || !(name == table || name.to_lowercase() == table) {
+            continue;
+        }
+        let is_aliased = match tokens.get(i + 1) {
+            Some(token @ Token::Ident(..)) => !CLAUSE_KEYWORDS
+                .iter()
+                .any(|keyword| is_keyword(token, keyword)),
+            _ => false,
+        };
+        let replacement = if is_aliased {
+            partition.to_string()
+        } else {
+            format!("{partition} AS {table}")
+        };
+        spliced.replace_range(range.clone(), &replacement);
+        found = true;
+    }
+    found.then_some(spliced)
+}
+
+pub(crate) mod error {
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("Couldn't enumerate partitioned tables: {0:#}")]
+        #[diagnostic(
+            code(cornucopia::partitions::catalog),
+            help("the role preparing the queries needs to read the `pg_catalog` schema")
+        )]
+        Catalog(postgres::Error),
+        #[error("Couldn't prepare query `{query}` against partition `{partition}`: {err:#}")]
+        #[diagnostic(code(cornucopia::partitions::prepare), help(
+            "partition variants are only generated for queries naming their partitioned table after `FROM`, `JOIN`, `UPDATE` or `INTO`"
+        ))]
+        Prepare {
+            query: String,
+            partition: String,
+            err: postgres::Error,
+        },
+    }
+}
@@ -2,17 +2,26 @@ use std::rc::Rc;
 
 use heck::ToUpperCamelCase;
 use indexmap::{map::Entry, IndexMap};
-use postgres::Client;
+use miette::SourceSpan;
+use postgres::{error::SqlState, Client, Column, GenericClient};
 use postgres_types::{Kind, Type};
 
 use crate::{
     codegen::GenCtx,
-    parser::{Module, NullableIdent, Query, Span, TypeAnnotation},
+    decode_benches::Benchmark,
+    domain_checks::Domain,
+    fixtures::execute_sql,
+    lexer::statements,
+    parser::{
+        CdcAnnotation, ChannelAnnotation, CopySql, Module, NullableIdent, Query, SchemaAnnotation,
+        ScriptAnnotation, Span, TransactionAnnotation, TypeAnnotation, TypeOverride,
+    },
     read_queries::ModuleInfo,
+    text_limits,
     type_registrar::CornucopiaType,
     type_registrar::TypeRegistrar,
     utils::KEYWORD,
-    validation,
+    validation, CodegenSettings,
 };
 
 use self::error::Error;
@@ -25,6 +34,57 @@ pub(crate) struct PreparedQuery {
     pub(crate) param: Option<(usize, Vec<usize>)>,
     pub(crate) row: Option<(usize, Vec<usize>)>,
     pub(crate) sql: String,
+    /// Rust name of the newer version superseding this query, if any
+    pub(crate) superseded_by: Option<String>,
+    /// Variants of this query targeting each partition of the partitioned table it references
+    pub(crate) partitioned: Option<PartitionedSql>,
+    /// Statements setting up the transaction this query runs in, such as `SET TRANSACTION`
+    pub(crate) transaction: Option<String>,
+    /// Maximum number of concurrent executions of this query in async code
+    pub(crate) max_concurrency: Option<usize>,
+    /// `COPY` statement of a `--! copy` query, copying rows in if it returns nothing and
+    /// out otherwise
+    pub(crate) copy: Option<String>,
+    /// Whether the query can read rows as of a snapshot exported by another transaction
+    pub(crate) snapshot: bool,
+    /// Keyset pagination of a `--! paginated` query
+    pub(crate) pagination: Option<Pagination>,
+    /// Comment preceding the query in its SQL file
+    pub(crate) doc: Option<String>,
+    /// `path:line` location of the query's annotation
+    pub(crate) source: String,
+    /// `pg_hint_plan` hints prefixing the statement, from `--! hint` options
+    pub(crate) hint: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Pagination {
+    /// Index of the cursor column among the query's columns
+    pub(crate) column: usize,
+    /// Field of the cursor column
+    pub(crate) cursor: PreparedField,
+    /// SQL fetching the rows following a cursor, in cursor order
+    pub(crate) sql: String,
+}
+
+impl Pagination {
+    /// Wraps `sql`, binding `nb_params` parameters, to fetch its rows following the cursor
+    /// bound last but one, ordered by the `column` cursor and limited by the last parameter.
+    fn sql(sql: &str, column: &str, nb_params: usize) -> String {
+        let (cursor, limit) = (nb_params + 1, nb_params + 2);
+        let column = format!("page.\"{}\"", column.replace('"', "\"\""));
+        format!(
+            "SELECT * FROM ({sql}\n) AS page WHERE {column} > ${cursor} OR ${cursor} IS NULL ORDER BY {column} LIMIT ${limit}"
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PartitionedSql {
+    /// Partitioned table, as keyed in [`PreparedModule::partitions`]
+    pub(crate) table: String,
+    /// SQL targeting each partition, in the order of the table's partitions
+    pub(crate) sqls: Vec<String>,
 }
 
 /// A normalized ident replacing all non-alphanumeric characters with an underscore (`_`)
@@ -63,25 +123,58 @@ impl Ident {
 }
 
 /// A row or params field
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct PreparedField {
     pub(crate) ident: Ident,
     pub(crate) ty: Rc<CornucopiaType>,
     pub(crate) is_nullable: bool,
     pub(crate) is_inner_nullable: bool, // Vec only
+    pub(crate) is_sensitive: bool,
+    /// Comment of the column the field is read from, if any
+    pub(crate) doc: Option<String>,
+    /// Maximum number of characters of the `varchar(n)` column the field is
+    /// read from or written to, if any
+    pub(crate) max_len: Option<usize>,
+}
+
+// Fields are equal when they generate the same struct field. `doc` and `max_len` are left
+// out, as rows shared by several queries may read the same field from a commented or
+// `varchar(n)` column in one query and from an expression in another: the shared row keeps
+// the documentation and limit of the query registering it first.
+impl PartialEq for PreparedField {
+    fn eq(&self, other: &Self) -> bool {
+        self.ident == other.ident
+            && self.ty == other.ty
+            && self.is_nullable == other.is_nullable
+            && self.is_inner_nullable == other.is_inner_nullable
+            && self.is_sensitive == other.is_sensitive
+    }
 }
 
+impl Eq for PreparedField {}
+
 impl PreparedField {
     pub(crate) fn new(
         db_ident: String,
         ty: Rc<CornucopiaType>,
         nullity: Option<&NullableIdent>,
     ) -> Self {
+        // Encrypted fields are read and written as their plaintext
+        let ty = if nullity.is_some_and(|it| it.encrypted) {
+            Rc::new(CornucopiaType::Encrypted {
+                pg_ty: ty.pg_ty().clone(),
+            })
+        } else {
+            ty
+        };
         Self {
             ident: Ident::new(db_ident),
             ty,
-            is_nullable: nullity.map_or(false, |it| it.nullable),
-            is_inner_nullable: nullity.map_or(false, |it| it.inner_nullable),
+            is_nullable: nullity.is_some_and(|it| it.nullable),
+            is_inner_nullable: nullity.is_some_and(|it| it.inner_nullable),
+            is_sensitive: nullity.is_some_and(|it| it.sensitive),
+            doc: None,
+            max_len: None,
         }
     }
 }
@@ -101,21 +194,39 @@ pub(crate) struct PreparedItem {
     pub(crate) is_copy: bool,
     pub(crate) is_named: bool,
     pub(crate) is_ref: bool,
+    /// Derive serde's `Serialize` and `Deserialize`, as requested by a `--! serialize`
+    /// query option
+    pub(crate) is_serialized: bool,
+    /// Traits derived on top of the default ones, as requested by `--! derive` query options
+    pub(crate) derives: Vec<String>,
+    /// Comment preceding the first documented query declaring the item
+    pub(crate) doc: Option<String>,
+    /// `path:line` location of the first declaration of the item
+    pub(crate) source: String,
 }
 
 impl PreparedItem {
-    pub fn new(name: Span<String>, fields: Vec<PreparedField>, is_implicit: bool) -> Self {
+    pub fn new(
+        name: Span<String>,
+        fields: Vec<PreparedField>,
+        is_implicit: bool,
+        source: String,
+    ) -> Self {
         Self {
             name,
             is_copy: fields.iter().all(|f| f.ty.is_copy()),
             is_ref: fields.iter().any(|f| f.ty.is_ref()),
             is_named: !is_implicit || fields.len() > 1,
+            is_serialized: false,
+            derives: Vec::new(),
+            doc: None,
+            source,
             fields,
         }
     }
 
     pub fn path(&self, ctx: &GenCtx) -> String {
-        ctx.path(ctx.depth - 2, &self.name)
+        ctx.module_path(&self.name)
     }
 }
 
@@ -126,6 +237,8 @@ pub(crate) struct PreparedType {
     pub(crate) content: PreparedContent,
     pub(crate) is_copy: bool,
     pub(crate) is_params: bool,
+    /// Comment of the type, if any
+    pub(crate) doc: Option<String>,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -142,15 +255,86 @@ pub(crate) struct PreparedModule {
     pub(crate) queries: IndexMap<Span<String>, PreparedQuery>,
     pub(crate) params: IndexMap<Span<String>, PreparedItem>,
     pub(crate) rows: IndexMap<Span<String>, PreparedItem>,
+    pub(crate) channels: Vec<ChannelAnnotation>,
+    pub(crate) cdc_tables: Vec<PreparedCdcTable>,
+    /// Partitioned tables referenced by this module's queries, with their partitions
+    pub(crate) partitions: IndexMap<String, Vec<String>>,
+    pub(crate) scripts: Vec<PreparedScript>,
+    pub(crate) batches: Vec<PreparedBatch>,
+    /// Search path the queries were prepared with, declared by a `--@ schema` annotation
+    pub(crate) search_path: Option<String>,
+}
+
+/// Statements of a query run together in a single batch
+#[derive(Debug, Clone)]
+pub(crate) struct PreparedBatch {
+    pub(crate) ident: Ident,
+    pub(crate) sql: String,
+}
+
+/// Queries executed in order in a single transaction
+#[derive(Debug, Clone)]
+pub(crate) struct PreparedScript {
+    pub(crate) name: Span<String>,
+    pub(crate) ident: Ident,
+    /// Index of each query in [`PreparedModule::queries`], spanning its reference
+    pub(crate) queries: Vec<Span<usize>>,
+}
+
+/// A table whose logical replication changes are decoded
+#[derive(Debug, Clone)]
+pub(crate) struct PreparedCdcTable {
+    pub(crate) schema: String,
+    pub(crate) table: String,
+    pub(crate) struct_name: String,
+    pub(crate) fields: Vec<CdcField>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CdcField {
+    pub(crate) ident: Ident,
+    pub(crate) rust_ty: &'static str,
+    pub(crate) is_nullable: bool,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct Preparation {
     pub(crate) modules: Vec<PreparedModule>,
     pub(crate) types: IndexMap<String, Vec<PreparedType>>,
+    /// Version of the schema the queries were prepared against
+    pub(crate) schema_version: Option<String>,
+    /// Decode benchmarks of the non-Copy rows and composite types
+    pub(crate) benchmarks: Vec<Benchmark>,
+    /// Domains with `CHECK` constraints used by params and composite type fields, by schema
+    pub(crate) domains: IndexMap<String, Vec<Domain>>,
+}
+
+impl Preparation {
+    /// Finds a query by its `module::query` name.
+    pub(crate) fn find_query(&self, query: &str) -> Option<(&PreparedModule, &PreparedQuery)> {
+        let (module, name) = query.rsplit_once("::")?;
+        let module = self
+            .modules
+            .iter()
+            .find(|it| it.info.qualified_name() == module)?;
+        let prepared = module.queries.values().find(|it| it.ident.db == name)?;
+        Some((module, prepared))
+    }
 }
 
 impl PreparedModule {
+    /// Sets the search path the queries were prepared with until the end of the current
+    /// transaction, so that they can be executed.
+    pub(crate) fn set_local_search_path(
+        &self,
+        client: &mut impl GenericClient,
+    ) -> Result<(), postgres::Error> {
+        if let Some(search_path) = &self.search_path {
+            client.execute("SELECT set_config('search_path', $1, true)", &[search_path])?;
+        }
+        Ok(())
+    }
+
     fn add(
         info: &ModuleInfo,
         map: &mut IndexMap<Span<String>, PreparedItem>,
@@ -177,7 +361,13 @@ impl PreparedModule {
                 Ok((o.index(), indexes))
             }
             Entry::Vacant(v) => {
-                v.insert(PreparedItem::new(name.clone(), fields.clone(), is_implicit));
+                let source = info.location(name.span.offset());
+                v.insert(PreparedItem::new(
+                    name.clone(),
+                    fields.clone(),
+                    is_implicit,
+                    source,
+                ));
                 Self::add(info, map, name, fields, is_implicit)
             }
         }
@@ -206,46 +396,74 @@ impl PreparedModule {
         Self::add(&self.info, &mut self.params, name, fields, is_implicit)
     }
 
+    /// Adds a query without options, returning it for its options to be set.
     fn add_query(
         &mut self,
         name: Span<String>,
         param_idx: Option<(usize, Vec<usize>)>,
         row_idx: Option<(usize, Vec<usize>)>,
         sql: String,
-    ) {
-        self.queries.insert(
+    ) -> &mut PreparedQuery {
+        let source = self.info.location(name.span.offset());
+        let (idx, _) = self.queries.insert_full(
             name.clone(),
             PreparedQuery {
                 ident: Ident::new(name.value),
                 row: row_idx,
                 sql,
                 param: param_idx,
+                superseded_by: None,
+                partitioned: None,
+                transaction: None,
+                max_concurrency: None,
+                copy: None,
+                snapshot: false,
+                pagination: None,
+                doc: None,
+                hint: None,
+                source,
             },
         );
+        &mut self.queries[idx]
     }
 }
 
-/// Prepares all modules
-pub(crate) fn prepare(client: &mut Client, modules: Vec<Module>) -> Result<Preparation, Error> {
-    let mut registrar = TypeRegistrar::default();
+/// Prepares all modules, mapping their types and inferring their nullability following
+/// `settings`
+pub(crate) fn prepare(
+    client: &mut Client,
+    modules: Vec<Module>,
+    settings: &CodegenSettings,
+) -> Result<Preparation, Error> {
+    let mut registrar = TypeRegistrar {
+        time_crate: settings.time_crate,
+        decimal_crate: settings.decimal_crate,
+        ..TypeRegistrar::default()
+    };
     let mut tmp = Preparation {
         modules: Vec::new(),
         types: IndexMap::new(),
+        schema_version: None,
+        benchmarks: Vec::new(),
+        domains: IndexMap::new(),
     };
     let declared: Vec<_> = modules
         .iter()
         .flat_map(|it| &it.types)
         .map(|ty| (*ty).clone())
         .collect();
+    registrar.overrides = type_overrides(client, &modules)?;
 
     for module in modules {
         tmp.modules
-            .push(prepare_module(client, module, &mut registrar)?);
+            .push(prepare_module(client, module, &mut registrar, settings)?);
     }
 
     // Prepare types grouped by schema
     for ((schema, name), ty) in &registrar.types {
-        if let Some(ty) = prepare_type(&registrar, name, ty, &declared) {
+        if let Some(ty) =
+            prepare_type(client, &registrar, name, ty, &declared).map_err(Error::TypeDocs)?
+        {
             match tmp.types.entry(schema.clone()) {
                 Entry::Occupied(mut entry) => {
                     entry.get_mut().push(ty);
@@ -259,17 +477,243 @@ pub(crate) fn prepare(client: &mut Client, modules: Vec<Module>) -> Result<Prepa
     Ok(tmp)
 }
 
+/// Prepares all modules without stopping at the first error, returning every error encountered.
+pub(crate) fn check(
+    client: &mut Client,
+    modules: Vec<Module>,
+    settings: &CodegenSettings,
+) -> Vec<Error> {
+    let mut registrar = TypeRegistrar::default();
+    let mut errors = Vec::new();
+    match type_overrides(client, &modules) {
+        Ok(overrides) => registrar.overrides = overrides,
+        Err(e) => return vec![e],
+    }
+    for mut module in modules {
+        if let Err(e) = validation::validate_module(&module, settings) {
+            errors.push(Error::from(e));
+            continue;
+        }
+        let queries = std::mem::take(&mut module.queries);
+        let channels = std::mem::take(&mut module.channels);
+        let search_path = module.schemas.first();
+        let mut tmp_prepared_module = PreparedModule {
+            info: module.info.clone(),
+            queries: IndexMap::new(),
+            params: IndexMap::new(),
+            rows: IndexMap::new(),
+            channels,
+            cdc_tables: Vec::new(),
+            partitions: IndexMap::new(),
+            scripts: Vec::new(),
+            batches: Vec::new(),
+            search_path: search_path.map(SchemaAnnotation::search_path),
+        };
+        let nb_errors = errors.len();
+        let result = with_search_path(client, search_path, &module.info, |client| {
+            for table in &module.cdc_tables {
+                match prepare_cdc_table(client, table, &module.info) {
+                    Ok(table) => tmp_prepared_module.cdc_tables.push(table),
+                    Err(e) => errors.push(e),
+                }
+            }
+            for query in queries {
+                if let Err(e) = prepare_query(
+                    client,
+                    &mut tmp_prepared_module,
+                    &mut registrar,
+                    &module,
+                    query,
+                    false,
+                ) {
+                    errors.push(e);
+                }
+            }
+        });
+        if let Err(e) = result {
+            errors.push(e);
+        }
+        // Scripts and name clashes are only meaningful if every query was prepared
+        if errors.len() == nb_errors {
+            prepare_scripts(&mut tmp_prepared_module, &module.scripts);
+            if let Err(e) = validation::validate_preparation(&tmp_prepared_module) {
+                errors.push(Error::from(e));
+            }
+            if let Err(e) = validation::export_columns(
+                &tmp_prepared_module,
+                settings.gen_csv,
+                settings.gen_arrow,
+            ) {
+                errors.push(Error::from(e));
+            }
+        }
+    }
+    errors
+}
+
+/// Runs `f` with the search path declared by the `--@ schema` annotation of a module, if
+/// any, restoring the previous search path of the session afterwards.
+fn with_search_path<T>(
+    client: &mut Client,
+    annotation: Option<&SchemaAnnotation>,
+    module_info: &ModuleInfo,
+    f: impl FnOnce(&mut Client) -> T,
+) -> Result<T, Error> {
+    let Some(annotation) = annotation else {
+        return Ok(f(client));
+    };
+    let set_search_path = |client: &mut Client, search_path: &str| {
+        client
+            .execute(
+                "SELECT set_config('search_path', $1, false)",
+                &[&search_path],
+            )
+            .map_err(|e| Error::Db {
+                msg: format!("{e:#}"),
+                help: None,
+                src: module_info.into(),
+                err_span: Some(annotation.span),
+            })
+    };
+    let previous: String = client
+        .query_one("SELECT current_setting('search_path')", &[])
+        .map_err(|e| Error::Db {
+            msg: format!("{e:#}"),
+            help: None,
+            src: module_info.into(),
+            err_span: Some(annotation.span),
+        })?
+        .get(0);
+    set_search_path(client, &annotation.search_path())?;
+    let result = f(client);
+    set_search_path(client, &previous)?;
+    Ok(result)
+}
+
+/// Gathers the type overrides of every module, as types are shared by all of them. Each
+/// override must map an existing type to the same rust type in every module.
+fn type_overrides(client: &mut Client, modules: &[Module]) -> Result<Vec<TypeOverride>, Error> {
+    let mut overrides: Vec<TypeOverride> = Vec::new();
+    for module in modules {
+        // Report duplicates within a module before conflicts between modules
+        validation::type_override_already_used(&module.info, &module.type_overrides)?;
+        for it in &module.type_overrides {
+            let exists: bool = client
+                .query_one("SELECT to_regtype($1) IS NOT NULL", &[&it.name.value])
+                .map_err(|e| Error::new_db_err(&e, &module.info, &it.name.span, &it.name))?
+                .get(0);
+            if !exists {
+                return Err(validation::unknown_type_override(&module.info, it).into());
+            }
+            match overrides.iter().find(|prev| prev.name == it.name) {
+                Some(prev) if prev.path != it.path => {
+                    return Err(
+                        validation::conflicting_type_override(&module.info, prev, it).into(),
+                    );
+                }
+                Some(_) => {}
+                None => overrides.push(it.clone()),
+            }
+        }
+    }
+    Ok(overrides)
+}
+
+/// Resolves the queries of each script, which were validated to exist.
+fn prepare_scripts(module: &mut PreparedModule, scripts: &[ScriptAnnotation]) {
+    module.scripts = scripts
+        .iter()
+        .map(|script| PreparedScript {
+            name: script.name.clone(),
+            ident: Ident::new(script.name.value.clone()),
+            queries: script
+                .queries
+                .iter()
+                .map(|name| name.map(|_| module.queries.get_index_of(name).unwrap()))
+                .collect(),
+        })
+        .collect();
+}
+
+/// Reads the columns of a CDC table from the catalog, mapping them to the rust types
+/// decoded from wal2json messages.
+///
+/// Only booleans, integers, floats and strings are supported, whose JSON values wal2json
+/// writes as is. Others, such as `numeric`, dates, arrays or custom types, are written in
+/// a form that depends on the type and its settings (`numeric` as a possibly lossy JSON
+/// number, dates following `DateStyle`, arrays in their text format), and tables with such
+/// columns are rejected rather than decoded wrongly.
+fn prepare_cdc_table(
+    client: &mut Client,
+    table: &CdcAnnotation,
+    module_info: &ModuleInfo,
+) -> Result<PreparedCdcTable, Error> {
+    let rows = client
+        .query(
+            "SELECT n.nspname, c.relname, a.attname, a.atttypid, a.attnotnull
+            FROM pg_attribute a
+            JOIN pg_class c ON c.oid = a.attrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE a.attrelid = to_regclass($1) AND a.attnum > 0 AND NOT a.attisdropped
+            ORDER BY a.attnum",
+            &[&table.name.value],
+        )
+        .map_err(|e| Error::new_db_err(&e, module_info, &table.name.span, &table.name))?;
+    let first = rows
+        .first()
+        .ok_or_else(|| validation::unknown_cdc_table(module_info, table))?;
+    let (schema, name): (String, String) = (first.get(0), first.get(1));
+    let fields = rows
+        .iter()
+        .map(|row| {
+            let column: String = row.get(2);
+            let ty = Type::from_oid(row.get(3));
+            let rust_ty = match ty {
+                Some(Type::BOOL) => "bool",
+                Some(Type::INT2) => "i16",
+                Some(Type::INT4) => "i32",
+                Some(Type::INT8) => "i64",
+                Some(Type::FLOAT4) => "f32",
+                Some(Type::FLOAT8) => "f64",
+                Some(Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::NAME) => "String",
+                _ => {
+                    let ty = ty.map_or_else(|| "custom".to_string(), |ty| ty.name().to_string());
+                    return Err(validation::unsupported_cdc_column(
+                        module_info,
+                        table,
+                        &column,
+                        &ty,
+                    ));
+                }
+            };
+            Ok(CdcField {
+                ident: Ident::new(column),
+                rust_ty,
+                is_nullable: !row.get::<_, bool>(4),
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(PreparedCdcTable {
+        struct_name: format!("{}Record", name.to_upper_camel_case()),
+        schema,
+        table: name,
+        fields,
+    })
+}
+
 fn normalize_rust_name(name: &str) -> String {
     name.replace(':', "_")
 }
 
-/// Prepares database custom types
+/// Prepares database custom types, documented with their comments and the comments of
+/// their fields.
 fn prepare_type(
+    client: &mut Client,
     registrar: &TypeRegistrar,
     name: &str,
     ty: &CornucopiaType,
     types: &[TypeAnnotation],
-) -> Option<PreparedType> {
+) -> Result<Option<PreparedType>, postgres::Error> {
     if let CornucopiaType::Custom {
         pg_ty,
         struct_name,
@@ -287,86 +731,221 @@ fn prepare_type(
                 PreparedContent::Enum(variants.clone().into_iter().map(Ident::new).collect())
             }
 
-            Kind::Domain(_) => return None,
-            Kind::Composite(fields) => PreparedContent::Composite(
-                fields
-                    .iter()
-                    .map(|field| {
-                        let nullity = declared.iter().find(|it| it.name.value == field.name());
-                        PreparedField::new(
-                            field.name().to_string(),
-                            registrar.ref_of(field.type_()),
-                            nullity,
-                        )
-                    })
-                    .collect(),
-            ),
+            Kind::Domain(_) => return Ok(None),
+            Kind::Composite(fields) => {
+                let docs = client.query(
+                    "SELECT a.attname::text, col_description(a.attrelid, a.attnum)
+                    FROM pg_type t JOIN pg_attribute a ON a.attrelid = t.typrelid
+                    WHERE t.oid = $1 AND col_description(a.attrelid, a.attnum) IS NOT NULL",
+                    &[&pg_ty.oid()],
+                )?;
+                PreparedContent::Composite(
+                    fields
+                        .iter()
+                        .map(|field| {
+                            let nullity = declared.iter().find(|it| it.name.value == field.name());
+                            let mut field = PreparedField::new(
+                                field.name().to_string(),
+                                registrar.ref_of(field.type_()),
+                                nullity,
+                            );
+                            field.doc = docs
+                                .iter()
+                                .find(|row| row.get::<_, &str>(0) == field.ident.db)
+                                .map(|row| row.get(1));
+                            field
+                        })
+                        .collect(),
+                )
+            }
             _ => unreachable!(),
         };
-        Some(PreparedType {
+        let doc = client
+            .query_one("SELECT obj_description($1, 'pg_type')", &[&pg_ty.oid()])?
+            .get(0);
+        Ok(Some(PreparedType {
             name: name.to_string(),
             struct_name: struct_name.clone(),
             content,
             is_copy: *is_copy,
             is_params: *is_params,
-        })
+            doc,
+        }))
     } else {
-        None
+        Ok(None)
     }
 }
 
 /// Prepares all queries in this module
 fn prepare_module(
     client: &mut Client,
-    module: Module,
+    mut module: Module,
     registrar: &mut TypeRegistrar,
+    settings: &CodegenSettings,
 ) -> Result<PreparedModule, Error> {
-    validation::validate_module(&module)?;
-
-    let mut tmp_prepared_module = PreparedModule {
-        info: module.info.clone(),
-        queries: IndexMap::new(),
-        params: IndexMap::new(),
-        rows: IndexMap::new(),
-    };
+    validation::validate_module(&module, settings)?;
 
-    for query in module.queries {
-        prepare_query(
-            client,
-            &mut tmp_prepared_module,
-            registrar,
-            &module.types,
-            query,
-            &module.info,
-        )?;
-    }
+    let queries = std::mem::take(&mut module.queries);
+    let channels = std::mem::take(&mut module.channels);
+    let search_path = module.schemas.first();
+    let mut tmp_prepared_module = with_search_path(client, search_path, &module.info, |client| {
+        let mut tmp_prepared_module = PreparedModule {
+            info: module.info.clone(),
+            queries: IndexMap::new(),
+            params: IndexMap::new(),
+            rows: IndexMap::new(),
+            channels,
+            cdc_tables: module
+                .cdc_tables
+                .iter()
+                .map(|table| prepare_cdc_table(client, table, &module.info))
+                .collect::<Result<_, _>>()?,
+            partitions: IndexMap::new(),
+            scripts: Vec::new(),
+            batches: Vec::new(),
+            search_path: search_path.map(SchemaAnnotation::search_path),
+        };
+        for query in queries {
+            prepare_query(
+                client,
+                &mut tmp_prepared_module,
+                registrar,
+                &module,
+                query,
+                settings.infer_nullability,
+            )?;
+        }
+        Ok::<_, Error>(tmp_prepared_module)
+    })??;
+    prepare_scripts(&mut tmp_prepared_module, &module.scripts);
 
     validation::validate_preparation(&tmp_prepared_module)?;
+    validation::export_columns(&tmp_prepared_module, settings.gen_csv, settings.gen_arrow)?;
+    deprecate_old_versions(&mut tmp_prepared_module);
 
     Ok(tmp_prepared_module)
 }
 
-/// Prepares a query
+/// Marks queries superseded by a newer version (`name@vN`) of themselves.
+/// An unversioned query is superseded by any versioned query of the same name.
+fn deprecate_old_versions(module: &mut PreparedModule) {
+    fn version(ident: &Ident) -> (&str, u32) {
+        ident
+            .db
+            .rsplit_once("@v")
+            .and_then(|(name, version)| Some((name, version.parse().ok()?)))
+            .unwrap_or((&ident.db, 0))
+    }
+
+    let mut latest: IndexMap<&str, (u32, &Ident)> = IndexMap::new();
+    for query in module.queries.values() {
+        let (name, version) = version(&query.ident);
+        match latest.entry(name) {
+            Entry::Occupied(mut entry) => {
+                if entry.get().0 < version {
+                    entry.insert((version, &query.ident));
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert((version, &query.ident));
+            }
+        }
+    }
+    let superseded: Vec<_> = module
+        .queries
+        .values()
+        .map(|query| {
+            let (name, version) = version(&query.ident);
+            let (latest_version, latest) = latest[name];
+            (latest_version > version).then(|| latest.rs.clone())
+        })
+        .collect();
+    for (query, superseded_by) in module.queries.values_mut().zip(superseded) {
+        query.superseded_by = superseded_by;
+    }
+}
+
+/// Prepares a query of the `source` module
 fn prepare_query(
     client: &mut Client,
     module: &mut PreparedModule,
     registrar: &mut TypeRegistrar,
-    types: &[TypeAnnotation],
+    source: &Module,
     Query {
         name,
         param,
         bind_params,
         row,
+        transaction,
+        serialize,
+        max_concurrency,
         sql_str,
         sql_span,
+        sql_edits,
+        list_params: _,
+        copy,
+        snapshot,
+        timeout,
+        read_only,
+        paginated,
+        is_batch,
+        doc,
+        hint,
+        planner_settings,
+        multi_exec,
+        derives,
     }: Query,
-    module_info: &ModuleInfo,
+    infer_nullability: bool,
 ) -> Result<(), Error> {
+    let (types, module_info) = (&source.types, &source.info);
+    if is_batch {
+        // Each statement is prepared once the previous ones ran, so that it sees the objects
+        // they create, and all are rolled back with the transaction
+        let mut transaction = client
+            .transaction()
+            .map_err(|e| Error::new_db_err(&e, module_info, &sql_span, &name))?;
+        let batch = statements(&sql_str).unwrap_or_else(|_| vec![sql_str.as_str()]);
+        for statement in batch {
+            // Errors are positioned in the statement, which starts within the query
+            let offset = statement.as_ptr() as usize - sql_str.as_ptr() as usize;
+            let span = SourceSpan::from((sql_span.offset() + offset, statement.len()));
+            let db_err = |e| Error::new_db_err(&e, module_info, &span, &name);
+            transaction.prepare(statement).map_err(db_err)?;
+            transaction.batch_execute(statement).map_err(db_err)?;
+        }
+        module.batches.push(PreparedBatch {
+            ident: Ident::new(name.value),
+            sql: sql_str,
+        });
+        return Ok(());
+    }
+    // Statements of a `--! multi_exec` query return their counts, validated beforehand
+    let multi_exec = multi_exec.and_then(|span| Some((span, Query::multi_exec_sql(&sql_str)?)));
+    let sql_str = match multi_exec {
+        Some((span, multi_exec)) => {
+            // Errors of the query as written point at its own SQL, unlike the rewritten one's
+            client
+                .prepare(&sql_str)
+                .map_err(|e| Error::new_query_err(&e, module_info, &sql_span, &sql_edits, &name))?;
+            if multi_exec.counts_merge {
+                let version = client
+                    .query_one("SELECT current_setting('server_version_num')::int", &[])
+                    .map_err(|e| Error::new_db_err(&e, module_info, &sql_span, &name))?
+                    .get(0);
+                validation::multi_exec_merge(module_info, &name, span, &sql_span, version)?;
+            }
+            multi_exec.sql
+        }
+        None => sql_str,
+    };
     // Prepare the statement
     let stmt = client
         .prepare(&sql_str)
+        .map_err(|e| Error::new_query_err(&e, module_info, &sql_span, &sql_edits, &name))?;
+    check_privileges(client, &sql_str, stmt.params().len())
         .map_err(|e| Error::new_db_err(&e, module_info, &sql_span, &name))?;
 
+    let copy_sql = copy.and_then(|_| Query::copy_sql(&sql_str, bind_params.len()));
     let (nullable_params_fields, params_name) = param.name_and_fields(types, &name, Some("Params"));
     let (nullable_row_fields, row_name) = row.name_and_fields(types, &name, None);
     let params_fields = {
@@ -382,6 +961,9 @@ fn prepare_query(
             // If none of the row's columns match the nullable column
             validation::nullable_param_name(&module.info, nullable_col, &params)
                 .map_err(Error::from)?;
+            if let Some((_, ty)) = params.iter().find(|it| it.0 == nullable_col.name) {
+                validation::encrypted_field(&module.info, nullable_col, ty)?;
+            }
         }
 
         let mut param_fields = Vec::new();
@@ -398,66 +980,240 @@ fn prepare_query(
                 nullity,
             ));
         }
+        let limits = text_limits::param_limits(client, &sql_str, param_fields.len())
+            .map_err(|e| Error::new_db_err(&e, module_info, &sql_span, &name))?;
+        for (field, limit) in param_fields.iter_mut().zip(limits) {
+            text_limits::document(field, limit);
+        }
         param_fields
     };
 
     let row_fields = {
-        let stmt_cols = stmt.columns();
+        // Calls to functions returning `void`, such as `SELECT pg_sleep(1)`, return nothing
+        let stmt_cols = if stmt.columns().iter().all(|col| col.type_() == &Type::VOID) {
+            &[]
+        } else {
+            stmt.columns()
+        };
         // Check for row declaration on execute
         validation::row_on_execute(&module.info, &name, &sql_span, &row, stmt_cols)?;
+        // Check that copied out queries return some rows
+        if matches!(copy_sql, Some(CopySql::Out(_))) {
+            validation::copy_out_rows(&module.info, &name, copy, &sql_span, stmt_cols)?;
+        }
+        // Check that snapshot reads return some rows
+        validation::snapshot_rows(&module.info, &name, snapshot, &sql_span, stmt_cols)?;
         // Check for duplicate names
         validation::duplicate_sql_col_name(&module.info, &name, stmt_cols).map_err(Error::from)?;
         for nullable_col in nullable_row_fields {
             // If none of the row's columns match the nullable column
             validation::nullable_column_name(&module.info, nullable_col, stmt_cols)
                 .map_err(Error::from)?;
+            if let Some(col) = stmt_cols
+                .iter()
+                .find(|it| it.name() == nullable_col.name.value)
+            {
+                validation::encrypted_field(&module.info, nullable_col, col.type_())?;
+            }
         }
 
+        let inferred = if infer_nullability {
+            nullable_table_columns(client, stmt_cols)
+                .map_err(|e| Error::new_db_err(&e, module_info, &sql_span, &name))?
+        } else {
+            vec![false; stmt_cols.len()]
+        };
+
+        let docs = column_docs(client, stmt_cols)
+            .map_err(|e| Error::new_db_err(&e, module_info, &sql_span, &name))?;
+        let limits = text_limits::column_limits(client, stmt_cols)
+            .map_err(|e| Error::new_db_err(&e, module_info, &sql_span, &name))?;
+
         let mut row_fields = Vec::new();
-        for (col_name, col_ty) in stmt_cols.iter().map(|c| (c.name().to_owned(), c.type_())) {
+        for (((col, is_nullable), doc), limit) in
+            stmt_cols.iter().zip(inferred).zip(docs).zip(limits)
+        {
+            let col_name = col.name().to_owned();
             let nullity = nullable_row_fields
                 .iter()
                 .find(|x| x.name.value == col_name);
             // Register type
             let ty = registrar
-                .register(&col_name, col_ty, &name, module_info)?
+                .register(&col_name, col.type_(), &name, module_info)?
                 .clone();
-            row_fields.push(PreparedField::new(
-                normalize_rust_name(&col_name),
-                ty,
-                nullity,
-            ));
+            let mut field = PreparedField::new(normalize_rust_name(&col_name), ty, nullity);
+            // Annotated columns keep their annotated nullability
+            field.is_nullable |= nullity.is_none() && is_nullable;
+            field.doc = doc;
+            text_limits::document(&mut field, limit);
+            row_fields.push(field);
         }
         row_fields
     };
 
+    let pagination = match &paginated {
+        Some(cursor) => {
+            let column =
+                validation::cursor_column(module_info, &name, cursor, &sql_span, &row_fields)?;
+            let sql = Pagination::sql(&sql_str, &cursor.value, stmt.params().len());
+            client
+                .prepare(&sql)
+                .map_err(|e| Error::new_db_err(&e, module_info, &sql_span, &name))?;
+            Some(Pagination {
+                column,
+                cursor: row_fields[column].clone(),
+                sql,
+            })
+        }
+        None => None,
+    };
+
     let row_idx = if row_fields.is_empty() {
         None
     } else {
-        Some(module.add_row(row_name, row_fields, row.is_implicit())?)
+        let (idx, indexes) = module.add_row(row_name, row_fields, row.is_implicit())?;
+        let row = &mut module.rows[idx];
+        row.is_serialized |= serialize;
+        for derive in derives {
+            if !row.derives.contains(&derive) {
+                row.derives.push(derive);
+            }
+        }
+        if row.doc.is_none() {
+            row.doc = doc.clone();
+        }
+        Some((idx, indexes))
     };
     let param_idx = if params_fields.is_empty() {
         None
     } else {
-        Some(module.add_param(params_name, params_fields, param.is_implicit())?)
+        let (idx, indexes) = module.add_param(params_name, params_fields, param.is_implicit())?;
+        let params = &mut module.params[idx];
+        if params.doc.is_none() {
+            params.doc = doc.clone();
+        }
+        Some((idx, indexes))
     };
-    module.add_query(name.clone(), param_idx, row_idx, sql_str);
+    // Unknown parameters and invalid values are only reported when set
+    for setting in &planner_settings {
+        let mut transaction = client
+            .transaction()
+            .map_err(|e| Error::new_db_err(&e, module_info, &sql_span, &name))?;
+        transaction
+            .batch_execute(&format!(
+                "SET LOCAL {} = {}",
+                setting.name.value, setting.value
+            ))
+            .map_err(|e| Error::new_db_err(&e, module_info, &sql_span, &setting.name))?;
+    }
+    let transaction = TransactionAnnotation::setup_sql(
+        transaction.as_ref(),
+        read_only.is_some(),
+        timeout.map(|it| it.value),
+        &planner_settings,
+    );
+    let query = module.add_query(name.clone(), param_idx, row_idx, sql_str);
+    query.transaction = transaction;
+    query.max_concurrency = max_concurrency.map(|it| it.value);
+    query.copy = copy_sql.map(|(CopySql::In(sql) | CopySql::Out(sql))| sql);
+    query.snapshot = snapshot.is_some();
+    query.pagination = pagination;
+    query.doc = doc;
+    query.hint = hint;
 
     Ok(())
 }
 
+/// Tells whether each column is selected straight from a table column lacking a `NOT NULL`
+/// constraint. Computed columns are left to annotations. All columns are looked up at once.
+fn nullable_table_columns(
+    client: &mut Client,
+    cols: &[Column],
+) -> Result<Vec<bool>, postgres::Error> {
+    let tables: Vec<_> = cols.iter().map(Column::table_oid).collect();
+    let attnums: Vec<_> = cols.iter().map(Column::column_id).collect();
+    Ok(client
+        .query(
+            "SELECT coalesce((SELECT NOT attnotnull FROM pg_attribute
+                WHERE attrelid = col.tbl AND attnum = col.attnum), false)
+            FROM unnest($1::oid[], $2::int2[]) WITH ORDINALITY AS col(tbl, attnum, i)
+            ORDER BY col.i",
+            &[&tables, &attnums],
+        )?
+        .iter()
+        .map(|row| row.get(0))
+        .collect())
+}
+
+/// Comment of the table column each column is selected straight from, if any, all looked
+/// up at once.
+fn column_docs(
+    client: &mut Client,
+    cols: &[Column],
+) -> Result<Vec<Option<String>>, postgres::Error> {
+    let tables: Vec<_> = cols.iter().map(Column::table_oid).collect();
+    let attnums: Vec<_> = cols
+        .iter()
+        .map(|col| col.column_id().map(i32::from))
+        .collect();
+    // `col_description` is strict, columns not selected from a table have no comment
+    Ok(client
+        .query(
+            "SELECT col_description(col.tbl, col.attnum)
+            FROM unnest($1::oid[], $2::int4[]) WITH ORDINALITY AS col(tbl, attnum, i)
+            ORDER BY col.i",
+            &[&tables, &attnums],
+        )?
+        .iter()
+        .map(|row| row.get(0))
+        .collect())
+}
+
+/// Plans the statement without running it, as table and column privileges are only checked
+/// when planning a statement, not when preparing it. Other planning errors, which can be
+/// caused by the placeholder `NULL` parameters, are ignored.
+fn check_privileges(
+    client: &mut Client,
+    sql: &str,
+    nb_params: usize,
+) -> Result<(), postgres::Error> {
+    if client
+        .batch_execute(&format!("PREPARE cornucopia_privileges AS {sql}"))
+        .is_err()
+    {
+        return Ok(());
+    }
+    let explain = format!(
+        "EXPLAIN {}",
+        execute_sql(
+            "cornucopia_privileges",
+            &vec!["NULL".to_string(); nb_params]
+        )
+    );
+    let result = client.batch_execute(&explain);
+    client.batch_execute("DEALLOCATE cornucopia_privileges")?;
+    match result {
+        Err(e) if e.code() == Some(&SqlState::INSUFFICIENT_PRIVILEGE) => Err(e),
+        _ => Ok(()),
+    }
+}
+
 pub(crate) mod error {
     use miette::{Diagnostic, NamedSource, SourceSpan};
     use thiserror::Error as ThisError;
 
     use crate::{
-        parser::Span, read_queries::ModuleInfo, type_registrar::error::Error as PostgresTypeError,
-        utils::db_err, validation::error::Error as ValidationError,
+        parser::{Query, Span, SqlEdit},
+        read_queries::ModuleInfo,
+        type_registrar::error::Error as PostgresTypeError,
+        utils::db_err,
+        validation::error::Error as ValidationError,
     };
 
     #[derive(Debug, ThisError, Diagnostic)]
     pub enum Error {
         #[error("Couldn't prepare query: {msg}")]
+        #[diagnostic(code(cornucopia::prepare_queries::db))]
         Db {
             msg: String,
             #[help]
@@ -467,6 +1223,12 @@ pub(crate) mod error {
             #[label("error occurs near this location")]
             err_span: Option<SourceSpan>,
         },
+        #[error("Couldn't read the comments of custom types: {0}")]
+        #[diagnostic(
+            code(cornucopia::prepare_queries::type_docs),
+            help("check that the database is still reachable and that your user can read `pg_description`")
+        )]
+        TypeDocs(postgres::Error),
         #[error(transparent)]
         #[diagnostic(transparent)]
         PostgresType(#[from] PostgresTypeError),
@@ -481,14 +1243,27 @@ pub(crate) mod error {
             module_info: &ModuleInfo,
             query_span: &SourceSpan,
             query_name: &Span<String>,
+        ) -> Self {
+            Self::new_query_err(err, module_info, query_span, &[], query_name)
+        }
+
+        /// Error of a query whose SQL was made by applying `edits` to the SQL as written,
+        /// pointing at the SQL as written.
+        pub(crate) fn new_query_err(
+            err: &postgres::Error,
+            module_info: &ModuleInfo,
+            query_span: &SourceSpan,
+            edits: &[SqlEdit],
+            query_name: &Span<String>,
         ) -> Self {
             let msg = format!("{err:#}");
             if let Some((position, msg, help)) = db_err(err) {
+                let offset = Query::source_offset(edits, position as usize - 1);
                 Self::Db {
                     msg,
                     help,
                     src: module_info.into(),
-                    err_span: Some((query_span.offset() + position as usize - 1).into()),
+                    err_span: Some((query_span.offset() + offset).into()),
                 }
             } else {
                 Self::Db {
@@ -5,72 +5,219 @@ use std::{
 
 use miette::NamedSource;
 
+use crate::{lexer::scan, utils::KEYWORD, ParamSyntax};
+
 use self::error::Error;
 
 #[derive(Debug, Clone)]
 pub(crate) struct ModuleInfo {
     pub(crate) path: PathBuf,
     pub(crate) name: String,
+    /// Subdirectories of the queries folder holding the file, whose modules nest this one
+    pub(crate) dirs: Vec<String>,
+    /// Content of the file, with its bind parameters translated to cornucopia's syntax
     pub(crate) content: Arc<String>,
+    /// Content of the file as written, shown in reports. Translated parameters keep their
+    /// length so that the spans of `content` point at the same text in it.
+    pub(crate) source: Arc<String>,
+}
+
+impl ModuleInfo {
+    /// Names of the modules nesting this one, followed by its own name.
+    pub(crate) fn module_path(&self) -> Vec<String> {
+        let mut path = self.dirs.clone();
+        path.push(self.name.clone());
+        path
+    }
+
+    /// Path of the module relative to the `queries` module, such as `admin::users`.
+    pub(crate) fn qualified_name(&self) -> String {
+        self.module_path().join("::")
+    }
+
+    /// Byte offset in the module's content of the character at `offset`, as the spans
+    /// of the parser count characters.
+    pub(crate) fn byte_offset(&self, offset: usize) -> usize {
+        self.content
+            .char_indices()
+            .nth(offset)
+            .map_or(self.content.len(), |(idx, _)| idx)
+    }
+
+    /// `path:line` location of `offset` in the module's file, with forward slashes so that
+    /// generated code doesn't depend on the platform it was generated on.
+    pub(crate) fn location(&self, offset: usize) -> String {
+        let line = self.content[..self.byte_offset(offset)]
+            .matches('\n')
+            .count()
+            + 1;
+        format!("{}:{line}", self.path.to_string_lossy().replace('\\', "/"))
+    }
 }
 
 impl From<ModuleInfo> for NamedSource {
     fn from(m: ModuleInfo) -> Self {
-        Self::new(m.path.to_string_lossy(), m.content)
+        Self::new(m.path.to_string_lossy(), m.source)
     }
 }
 
 impl From<&ModuleInfo> for NamedSource {
     fn from(m: &ModuleInfo) -> Self {
-        Self::new(m.path.to_string_lossy(), m.content.clone())
+        Self::new(m.path.to_string_lossy(), m.source.clone())
     }
 }
 
-/// Reads queries in the directory. Only .sql files are considered.
+/// Reads queries in the directory and its subdirectories. Only .sql files are considered,
+/// the modules of subdirectories being nested in a module named after them. Bind parameters
+/// written using `param_syntax` are translated to cornucopia's `:name` syntax.
 ///
 /// # Error
-/// Returns an error if `dir_path` does not point to a valid directory or if a query file cannot be parsed.
-pub(crate) fn read_query_modules(dir_path: &Path) -> Result<Vec<ModuleInfo>, Error> {
+/// Returns an error if `dir_path` does not point to a valid directory, if a query file cannot
+/// be parsed, or if a query file and a subdirectory would declare the same module.
+pub(crate) fn read_query_modules(
+    dir_path: &Path,
+    param_syntax: ParamSyntax,
+) -> Result<Vec<ModuleInfo>, Error> {
     let mut modules_info = Vec::new();
-    for entry_result in std::fs::read_dir(dir_path).map_err(|err| Error {
+    read_dir_modules(dir_path, &[], param_syntax, &mut modules_info)?;
+    // Sort module for consistent codegen, the modules of a subdirectory following each other
+    modules_info.sort_by_key(ModuleInfo::module_path);
+    // A module is sorted right before the first module nested in it, if any
+    for pair in modules_info.windows(2) {
+        let (file, nested) = (&pair[0], &pair[1]);
+        if nested.dirs.starts_with(&file.module_path()) {
+            return Err(Error::DuplicateModule {
+                file: file.path.clone(),
+                dir: file.path.with_extension(""),
+                module: file.qualified_name(),
+            });
+        }
+    }
+    Ok(modules_info)
+}
+
+/// Reads the queries of the `dirs` subdirectory, `dir_path`, into `modules_info`.
+fn read_dir_modules(
+    dir_path: &Path,
+    dirs: &[String],
+    param_syntax: ParamSyntax,
+    modules_info: &mut Vec<ModuleInfo>,
+) -> Result<(), Error> {
+    for entry_result in std::fs::read_dir(dir_path).map_err(|err| Error::Io {
         err,
         path: dir_path.to_owned(),
     })? {
         // Directory entry
-        let entry = entry_result.map_err(|err| Error {
+        let entry = entry_result.map_err(|err| Error::Io {
             err,
             path: dir_path.to_owned(),
         })?;
         let path_buf = entry.path();
-
-        // Check we're dealing with a .sql file
-        if path_buf
-            .extension()
-            .map(|extension| extension == "sql")
-            .unwrap_or_default()
-        {
-            let module_name = path_buf
+        let file_stem = || {
+            path_buf
                 .file_stem()
                 .expect("is a file")
                 .to_str()
                 .expect("file name is valid utf8")
-                .to_string();
+                .to_string()
+        };
 
-            let file_contents = std::fs::read_to_string(&path_buf).map_err(|err| Error {
+        if path_buf.is_dir() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            // Hidden folders, such as `.git`, don't hold queries
+            if name.starts_with('.') {
+                continue;
+            }
+            // Subdirectories are declared as modules of the generated code
+            let is_ident = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !is_ident || KEYWORD.binary_search(&name.as_str()).is_ok() {
+                return Err(Error::InvalidModuleDir {
+                    dir: path_buf,
+                    name,
+                });
+            }
+            let mut dirs = dirs.to_vec();
+            dirs.push(name);
+            read_dir_modules(&path_buf, &dirs, param_syntax, modules_info)?;
+        } else if path_buf
+            .extension()
+            .map(|extension| extension == "sql")
+            .unwrap_or_default()
+        {
+            // Check we're dealing with a .sql file
+            let file_contents = std::fs::read_to_string(&path_buf).map_err(|err| Error::Io {
                 err,
                 path: dir_path.to_owned(),
             })?;
 
+            let source = Arc::new(file_contents);
+            let content = match param_syntax {
+                ParamSyntax::Colon => source.clone(),
+                _ => Arc::new(translate_params(&source, param_syntax)),
+            };
             modules_info.push(ModuleInfo {
+                name: file_stem(),
+                dirs: dirs.to_vec(),
                 path: path_buf,
-                name: module_name,
-                content: Arc::new(file_contents),
+                content,
+                source,
             });
         }
     }
-    // Sort module for consistent codegen
-    modules_info.sort_by(|a, b| a.name.cmp(&b.name));
-    Ok(modules_info)
+    Ok(())
+}
+
+/// Translates bind parameters written using `syntax` to cornucopia's `:name` syntax,
+/// leaving comments, string literals, quoted identifiers and identifiers untouched.
+fn translate_params(sql: &str, syntax: ParamSyntax) -> String {
+    if syntax == ParamSyntax::Colon {
+        return sql.to_string();
+    }
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let ident_len = |s: &str| s.find(|c| !is_ident(c)).unwrap_or(s.len());
+    let starts_ident = |s: &str| s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_');
+
+    let mut out = String::with_capacity(sql.len());
+    let mut i = 0;
+    while let Some(c) = sql[i..].chars().next() {
+        // Scanned like the lexer does, so that both agree on where literals end
+        if let Some((_, end)) = scan(sql, i) {
+            out.push_str(&sql[i..end]);
+            i = end;
+            continue;
+        }
+
+        // Length of the parameter's prefix and name
+        let rest = &sql[i..];
+        let param = match (syntax, c) {
+            (ParamSyntax::Dollar, '$') | (ParamSyntax::At, '@') if starts_ident(&rest[1..]) => {
+                Some((1, ident_len(&rest[1..])))
+            }
+            (ParamSyntax::Pyformat, '%') if rest.starts_with("%(") => {
+                let len = ident_len(&rest[2..]);
+                (len > 0 && rest[2 + len..].starts_with(")s")).then_some((2, len))
+            }
+            _ => None,
+        };
+        if let Some((prefix, len)) = param {
+            let suffix = if syntax == ParamSyntax::Pyformat {
+                2
+            } else {
+                0
+            };
+            // Padded to the length of the original parameter, keeping the spans of the
+            // translated content valid in the original one
+            out.push(':');
+            out.push_str(&rest[prefix..prefix + len]);
+            out.push_str(&" ".repeat(prefix + suffix - 1));
+            i += prefix + len + suffix;
+        } else {
+            out.push(c);
+            i += c.len_utf8();
+        }
+    }
+    out
 }
 
 pub(crate) mod error {
@@ -80,9 +227,28 @@ pub(crate) mod error {
     use thiserror::Error as ThisError;
 
     #[derive(Debug, ThisError, Diagnostic)]
-    #[error("[{path}] : {err:#}")]
-    pub struct Error {
-        pub(crate) err: std::io::Error,
-        pub(crate) path: PathBuf,
+    pub enum Error {
+        #[error("Couldn't read queries from `{path}`: {err:#}")]
+        #[diagnostic(
+            code(cornucopia::read_queries),
+            help("check that the queries folder exists, or point to it with `--queries-path`")
+        )]
+        Io { err: std::io::Error, path: PathBuf },
+        #[error("Both `{file}` and the folder `{dir}` declare the query module `{module}`")]
+        #[diagnostic(
+            code(cornucopia::read_queries::duplicate_module),
+            help("rename the file or the folder, as their modules can't be flattened into one")
+        )]
+        DuplicateModule {
+            file: PathBuf,
+            dir: PathBuf,
+            module: String,
+        },
+        #[error("The folder `{dir}` can't declare the query module `{name}`")]
+        #[diagnostic(
+            code(cornucopia::read_queries::invalid_module_dir),
+            help("rename the folder to a Rust identifier that isn't a keyword, such as `v1_2` or `fn_`")
+        )]
+        InvalidModuleDir { dir: PathBuf, name: String },
     }
 }
@@ -0,0 +1,64 @@
+use postgres::Client;
+
+use self::error::Error;
+
+/// Migration history tables of common migration tools, whose version column sorts
+/// by application order.
+const MIGRATION_TABLES: [&str; 3] = [
+    "refinery_schema_history",
+    "_sqlx_migrations",
+    "__diesel_schema_migrations",
+];
+
+/// Fingerprints the columns and enum labels of the search path, so that any change
+/// of the row or enum types used by queries changes the fingerprint.
+const FINGERPRINT_SQL: &str = "SELECT left(md5(concat_ws(';',
+    (SELECT string_agg(
+        format('%s.%s:%s:%s', c.relname, a.attname, format_type(a.atttypid, a.atttypmod), a.attnotnull),
+        ',' ORDER BY c.relname, a.attnum)
+    FROM pg_attribute a
+    JOIN pg_class c ON c.oid = a.attrelid
+    WHERE pg_table_is_visible(c.oid)
+        AND c.relkind IN ('r', 'v', 'm', 'p', 'c')
+        AND c.relnamespace NOT IN ('pg_catalog'::regnamespace, 'information_schema'::regnamespace)
+        AND a.attnum > 0 AND NOT a.attisdropped),
+    (SELECT string_agg(format('%s:%s', t.typname, e.enumlabel), ',' ORDER BY t.typname, e.enumsortorder)
+    FROM pg_enum e
+    JOIN pg_type t ON t.oid = e.enumtypid
+    WHERE pg_type_is_visible(t.oid))
+)), 16)";
+
+/// Returns the latest migration version applied by a known migration tool, or a
+/// fingerprint of the schema otherwise.
+pub(crate) fn schema_version(client: &mut Client) -> Result<String, Error> {
+    for table in MIGRATION_TABLES {
+        let exists: bool = client
+            .query_one("SELECT to_regclass($1) IS NOT NULL", &[&table])
+            .map_err(Error)?
+            .get(0);
+        if exists {
+            let version: Option<String> = client
+                .query_one(&format!("SELECT max(version)::text FROM {table}"), &[])
+                .map_err(Error)?
+                .get(0);
+            if let Some(version) = version {
+                return Ok(version);
+            }
+        }
+    }
+    let fingerprint = client.query_one(FINGERPRINT_SQL, &[]).map_err(Error)?;
+    Ok(fingerprint.get(0))
+}
+
+pub(crate) mod error {
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    #[error("Couldn't read the schema version: {0:#}")]
+    #[diagnostic(
+        code(cornucopia::schema_info),
+        help("the role preparing the queries needs to read the migrations table")
+    )]
+    pub struct Error(pub(crate) postgres::Error);
+}
@@ -0,0 +1,227 @@
+use postgres::{Client, Column};
+
+use crate::{
+    lexer::{is_keyword, lex, Token},
+    prepare_queries::PreparedField,
+};
+
+/// Keywords ending the assignments of a `SET` clause
+const SET_END_KEYWORDS: [&str; 3] = ["FROM", "WHERE", "RETURNING"];
+
+/// Maximum number of characters of each `varchar(n)` column `(table, attnum)`, if any
+const COLUMN_LIMITS: &str = "SELECT (SELECT atttypmod - 4 FROM pg_attribute
+        WHERE attrelid = col.tbl AND attnum = col.attnum
+        AND atttypid = 'varchar'::regtype AND atttypmod > 4)
+    FROM unnest($1::oid[], $2::int2[]) WITH ORDINALITY AS col(tbl, attnum, i)
+    ORDER BY col.i";
+
+/// Maximum number of characters of each `varchar(n)` column `(table, column)`, if any
+const NAMED_COLUMN_LIMITS: &str = "SELECT (SELECT atttypmod - 4 FROM pg_attribute
+        WHERE attrelid = to_regclass(col.tbl) AND attname = col.name
+        AND atttypid = 'varchar'::regtype AND atttypmod > 4)
+    FROM unnest($1::text[], $2::text[]) WITH ORDINALITY AS col(tbl, name, i)
+    ORDER BY col.i";
+
+/// Maximum number of characters of the length-limited table column each column is selected
+/// straight from, if any, all looked up at once.
+pub(crate) fn column_limits(
+    client: &mut Client,
+    cols: &[Column],
+) -> Result<Vec<Option<usize>>, postgres::Error> {
+    let tables: Vec<_> = cols.iter().map(Column::table_oid).collect();
+    let attnums: Vec<_> = cols.iter().map(Column::column_id).collect();
+    Ok(client
+        .query(COLUMN_LIMITS, &[&tables, &attnums])?
+        .iter()
+        .map(|row| row.get::<_, Option<i32>>(0).map(|it| it as usize))
+        .collect())
+}
+
+/// Maximum number of characters of the length-limited column each of the `nb_params`
+/// parameters of `sql` is written to, if any. Parameters are only matched to the columns
+/// they are directly written to, by an `INSERT` column list or a `SET` clause.
+pub(crate) fn param_limits(
+    client: &mut Client,
+    sql: &str,
+    nb_params: usize,
+) -> Result<Vec<Option<usize>>, postgres::Error> {
+    let mut limits = vec![None; nb_params];
+    let written: Vec<_> = written_columns(sql)
+        .into_iter()
+        .filter(|(param, _, _)| (1..=nb_params).contains(param))
+        .collect();
+    if written.is_empty() {
+        return Ok(limits);
+    }
+    let (tables, columns): (Vec<_>, Vec<_>) = written
+        .iter()
+        .map(|(_, table, column)| (table, column))
+        .unzip();
+    let rows = client.query(NAMED_COLUMN_LIMITS, &[&tables, &columns])?;
+    for ((param, _, _), row) in written.iter().zip(rows) {
+        if let Some(max_len) = row.get::<_, Option<i32>>(0) {
+            let max_len = max_len as usize;
+            let limit = &mut limits[param - 1];
+            // Parameters written to several columns are limited by the shortest
+            *limit = Some(limit.map_or(max_len, |it: usize| it.min(max_len)));
+        }
+    }
+    Ok(limits)
+}
+
+/// Appends the maximum number of characters of the field to its doc comment.
+pub(crate) fn document(field: &mut PreparedField, max_len: Option<usize>) {
+    let Some(max_len) = max_len else {
+        return;
+    };
+    let mut doc = field
+        .doc
+        .take()
+        .map_or_else(String::new, |doc| doc + "\n\n");
+    doc.push_str(&format!("At most {max_len} characters."));
+    field.doc = Some(doc);
+    field.max_len = Some(max_len);
+}
+
+/// Parameters of `sql` directly written to a table column, as `(param, table, column)`.
+/// Tables are qualified and quoted as expected by `to_regclass`.
+fn written_columns(sql: &str) -> Vec<(usize, String, String)> {
+    // Prepared queries only bind valid parameters
+    let Ok(tokens) = lex(sql) else {
+        return Vec::new();
+    };
+    let name = |token: &Token| match token {
+        // Unquoted identifiers are folded to lower case
+        Token::Ident(ident, range) if sql[range.clone()].starts_with('"') => Some(ident.clone()),
+        Token::Ident(ident, _) => Some(ident.to_lowercase()),
+        _ => None,
+    };
+    let mut written = Vec::new();
+    let mut table = None;
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        i += 1;
+        let is_update = is_keyword(token, "UPDATE");
+        if is_update && tokens.get(i).is_some_and(|it| is_keyword(it, "SET")) {
+            // `ON CONFLICT DO UPDATE SET` writes to the table of the `INSERT`
+            continue;
+        }
+        if is_keyword(token, "INTO") || is_update {
+            if is_update && tokens.get(i).is_some_and(|it| is_keyword(it, "ONLY")) {
+                i += 1;
+            }
+            // Possibly qualified table name
+            let mut parts = Vec::new();
+            while let Some(part) = tokens.get(i).and_then(name) {
+                parts.push(format!("\"{}\"", part.replace('"', "\"\"")));
+                i += 1;
+                if tokens.get(i) != Some(&Token::Punct('.')) {
+                    break;
+                }
+                i += 1;
+            }
+            table = (!parts.is_empty()).then(|| parts.join("."));
+            if is_keyword(token, "INTO") {
+                if let Some(table) = &table {
+                    i = insert_columns(&tokens, i, &name, table, &mut written);
+                }
+            }
+        } else if is_keyword(token, "SET") {
+            let Some(table) = &table else {
+                continue;
+            };
+            let ends_clause = |token: &Token| {
+                SET_END_KEYWORDS
+                    .iter()
+                    .any(|keyword| is_keyword(token, keyword))
+            };
+            // `column = $n` assignments, up to the end of the clause
+            while let Some(token) = tokens.get(i) {
+                if ends_clause(token) {
+                    break;
+                }
+                if let (Some(column), Some(Token::Op(op)), Some(Token::Param(param, _))) =
+                    (name(token), tokens.get(i + 1), tokens.get(i + 2))
+                {
+                    let is_whole_value = match tokens.get(i + 3) {
+                        None | Some(Token::Punct(',' | ';')) => true,
+                        Some(next) => ends_clause(next),
+                    };
+                    if op == "=" && is_whole_value {
+                        written.push((*param, table.clone(), column));
+                    }
+                }
+                i += 1;
+            }
+        }
+    }
+    written
+}
+
+/// Matches the parameters of the `VALUES` lists of an `INSERT` to its column list starting
+/// at `i`, returning the index of the first token following them.
+fn insert_columns(
+    tokens: &[Token],
+    mut i: usize,
+    name: &impl Fn(&Token) -> Option<String>,
+    table: &str,
+    written: &mut Vec<(usize, String, String)>,
+) -> usize {
+    // Skip the alias
+    if tokens.get(i).is_some_and(|it| is_keyword(it, "AS")) {
+        i += 2;
+    }
+    if tokens.get(i) != Some(&Token::Punct('(')) {
+        return i;
+    }
+    let mut columns = Vec::new();
+    i += 1;
+    while let Some(column) = tokens.get(i).and_then(name) {
+        columns.push(column);
+        i += 1;
+        match tokens.get(i) {
+            Some(Token::Punct(',')) => i += 1,
+            Some(Token::Punct(')')) => break,
+            _ => return i,
+        }
+    }
+    i += 1;
+    if !tokens.get(i).is_some_and(|it| is_keyword(it, "VALUES")) {
+        return i;
+    }
+    i += 1;
+    // Each row of values, whose items are only matched if they are a parameter
+    while tokens.get(i) == Some(&Token::Punct('(')) {
+        let (mut depth, mut item, mut start) = (0, 0, i + 1);
+        i += 1;
+        while let Some(token) = tokens.get(i) {
+            match token {
+                Token::Punct('(') => depth += 1,
+                Token::Punct(')') if depth > 0 => depth -= 1,
+                Token::Punct(',' | ')') if depth == 0 => {
+                    if let (Token::Param(param, _), Some(column)) =
+                        (&tokens[start], columns.get(item))
+                    {
+                        if start + 1 == i {
+                            written.push((*param, table.to_string(), column.clone()));
+                        }
+                    }
+                    item += 1;
+                    start = i + 1;
+                    if token == &Token::Punct(')') {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        i += 1;
+        if tokens.get(i) != Some(&Token::Punct(',')) {
+            break;
+        }
+        i += 1;
+    }
+    i
+}
@@ -0,0 +1,111 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::{
+    codegen::GenCtx,
+    prepare_queries::{Preparation, PreparedContent},
+    type_registrar::custom_ty_path,
+};
+
+/// Renders every custom type registered while preparing queries, sorted by PostgreSQL
+/// name. Each type starts an unindented line with its kind, PostgreSQL name and Rust
+/// path, followed by one indented line per variant or field.
+pub(crate) fn dump_types(preparation: &Preparation) -> String {
+    let ctx = GenCtx::new(0, false, false);
+    let mut entries = BTreeMap::new();
+    for (schema, types) in &preparation.types {
+        for ty in types {
+            let path = custom_ty_path(schema, &ty.struct_name, &ctx);
+            let mut entry = String::new();
+            match &ty.content {
+                PreparedContent::Enum(variants) => {
+                    writeln!(entry, "enum {schema}.{} -> {path}", ty.name).unwrap();
+                    for variant in variants {
+                        writeln!(entry, "    {} -> {}", variant.db, variant.rs).unwrap();
+                    }
+                }
+                PreparedContent::Composite(fields) => {
+                    writeln!(entry, "composite {schema}.{} -> {path}", ty.name).unwrap();
+                    for field in fields {
+                        writeln!(entry, "    {}: {}", field.ident.db, field.own_struct(&ctx))
+                            .unwrap();
+                    }
+                }
+            }
+            entries.insert(format!("{schema}.{}", ty.name), entry);
+        }
+    }
+    let mut out = String::from("# Custom types registered by `cornucopia`\n");
+    for entry in entries.values() {
+        out.push_str(entry);
+    }
+    out
+}
+
+/// Splits a dump into its entries, keyed by PostgreSQL type name.
+fn parse_dump(dump: &str) -> BTreeMap<&str, (&str, Vec<&str>)> {
+    let mut entries = BTreeMap::new();
+    let mut current: Option<(&str, (&str, Vec<&str>))> = None;
+    for line in dump.lines().filter(|line| !line.starts_with('#')) {
+        if let Some(inner) = line.strip_prefix("    ") {
+            if let Some((_, (_, lines))) = &mut current {
+                lines.push(inner);
+            }
+        } else if !line.trim().is_empty() {
+            entries.extend(current.take());
+            let name = line.split_whitespace().nth(1).unwrap_or(line);
+            current = Some((name, (line, Vec::new())));
+        }
+    }
+    entries.extend(current);
+    entries
+}
+
+/// Lists the differences between a `previous` and a `current` dump, one line per added
+/// (`+`), removed (`-`) or changed (`~`) type, followed by its changed lines.
+pub(crate) fn diff_types(previous: &str, current: &str) -> Vec<String> {
+    let previous = parse_dump(previous);
+    let current = parse_dump(current);
+    let mut changes = Vec::new();
+    for (name, (header, _)) in &previous {
+        if !current.contains_key(name) {
+            changes.push(format!("- {header}"));
+        }
+    }
+    for (name, (header, lines)) in &current {
+        match previous.get(name) {
+            None => changes.push(format!("+ {header}")),
+            Some((old_header, old_lines)) if old_header != header || old_lines != lines => {
+                changes.push(format!("~ {header}"));
+                if old_header != header {
+                    changes.push(format!("    - {old_header}"));
+                }
+                for line in old_lines.iter().filter(|line| !lines.contains(line)) {
+                    changes.push(format!("    - {line}"));
+                }
+                for line in lines.iter().filter(|line| !old_lines.contains(line)) {
+                    changes.push(format!("    + {line}"));
+                }
+            }
+            Some(_) => {}
+        }
+    }
+    changes
+}
+
+pub(crate) mod error {
+    use std::path::PathBuf;
+
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    #[error("Couldn't read previous type dump `{path}`: ({err})")]
+    #[diagnostic(
+        code(cornucopia::type_dump),
+        help("pass a file written by a previous `cornucopia types dump`")
+    )]
+    pub struct Error {
+        pub(crate) path: PathBuf,
+        pub(crate) err: std::io::Error,
+    }
+}
@@ -6,11 +6,16 @@ use postgres_types::{Kind, Type};
 
 use crate::{
     codegen::{idx_char, GenCtx},
-    parser::Span,
+    parser::{Span, TypeOverride},
     read_queries::ModuleInfo,
     utils::SchemaKey,
+    DecimalCrate, TimeCrate,
 };
 
+/// Owned type of `numeric` values mapped to bigdecimal, read and written through the
+/// client's `Numeric` wrapper
+pub(crate) const BIG_DECIMAL: &str = "bigdecimal::BigDecimal";
+
 use self::error::Error;
 
 /// A struct containing a postgres type and its Rust-equivalent.
@@ -28,46 +33,165 @@ pub(crate) enum CornucopiaType {
         pg_ty: Type,
         inner: Rc<CornucopiaType>,
     },
+    Range {
+        pg_ty: Type,
+        inner: Rc<CornucopiaType>,
+    },
     Custom {
         pg_ty: Type,
         struct_name: String,
         is_copy: bool,
         is_params: bool,
     },
+    /// A type mapped to an existing rust type by a `--: name as path` annotation
+    Override {
+        pg_ty: Type,
+        path: String,
+    },
+    /// A `bytea` field declared encrypted, read and written through the client's
+    /// `Encrypted` wrapper
+    Encrypted {
+        pg_ty: Type,
+    },
 }
 
 impl CornucopiaType {
     /// Is this type need a generic lifetime
     pub fn is_ref(&self) -> bool {
         match self {
-            CornucopiaType::Simple { pg_ty, .. } => match *pg_ty {
+            CornucopiaType::Simple {
+                pg_ty, rust_name, ..
+            } => match *pg_ty {
                 Type::BYTEA | Type::TEXT | Type::VARCHAR | Type::JSON | Type::JSONB => false,
+                _ if *rust_name == BIG_DECIMAL => false,
                 _ => !self.is_copy(),
             },
-            CornucopiaType::Domain { inner, .. } | CornucopiaType::Array { inner } => {
-                inner.is_ref()
-            }
+            CornucopiaType::Domain { inner, .. }
+            | CornucopiaType::Array { inner }
+            | CornucopiaType::Range { inner, .. } => inner.is_ref(),
+            CornucopiaType::Override { .. } | CornucopiaType::Encrypted { .. } => false,
             _ => !self.is_copy(),
         }
     }
 
+    /// Can this be written as a single CSV field
+    pub fn is_csv_scalar(&self) -> bool {
+        match self {
+            // `time` types serialize as tuples, spanning several fields
+            CornucopiaType::Simple { pg_ty, .. } => !matches!(
+                *pg_ty,
+                Type::BYTEA
+                    | Type::JSON
+                    | Type::JSONB
+                    | Type::TIMESTAMP
+                    | Type::TIMESTAMPTZ
+                    | Type::DATE
+                    | Type::TIME
+            ),
+            CornucopiaType::Array { .. }
+            | CornucopiaType::Range { .. }
+            | CornucopiaType::Override { .. }
+            | CornucopiaType::Encrypted { .. } => false,
+            CornucopiaType::Domain { inner, .. } => inner.is_csv_scalar(),
+            CornucopiaType::Custom { pg_ty, .. } => matches!(pg_ty.kind(), Kind::Enum(_)),
+        }
+    }
+
+    /// Schema and name of the custom type this type is built from, if any
+    pub fn custom_dependency(&self) -> Option<(&str, &str)> {
+        match self {
+            CornucopiaType::Simple { .. }
+            | CornucopiaType::Override { .. }
+            | CornucopiaType::Encrypted { .. } => None,
+            CornucopiaType::Array { inner }
+            | CornucopiaType::Domain { inner, .. }
+            | CornucopiaType::Range { inner, .. } => inner.custom_dependency(),
+            CornucopiaType::Custom { pg_ty, .. } => Some((pg_ty.schema(), pg_ty.name())),
+        }
+    }
+
+    /// Does this type have a sqlx equivalent without extra sqlx features
+    pub fn is_sqlx_compatible(&self) -> bool {
+        fn is_sqlx_type(ty: &Type) -> bool {
+            match ty.kind() {
+                Kind::Enum(_) => true,
+                Kind::Composite(fields) => fields.iter().all(|field| is_sqlx_type(field.type_())),
+                Kind::Array(inner) => is_sqlx_type(inner),
+                Kind::Simple => matches!(
+                    *ty,
+                    Type::BOOL
+                        | Type::CHAR
+                        | Type::INT2
+                        | Type::INT4
+                        | Type::INT8
+                        | Type::FLOAT4
+                        | Type::FLOAT8
+                        | Type::TEXT
+                        | Type::VARCHAR
+                        | Type::BYTEA
+                ),
+                _ => false,
+            }
+        }
+        match self {
+            CornucopiaType::Array { inner } => inner.is_sqlx_compatible(),
+            CornucopiaType::Override { .. } | CornucopiaType::Encrypted { .. } => false,
+            CornucopiaType::Simple { pg_ty, .. }
+            | CornucopiaType::Custom { pg_ty, .. }
+            | CornucopiaType::Domain { pg_ty, .. }
+            | CornucopiaType::Range { pg_ty, .. } => is_sqlx_type(pg_ty),
+        }
+    }
+
+    /// Arrow array and data type names of this type, if it has a direct Arrow equivalent
+    pub fn arrow_ty(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            CornucopiaType::Simple { pg_ty, .. } => Some(match *pg_ty {
+                Type::BOOL => ("BooleanArray", "Boolean"),
+                Type::CHAR => ("Int8Array", "Int8"),
+                Type::INT2 => ("Int16Array", "Int16"),
+                Type::INT4 => ("Int32Array", "Int32"),
+                Type::INT8 => ("Int64Array", "Int64"),
+                Type::FLOAT4 => ("Float32Array", "Float32"),
+                Type::FLOAT8 => ("Float64Array", "Float64"),
+                Type::TEXT | Type::VARCHAR => ("StringArray", "Utf8"),
+                Type::BYTEA => ("BinaryArray", "Binary"),
+                _ => return None,
+            }),
+            CornucopiaType::Domain { inner, .. } => inner.arrow_ty(),
+            CornucopiaType::Array { .. }
+            | CornucopiaType::Range { .. }
+            | CornucopiaType::Custom { .. }
+            | CornucopiaType::Override { .. }
+            | CornucopiaType::Encrypted { .. } => None,
+        }
+    }
+
     /// Is this type copyable
     pub fn is_copy(&self) -> bool {
         match self {
             CornucopiaType::Simple { is_copy, .. } | CornucopiaType::Custom { is_copy, .. } => {
                 *is_copy
             }
-            CornucopiaType::Domain { inner, .. } => inner.is_copy(),
-            CornucopiaType::Array { .. } => false,
+            CornucopiaType::Domain { inner, .. } | CornucopiaType::Range { inner, .. } => {
+                inner.is_copy()
+            }
+            CornucopiaType::Array { .. }
+            | CornucopiaType::Override { .. }
+            | CornucopiaType::Encrypted { .. } => false,
         }
     }
 
     /// Can this used in parameters as it is
     pub fn is_params(&self) -> bool {
         match self {
-            CornucopiaType::Simple { .. } => true,
+            CornucopiaType::Simple { .. }
+            | CornucopiaType::Override { .. }
+            | CornucopiaType::Encrypted { .. } => true,
             CornucopiaType::Array { .. } => false,
-            CornucopiaType::Domain { inner, .. } => inner.is_params(),
+            CornucopiaType::Domain { inner, .. } | CornucopiaType::Range { inner, .. } => {
+                inner.is_params()
+            }
             CornucopiaType::Custom { is_params, .. } => *is_params,
         }
     }
@@ -119,7 +243,10 @@ impl CornucopiaType {
         match self {
             CornucopiaType::Simple { pg_ty, .. }
             | CornucopiaType::Custom { pg_ty, .. }
-            | CornucopiaType::Domain { pg_ty, .. } => pg_ty,
+            | CornucopiaType::Domain { pg_ty, .. }
+            | CornucopiaType::Range { pg_ty, .. }
+            | CornucopiaType::Override { pg_ty, .. }
+            | CornucopiaType::Encrypted { pg_ty } => pg_ty,
             CornucopiaType::Array { inner } => inner.pg_ty(),
         }
     }
@@ -131,7 +258,13 @@ impl CornucopiaType {
         is_nullable: bool,
         is_inner_nullable: bool,
     ) -> String {
-        if self.is_copy() {
+        // Overriding types and encrypted values are decoded as owned values
+        if self.is_copy()
+            || matches!(
+                self,
+                CornucopiaType::Override { .. } | CornucopiaType::Encrypted { .. }
+            )
+        {
             return name.into();
         }
 
@@ -149,6 +282,10 @@ impl CornucopiaType {
                 format!("{name}.map(|v| {inner}).collect()")
             }
             CornucopiaType::Domain { inner, .. } => inner.owning_call(name, is_nullable, false),
+            CornucopiaType::Range { inner, .. } => {
+                let inner = inner.owning_call("v", false, false);
+                format!("{name}.map(|v| {inner})")
+            }
             _ => {
                 format!("{name}.into()")
             }
@@ -168,9 +305,17 @@ impl CornucopiaType {
                 }
             }
             CornucopiaType::Domain { inner, .. } => inner.own_ty(false, ctx),
+            CornucopiaType::Range { inner, .. } => {
+                let client_name = ctx.client_name();
+                format!("{client_name}::Range<{}>", inner.own_ty(false, ctx))
+            }
             CornucopiaType::Custom {
                 struct_name, pg_ty, ..
             } => custom_ty_path(pg_ty.schema(), struct_name, ctx),
+            CornucopiaType::Override { path, .. } => path.clone(),
+            CornucopiaType::Encrypted { .. } => {
+                format!("{}::encryption::Encrypted", ctx.client_name())
+            }
         }
     }
 
@@ -211,7 +356,38 @@ impl CornucopiaType {
             CornucopiaType::Domain { inner, .. } => {
                 inner.param_ergo_ty(is_inner_nullable, traits, ctx)
             }
-            CornucopiaType::Custom { .. } => self.param_ty(is_inner_nullable, ctx),
+            CornucopiaType::Range { .. }
+            | CornucopiaType::Custom { .. }
+            | CornucopiaType::Override { .. }
+            | CornucopiaType::Encrypted { .. } => self.param_ty(is_inner_nullable, ctx),
+        }
+    }
+
+    /// Owned types instantiating the generics of `param_ergo_ty`, in the same order
+    pub(crate) fn own_generics(
+        &self,
+        is_inner_nullable: bool,
+        generics: &mut Vec<String>,
+        ctx: &GenCtx,
+    ) {
+        match self {
+            CornucopiaType::Simple { pg_ty, .. } => match *pg_ty {
+                Type::BYTEA => generics.push("Vec<u8>".to_string()),
+                Type::TEXT | Type::VARCHAR => generics.push("String".to_string()),
+                Type::JSON | Type::JSONB => generics.push("serde_json::Value".to_string()),
+                _ => {}
+            },
+            CornucopiaType::Array { inner, .. } => {
+                inner.own_generics(is_inner_nullable, generics, ctx);
+                generics.push(self.own_ty(is_inner_nullable, ctx));
+            }
+            CornucopiaType::Domain { inner, .. } => {
+                inner.own_generics(is_inner_nullable, generics, ctx)
+            }
+            CornucopiaType::Range { .. }
+            | CornucopiaType::Custom { .. }
+            | CornucopiaType::Override { .. }
+            | CornucopiaType::Encrypted { .. } => {}
         }
     }
 
@@ -233,6 +409,10 @@ impl CornucopiaType {
                 format!("&'a [{inner}]")
             }
             CornucopiaType::Domain { inner, .. } => inner.param_ty(false, ctx),
+            CornucopiaType::Range { inner, .. } => {
+                let client_name = ctx.client_name();
+                format!("{client_name}::Range<{}>", inner.param_ty(false, ctx))
+            }
             CornucopiaType::Custom {
                 is_params,
                 is_copy,
@@ -247,6 +427,10 @@ impl CornucopiaType {
                     self.brw_ty(is_inner_nullable, true, ctx)
                 }
             }
+            CornucopiaType::Override { path, .. } => path.clone(),
+            CornucopiaType::Encrypted { .. } => {
+                format!("{}::encryption::Encrypted", ctx.client_name())
+            }
         }
     }
 
@@ -268,6 +452,7 @@ impl CornucopiaType {
                 Type::JSON | Type::JSONB => {
                     format!("postgres_types::Json<&{lifetime} serde_json::value::RawValue>")
                 }
+                _ if *rust_name == BIG_DECIMAL => format!("{}::Numeric", ctx.client_name()),
                 _ => (*rust_name).to_string(),
             },
             CornucopiaType::Array { inner, .. } => {
@@ -283,6 +468,11 @@ impl CornucopiaType {
                 format!("{client_name}::ArrayIterator<{lifetime}, {inner}>")
             }
             CornucopiaType::Domain { inner, .. } => inner.brw_ty(false, has_lifetime, ctx),
+            CornucopiaType::Range { inner, .. } => {
+                let client_name = ctx.client_name();
+                let inner = inner.brw_ty(false, has_lifetime, ctx);
+                format!("{client_name}::Range<{inner}>")
+            }
             CornucopiaType::Custom {
                 is_copy,
                 pg_ty,
@@ -296,6 +486,10 @@ impl CornucopiaType {
                     format!("{path}Borrowed<{lifetime}>")
                 }
             }
+            CornucopiaType::Override { path, .. } => path.clone(),
+            CornucopiaType::Encrypted { .. } => {
+                format!("{}::encryption::Encrypted", ctx.client_name())
+            }
         }
     }
 }
@@ -314,6 +508,12 @@ pub fn custom_ty_path(schema: &str, struct_name: &str, ctx: &GenCtx) -> String {
 #[derive(Debug, Clone, Default)]
 pub(crate) struct TypeRegistrar {
     pub types: IndexMap<(String, String), Rc<CornucopiaType>>,
+    /// Types mapped to existing rust types, by every module
+    pub overrides: Vec<TypeOverride>,
+    /// Crate the date and time types are mapped to
+    pub time_crate: TimeCrate,
+    /// Crate the `numeric` type is mapped to
+    pub decimal_crate: DecimalCrate,
 }
 
 impl TypeRegistrar {
@@ -344,6 +544,22 @@ impl TypeRegistrar {
         if let Some(idx) = self.types.get_index_of(&SchemaKey::from(ty)) {
             return Ok(&self.types[idx]);
         }
+        if let Some(it) = self.overrides.iter().find(|it| it.matches(ty)) {
+            let path = it.path.value.clone();
+            return Ok(self.insert(ty, || CornucopiaType::Override {
+                pg_ty: ty.clone(),
+                path: path.clone(),
+            }));
+        }
+
+        // The fields of anonymous records are only described by each row's data
+        if *ty == Type::RECORD {
+            return Err(Error::AnonymousRecord {
+                src: module_info.clone().into(),
+                query: query_name.span,
+                col_name: name.to_string(),
+            });
+        }
 
         Ok(match ty.kind() {
             Kind::Enum(_) => self.insert(ty, || custom(ty, true, true)),
@@ -361,6 +577,15 @@ impl TypeRegistrar {
                     .clone();
                 self.insert(ty, || domain(ty, inner.clone()))
             }
+            Kind::Range(inner_ty) => {
+                let inner = self
+                    .register(name, inner_ty, query_name, module_info)?
+                    .clone();
+                self.insert(ty, || CornucopiaType::Range {
+                    pg_ty: ty.clone(),
+                    inner: inner.clone(),
+                })
+            }
             Kind::Composite(composite_fields) => {
                 let mut is_copy = true;
                 let mut is_params = true;
@@ -372,6 +597,7 @@ impl TypeRegistrar {
                 self.insert(ty, || custom(ty, is_copy, is_params))
             }
             Kind::Simple => {
+                let chrono = self.time_crate == TimeCrate::Chrono;
                 let (rust_name, is_copy) = match *ty {
                     Type::BOOL => ("bool", true),
                     Type::CHAR => ("i8", true),
@@ -382,6 +608,10 @@ impl TypeRegistrar {
                     Type::FLOAT8 => ("f64", true),
                     Type::TEXT | Type::VARCHAR => ("String", false),
                     Type::BYTEA => ("Vec<u8>", false),
+                    Type::TIMESTAMP if chrono => ("chrono::NaiveDateTime", true),
+                    Type::TIMESTAMPTZ if chrono => ("chrono::DateTime<chrono::Utc>", true),
+                    Type::DATE if chrono => ("chrono::NaiveDate", true),
+                    Type::TIME if chrono => ("chrono::NaiveTime", true),
                     Type::TIMESTAMP => ("time::PrimitiveDateTime", true),
                     Type::TIMESTAMPTZ => ("time::OffsetDateTime", true),
                     Type::DATE => ("time::Date", true),
@@ -390,6 +620,9 @@ impl TypeRegistrar {
                     Type::UUID => ("uuid::Uuid", true),
                     Type::INET => ("std::net::IpAddr", true),
                     Type::MACADDR => ("eui48::MacAddress", true),
+                    Type::NUMERIC if self.decimal_crate == DecimalCrate::Bigdecimal => {
+                        (BIG_DECIMAL, false)
+                    }
                     Type::NUMERIC => ("rust_decimal::Decimal", true),
                     _ => {
                         return Err(Error::UnsupportedPostgresType {
@@ -455,7 +688,17 @@ pub(crate) mod error {
     #[derive(Debug, ThisError, Diagnostic)]
     #[error("Couldn't register SQL type.")]
     pub enum Error {
+        #[diagnostic(
+            code(cornucopia::type_registrar::db),
+            help(
+                "check that the database is still reachable and that your user can read `pg_type`"
+            )
+        )]
         Db(#[from] postgres::Error),
+        #[diagnostic(
+            code(cornucopia::type_registrar::unsupported_postgres_type),
+            help("cast the column to a supported type in your query, e.g. `col::text`")
+        )]
         UnsupportedPostgresType {
             #[source_code]
             src: NamedSource,
@@ -464,5 +707,16 @@ pub(crate) mod error {
             col_name: String,
             col_ty: String,
         },
+        #[diagnostic(
+            code(cornucopia::type_registrar::anonymous_record),
+            help("declare a composite type with `CREATE TYPE` and cast the record to it, e.g. `ROW(a, b)::my_type`, or select its fields as separate columns")
+        )]
+        AnonymousRecord {
+            #[source_code]
+            src: NamedSource,
+            #[label("this query contains an anonymous record whose fields can't be known before execution (name: {col_name})")]
+            query: SourceSpan,
+            col_name: String,
+        },
     }
 }
@@ -1,3 +1,5 @@
+use std::{fs::OpenOptions, io::Write, path::Path};
+
 use indexmap::Equivalent;
 use postgres::error::ErrorPosition;
 use postgres_types::Type;
@@ -33,6 +35,25 @@ pub fn find_duplicate<T>(slice: &[T], eq: fn(&T, &T) -> bool) -> Option<(&T, &T)
     None
 }
 
+/// Appends an annotated query to a query file, separating it from existing queries.
+pub(crate) fn append_query(path: &Path, query: &str) -> std::io::Result<()> {
+    let existing = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    let separator = match existing.as_str() {
+        "" => "",
+        s if s.ends_with('\n') => "\n",
+        _ => "\n\n",
+    };
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| write!(file, "{separator}{query}"))
+}
+
 /// Extracts useful info from a `postgres`-generated error.
 pub(crate) fn db_err(err: &postgres::Error) -> Option<(u32, String, Option<String>)> {
     if let Some(db_err) = err.as_db_error() {
@@ -1,10 +1,16 @@
 use std::collections::BTreeMap;
 
 use crate::{
-    parser::{Module, NullableIdent, Query, QueryDataStruct, Span, TypeAnnotation},
-    prepare_queries::{PreparedField, PreparedModule},
+    parser::{
+        CdcAnnotation, ChannelAnnotation, ChannelPayload, CopySql, ListParam, Module,
+        NullableIdent, Query, QueryDataStruct, SchemaAnnotation, ScriptAnnotation, Span,
+        TypeAnnotation, TypeOverride,
+    },
+    prepare_queries::{PreparedField, PreparedModule, PreparedScript},
     read_queries::ModuleInfo,
+    type_registrar::CornucopiaType,
     utils::{find_duplicate, STRICT_KEYWORD},
+    CodegenSettings,
 };
 
 use error::Error;
@@ -55,6 +61,25 @@ pub(crate) fn query_name_already_used(
     })
 }
 
+/// With the facade enabled, a query named `new` would clash with the constructor of `Queries`.
+pub(crate) fn facade_query_name(
+    info: &ModuleInfo,
+    queries: &[Query],
+    settings: &CodegenSettings,
+) -> Result<(), Box<Error>> {
+    if !settings.gen_facade {
+        return Ok(());
+    }
+    match queries.iter().find(|query| query.name.value == "new") {
+        Some(query) => Err(Box::new(Error::FacadeQueryName {
+            src: info.into(),
+            name: query.name.value.clone(),
+            pos: query.name.span,
+        })),
+        None => Ok(()),
+    }
+}
+
 pub(crate) fn named_type_already_used(
     info: &ModuleInfo,
     types: &[TypeAnnotation],
@@ -70,6 +95,230 @@ pub(crate) fn named_type_already_used(
     })
 }
 
+pub(crate) fn type_override_already_used(
+    info: &ModuleInfo,
+    overrides: &[TypeOverride],
+) -> Result<(), Box<Error>> {
+    find_duplicate(overrides, |a, b| a.name == b.name).map_or(Ok(()), |(first, second)| {
+        Err(Box::new(Error::DuplicateType {
+            src: info.into(),
+            ty: "type override",
+            name: first.name.value.clone(),
+            first: first.name.span,
+            second: second.name.span,
+        }))
+    })
+}
+
+/// Checks that a type override maps to a valid rust type.
+fn type_override_path(info: &ModuleInfo, it: &TypeOverride) -> Result<(), Box<Error>> {
+    if syn::parse_str::<syn::Type>(&it.path.value).is_err() {
+        return Err(Box::new(Error::InvalidRustType {
+            src: info.into(),
+            ty: it.path.value.clone(),
+            pos: it.path.span,
+            help: "types are overridden by the path of an existing rust type, e.g. `crate::models::Thing`",
+        }));
+    }
+    Ok(())
+}
+
+pub(crate) fn unknown_type_override(info: &ModuleInfo, it: &TypeOverride) -> Box<Error> {
+    Box::new(Error::UnknownTypeOverride {
+        src: info.into(),
+        name: it.name.value.clone(),
+        pos: it.name.span,
+    })
+}
+
+pub(crate) fn conflicting_type_override(
+    info: &ModuleInfo,
+    previous: &TypeOverride,
+    it: &TypeOverride,
+) -> Box<Error> {
+    Box::new(Error::ConflictingTypeOverride {
+        src: info.into(),
+        name: it.name.value.clone(),
+        previous: previous.path.value.clone(),
+        pos: it.path.span,
+    })
+}
+
+pub(crate) fn channel_already_used(
+    info: &ModuleInfo,
+    channels: &[ChannelAnnotation],
+) -> Result<(), Box<Error>> {
+    find_duplicate(channels, |a, b| a.name == b.name).map_or(Ok(()), |(first, second)| {
+        Err(Box::new(Error::DuplicateType {
+            src: info.into(),
+            ty: "channel",
+            name: first.name.value.clone(),
+            first: first.name.span,
+            second: second.name.span,
+        }))
+    })
+}
+
+pub(crate) fn cdc_table_already_used(
+    info: &ModuleInfo,
+    tables: &[CdcAnnotation],
+) -> Result<(), Box<Error>> {
+    find_duplicate(tables, |a, b| a.name == b.name).map_or(Ok(()), |(first, second)| {
+        Err(Box::new(Error::DuplicateType {
+            src: info.into(),
+            ty: "CDC table",
+            name: first.name.value.clone(),
+            first: first.name.span,
+            second: second.name.span,
+        }))
+    })
+}
+
+pub(crate) fn script_already_used(
+    info: &ModuleInfo,
+    scripts: &[ScriptAnnotation],
+) -> Result<(), Box<Error>> {
+    find_duplicate(scripts, |a, b| a.name == b.name).map_or(Ok(()), |(first, second)| {
+        Err(Box::new(Error::DuplicateType {
+            src: info.into(),
+            ty: "script",
+            name: first.name.value.clone(),
+            first: first.name.span,
+            second: second.name.span,
+        }))
+    })
+}
+
+pub(crate) fn search_path_already_declared(
+    info: &ModuleInfo,
+    schemas: &[SchemaAnnotation],
+) -> Result<(), Box<Error>> {
+    match schemas {
+        [first, second, ..] => Err(Box::new(Error::DuplicateSearchPath {
+            src: info.into(),
+            first: first.span,
+            second: second.span,
+        })),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn script(
+    info: &ModuleInfo,
+    queries: &[Query],
+    script: &ScriptAnnotation,
+) -> Result<(), Box<Error>> {
+    if let Some((first, second)) = find_duplicate(&script.queries, |a, b| a == b) {
+        return Err(Box::new(Error::DuplicateType {
+            src: info.into(),
+            ty: "script query",
+            name: first.value.clone(),
+            first: first.span,
+            second: second.span,
+        }));
+    }
+    if let Some(unknown) = script
+        .queries
+        .iter()
+        .find(|name| !queries.iter().any(|query| query.name == **name))
+    {
+        return Err(Box::new(Error::UnknownScriptQuery {
+            src: info.into(),
+            name: unknown.value.clone(),
+            script: script.name.value.clone(),
+            pos: unknown.span,
+        }));
+    }
+    Ok(())
+}
+
+pub(crate) fn unknown_cdc_table(info: &ModuleInfo, table: &CdcAnnotation) -> Box<Error> {
+    Box::new(Error::UnknownCdcTable {
+        src: info.into(),
+        name: table.name.value.clone(),
+        pos: table.name.span,
+    })
+}
+
+pub(crate) fn unsupported_cdc_column(
+    info: &ModuleInfo,
+    table: &CdcAnnotation,
+    column: &str,
+    ty: &str,
+) -> Box<Error> {
+    Box::new(Error::UnsupportedCdcColumn {
+        src: info.into(),
+        column: column.to_string(),
+        ty: ty.to_string(),
+        pos: table.name.span,
+    })
+}
+
+/// Checks that every field of the named rows of `module` can be exported to CSV if
+/// `gen_csv` is set, and to Arrow if `gen_arrow` is set.
+pub(crate) fn export_columns(
+    module: &PreparedModule,
+    gen_csv: bool,
+    gen_arrow: bool,
+) -> Result<(), Box<Error>> {
+    for (origin, row) in module.rows.iter().filter(|(_, row)| row.is_named) {
+        for field in &row.fields {
+            let format = if gen_csv && !field.ty.is_csv_scalar() {
+                "CSV"
+            } else if gen_arrow && field.ty.arrow_ty().is_none() {
+                "Arrow"
+            } else {
+                continue;
+            };
+            return Err(Box::new(Error::UnsupportedExportColumn {
+                src: (&module.info).into(),
+                format,
+                row: row.name.value.clone(),
+                column: field.ident.db.clone(),
+                ty: match &*field.ty {
+                    CornucopiaType::Array { inner } => format!("{}[]", inner.pg_ty().name()),
+                    ty => ty.pg_ty().name().to_string(),
+                },
+                pos: origin.span,
+            }));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn channel_payload(
+    info: &ModuleInfo,
+    channel: &ChannelAnnotation,
+) -> Result<(), Box<Error>> {
+    let invalid_type = |ty: &Span<String>| {
+        if syn::parse_str::<syn::Type>(&ty.value).is_err() {
+            Err(Box::new(Error::InvalidRustType {
+                src: info.into(),
+                ty: ty.value.clone(),
+                pos: ty.span,
+                help: "notification payloads are described using rust types, e.g. `(id: i32, title?: String)`",
+            }))
+        } else {
+            Ok(())
+        }
+    };
+    match &channel.payload {
+        ChannelPayload::Path(path) => invalid_type(path),
+        ChannelPayload::Fields(fields) => {
+            if let Some((first, second)) = find_duplicate(fields, |a, b| a.name == b.name) {
+                return Err(Box::new(Error::DuplicateType {
+                    src: info.into(),
+                    ty: "payload field",
+                    name: first.name.value.clone(),
+                    first: first.name.span,
+                    second: second.name.span,
+                }));
+            }
+            fields.iter().try_for_each(|field| invalid_type(&field.ty))
+        }
+    }
+}
+
 pub(crate) fn inline_conflict_declared(
     info: &ModuleInfo,
     name: &Span<String>,
@@ -151,6 +400,256 @@ pub(crate) fn nullable_param_name(
     Ok(())
 }
 
+/// Checks that a field declared encrypted is stored as `bytea`, holding its ciphertext.
+pub(crate) fn encrypted_field(
+    info: &ModuleInfo,
+    nullable_col: &NullableIdent,
+    ty: &Type,
+) -> Result<(), Box<Error>> {
+    if nullable_col.encrypted && *ty != Type::BYTEA {
+        return Err(Box::new(Error::InvalidEncryptedField {
+            src: info.into(),
+            name: nullable_col.name.value.clone(),
+            pos: nullable_col.name.span,
+            ty: ty.name().to_string(),
+        }));
+    }
+    Ok(())
+}
+
+/// Checks that the parameters declared with `--! list` are bound by the query, only in
+/// `IN (:param)` lists.
+pub(crate) fn list_param_name(
+    info: &ModuleInfo,
+    list_param: &ListParam,
+    bind_params: &[Span<String>],
+) -> Result<(), Box<Error>> {
+    if !bind_params.contains(&list_param.name) {
+        return Err(Box::new(Error::UnknownFieldName {
+            src: info.into(),
+            pos: list_param.name.span,
+            known: bind_params
+                .iter()
+                .map(|it| it.value.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        }));
+    }
+    if !list_param.is_expanded {
+        return Err(Box::new(Error::ListParamUse {
+            src: info.into(),
+            name: list_param.name.value.clone(),
+            pos: list_param.name.span,
+        }));
+    }
+    Ok(())
+}
+
+/// Checks that a `--! copy` query can be run with `COPY`, which can't bind parameters.
+fn copy_query(info: &ModuleInfo, query: &Query) -> Result<(), Box<Error>> {
+    let Some(copy) = query.copy else {
+        return Ok(());
+    };
+    let reason = match Query::copy_sql(&query.sql_str, query.bind_params.len()) {
+        None => "but its values aren't its parameters in order",
+        Some(CopySql::Out(_)) if !query.bind_params.is_empty() => "but query has parameters",
+        Some(_) => return Ok(()),
+    };
+    Err(Box::new(Error::InvalidCopy {
+        src: info.into(),
+        name: query.name.value.clone(),
+        copy,
+        query: query.sql_span,
+        reason,
+    }))
+}
+
+/// Checks that the statements of a `--! multi_exec` query can be counted, which requires
+/// them to write data from a `WITH` clause whose statements aren't named after the
+/// command of the main statement, as the main statement is counted under that name.
+fn multi_exec_query(info: &ModuleInfo, query: &Query) -> Result<(), Box<Error>> {
+    let Some(multi_exec) = query.multi_exec else {
+        return Ok(());
+    };
+    let Some(sql) = Query::multi_exec_sql(&query.sql_str) else {
+        return Err(Box::new(Error::InvalidMultiExec {
+            src: info.into(),
+            name: query.name.value.clone(),
+            multi_exec,
+            query: query.sql_span,
+        }));
+    };
+    if sql.names.contains(&sql.command) {
+        return Err(Box::new(Error::MultiExecNameCollision {
+            src: info.into(),
+            name: query.name.value.clone(),
+            command: sql.command,
+            multi_exec,
+            query: query.sql_span,
+        }));
+    }
+    Ok(())
+}
+
+/// Checks that the `MERGE` statements counted by a `--! multi_exec` query can return rows,
+/// which PostgreSQL supports since version 17.
+pub(crate) fn multi_exec_merge(
+    info: &ModuleInfo,
+    query_name: &Span<String>,
+    multi_exec: SourceSpan,
+    query: &SourceSpan,
+    server_version_num: i32,
+) -> Result<(), Box<Error>> {
+    if server_version_num >= 170_000 {
+        return Ok(());
+    }
+    Err(Box::new(Error::MultiExecMerge {
+        src: info.into(),
+        name: query_name.value.clone(),
+        version: server_version_num / 10_000,
+        multi_exec,
+        query: *query,
+    }))
+}
+
+/// Checks that the options setting up the transaction of a query come with its isolation,
+/// as they are applied by the transaction `bind_isolated` runs, and don't contradict it.
+fn transaction_options(info: &ModuleInfo, query: &Query) -> Result<(), Box<Error>> {
+    let Some(transaction) = &query.transaction else {
+        let option = [
+            query.timeout.as_ref().map(|it| ("timeout", it.span)),
+            query.read_only.map(|it| ("read_only", it)),
+            query
+                .planner_settings
+                .first()
+                .map(|it| ("set", it.name.span)),
+        ]
+        .into_iter()
+        .flatten()
+        .min_by_key(|(_, span)| span.offset());
+        return match option {
+            Some((option, span)) => Err(Box::new(Error::TransactionOptionWithoutIsolation {
+                src: info.into(),
+                name: query.name.value.clone(),
+                option,
+                span,
+                query: query.sql_span,
+            })),
+            None => Ok(()),
+        };
+    };
+    let read_only = transaction
+        .modes
+        .iter()
+        .find(|it| it.value == "read_only")
+        .map(|it| it.span)
+        .or(query.read_only);
+    let modes = |name: &str| transaction.modes.iter().find(|it| it.value == name);
+    for (first, second) in [
+        (read_only, modes("read_write")),
+        (
+            modes("deferrable").map(|it| it.span),
+            modes("not_deferrable"),
+        ),
+    ] {
+        if let (Some(first), Some(second)) = (first, second) {
+            return Err(Box::new(Error::ConflictingTransactionModes {
+                src: info.into(),
+                name: query.name.value.clone(),
+                first,
+                second: second.span,
+                mode: second.value.clone(),
+            }));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that a query holding several statements can be run as a single batch, which
+/// can't bind parameters nor return rows.
+fn batch_query(info: &ModuleInfo, query: &Query) -> Result<(), Box<Error>> {
+    if !query.is_batch {
+        return Ok(());
+    }
+    let reason = if !query.bind_params.is_empty() || !query.param.is_empty() {
+        "but batch binds parameters"
+    } else if query.row.name.is_some() || query.row.idents.is_some() {
+        "but batch declares a row"
+    } else {
+        return Ok(());
+    };
+    Err(Box::new(Error::InvalidBatch {
+        src: info.into(),
+        name: query.name.value.clone(),
+        query: query.sql_span,
+        reason,
+    }))
+}
+
+/// Checks that a `--! copy` query copying rows out returns some.
+pub(crate) fn copy_out_rows(
+    info: &ModuleInfo,
+    name: &Span<String>,
+    copy: Option<SourceSpan>,
+    query: &SourceSpan,
+    columns: &[Column],
+) -> Result<(), Box<Error>> {
+    match copy {
+        Some(copy) if columns.is_empty() => Err(Box::new(Error::InvalidCopy {
+            src: info.into(),
+            name: name.value.clone(),
+            copy,
+            query: *query,
+            reason: "but query returns nothing",
+        })),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that a `--! snapshot` query returns rows, as it runs in a read-only transaction.
+pub(crate) fn snapshot_rows(
+    info: &ModuleInfo,
+    name: &Span<String>,
+    snapshot: Option<SourceSpan>,
+    query: &SourceSpan,
+    columns: &[Column],
+) -> Result<(), Box<Error>> {
+    match snapshot {
+        Some(snapshot) if columns.is_empty() => Err(Box::new(Error::InvalidSnapshot {
+            src: info.into(),
+            name: name.value.clone(),
+            snapshot,
+            query: *query,
+        })),
+        _ => Ok(()),
+    }
+}
+
+/// Finds the cursor column of a paginated query among the fields of its rows.
+pub(crate) fn cursor_column(
+    info: &ModuleInfo,
+    name: &Span<String>,
+    cursor: &Span<String>,
+    query: &SourceSpan,
+    fields: &[PreparedField],
+) -> Result<usize, Box<Error>> {
+    let reason = match fields.iter().position(|it| it.ident.db == cursor.value) {
+        None => "but query returns no such column",
+        Some(idx) if fields[idx].is_nullable => "but this column is nullable",
+        Some(idx) if !matches!(*fields[idx].ty, CornucopiaType::Simple { .. }) => {
+            "but this column isn't of a built-in scalar type"
+        }
+        Some(idx) => return Ok(idx),
+    };
+    Err(Box::new(Error::InvalidCursor {
+        src: info.into(),
+        name: name.value.clone(),
+        cursor: cursor.span,
+        query: *query,
+        reason,
+    }))
+}
+
 pub(crate) fn row_on_execute(
     info: &ModuleInfo,
     name: &Span<String>,
@@ -278,14 +777,14 @@ pub(crate) fn validate_preparation(module: &PreparedModule) -> Result<(), Box<Er
             } else {
                 ((span, ty), prev)
             };
-            Err(Error::DuplicateName {
+            Err(Box::new(Error::DuplicateName {
                 src: (&module.info).into(),
                 name,
                 first: first.0,
                 first_ty: first.1,
                 second: second.0,
                 second_ty: second.1,
-            })
+            }))
         } else {
             Ok(())
         }
@@ -322,22 +821,119 @@ pub(crate) fn validate_preparation(module: &PreparedModule) -> Result<(), Box<Er
             }
         }
     }
+    for script in &module.scripts {
+        reserved_type_keyword(&module.info, &script.name)?;
+        check_name(
+            format!("{}Output", script.ident.type_ident()),
+            script.name.span,
+            "script output",
+        )?;
+        if let Some((origin, _)) = module
+            .queries
+            .iter()
+            .find(|(_, query)| query.ident.rs == script.ident.rs)
+        {
+            return Err(Box::new(Error::DuplicateName {
+                src: (&module.info).into(),
+                name: script.ident.rs.clone(),
+                first_ty: "query",
+                first: origin.span,
+                second_ty: "script",
+                second: script.name.span,
+            }));
+        }
+        script_params(module, script)?;
+    }
     Ok(())
 }
 
+/// Parameters with the same name are shared by a script's queries, so they must match.
+fn script_params(module: &PreparedModule, script: &PreparedScript) -> Result<(), Box<Error>> {
+    let mut declared: BTreeMap<&str, (&PreparedField, SourceSpan)> = BTreeMap::new();
+    for idx in &script.queries {
+        let Some((param, _)) = &module.queries[idx.value].param else {
+            continue;
+        };
+        for field in &module.params[*param].fields {
+            match declared.get(field.ident.db.as_str()) {
+                Some((prev, _)) if *prev == field => {}
+                Some((_, first)) => {
+                    return Err(Box::new(Error::ScriptParamConflict {
+                        src: (&module.info).into(),
+                        name: field.ident.db.clone(),
+                        script: script.name.value.clone(),
+                        first: *first,
+                        second: idx.span,
+                    }))
+                }
+                None => {
+                    declared.insert(&field.ident.db, (field, idx.span));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Largest `--! max_concurrency` tokio's semaphores allow on every target, 32-bit ones
+/// included.
+const MAX_CONCURRENCY: usize = u32::MAX as usize >> 3;
+
+fn max_concurrency(info: &ModuleInfo, query: &Query) -> Result<(), Box<Error>> {
+    match &query.max_concurrency {
+        Some(max) if !(1..=MAX_CONCURRENCY).contains(&max.value) => {
+            Err(Box::new(Error::InvalidMaxConcurrency {
+                src: info.into(),
+                max: MAX_CONCURRENCY,
+                pos: max.span,
+            }))
+        }
+        _ => Ok(()),
+    }
+}
+
 pub(crate) fn validate_module(
     Module {
         info,
         types,
+        type_overrides,
         queries,
+        channels,
+        cdc_tables,
+        scripts,
+        schemas,
     }: &Module,
+    settings: &CodegenSettings,
 ) -> Result<(), Box<Error>> {
     query_name_already_used(info, queries)?;
+    facade_query_name(info, queries, settings)?;
+    search_path_already_declared(info, schemas)?;
     named_type_already_used(info, types)?;
+    type_override_already_used(info, type_overrides)?;
+    for it in type_overrides {
+        type_override_path(info, it)?;
+    }
+    channel_already_used(info, channels)?;
+    cdc_table_already_used(info, cdc_tables)?;
+    script_already_used(info, scripts)?;
+    for it in scripts {
+        script(info, queries, it)?;
+    }
+    for channel in channels {
+        channel_payload(info, channel)?;
+    }
     for ty in types {
         duplicate_nullable_ident(info, &ty.fields)?;
     }
     for query in queries {
+        for list_param in &query.list_params {
+            list_param_name(info, list_param, &query.bind_params)?;
+        }
+        copy_query(info, query)?;
+        multi_exec_query(info, query)?;
+        batch_query(info, query)?;
+        transaction_options(info, query)?;
+        max_concurrency(info, query)?;
         for (it, ty) in [(&query.param, "param"), (&query.row, "row")] {
             if let Some(idents) = &it.idents {
                 duplicate_nullable_ident(info, idents)?;
@@ -363,7 +959,10 @@ pub mod error {
     #[derive(Debug, ThisError, Diagnostic)]
     pub enum Error {
         #[error("column `{name}` appear multiple time")]
-        #[diagnostic(help("disambiguate column names in your SQL using an `AS` clause"))]
+        #[diagnostic(
+            code(cornucopia::validation::duplicate_sql_col_name),
+            help("disambiguate column names in your SQL using an `AS` clause")
+        )]
         DuplicateSqlColName {
             #[source_code]
             src: NamedSource,
@@ -372,7 +971,10 @@ pub mod error {
             pos: SourceSpan,
         },
         #[error("the field `{name}` is declared null multiple time")]
-        #[diagnostic(help("remove one of the two declaration"))]
+        #[diagnostic(
+            code(cornucopia::validation::duplicate_field_nullity),
+            help("remove one of the two declaration")
+        )]
         DuplicateFieldNullity {
             #[source_code]
             src: NamedSource,
@@ -383,7 +985,10 @@ pub mod error {
             second: SourceSpan,
         },
         #[error("the {ty} `{name}` is defined multiple time")]
-        #[diagnostic(help("use a different name for one of those"))]
+        #[diagnostic(
+            code(cornucopia::validation::duplicate_type),
+            help("use a different name for one of those")
+        )]
         DuplicateType {
             #[source_code]
             src: NamedSource,
@@ -394,8 +999,24 @@ pub mod error {
             #[label("redefined here")]
             second: SourceSpan,
         },
+        #[error("the search path of the module is declared multiple time")]
+        #[diagnostic(
+            code(cornucopia::validation::duplicate_search_path),
+            help("list every schema of the search path in a single `--@ schema` annotation")
+        )]
+        DuplicateSearchPath {
+            #[source_code]
+            src: NamedSource,
+            #[label("previous declaration here")]
+            first: SourceSpan,
+            #[label("redeclared here")]
+            second: SourceSpan,
+        },
         #[error("reference to an unknown named {ty} `{name}`")]
-        #[diagnostic(help("declare an inline named type using `()`: {name}()"))]
+        #[diagnostic(
+            code(cornucopia::validation::unknown_named_type),
+            help("declare an inline named type using `()`: {name}()")
+        )]
         UnknownNamedType {
             #[source_code]
             src: NamedSource,
@@ -405,7 +1026,10 @@ pub mod error {
             pos: SourceSpan,
         },
         #[error("unknown field")]
-        #[diagnostic(help("use one of those names: {known}"))]
+        #[diagnostic(
+            code(cornucopia::validation::unknown_field_name),
+            help("use one of those names: {known}")
+        )]
         UnknownFieldName {
             #[source_code]
             src: NamedSource,
@@ -413,8 +1037,36 @@ pub mod error {
             pos: SourceSpan,
             known: String,
         },
+        #[error("the list parameter `{name}` is used outside of an `IN` list")]
+        #[diagnostic(
+            code(cornucopia::validation::list_param_use),
+            help("only use it alone in `IN (:{name})` or `NOT IN (:{name})` lists")
+        )]
+        ListParamUse {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("declared as a list here")]
+            pos: SourceSpan,
+        },
+        #[error("the field `{name}` can't be encrypted")]
+        #[diagnostic(
+            code(cornucopia::validation::invalid_encrypted_field),
+            help("store encrypted fields in a `bytea` column")
+        )]
+        InvalidEncryptedField {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("this field is of type `{ty}`")]
+            pos: SourceSpan,
+            ty: String,
+        },
         #[error("conflicting uses of named type `{name}`")]
-        #[diagnostic(help("use a different named type for each query"))]
+        #[diagnostic(
+            code(cornucopia::validation::incompatible_named_type),
+            help("use a different named type for each query")
+        )]
         IncompatibleNamedType {
             #[source_code]
             src: NamedSource,
@@ -427,7 +1079,10 @@ pub mod error {
             second: SourceSpan,
         },
         #[error("the query `{name}` declare a row but return nothing")]
-        #[diagnostic(help("remove row declaration"))]
+        #[diagnostic(
+            code(cornucopia::validation::row_on_execute),
+            help("remove row declaration")
+        )]
         RowOnExecute {
             #[source_code]
             src: NamedSource,
@@ -437,8 +1092,142 @@ pub mod error {
             #[label("but query return nothing")]
             query: SourceSpan,
         },
+        #[error("the query `{name}` can't be run with `COPY`")]
+        #[diagnostic(
+            code(cornucopia::validation::invalid_copy),
+            help("copy rows in with `INSERT INTO table (a, b) VALUES (:a, :b)`, or out with a query without parameters")
+        )]
+        InvalidCopy {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("copy declared here")]
+            copy: SourceSpan,
+            #[label("{reason}")]
+            query: SourceSpan,
+            reason: &'static str,
+        },
+        #[error("the query `{name}` can't count the rows of each of its statements")]
+        #[diagnostic(
+            code(cornucopia::validation::invalid_multi_exec),
+            help("write data from statements of a `WITH` clause followed by an `INSERT`, `UPDATE`, `DELETE` or `MERGE`, whose counts are fields named after them")
+        )]
+        InvalidMultiExec {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("multi_exec declared here")]
+            multi_exec: SourceSpan,
+            #[label("but query isn't a `WITH` clause followed by a data-modifying statement")]
+            query: SourceSpan,
+        },
+        #[error("the query `{name}` can't count the rows of its main statement as `{command}`")]
+        #[diagnostic(
+            code(cornucopia::validation::multi_exec_name_collision),
+            help("rename the statement `{command}` of the `WITH` clause, as the main statement is counted under the name of its command")
+        )]
+        MultiExecNameCollision {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            command: String,
+            #[label("multi_exec declared here")]
+            multi_exec: SourceSpan,
+            #[label("but a statement of this `WITH` clause is already named `{command}`")]
+            query: SourceSpan,
+        },
+        #[error("the query `{name}` can't count the rows of its `MERGE` statements on PostgreSQL {version}")]
+        #[diagnostic(
+            code(cornucopia::validation::multi_exec_merge),
+            help("`MERGE` returns rows since PostgreSQL 17, upgrade the database or count the rows of an `INSERT`, `UPDATE` or `DELETE` instead")
+        )]
+        MultiExecMerge {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            version: i32,
+            #[label("multi_exec declared here")]
+            multi_exec: SourceSpan,
+            #[label("this query counts the rows of a `MERGE`")]
+            query: SourceSpan,
+        },
+        #[error("the query `{name}` can't run its statements as a batch")]
+        #[diagnostic(
+            code(cornucopia::validation::invalid_batch),
+            help("batches run in a single round trip without parameters nor rows, run such statements as separate queries of a `--&` script")
+        )]
+        InvalidBatch {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("{reason}")]
+            query: SourceSpan,
+            reason: &'static str,
+        },
+        #[error("the query `{name}` sets up its transaction without declaring its isolation")]
+        #[diagnostic(
+            code(cornucopia::validation::transaction_option_without_isolation),
+            help("these options set up the transaction run by `bind_isolated`, declare its isolation with `--! isolation read_committed`")
+        )]
+        TransactionOptionWithoutIsolation {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            option: &'static str,
+            #[label("{option} declared here")]
+            span: SourceSpan,
+            #[label("but query runs in the transaction of its caller")]
+            query: SourceSpan,
+        },
+        #[error("the query `{name}` declares contradicting transaction modes")]
+        #[diagnostic(
+            code(cornucopia::validation::conflicting_transaction_modes),
+            help("remove one of the modes")
+        )]
+        ConflictingTransactionModes {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("first declared here")]
+            first: SourceSpan,
+            mode: String,
+            #[label("but `{mode}` contradicts it")]
+            second: SourceSpan,
+        },
+        #[error("the query `{name}` can't read rows as of a snapshot")]
+        #[diagnostic(
+            code(cornucopia::validation::invalid_snapshot),
+            help("snapshot reads run in a read-only transaction, only annotate queries returning rows")
+        )]
+        InvalidSnapshot {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("snapshot declared here")]
+            snapshot: SourceSpan,
+            #[label("but query returns nothing")]
+            query: SourceSpan,
+        },
+        #[error("the query `{name}` can't be paginated on this column")]
+        #[diagnostic(
+            code(cornucopia::validation::invalid_cursor),
+            help("paginate on a non-nullable column of a built-in type that is unique, such as a primary key")
+        )]
+        InvalidCursor {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("cursor declared here")]
+            cursor: SourceSpan,
+            #[label("{reason}")]
+            query: SourceSpan,
+            reason: &'static str,
+        },
         #[error("the query `{name}` declares a parameter but has no binding")]
-        #[diagnostic(help("remove parameter declaration"))]
+        #[diagnostic(
+            code(cornucopia::validation::params_on_simple_query),
+            help("remove parameter declaration")
+        )]
         ParamsOnSimpleQuery {
             #[source_code]
             src: NamedSource,
@@ -449,7 +1238,10 @@ pub mod error {
             query: SourceSpan,
         },
         #[error("`{name}` is used multiple time")]
-        #[diagnostic(help("use a different name for one of those"))]
+        #[diagnostic(
+            code(cornucopia::validation::duplicate_name),
+            help("use a different name for one of those")
+        )]
         DuplicateName {
             #[source_code]
             src: NamedSource,
@@ -461,8 +1253,141 @@ pub mod error {
             #[label("redefined as {second_ty} here")]
             second: SourceSpan,
         },
+        #[error("`{ty}` is not a valid rust type")]
+        #[diagnostic(code(cornucopia::validation::invalid_rust_type))]
+        InvalidRustType {
+            #[source_code]
+            src: NamedSource,
+            ty: String,
+            #[label("invalid rust type")]
+            pos: SourceSpan,
+            #[help]
+            help: &'static str,
+        },
+        #[error("the type `{name}` doesn't exist")]
+        #[diagnostic(
+            code(cornucopia::validation::unknown_type_override),
+            help("only existing types can be overridden, qualify the type with its schema if it isn't in the search path")
+        )]
+        UnknownTypeOverride {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("unknown type")]
+            pos: SourceSpan,
+        },
+        #[error("the type `{name}` is already overridden by `{previous}` in another module")]
+        #[diagnostic(
+            code(cornucopia::validation::conflicting_type_override),
+            help("types are shared by every module, override this one with the same rust type everywhere")
+        )]
+        ConflictingTypeOverride {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            previous: String,
+            #[label("conflicting rust type")]
+            pos: SourceSpan,
+        },
+        #[error("the table `{name}` doesn't exist")]
+        #[diagnostic(
+            code(cornucopia::validation::unknown_cdc_table),
+            help("CDC decoders can only be generated for existing tables")
+        )]
+        UnknownCdcTable {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("unknown table")]
+            pos: SourceSpan,
+        },
+        #[error("the column `{column}` has type `{ty}`, which CDC decoders don't support")]
+        #[diagnostic(
+            code(cornucopia::validation::unsupported_cdc_column),
+            help("supported types are bool, int2, int4, int8, float4, float8, text, varchar, bpchar and name, whose wal2json values don't depend on the server's settings")
+        )]
+        UnsupportedCdcColumn {
+            #[source_code]
+            src: NamedSource,
+            column: String,
+            ty: String,
+            #[label("table declared here")]
+            pos: SourceSpan,
+        },
+        #[error("the concurrency limit must be between 1 and {max}")]
+        #[diagnostic(
+            code(cornucopia::validation::invalid_max_concurrency),
+            help("the limit is the number of permits of a tokio semaphore")
+        )]
+        InvalidMaxConcurrency {
+            #[source_code]
+            src: NamedSource,
+            max: usize,
+            #[label("out of range limit")]
+            pos: SourceSpan,
+        },
+        #[error("the column `{column}` of row `{row}` has type `{ty}`, which can't be exported to {format}")]
+        #[diagnostic(
+            code(cornucopia::validation::unsupported_export_column),
+            help("cast the column to a scalar type such as `text` in the query, or disable the {format} export")
+        )]
+        UnsupportedExportColumn {
+            #[source_code]
+            src: NamedSource,
+            format: &'static str,
+            row: String,
+            column: String,
+            ty: String,
+            #[label("row declared here")]
+            pos: SourceSpan,
+        },
+        #[error("the script `{script}` references an unknown query `{name}`")]
+        #[diagnostic(
+            code(cornucopia::validation::unknown_script_query),
+            help("scripts can only run queries declared in the same file")
+        )]
+        UnknownScriptQuery {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            script: String,
+            #[label("unknown query")]
+            pos: SourceSpan,
+        },
+        #[error("the queries of script `{script}` declare conflicting parameters `{name}`")]
+        #[diagnostic(
+            code(cornucopia::validation::script_param_conflict),
+            help(
+                "parameters with the same name are shared by a script's queries, rename one of them"
+            )
+        )]
+        ScriptParamConflict {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            script: String,
+            #[label("first declared by this query")]
+            first: SourceSpan,
+            #[label("then differently by this one")]
+            second: SourceSpan,
+        },
+        #[error("the query `{name}` clashes with the constructor of the `Queries` facade")]
+        #[diagnostic(
+            code(cornucopia::validation::facade_query_name),
+            help("use a different name or disable `--facade`")
+        )]
+        FacadeQueryName {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("query declared here")]
+            pos: SourceSpan,
+        },
         #[error("`{name}` is a reserved rust keyword that cannot be escaped")]
-        #[diagnostic(help("use a different name"))]
+        #[diagnostic(
+            code(cornucopia::validation::type_rust_keyword),
+            help("use a different name")
+        )]
         TypeRustKeyword {
             #[source_code]
             src: NamedSource,
@@ -471,7 +1396,10 @@ pub mod error {
             pos: SourceSpan,
         },
         #[error("`{name}` is a reserved rust keyword that cannot be escaped")]
-        #[diagnostic(help("use a different name"))]
+        #[diagnostic(
+            code(cornucopia::validation::name_rust_keyword),
+            help("use a different name")
+        )]
         NameRustKeyword {
             #[source_code]
             src: NamedSource,
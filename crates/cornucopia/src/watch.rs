@@ -0,0 +1,183 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::sleep,
+    time::{Duration, SystemTime},
+};
+
+use postgres::Client;
+
+use crate::{
+    compat_report, conn,
+    container::{self, ManagedContainer},
+    error::Error,
+    generate_live, load_schema, CodegenSettings,
+};
+
+use self::error::Error as WatchError;
+
+/// Delay between two scans of the watched files.
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Modification time of every file found under the watched paths.
+type Snapshot = BTreeMap<PathBuf, Option<SystemTime>>;
+
+/// Regenerates the code from the queries located at `queries_path` to `destination` every
+/// time one of them changes, using a live database managed by you. Errors are reported
+/// without stopping the watch, which runs until `stop` is raised.
+///
+/// If `previous_code` is given, breaking changes of the generated API are reported as
+/// errors.
+pub fn watch_live(
+    client: &mut Client,
+    queries_path: &Path,
+    destination: &Path,
+    settings: &CodegenSettings,
+    previous_code: Option<&str>,
+    stop: &AtomicBool,
+) -> Result<(), Error> {
+    watch(&[queries_path], stop, || {
+        generate_live(
+            &mut *client,
+            queries_path,
+            Some(destination),
+            settings.clone(),
+        )
+        .and_then(|code| report(previous_code, &code))
+    });
+    Ok(())
+}
+
+/// Regenerates the code from the queries located at `queries_path` every time one of
+/// them or one of the `schema_files` changes. The container managed by cornucopia is
+/// started once and kept alive between runs, its schema being recreated from scratch
+/// before each run. Runs until interrupted, then removes the container.
+pub(crate) fn watch_managed(
+    queries_path: &Path,
+    schema_files: &[PathBuf],
+    destination: &Path,
+    managed: &ManagedContainer,
+    app_url: Option<&str>,
+    settings: &CodegenSettings,
+    previous_code: Option<&str>,
+) -> Result<(), Error> {
+    let stop = stop_signal()?;
+    container::setup(managed)?;
+    let mut watched = vec![queries_path];
+    watched.extend(schema_files.iter().map(PathBuf::as_path));
+    let generate = || {
+        let mut client = conn::cornucopia_conn()?;
+        reset_schema(&mut client).map_err(WatchError::Reset)?;
+        load_schema(&mut client, schema_files)?;
+        let mut client = conn::app_conn(client, app_url)?;
+        generate_live(
+            &mut client,
+            queries_path,
+            Some(destination),
+            settings.clone(),
+        )
+        .and_then(|code| report(previous_code, &code))
+    };
+    watch(&watched, &stop, || {
+        generate().map_err(|err| container::with_logs(managed.podman, err))
+    });
+    container::cleanup(managed.podman)?;
+    Ok(())
+}
+
+/// Runs `generate` once, then again after each change of the `watched` paths, until
+/// `stop` is raised. Errors are reported without stopping the watch.
+fn watch(watched: &[&Path], stop: &AtomicBool, mut generate: impl FnMut() -> Result<(), Error>) {
+    let mut previous = snapshot(watched);
+    loop {
+        match generate() {
+            Ok(()) => eprintln!("Generated code is up to date"),
+            Err(e) => eprintln!("{:?}", miette::Report::new(e)),
+        }
+        eprintln!("Watching for changes, press Ctrl-C to stop...");
+        loop {
+            if stop.load(Ordering::Relaxed) {
+                return;
+            }
+            sleep(POLL_INTERVAL);
+            let current = snapshot(watched);
+            if current != previous {
+                previous = current;
+                break;
+            }
+        }
+    }
+}
+
+/// Fails with the breaking changes of the generated API compared to `previous_code`.
+fn report(previous_code: Option<&str>, code: &str) -> Result<(), Error> {
+    match previous_code {
+        Some(previous_code) => compat_report(previous_code, code),
+        None => Ok(()),
+    }
+}
+
+/// Raises the returned flag when the process receives Ctrl-C, instead of exiting.
+pub(crate) fn stop_signal() -> Result<Arc<AtomicBool>, WatchError> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let handler_stop = stop.clone();
+    ctrlc::set_handler(move || handler_stop.store(true, Ordering::Relaxed))?;
+    Ok(stop)
+}
+
+/// Lists every file under the `watched` paths, recursing into directories.
+fn snapshot(watched: &[&Path]) -> Snapshot {
+    let mut snapshot = Snapshot::new();
+    let mut pending: Vec<PathBuf> = watched.iter().map(|path| path.to_path_buf()).collect();
+    while let Some(path) = pending.pop() {
+        if let Ok(entries) = std::fs::read_dir(&path) {
+            pending.extend(entries.flatten().map(|entry| entry.path()));
+        } else {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+            snapshot.insert(path, modified);
+        }
+    }
+    snapshot
+}
+
+/// Drops every user schema, so that schema files can be loaded again.
+fn reset_schema(client: &mut Client) -> Result<(), postgres::Error> {
+    client.batch_execute(
+        "DO $$
+        DECLARE schema_name TEXT;
+        BEGIN
+            FOR schema_name IN SELECT nspname FROM pg_namespace
+                WHERE nspname NOT LIKE 'pg\\_%' AND nspname <> 'information_schema'
+            LOOP
+                EXECUTE format('DROP SCHEMA %I CASCADE', schema_name);
+            END LOOP;
+        END $$;
+        CREATE SCHEMA public AUTHORIZATION pg_database_owner;
+        GRANT USAGE ON SCHEMA public TO PUBLIC;",
+    )
+}
+
+pub(crate) mod error {
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("Couldn't listen for Ctrl-C: ({0})")]
+        #[diagnostic(
+            code(cornucopia::watch::signal),
+            help("another Ctrl-C handler may already be installed in this process")
+        )]
+        Signal(#[from] ctrlc::Error),
+        #[error("Couldn't reset the schema of the managed container: ({0})")]
+        #[diagnostic(
+            code(cornucopia::watch::reset),
+            help("the managed container may have been stopped, restart the watch")
+        )]
+        Reset(postgres::Error),
+    }
+}
@@ -66,6 +66,14 @@ pub mod queries {
                 Ok(it)
             }
         }
+        /// ```sql
+        /// SELECT
+        ///     *
+        /// FROM
+        ///     example_table
+        /// ```
+        ///
+        /// Source: `queries/module_1.sql:1`
         pub fn example_query() -> ExampleQueryStmt {
             ExampleQueryStmt(cornucopia_async::private::Stmt::new(
                 "SELECT
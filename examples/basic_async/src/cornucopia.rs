@@ -1,5 +1,86 @@
 // This file was generated with `cornucopia`. Do not modify.
 
+#[allow(clippy::all, clippy::pedantic)]
+#[allow(dead_code)]
+pub mod pool {
+    /// Whether TLS is used when connecting to the database.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum TlsMode {
+        Disable,
+        Prefer,
+        Require,
+    }
+    /// Whether SCRAM channel binding is used when authenticating.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ChannelBinding {
+        Disable,
+        Prefer,
+        Require,
+    }
+    /// Settings used by `create_pool` to build a connection pool.
+    #[derive(Debug, Clone)]
+    pub struct PoolSettings {
+        pub host: String,
+        pub port: u16,
+        pub user: String,
+        pub password: Option<String>,
+        pub dbname: String,
+        pub tls_mode: TlsMode,
+        pub channel_binding: ChannelBinding,
+        pub pool_size: usize,
+    }
+    impl Default for PoolSettings {
+        fn default() -> Self {
+            Self {
+                host: String::from("localhost"),
+                port: 5432,
+                user: String::from("postgres"),
+                password: None,
+                dbname: String::from("postgres"),
+                tls_mode: TlsMode::Prefer,
+                channel_binding: ChannelBinding::Prefer,
+                pool_size: 16,
+            }
+        }
+    }
+    /// Creates a pool of connections compatible with the generated queries.
+    ///
+    /// The `tls` connector is only used when `tls_mode` is not `TlsMode::Disable`,
+    /// in which case `tokio_postgres::NoTls` can be passed.
+    pub fn create_pool<T>(
+        settings: &PoolSettings,
+        tls: T,
+    ) -> Result<deadpool_postgres::Pool, deadpool_postgres::CreatePoolError>
+    where
+        T: tokio_postgres::tls::MakeTlsConnect<tokio_postgres::Socket>
+            + Clone
+            + Sync
+            + Send
+            + 'static,
+        T::Stream: Sync + Send,
+        T::TlsConnect: Sync + Send,
+        <T::TlsConnect as tokio_postgres::tls::TlsConnect<tokio_postgres::Socket>>::Future: Send,
+    {
+        let mut cfg = deadpool_postgres::Config::new();
+        cfg.host = Some(settings.host.clone());
+        cfg.port = Some(settings.port);
+        cfg.user = Some(settings.user.clone());
+        cfg.password = settings.password.clone();
+        cfg.dbname = Some(settings.dbname.clone());
+        cfg.ssl_mode = Some(match settings.tls_mode {
+            TlsMode::Disable => deadpool_postgres::SslMode::Disable,
+            TlsMode::Prefer => deadpool_postgres::SslMode::Prefer,
+            TlsMode::Require => deadpool_postgres::SslMode::Require,
+        });
+        cfg.channel_binding = Some(match settings.channel_binding {
+            ChannelBinding::Disable => deadpool_postgres::ChannelBinding::Disable,
+            ChannelBinding::Prefer => deadpool_postgres::ChannelBinding::Prefer,
+            ChannelBinding::Require => deadpool_postgres::ChannelBinding::Require,
+        });
+        cfg.pool = Some(deadpool_postgres::PoolConfig::new(settings.pool_size));
+        cfg.create_pool(Some(deadpool_postgres::Runtime::Tokio1), tls)
+    }
+}
 #[allow(clippy::all, clippy::pedantic)]
 #[allow(unused_variables)]
 #[allow(unused_imports)]
@@ -88,7 +169,7 @@ pub mod types {
                 }
             }
         }
-        #[derive(Debug, postgres_types :: FromSql, Clone, PartialEq)]
+        #[derive(Debug, postgres_types::FromSql, Clone, PartialEq)]
         #[postgres(name = "voiceactor")]
         pub struct Voiceactor {
             #[postgres(name = "name")]
@@ -212,6 +293,12 @@ pub mod queries {
         use cornucopia_async::GenericClient;
         use futures;
         use futures::{StreamExt, TryStreamExt};
+        /// ```sql
+        /// INSERT INTO Book (title)
+        ///   VALUES ($1)
+        /// ```
+        ///
+        /// Source: `queries/module_1.sql:1`
         pub fn insert_book() -> InsertBookStmt {
             InsertBookStmt(cornucopia_async::private::Stmt::new(
                 "INSERT INTO Book (title)
@@ -231,14 +318,18 @@ pub mod queries {
         }
     }
     pub mod module_2 {
+        /// Source: `queries/module_2.sql:21`
         #[derive(Debug)]
         pub struct AuthorNameStartingWithParams<T1: cornucopia_async::StringSql> {
             pub start_str: T1,
         }
+        /// Source: `queries/module_2.sql:1`
         #[derive(Debug, Clone, PartialEq)]
         pub struct Authors {
             pub id: i32,
+            /// At most 70 characters.
             pub name: String,
+            /// At most 100 characters.
             pub country: String,
         }
         pub struct AuthorsBorrowed<'a> {
@@ -255,11 +346,29 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_async::FromPgRow<'a> for AuthorsBorrowed<'a> {
+            fn from_row(row: &'a tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                Ok(Self {
+                    id: row.try_get("id")?,
+                    name: row.try_get("name")?,
+                    country: row.try_get("country")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_async::FromPgRow<'a> for Authors {
+            fn from_row(row: &'a tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                <AuthorsBorrowed<'a> as cornucopia_async::FromPgRow<'a>>::from_row(row)
+                    .map(Into::into)
+            }
+        }
+        /// Source: `queries/module_2.sql:21`
         #[derive(Debug, Clone, PartialEq)]
         pub struct AuthorNameStartingWith {
             pub authorid: i32,
+            /// At most 70 characters.
             pub name: String,
             pub bookid: i32,
+            /// At most 50 characters.
             pub title: String,
         }
         pub struct AuthorNameStartingWithBorrowed<'a> {
@@ -285,8 +394,28 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_async::FromPgRow<'a> for AuthorNameStartingWithBorrowed<'a> {
+            fn from_row(row: &'a tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                Ok(Self {
+                    authorid: row.try_get("authorid")?,
+                    name: row.try_get("name")?,
+                    bookid: row.try_get("bookid")?,
+                    title: row.try_get("title")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_async::FromPgRow<'a> for AuthorNameStartingWith {
+            fn from_row(row: &'a tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                <AuthorNameStartingWithBorrowed<'a> as cornucopia_async::FromPgRow<'a>>::from_row(
+                    row,
+                )
+                .map(Into::into)
+            }
+        }
+        /// Source: `queries/module_2.sql:42`
         #[derive(Debug, Clone, PartialEq)]
         pub struct SelectTranslations {
+            /// At most 50 characters.
             pub title: String,
             pub translations: Vec<String>,
         }
@@ -307,6 +436,20 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_async::FromPgRow<'a> for SelectTranslationsBorrowed<'a> {
+            fn from_row(row: &'a tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                Ok(Self {
+                    title: row.try_get("title")?,
+                    translations: row.try_get("translations")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_async::FromPgRow<'a> for SelectTranslations {
+            fn from_row(row: &'a tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                <SelectTranslationsBorrowed<'a> as cornucopia_async::FromPgRow<'a>>::from_row(row)
+                    .map(Into::into)
+            }
+        }
         use cornucopia_async::GenericClient;
         use futures;
         use futures::{StreamExt, TryStreamExt};
@@ -579,6 +722,14 @@ pub mod queries {
                 Ok(it)
             }
         }
+        /// ```sql
+        /// SELECT
+        ///     *
+        /// FROM
+        ///     Author
+        /// ```
+        ///
+        /// Source: `queries/module_2.sql:1`
         pub fn authors() -> AuthorsStmt {
             AuthorsStmt(cornucopia_async::private::Stmt::new(
                 "SELECT
@@ -606,6 +757,14 @@ FROM
                 }
             }
         }
+        /// ```sql
+        /// SELECT
+        ///     Title
+        /// FROM
+        ///     Book
+        /// ```
+        ///
+        /// Source: `queries/module_2.sql:7`
         pub fn books() -> BooksStmt {
             BooksStmt(cornucopia_async::private::Stmt::new(
                 "SELECT
@@ -629,6 +788,16 @@ FROM
                 }
             }
         }
+        /// ```sql
+        /// SELECT
+        ///     Author.Name
+        /// FROM
+        ///     Author
+        /// WHERE
+        ///     Author.Id = $1
+        /// ```
+        ///
+        /// Source: `queries/module_2.sql:13`
         pub fn author_name_by_id() -> AuthorNameByIdStmt {
             AuthorNameByIdStmt(cornucopia_async::private::Stmt::new(
                 "SELECT
@@ -655,6 +824,21 @@ WHERE
                 }
             }
         }
+        /// ```sql
+        /// SELECT
+        ///     BookAuthor.AuthorId,
+        ///     Author.Name,
+        ///     BookAuthor.BookId,
+        ///     Book.Title
+        /// FROM
+        ///     BookAuthor
+        ///     INNER JOIN Author ON Author.id = BookAuthor.AuthorId
+        ///     INNER JOIN Book ON Book.Id = BookAuthor.BookId
+        /// WHERE
+        ///     Author.Name LIKE CONCAT($1::text, '%')
+        /// ```
+        ///
+        /// Source: `queries/module_2.sql:21`
         pub fn author_name_starting_with() -> AuthorNameStartingWithStmt {
             AuthorNameStartingWithStmt(cornucopia_async::private::Stmt::new(
                 "SELECT
@@ -707,6 +891,16 @@ WHERE
                 self.bind(client, &params.start_str)
             }
         }
+        /// ```sql
+        /// SELECT
+        ///     voice_actor
+        /// FROM
+        ///     SpongeBobVoiceActor
+        /// WHERE
+        ///     character = $1
+        /// ```
+        ///
+        /// Source: `queries/module_2.sql:34`
         pub fn select_voice_actor_with_character() -> SelectVoiceActorWithCharacterStmt {
             SelectVoiceActorWithCharacterStmt(cornucopia_async::private::Stmt::new(
                 "SELECT
@@ -734,6 +928,15 @@ WHERE
                 }
             }
         }
+        /// ```sql
+        /// SELECT
+        ///     Title,
+        ///     Translations
+        /// FROM
+        ///     Book
+        /// ```
+        ///
+        /// Source: `queries/module_2.sql:42`
         pub fn select_translations() -> SelectTranslationsStmt {
             SelectTranslationsStmt(cornucopia_async::private::Stmt::new(
                 "SELECT
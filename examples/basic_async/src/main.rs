@@ -2,6 +2,7 @@
 // see what it looks like under the hood.
 mod cornucopia;
 use crate::cornucopia::{
+    pool::{create_pool, PoolSettings, TlsMode},
     queries::{
         module_1::insert_book,
         module_2::{
@@ -17,7 +18,15 @@ use cornucopia_async::Params;
 pub async fn main() {
     // You can learn which database connection types are compatible with Cornucopia in the book
     // https://cornucopia-rs.netlify.app/book/using_queries/db_connections.html
-    let pool = create_pool().await.unwrap();
+    // The `create_pool` helper is generated when using the `--pool` flag.
+    let settings = PoolSettings {
+        host: String::from("127.0.0.1"),
+        port: 5435,
+        password: Some(String::from("postgres")),
+        tls_mode: TlsMode::Disable,
+        ..PoolSettings::default()
+    };
+    let pool = create_pool(&settings, tokio_postgres::NoTls).unwrap();
     let mut client = pool.get().await.unwrap();
 
     // The `all` method returns queried rows collected into a `Vec`
@@ -105,20 +114,3 @@ pub async fn main() {
         .unwrap();
     dbg!(translations);
 }
-
-/// Connection pool configuration.
-///
-/// This is just a simple example config, please look at
-/// `tokio_postgres` and `deadpool_postgres` for details.
-use deadpool_postgres::{Config, CreatePoolError, Pool, Runtime};
-use tokio_postgres::NoTls;
-
-async fn create_pool() -> Result<Pool, CreatePoolError> {
-    let mut cfg = Config::new();
-    cfg.user = Some(String::from("postgres"));
-    cfg.password = Some(String::from("postgres"));
-    cfg.host = Some(String::from("127.0.0.1"));
-    cfg.port = Some(5435);
-    cfg.dbname = Some(String::from("postgres"));
-    cfg.create_pool(Some(Runtime::Tokio1), NoTls)
-}
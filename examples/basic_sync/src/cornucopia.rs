@@ -88,7 +88,7 @@ pub mod types {
                 }
             }
         }
-        #[derive(Debug, postgres_types :: FromSql, Clone, PartialEq)]
+        #[derive(Debug, postgres_types::FromSql, Clone, PartialEq)]
         #[postgres(name = "voiceactor")]
         pub struct Voiceactor {
             #[postgres(name = "name")]
@@ -210,6 +210,12 @@ pub mod types {
 pub mod queries {
     pub mod module_1 {
         use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+        /// ```sql
+        /// INSERT INTO Book (title)
+        ///   VALUES ($1)
+        /// ```
+        ///
+        /// Source: `queries/module_1.sql:1`
         pub fn insert_book() -> InsertBookStmt {
             InsertBookStmt(cornucopia_sync::private::Stmt::new(
                 "INSERT INTO Book (title)
@@ -229,14 +235,18 @@ pub mod queries {
         }
     }
     pub mod module_2 {
+        /// Source: `queries/module_2.sql:21`
         #[derive(Debug)]
         pub struct AuthorNameStartingWithParams<T1: cornucopia_sync::StringSql> {
             pub start_str: T1,
         }
+        /// Source: `queries/module_2.sql:1`
         #[derive(Debug, Clone, PartialEq)]
         pub struct Authors {
             pub id: i32,
+            /// At most 70 characters.
             pub name: String,
+            /// At most 100 characters.
             pub country: String,
         }
         pub struct AuthorsBorrowed<'a> {
@@ -253,11 +263,29 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for AuthorsBorrowed<'a> {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                Ok(Self {
+                    id: row.try_get("id")?,
+                    name: row.try_get("name")?,
+                    country: row.try_get("country")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for Authors {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                <AuthorsBorrowed<'a> as cornucopia_sync::FromPgRow<'a>>::from_row(row)
+                    .map(Into::into)
+            }
+        }
+        /// Source: `queries/module_2.sql:21`
         #[derive(Debug, Clone, PartialEq)]
         pub struct AuthorNameStartingWith {
             pub authorid: i32,
+            /// At most 70 characters.
             pub name: String,
             pub bookid: i32,
+            /// At most 50 characters.
             pub title: String,
         }
         pub struct AuthorNameStartingWithBorrowed<'a> {
@@ -283,8 +311,28 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for AuthorNameStartingWithBorrowed<'a> {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                Ok(Self {
+                    authorid: row.try_get("authorid")?,
+                    name: row.try_get("name")?,
+                    bookid: row.try_get("bookid")?,
+                    title: row.try_get("title")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for AuthorNameStartingWith {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                <AuthorNameStartingWithBorrowed<'a> as cornucopia_sync::FromPgRow<'a>>::from_row(
+                    row,
+                )
+                .map(Into::into)
+            }
+        }
+        /// Source: `queries/module_2.sql:42`
         #[derive(Debug, Clone, PartialEq)]
         pub struct SelectTranslations {
+            /// At most 50 characters.
             pub title: String,
             pub translations: Vec<String>,
         }
@@ -305,6 +353,20 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for SelectTranslationsBorrowed<'a> {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                Ok(Self {
+                    title: row.try_get("title")?,
+                    translations: row.try_get("translations")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for SelectTranslations {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                <SelectTranslationsBorrowed<'a> as cornucopia_sync::FromPgRow<'a>>::from_row(row)
+                    .map(Into::into)
+            }
+        }
         use postgres::{fallible_iterator::FallibleIterator, GenericClient};
         pub struct AuthorsQuery<'a, C: GenericClient, T, const N: usize> {
             client: &'a mut C,
@@ -555,6 +617,14 @@ pub mod queries {
                 Ok(it)
             }
         }
+        /// ```sql
+        /// SELECT
+        ///     *
+        /// FROM
+        ///     Author
+        /// ```
+        ///
+        /// Source: `queries/module_2.sql:1`
         pub fn authors() -> AuthorsStmt {
             AuthorsStmt(cornucopia_sync::private::Stmt::new(
                 "SELECT
@@ -582,6 +652,14 @@ FROM
                 }
             }
         }
+        /// ```sql
+        /// SELECT
+        ///     Title
+        /// FROM
+        ///     Book
+        /// ```
+        ///
+        /// Source: `queries/module_2.sql:7`
         pub fn books() -> BooksStmt {
             BooksStmt(cornucopia_sync::private::Stmt::new(
                 "SELECT
@@ -605,6 +683,16 @@ FROM
                 }
             }
         }
+        /// ```sql
+        /// SELECT
+        ///     Author.Name
+        /// FROM
+        ///     Author
+        /// WHERE
+        ///     Author.Id = $1
+        /// ```
+        ///
+        /// Source: `queries/module_2.sql:13`
         pub fn author_name_by_id() -> AuthorNameByIdStmt {
             AuthorNameByIdStmt(cornucopia_sync::private::Stmt::new(
                 "SELECT
@@ -631,6 +719,21 @@ WHERE
                 }
             }
         }
+        /// ```sql
+        /// SELECT
+        ///     BookAuthor.AuthorId,
+        ///     Author.Name,
+        ///     BookAuthor.BookId,
+        ///     Book.Title
+        /// FROM
+        ///     BookAuthor
+        ///     INNER JOIN Author ON Author.id = BookAuthor.AuthorId
+        ///     INNER JOIN Book ON Book.Id = BookAuthor.BookId
+        /// WHERE
+        ///     Author.Name LIKE CONCAT($1::text, '%')
+        /// ```
+        ///
+        /// Source: `queries/module_2.sql:21`
         pub fn author_name_starting_with() -> AuthorNameStartingWithStmt {
             AuthorNameStartingWithStmt(cornucopia_sync::private::Stmt::new(
                 "SELECT
@@ -683,6 +786,16 @@ WHERE
                 self.bind(client, &params.start_str)
             }
         }
+        /// ```sql
+        /// SELECT
+        ///     voice_actor
+        /// FROM
+        ///     SpongeBobVoiceActor
+        /// WHERE
+        ///     character = $1
+        /// ```
+        ///
+        /// Source: `queries/module_2.sql:34`
         pub fn select_voice_actor_with_character() -> SelectVoiceActorWithCharacterStmt {
             SelectVoiceActorWithCharacterStmt(cornucopia_sync::private::Stmt::new(
                 "SELECT
@@ -710,6 +823,15 @@ WHERE
                 }
             }
         }
+        /// ```sql
+        /// SELECT
+        ///     Title,
+        ///     Translations
+        /// FROM
+        ///     Book
+        /// ```
+        ///
+        /// Source: `queries/module_2.sql:42`
         pub fn select_translations() -> SelectTranslationsStmt {
             SelectTranslationsStmt(cornucopia_sync::private::Stmt::new(
                 "SELECT
@@ -0,0 +1,26 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#![forbid(unsafe_code)]
+#![allow(clippy::all, dead_code)]
+#[cfg(not(debug_assertions))]
+static SQL_TABLE:
+cornucopia_sync::private::SqlTable = cornucopia_sync::private::SqlTable::new(b"\x38\xe6\x0b\x3f\x2f\x29\xdb\x6c\x00\xae\x80\x2d\x17\x3e\x31\x7d\x3f\x2f\x07\x4a\x28\x5b\x6f\xce\xf8\xd5\xd5\x64\x21\xdb\xbe\xd3\xed\x92\x6d\xf2\x9f\x1b\x87\x94\x13\xa9\x2a\xba\x7e\x73\xad\xf6\x59\x02\xb9\x3b\xe1\xc2\x55\x37\x5c\x76\x25\xb9\x23\x19\x56\x15\x37\xb9\xfa\x16\xbc\xf4\x55\x8f\x9f\xa8\x25\x26\x27\x87\xbb\xac\x91\xf1\x06\xd6\x44\xd2\xc2\xe7\xd5\x43\x27\x12\x62\xec\x24\xae\x35\x2e\x46\x26\x8d\x47\x3d\x7e\x38\x85\x47\x96\x44\x63\x3b\x18\x60\xf5\x22\xa2\x2e\xf6\x70\xae\x9f\xc0\xd4\x80\x22\x0d\xca\x33\xb3\x3b\x0e\xca\xb6\x44\xac\xb9\x32\xb1\x1b\x41\x32\xbc\x62\xb6\x5b\x87\xc1\x98\xca\x23\x91\xf6\xc1\xf3\x5d\xbe\x49\xc8\x3c\xef\x4b\x7b\x8b\xa8\xee\x52\x4d\xee\xe4\x43\xcd\xfa\x15\xe9\xff\x26\xf8\xe3\xc3\x5f\x25\x53\xe4\xba\x61\x47\x69\x83\x31\xce\xe9\xb9\x14\x16\xfb\x9f\x99\x39\x0e\x4c\xc7\x98\x04\x26\xcb\x52\x9d\x72\x1b\x48\xc2\xf0\x86\x88\x90\xa5\x33\xb9\xc9\x15\x1b\xcc\x64\x72\xaf\x54\x4b\xff\xc7\xfb\x52\x91\xe8\x11\xf1\x9c\xe4\xc8\x1d\x43\xba\x75\x77\x54\x1e\x78\x5b\x1a\xcc\x8e\xf4\xa3\x58\xa2\xa5\xb2\xb5\xc0\x95\xf1\x77\xc8\xb6\x6a\x7a\x47\x60\x37\x2c\xde\xd6\xc1\xe1\x0c\x26\xaf\x52\xff\x8e\x9f\x04\x0e\xa4\x54\xc7\xee\x0a\x3b\x18\x60\xd4\xd9\xec\xa7\xed\xc8\x3e\x80\x03\x9e\xeb\xfc\xea\xd7\xf2\xc0\xf0\x9d\x08\x87\x47\x07\x75\x8f\x6c\xbc\x7d\x6e\x19\xaf\x99\xf7\xe6\xac\xb1\x55\x45\xbf\xa4\x69\x9e\xc2\xa9\x57\xff\x4d\x88\x91\x7e\xca\x14\x12\x20\x22\x27\xb2\x0c\x3a\xa4\xbc\xa2\x5a\x5f\x98\x69\x4c\xf5\x4e\x10\xf0\x06\xb1\xdb\x60\xa5\x79\x52\x7b\x3f\xbc\x95\x6a\x81\x35\x78\xfa\xa4\xc8\x18\xba\x72\xc6\x0b\xe7\xdd\x66\xed\x4c\x2c\xc2\x49\xd8\xd8\x1b\x10\x2c\x3f\xd2\xec\x6a\x3a\xdd\x09\x54\x0e\x61\x26\x4b\x01\xb5\x8a\x80\x12\xdb\xde\x66");
+#[cfg(debug_assertions)]
+macro_rules! cornucopia_sql {
+    ($table:path, $hash:literal, $sql:expr) => {
+        $sql
+    };
+}
+#[cfg(not(debug_assertions))]
+macro_rules! cornucopia_sql {
+    ($table:path, $hash:literal, $sql:expr) => {
+        $table.get($hash)
+    };
+}
+pub mod types;
+pub mod queries {
+    pub mod nested {
+        pub mod sidecar;
+    }
+    pub mod sidecar;
+}
@@ -0,0 +1,452 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+/// Source: `queries_external/sidecar.sql:4`
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct NamedByName {
+    pub id: i32,
+    pub name: String,
+}
+pub struct NamedByNameBorrowed<'a> {
+    pub id: i32,
+    pub name: &'a str,
+}
+impl<'a> From<NamedByNameBorrowed<'a>> for NamedByName {
+    fn from(NamedByNameBorrowed { id, name }: NamedByNameBorrowed<'a>) -> Self {
+        Self {
+            id,
+            name: name.into(),
+        }
+    }
+}
+impl<'a> cornucopia_sync::FromPgRow<'a> for NamedByNameBorrowed<'a> {
+    fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+        })
+    }
+}
+impl<'a> cornucopia_sync::FromPgRow<'a> for NamedByName {
+    fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+        <NamedByNameBorrowed<'a> as cornucopia_sync::FromPgRow<'a>>::from_row(row).map(Into::into)
+    }
+}
+/// Partitions of the `event` table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventPartition {
+    Event2023,
+    Event2024,
+}
+impl EventPartition {
+    /// Every partition, in catalog order.
+    pub const ALL: [Self; 2] = [Self::Event2023, Self::Event2024];
+    /// Name of the partition, schema-qualified if it is not in the search path.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Event2023 => "event_2023",
+            Self::Event2024 => "event_2024",
+        }
+    }
+}
+use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+pub struct StringQuery<'a, C: GenericClient, T, const N: usize> {
+    client: &'a mut C,
+    params: [&'a (dyn postgres_types::ToSql + Sync); N],
+    stmt: &'a mut cornucopia_sync::private::Stmt,
+    extractor: fn(&postgres::Row) -> &str,
+    mapper: fn(&str) -> T,
+}
+impl<'a, C, T: 'a, const N: usize> StringQuery<'a, C, T, N>
+where
+    C: GenericClient,
+{
+    pub fn map<R>(self, mapper: fn(&str) -> R) -> StringQuery<'a, C, R, N> {
+        StringQuery {
+            client: self.client,
+            params: self.params,
+            stmt: self.stmt,
+            extractor: self.extractor,
+            mapper,
+        }
+    }
+    pub fn one(self) -> Result<T, postgres::Error> {
+        let stmt = self.stmt.prepare(self.client)?;
+        let row = self.client.query_one(stmt, &self.params)?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    }
+    pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+        self.iter()?.collect()
+    }
+    pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+        let stmt = self.stmt.prepare(self.client)?;
+        Ok(self
+            .client
+            .query_opt(stmt, &self.params)?
+            .map(|row| (self.mapper)((self.extractor)(&row))))
+    }
+    pub fn iter(
+        self,
+    ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error> {
+        let stmt = self.stmt.prepare(self.client)?;
+        let it = self
+            .client
+            .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+            .iterator()
+            .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+        Ok(it)
+    }
+}
+pub struct NamedByNameQuery<'a, C: GenericClient, T, const N: usize> {
+    client: &'a mut C,
+    params: [&'a (dyn postgres_types::ToSql + Sync); N],
+    stmt: &'a mut cornucopia_sync::private::Stmt,
+    extractor: fn(&postgres::Row) -> NamedByNameBorrowed,
+    mapper: fn(NamedByNameBorrowed) -> T,
+}
+impl<'a, C, T: 'a, const N: usize> NamedByNameQuery<'a, C, T, N>
+where
+    C: GenericClient,
+{
+    pub fn map<R>(self, mapper: fn(NamedByNameBorrowed) -> R) -> NamedByNameQuery<'a, C, R, N> {
+        NamedByNameQuery {
+            client: self.client,
+            params: self.params,
+            stmt: self.stmt,
+            extractor: self.extractor,
+            mapper,
+        }
+    }
+    pub fn one(self) -> Result<T, postgres::Error> {
+        let stmt = self.stmt.prepare(self.client)?;
+        let row = self.client.query_one(stmt, &self.params)?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    }
+    pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+        self.iter()?.collect()
+    }
+    pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+        let stmt = self.stmt.prepare(self.client)?;
+        Ok(self
+            .client
+            .query_opt(stmt, &self.params)?
+            .map(|row| (self.mapper)((self.extractor)(&row))))
+    }
+    pub fn iter(
+        self,
+    ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error> {
+        let stmt = self.stmt.prepare(self.client)?;
+        let it = self
+            .client
+            .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+            .iterator()
+            .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+        Ok(it)
+    }
+}
+pub struct I64Query<'a, C: GenericClient, T, const N: usize> {
+    client: &'a mut C,
+    params: [&'a (dyn postgres_types::ToSql + Sync); N],
+    stmt: &'a mut cornucopia_sync::private::Stmt,
+    extractor: fn(&postgres::Row) -> i64,
+    mapper: fn(i64) -> T,
+}
+impl<'a, C, T: 'a, const N: usize> I64Query<'a, C, T, N>
+where
+    C: GenericClient,
+{
+    pub fn map<R>(self, mapper: fn(i64) -> R) -> I64Query<'a, C, R, N> {
+        I64Query {
+            client: self.client,
+            params: self.params,
+            stmt: self.stmt,
+            extractor: self.extractor,
+            mapper,
+        }
+    }
+    pub fn one(self) -> Result<T, postgres::Error> {
+        let stmt = self.stmt.prepare(self.client)?;
+        let row = self.client.query_one(stmt, &self.params)?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    }
+    pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+        self.iter()?.collect()
+    }
+    pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+        let stmt = self.stmt.prepare(self.client)?;
+        Ok(self
+            .client
+            .query_opt(stmt, &self.params)?
+            .map(|row| (self.mapper)((self.extractor)(&row))))
+    }
+    pub fn iter(
+        self,
+    ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error> {
+        let stmt = self.stmt.prepare(self.client)?;
+        let it = self
+            .client
+            .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+            .iterator()
+            .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+        Ok(it)
+    }
+}
+pub struct I32Query<'a, C: GenericClient, T, const N: usize> {
+    client: &'a mut C,
+    params: [&'a (dyn postgres_types::ToSql + Sync); N],
+    stmt: &'a mut cornucopia_sync::private::Stmt,
+    extractor: fn(&postgres::Row) -> i32,
+    mapper: fn(i32) -> T,
+}
+impl<'a, C, T: 'a, const N: usize> I32Query<'a, C, T, N>
+where
+    C: GenericClient,
+{
+    pub fn map<R>(self, mapper: fn(i32) -> R) -> I32Query<'a, C, R, N> {
+        I32Query {
+            client: self.client,
+            params: self.params,
+            stmt: self.stmt,
+            extractor: self.extractor,
+            mapper,
+        }
+    }
+    pub fn one(self) -> Result<T, postgres::Error> {
+        let stmt = self.stmt.prepare(self.client)?;
+        let row = self.client.query_one(stmt, &self.params)?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    }
+    pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+        self.iter()?.collect()
+    }
+    pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+        let stmt = self.stmt.prepare(self.client)?;
+        Ok(self
+            .client
+            .query_opt(stmt, &self.params)?
+            .map(|row| (self.mapper)((self.extractor)(&row))))
+    }
+    pub fn iter(
+        self,
+    ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error> {
+        let stmt = self.stmt.prepare(self.client)?;
+        let it = self
+            .client
+            .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+            .iterator()
+            .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+        Ok(it)
+    }
+}
+/// ```sql
+/// SELECT 'back\slash "quoted" it''s' AS text
+/// ```
+///
+/// Source: `queries_external/sidecar.sql:1`
+pub fn escaped_text() -> EscapedTextStmt {
+    EscapedTextStmt(cornucopia_sync::private::Stmt::new(cornucopia_sql!(
+        super::super::SQL_TABLE,
+        0x83c2e77b16b010e1,
+        "SELECT 'back\\slash \"quoted\" it''s' AS text"
+    )))
+}
+pub struct EscapedTextStmt(cornucopia_sync::private::Stmt);
+impl EscapedTextStmt {
+    pub fn bind<'a, C: GenericClient>(
+        &'a mut self,
+        client: &'a mut C,
+    ) -> StringQuery<'a, C, String, 0> {
+        StringQuery {
+            client,
+            params: [],
+            stmt: &mut self.0,
+            extractor: |row| row.get(0),
+            mapper: |it| it.into(),
+        }
+    }
+}
+/// ```sql
+/// SELECT id, name FROM named WHERE name = $1
+/// ```
+///
+/// Source: `queries_external/sidecar.sql:4`
+pub fn named_by_name() -> NamedByNameStmt {
+    NamedByNameStmt(cornucopia_sync::private::Stmt::new(cornucopia_sql!(
+        super::super::SQL_TABLE,
+        0x4e5cf915a843a487,
+        "SELECT id, name FROM named WHERE name = $1"
+    )))
+}
+pub struct NamedByNameStmt(cornucopia_sync::private::Stmt);
+impl NamedByNameStmt {
+    pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+        &'a mut self,
+        client: &'a mut C,
+        name: &'a T1,
+    ) -> NamedByNameQuery<'a, C, NamedByName, 1> {
+        NamedByNameQuery {
+            client,
+            params: [name],
+            stmt: &mut self.0,
+            extractor: |row| NamedByNameBorrowed {
+                id: row.get(0),
+                name: row.get(1),
+            },
+            mapper: |it| <NamedByName>::from(it),
+        }
+    }
+}
+/// ```sql
+/// SELECT count(*) AS count FROM event
+/// ```
+///
+/// Source: `queries_external/sidecar.sql:8`
+pub fn count_events() -> CountEventsStmt {
+    CountEventsStmt(cornucopia_sync::private::Stmt::new(cornucopia_sql!(
+        super::super::SQL_TABLE,
+        0x27a3b357fd9511ad,
+        "SELECT count(*) AS count FROM event"
+    )))
+}
+pub struct CountEventsStmt(cornucopia_sync::private::Stmt);
+impl CountEventsStmt {
+    pub fn bind<'a, C: GenericClient>(&'a mut self, client: &'a mut C) -> I64Query<'a, C, i64, 0> {
+        I64Query {
+            client,
+            params: [],
+            stmt: &mut self.0,
+            extractor: |row| row.get(0),
+            mapper: |it| it,
+        }
+    }
+}
+pub fn count_events_in(partition: EventPartition) -> CountEventsStmt {
+    CountEventsStmt(cornucopia_sync::private::Stmt::new(match partition {
+        EventPartition::Event2023 => cornucopia_sql!(
+            super::super::SQL_TABLE,
+            0x820705b30829ddf6,
+            "SELECT count(*) AS count FROM event_2023 AS event"
+        ),
+        EventPartition::Event2024 => cornucopia_sql!(
+            super::super::SQL_TABLE,
+            0x820708b30829e30f,
+            "SELECT count(*) AS count FROM event_2024 AS event"
+        ),
+    }))
+}
+/// ```sql
+/// SELECT id FROM named WHERE name LIKE $1
+/// ```
+///
+/// Source: `queries_external/sidecar.sql:11`
+pub fn named_ids_paged() -> NamedIdsPagedStmt {
+    NamedIdsPagedStmt(cornucopia_sync::private::Stmt::new(cornucopia_sql!(
+        super::super::SQL_TABLE,
+        0x3876ef24e59c4102,
+        "SELECT id FROM named WHERE name LIKE $1"
+    )))
+}
+/// Fetches the rows of the query by pages, in `id` order.
+pub fn named_ids_paged_pages() -> NamedIdsPagedPagesStmt {
+    NamedIdsPagedPagesStmt(cornucopia_sync::private::Stmt::new(cornucopia_sql!(
+        super::super::SQL_TABLE,
+        0x69698a2b457fa1c4,
+        "SELECT * FROM (SELECT id FROM named WHERE name LIKE $1
+) AS page WHERE page.\"id\" > $2 OR $2 IS NULL ORDER BY page.\"id\" LIMIT $3"
+    )))
+}
+pub struct NamedIdsPagedPagesStmt(cornucopia_sync::private::Stmt);
+impl NamedIdsPagedPagesStmt {
+    /// Fetches up to `limit` rows following `cursor`, the next cursor of the
+    /// previous page or `None` for the first page.
+    pub fn page<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+        &'a mut self,
+        client: &'a mut C,
+        pattern: &'a T1,
+        limit: u32,
+        cursor: Option<i32>,
+    ) -> Result<cornucopia_sync::Page<i32, i32>, postgres::Error> {
+        let stmt = self.0.prepare(client)?;
+        let rows = client.query(stmt, &[pattern, &cursor, &(i64::from(limit) + 1)])?;
+        let rows = rows.iter().map(|row| {
+            let it = {
+                let it: i32 = row.get(0);
+                it
+            };
+            (it, row.get(0))
+        });
+        Ok(cornucopia_sync::Page::new(rows.collect(), limit))
+    }
+}
+pub struct NamedIdsPagedStmt(cornucopia_sync::private::Stmt);
+impl NamedIdsPagedStmt {
+    pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+        &'a mut self,
+        client: &'a mut C,
+        pattern: &'a T1,
+    ) -> I32Query<'a, C, i32, 1> {
+        I32Query {
+            client,
+            params: [pattern],
+            stmt: &mut self.0,
+            extractor: |row| row.get(0),
+            mapper: |it| it,
+        }
+    }
+}
+/// ```sql
+/// SELECT id FROM bulk ORDER BY id
+/// ```
+///
+/// Source: `queries_external/sidecar.sql:15`
+pub fn select_bulk_ids() -> SelectBulkIdsStmt {
+    SelectBulkIdsStmt(cornucopia_sync::private::Stmt::new(cornucopia_sql!(
+        super::super::SQL_TABLE,
+        0x2a4ef56b435656f4,
+        "SELECT id FROM bulk ORDER BY id"
+    )))
+}
+pub struct SelectBulkIdsStmt(cornucopia_sync::private::Stmt);
+impl SelectBulkIdsStmt {
+    pub fn bind<'a, C: GenericClient>(&'a mut self, client: &'a mut C) -> I32Query<'a, C, i32, 0> {
+        I32Query {
+            client,
+            params: [],
+            stmt: &mut self.0,
+            extractor: |row| row.get(0),
+            mapper: |it| it,
+        }
+    }
+    /// Copies the rows of the query out in bulk, decoded from `COPY`'s binary format.
+    pub fn copy_out<'a, C: GenericClient>(
+        &'a mut self,
+        client: &'a mut C,
+    ) -> Result<impl Iterator<Item = Result<i32, postgres::Error>> + 'a, postgres::Error> {
+        let stmt = self.0.prepare(client)?;
+        let types: Vec<_> = stmt.columns().iter().map(|c| c.type_().clone()).collect();
+        let reader = client.copy_out(cornucopia_sql!(
+            super::super::SQL_TABLE,
+            0xaea845073a43c243,
+            "COPY (SELECT id FROM bulk ORDER BY id) TO STDOUT (FORMAT binary)"
+        ))?;
+        Ok(
+            postgres::binary_copy::BinaryCopyOutIter::new(reader, &types)
+                .iterator()
+                .map(|row| {
+                    row.map(|row| {
+                        let it = row.get(0);
+                        it
+                    })
+                }),
+        )
+    }
+}
+/// Runs the statements of the batch in a single round trip. They run in an implicit
+/// transaction, unless the batch controls its own transactions.
+pub fn reset_sidecar_scratch<C: GenericClient>(client: &mut C) -> Result<(), postgres::Error> {
+    client.batch_execute(cornucopia_sql!(
+        super::super::SQL_TABLE,
+        0xa2ee70f9d1d9e00a,
+        "CREATE TEMP TABLE IF NOT EXISTS sidecar_scratch (n INT);
+TRUNCATE sidecar_scratch"
+    ))
+}
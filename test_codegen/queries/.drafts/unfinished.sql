@@ -0,0 +1,2 @@
+--! unfinished
+SELECT FROM WHERE
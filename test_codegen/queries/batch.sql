@@ -0,0 +1,14 @@
+--! setup_audit
+DELETE FROM audit WHERE note LIKE 'setup;%';
+INSERT INTO audit (note) VALUES ('setup; once');
+
+--! count_setup
+SELECT count(*) AS count FROM audit WHERE note LIKE 'setup;%';
+
+--! setup_scratch
+CREATE TEMP TABLE scratch (n INT);
+INSERT INTO scratch (n) VALUES (1), (2);
+
+--! setup_dollar_scratch
+CREATE TEMP TABLE scratch$ (n$ INT);
+INSERT INTO scratch$ (n$) VALUES (1);
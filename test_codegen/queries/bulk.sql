@@ -0,0 +1,11 @@
+--! copy_bulk (tags?)
+--! copy
+INSERT INTO bulk (id, name, tags) VALUES (:id, :name, :tags);
+
+--! copy_bulk_ids
+--! copy
+INSERT INTO bulk (id) VALUES (:id);
+
+--! select_bulk : (tags?)
+--! copy
+SELECT id, name, tags FROM bulk ORDER BY id;
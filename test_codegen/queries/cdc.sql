@@ -0,0 +1,2 @@
+--% named
+--% public.book
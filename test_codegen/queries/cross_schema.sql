@@ -0,0 +1,5 @@
+--! insert_cross_schema
+INSERT INTO cross_schema (composite) VALUES (:composite);
+
+--! select_cross_schema
+SELECT composite FROM cross_schema;
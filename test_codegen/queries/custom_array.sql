@@ -0,0 +1,5 @@
+--! insert_custom_array
+INSERT INTO custom_array (spongebob, custom) VALUES (:spongebob, :custom);
+
+--! select_custom_array
+SELECT spongebob, custom FROM custom_array;
@@ -0,0 +1,3 @@
+--! named_labels
+--! derive(Hash, Eq, PartialOrd, Ord)
+SELECT id, name FROM named;
@@ -5,4 +5,10 @@ SELECT txt, json, nb, arr FROM nightmare_domain;
 INSERT INTO nightmare_domain (txt, json, nb, arr, composite) VALUES (:txt, :json, :nb, :arr, :composite);
 
 --! select_nightmare_domain_null: (txt?, json?, nb?, arr?[?], composite?)
-SELECT * FROM nightmare_domain;
\ No newline at end of file
+SELECT * FROM nightmare_domain;
+
+--! insert_review (code?)
+INSERT INTO review (rating, code) VALUES (:rating, :code);
+
+--! select_reviews: (code?)
+SELECT rating, code FROM review;
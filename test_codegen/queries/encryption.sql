@@ -0,0 +1,8 @@
+--! insert_patient (ssn#, notes?#)
+INSERT INTO patient (name, ssn, notes) VALUES (:name, :ssn, :notes) RETURNING id;
+
+--! patient_by_id : (ssn#, notes?#)
+SELECT name, ssn, notes FROM patient WHERE id = :id;
+
+--! patient_ciphertext
+SELECT ssn FROM patient WHERE id = :id;
@@ -0,0 +1,15 @@
+--! migrate_named
+--! multi_exec
+WITH hidden AS (
+    DELETE FROM named WHERE NOT show
+),
+renamed AS (
+    UPDATE named SET name = upper(name) WHERE show RETURNING id
+),
+listed AS (
+    SELECT count(*) FROM named
+)
+INSERT INTO named (name, show) VALUES (:name, true);
+
+--! touch_named
+SELECT pg_sleep(0);
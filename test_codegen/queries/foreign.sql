@@ -0,0 +1,5 @@
+--! insert_shipment
+INSERT INTO shipment (id, state) VALUES (:id, :state);
+
+--! remote_shipments
+SELECT id, state FROM remote.shipment ORDER BY id;
@@ -0,0 +1,8 @@
+--! insert_named_batch
+WITH item AS (
+    SELECT name, position, CAST(nextval(pg_get_serial_sequence('named', 'id')) AS int) AS id
+    FROM unnest(CAST(:names AS text[])) WITH ORDINALITY AS item (name, position)
+), inserted AS (
+    INSERT INTO named (id, name, show) SELECT id, name, false FROM item
+)
+SELECT position, id FROM item;
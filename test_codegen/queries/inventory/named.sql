@@ -0,0 +1,5 @@
+-- Query files in subdirectories are nested in a module named after them, so that this
+-- module doesn't collide with `queries::named`
+--! visible_named : (price?)
+--! max_concurrency 2
+SELECT id, name, price FROM named WHERE show AND name = :name;
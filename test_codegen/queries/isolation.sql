@@ -0,0 +1,26 @@
+--! count_books_isolated
+--! isolation serializable, read_only
+SELECT count(*) FROM book;
+
+--! insert_book_isolated (author?)
+--! isolation repeatable_read
+INSERT INTO book (author, name) VALUES (:author, :name);
+
+--! insert_book_read_only
+--! isolation read_committed, read_only
+INSERT INTO book (name) VALUES (:name);
+
+--! flaky
+--! isolation serializable
+SELECT flaky();
+
+--! insert_book_guarded
+--! isolation read_committed
+--! read_only
+--! timeout 5s
+INSERT INTO book (name) VALUES (:name);
+
+--! sleep_timed_out
+--! isolation read_committed
+--! timeout 50ms
+SELECT 1 AS slept FROM pg_sleep(1);
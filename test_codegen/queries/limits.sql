@@ -0,0 +1,7 @@
+--! heavy_report
+--! max_concurrency 2
+SELECT count(*) FROM book;
+
+--! heavy_insert
+--! max_concurrency 1
+INSERT INTO book (name) VALUES (:name);
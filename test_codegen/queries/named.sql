@@ -2,10 +2,17 @@
 --: Named(price?)
 --: NamedParams(price?)
 
+-- Prix en € — 名前と価格のある商品
+
+-- Inserts a visible product, returning its id.
+--
+-- Hidden products are inserted by `new_named_hidden`.
 --! new_named_visible NamedParams: Id
 INSERT INTO named (name, price, show) VALUES (:name, :price, true) RETURNING id ; 
 --! new_named_hidden NamedParams: Id
 INSERT INTO named (price, name, show) VALUES (:price, :name, false) RETURNING id;
+-- Every product, visible or not — priced in €
+-- 名前と価格のある商品
 --! named: Named
 SELECT * FROM named;
 --! named_by_id: Named
@@ -0,0 +1,3 @@
+--~ book_events (id: i32, title?: String, tags: Vec<String>, "author-name"?: String)
+--~ raw_events serde_json::Value
+--~ light_events crate::LightEvent
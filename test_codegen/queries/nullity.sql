@@ -6,3 +6,5 @@
 INSERT INTO nullity(texts, name, composite) VALUES (:texts, :name, :composite); 
 --! nullity: Nullity
 SELECT * FROM nullity;
+--! composite_is_null (composite?)
+SELECT :composite::nullity_composite IS NULL AS is_null;
@@ -0,0 +1,7 @@
+--: traffic_light as crate::TrafficLight
+
+--! insert_crossing (lights?)
+INSERT INTO crossings (id, light, lights) VALUES (:id, :light, :lights);
+
+--! select_crossings : (lights?)
+SELECT id, light, lights FROM crossings ORDER BY id;
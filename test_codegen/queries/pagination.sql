@@ -0,0 +1,7 @@
+--! named_like_paged
+--! paginated id
+SELECT id, name FROM named WHERE name LIKE :pattern;
+
+--! named_ids_paged
+--! paginated id
+SELECT id FROM named WHERE name LIKE :pattern;
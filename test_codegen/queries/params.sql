@@ -11,4 +11,21 @@ SELECT * FROM book WHERE name = ANY (:title);
 UPDATE book SET name = :name WHERE length(name) > 42 AND length(:name) < 42;
 
 --! params_order
-UPDATE imaginary SET c=:c, a=:a, z=:a, r=:c;
\ No newline at end of file
+UPDATE imaginary SET c=:c, a=:a, z=:a, r=:c;
+--! insert_secret_book (author?*)
+INSERT INTO book (author, name) VALUES (:author, :name);
+
+--! select_secret_book: (author?*)
+SELECT * FROM book;
+
+--! books_by_names: (author?)
+--! list names
+SELECT * FROM book WHERE name IN (:names);
+
+--! book_names_except
+--! list names
+SELECT name FROM book WHERE name NOT IN ( :names );
+
+--! book_names_labeled
+--! list names
+SELECT name || ' NOT IN ($1)' FROM book WHERE name IN (:names);
@@ -0,0 +1,11 @@
+--! insert_event
+INSERT INTO event (id, year, name) VALUES (:id, :year, :name);
+
+--! events_by_year
+SELECT event.id, event.name FROM event WHERE event.year = :year ORDER BY id;
+
+--! count_events
+SELECT count(*) FROM event e;
+
+--! rename_event
+UPDATE event SET name = :name WHERE id = :id;
@@ -0,0 +1,6 @@
+--! planned_settings
+--! hint SeqScan(book)
+--! isolation read_committed
+--! set work_mem = '64MB'
+--! set enable_indexscan = off
+SELECT current_setting('work_mem') AS work_mem, current_setting('enable_indexscan') AS index_scan, current_query() AS query;
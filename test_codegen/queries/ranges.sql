@@ -0,0 +1,8 @@
+--! insert_ranges (days?)
+INSERT INTO ranges (during, ages, days) VALUES (:during, :ages, :days);
+
+--! select_ranges : (days?)
+SELECT during, ages, days FROM ranges;
+
+--! ranges_containing
+SELECT ages FROM ranges WHERE ages @> :age::int4;
@@ -0,0 +1,12 @@
+--! script_insert_book (author?)
+INSERT INTO book (author, name) VALUES (:author, :name);
+
+--! books_by_author (author?)
+SELECT name FROM book WHERE author = :author;
+
+--! divide
+SELECT 1 / :divisor::int AS quotient;
+
+--& publish_book (script_insert_book, books_by_author)
+
+--& publish_and_divide (script_insert_book, divide)
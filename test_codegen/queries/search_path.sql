@@ -0,0 +1,10 @@
+--@ schema inventory, public
+
+--! insert_item
+INSERT INTO item (label) VALUES (:label) RETURNING id;
+
+--! items
+SELECT * FROM item ORDER BY id;
+
+--! item_members
+SELECT item.label, member.handle FROM item JOIN member ON member.id = item.id ORDER BY item.id;
@@ -0,0 +1,3 @@
+--! named_like_at
+--! snapshot
+SELECT id, name FROM named WHERE name LIKE :pattern ORDER BY id;
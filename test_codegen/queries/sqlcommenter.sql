@@ -0,0 +1,2 @@
+--! current_query
+SELECT current_query();
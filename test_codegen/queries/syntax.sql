@@ -46,10 +46,19 @@ INSERT INTO syntax ("trick:y", async, enum) VALUES (e'this is ''not'' a \':bind_
 INSERT INTO syntax ("trick:y", async, enum) VALUES (E'this is \'not\' a \':bind_param\'', :async, :enum);
 --! tricky_sql10
 INSERT INTO syntax ("trick:y", async, enum) VALUES ('this is just a cast'::text, :async, :enum);
+--! tricky_sql11
+INSERT INTO syntax ("trick:y", async, enum) VALUES (E'this is not a \';batch', :async, :enum);
+--! tricky_sql12
+INSERT INTO syntax ("trick:y", async, enum) /*/ ; */ /* /* */ ; */ VALUES ('this is not a batch', :async, :enum);
 
 --! typeof
 SELECT * FROM syntax;
 
+--! normalized_sql
+SELECT /*+  SeqScan(named)  */  name, -- trailing comment
+    /* block /* nested */ comment */   '  spaced  -- literal  '   AS literal
+FROM named;
+
 -- Multi
 
 -- Comment
\ No newline at end of file
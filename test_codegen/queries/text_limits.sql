@@ -0,0 +1,8 @@
+--! insert_member (country?)
+INSERT INTO member (handle, country) VALUES (:handle, :country) RETURNING id;
+
+--! rename_member
+UPDATE member SET handle = :handle WHERE id = :id;
+
+--! members: (country?)
+SELECT * FROM member ORDER BY id;
@@ -0,0 +1,5 @@
+--! select_book_names
+SELECT name FROM book;
+
+--! select_book_names@v2: (author?)
+SELECT name, author FROM book;
@@ -0,0 +1,2 @@
+--! echo_numeric
+SELECT :value::numeric AS value, :values::numeric[] AS values;
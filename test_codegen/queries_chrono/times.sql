@@ -0,0 +1,7 @@
+--! echo_times (timestamps?)
+SELECT
+    :timestamp::timestamp AS timestamp,
+    :timestamptz::timestamptz AS timestamptz,
+    :date::date AS date,
+    :time::time AS time,
+    :timestamps::timestamptz[] AS timestamps;
@@ -0,0 +1,8 @@
+--: Product(price?)
+
+--! insert_product (price?)
+INSERT INTO named (name, price, show) VALUES (:name, :price, true) RETURNING id;
+--! product_by_id: Product
+SELECT * FROM named WHERE id = :id;
+--! products: Product
+SELECT * FROM named;
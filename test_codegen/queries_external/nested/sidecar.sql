@@ -0,0 +1,2 @@
+--! nested_text
+SELECT 'nested' AS text;
@@ -0,0 +1,21 @@
+--! escaped_text
+SELECT 'back\slash "quoted" it''s' AS text;
+
+--! named_by_name
+--! serialize
+SELECT id, name FROM named WHERE name = :name;
+
+--! count_events
+SELECT count(*) AS count FROM event;
+
+--! named_ids_paged
+--! paginated id
+SELECT id FROM named WHERE name LIKE :pattern;
+
+--! select_bulk_ids
+--! copy
+SELECT id FROM bulk ORDER BY id;
+
+--! reset_sidecar_scratch
+CREATE TEMP TABLE IF NOT EXISTS sidecar_scratch (n INT);
+TRUNCATE sidecar_scratch;
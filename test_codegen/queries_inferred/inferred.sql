@@ -0,0 +1,8 @@
+--! inferred_named
+SELECT * FROM named;
+
+--! annotated_price : (price)
+SELECT id, price FROM named WHERE price IS NOT NULL;
+
+--! computed_price
+SELECT coalesce(price, 0) AS price FROM named;
@@ -0,0 +1,9 @@
+--! insert_bare (price?)
+INSERT INTO named (name, price, show) VALUES (:name, :price, :show);
+
+--! bare_by_name
+SELECT id, show FROM named WHERE name = :name AND show = :show;
+
+--! copy_bare
+--! copy
+INSERT INTO named (name, show) VALUES (:name, :show);
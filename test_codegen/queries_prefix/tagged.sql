@@ -0,0 +1,2 @@
+--! current_sql
+SELECT current_query() AS sql;
@@ -0,0 +1,4 @@
+--! tenant_accounts
+SELECT accounts.id, email, tenants.name AS tenant
+FROM accounts JOIN tenants ON tenants.id = accounts.tenant_id
+WHERE tenants.id = :tenant_id;
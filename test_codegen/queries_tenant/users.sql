@@ -0,0 +1,8 @@
+--! insert_user
+INSERT INTO tenant_template.users (name) VALUES (:name) RETURNING id;
+
+--! users
+SELECT id, name FROM tenant_template.users ORDER BY id;
+
+--! user_notes
+SELECT name, 'tenant_template.users' AS note FROM "tenant_template".users /* tenant_template.users */ ORDER BY id;
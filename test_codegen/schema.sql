@@ -41,6 +41,14 @@ CREATE TABLE nightmare_domain (
     composite domain_composite
 );
 
+CREATE DOMAIN rating AS SMALLINT CHECK (value BETWEEN 1 AND 5);
+CREATE DOMAIN short_code AS TEXT CHECK (char_length(value) <= 8);
+
+CREATE TABLE review (
+    rating rating NOT NULL,
+    code short_code
+);
+
 -- Named
 
 CREATE TABLE named (
@@ -66,6 +74,20 @@ CREATE TABLE named_complex (
     "named.with_dot" "named_composite.with_dot"
 );
 
+-- Isolation
+
+-- Fails with a serialization failure until called more times than the `flaky.failures`
+-- setting of the session, so that only sessions setting it see failures
+CREATE SEQUENCE flaky_calls;
+CREATE FUNCTION flaky() RETURNS INT LANGUAGE plpgsql AS $$
+BEGIN
+    IF nextval('flaky_calls') <= coalesce(nullif(current_setting('flaky.failures', true), ''), '0')::INT THEN
+        RAISE EXCEPTION 'could not serialize access' USING ERRCODE = 'serialization_failure';
+    END IF;
+    RETURN currval('flaky_calls');
+END
+$$;
+
 -- Nullity
 
 CREATE TYPE nullity_composite AS (
@@ -79,6 +101,16 @@ CREATE TABLE nullity (
     composite nullity_composite
 );
 
+-- Partitions
+
+CREATE TABLE event (
+    id INT NOT NULL,
+    year INT NOT NULL,
+    name TEXT NOT NULL
+) PARTITION BY LIST (year);
+CREATE TABLE event_2023 PARTITION OF event FOR VALUES IN (2023);
+CREATE TABLE event_2024 PARTITION OF event FOR VALUES IN (2024);
+
 -- Params
 
 CREATE TABLE Book (
@@ -86,6 +118,8 @@ CREATE TABLE Book (
     author TEXT
 );
 
+COMMENT ON COLUMN Book.author IS 'Author of the book, unknown for anonymous works';
+
 CREATE TABLE imaginary (
     a SERIAL,
     c SERIAL,
@@ -176,6 +210,9 @@ CREATE TYPE custom_composite AS (
     nice spongebob_character
 );
 
+COMMENT ON TYPE custom_composite IS E'Composite exercising custom field types.\n\nNested in the arrays of `nightmare_composite`.';
+COMMENT ON COLUMN custom_composite.wow IS 'Exclamation of the character';
+
 CREATE DOMAIN my_domain AS TEXT CHECK (value ~ '^\w{5}$');
 CREATE DOMAIN custom_domain AS custom_composite[];
 
@@ -200,3 +237,119 @@ CREATE TABLE Syntax (
     async syntax_composite,
     enum syntax_enum
 );
+
+-- Cross schema
+
+CREATE SCHEMA extra;
+CREATE TYPE extra.mood AS ENUM ('happy', 'sad');
+CREATE TYPE extra.mood_note AS (
+    mood extra.mood,
+    note TEXT
+);
+CREATE TYPE cross_composite AS (
+    mood extra.mood,
+    moods extra.mood[],
+    note extra.mood_note
+);
+CREATE TABLE cross_schema (
+    composite cross_composite NOT NULL
+);
+
+-- Custom arrays
+
+CREATE TABLE custom_array (
+    spongebob spongebob_character[] NOT NULL,
+    custom custom_composite[] NOT NULL
+);
+
+-- Ranges
+
+CREATE TABLE ranges (
+    during tstzrange NOT NULL,
+    ages int4range NOT NULL,
+    days daterange
+);
+
+-- Bulk copies
+
+CREATE TABLE bulk (
+    id INT NOT NULL,
+    name TEXT NOT NULL DEFAULT 'anonymous',
+    tags TEXT[]
+);
+
+-- Type overrides
+
+CREATE TYPE traffic_light AS ENUM ('red', 'amber', 'green');
+CREATE TABLE crossings (
+    id INT NOT NULL,
+    light traffic_light NOT NULL,
+    lights traffic_light[]
+);
+
+-- Tenancy
+
+CREATE SCHEMA tenant_template;
+CREATE TABLE tenant_template.users (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+-- Text length limits
+
+CREATE TABLE member (
+    id SERIAL PRIMARY KEY,
+    handle VARCHAR(16) NOT NULL,
+    country VARCHAR(2)
+);
+
+-- Search path
+
+CREATE SCHEMA inventory;
+CREATE TABLE inventory.item (
+    id SERIAL PRIMARY KEY,
+    label TEXT NOT NULL
+);
+
+-- Column encryption
+
+CREATE TABLE patient (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    ssn BYTEA NOT NULL,
+    notes BYTEA
+);
+
+-- Batches
+
+CREATE TABLE audit (
+    id SERIAL PRIMARY KEY,
+    note TEXT NOT NULL
+);
+
+-- Foreign tables
+
+CREATE EXTENSION postgres_fdw;
+-- Loopback server, importing tables of this database through the server's own socket
+DO $$
+BEGIN
+    EXECUTE format(
+        'CREATE SERVER loopback FOREIGN DATA WRAPPER postgres_fdw OPTIONS (host %L, port %L, dbname %L)',
+        split_part(current_setting('unix_socket_directories'), ',', 1),
+        current_setting('port'),
+        current_database()
+    );
+END $$;
+CREATE USER MAPPING FOR CURRENT_USER SERVER loopback;
+CREATE TYPE shipment_state AS ENUM ('pending', 'shipped');
+CREATE TABLE shipment (
+    id INT PRIMARY KEY,
+    state shipment_state NOT NULL
+);
+-- Declared rather than imported, as the schema is loaded in a transaction the loopback
+-- connection doesn't see
+CREATE SCHEMA remote;
+CREATE FOREIGN TABLE remote.shipment (
+    id INT NOT NULL,
+    state shipment_state NOT NULL
+) SERVER loopback OPTIONS (schema_name 'public', table_name 'shipment');
@@ -0,0 +1,5 @@
+CREATE TABLE accounts (
+    id serial PRIMARY KEY,
+    tenant_id INTEGER NOT NULL REFERENCES tenants (id),
+    email TEXT NOT NULL
+);
@@ -0,0 +1,4 @@
+CREATE TABLE tenants (
+    id serial PRIMARY KEY,
+    name TEXT NOT NULL
+);
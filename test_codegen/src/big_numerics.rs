@@ -0,0 +1,152 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#![allow(clippy::all, dead_code)]
+pub mod types {}
+pub mod queries {
+    pub mod numerics {
+        /// Source: `queries_bigdecimal/numerics.sql:1`
+        #[derive(Debug)]
+        pub struct EchoNumericParams<T1: cornucopia_sync::ArraySql<Item = cornucopia_sync::Numeric>> {
+            pub value: cornucopia_sync::Numeric,
+            pub values: T1,
+        }
+        /// Source: `queries_bigdecimal/numerics.sql:1`
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct EchoNumeric {
+            pub value: bigdecimal::BigDecimal,
+            pub values: Vec<bigdecimal::BigDecimal>,
+        }
+        pub struct EchoNumericBorrowed<'a> {
+            pub value: cornucopia_sync::Numeric,
+            pub values: cornucopia_sync::ArrayIterator<'a, cornucopia_sync::Numeric>,
+        }
+        impl<'a> From<EchoNumericBorrowed<'a>> for EchoNumeric {
+            fn from(EchoNumericBorrowed { value, values }: EchoNumericBorrowed<'a>) -> Self {
+                Self {
+                    value: value.into(),
+                    values: values.map(|v| v.into()).collect(),
+                }
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for EchoNumericBorrowed<'a> {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                Ok(Self {
+                    value: row.try_get("value")?,
+                    values: row.try_get("values")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for EchoNumeric {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                <EchoNumericBorrowed<'a> as cornucopia_sync::FromPgRow<'a>>::from_row(row)
+                    .map(Into::into)
+            }
+        }
+        use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+        pub struct EchoNumericQuery<'a, C: GenericClient, T, const N: usize> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> EchoNumericBorrowed,
+            mapper: fn(EchoNumericBorrowed) -> T,
+        }
+        impl<'a, C, T: 'a, const N: usize> EchoNumericQuery<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(
+                self,
+                mapper: fn(EchoNumericBorrowed) -> R,
+            ) -> EchoNumericQuery<'a, C, R, N> {
+                EchoNumericQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                }
+            }
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                let row = self.client.query_one(stmt, &self.params)?;
+                Ok((self.mapper)((self.extractor)(&row)))
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                self.iter()?.collect()
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                Ok(self
+                    .client
+                    .query_opt(stmt, &self.params)?
+                    .map(|row| (self.mapper)((self.extractor)(&row))))
+            }
+            pub fn iter(
+                self,
+            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
+            {
+                let stmt = self.stmt.prepare(self.client)?;
+                let it = self
+                    .client
+                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .iterator()
+                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                Ok(it)
+            }
+        }
+        /// ```sql
+        /// SELECT $1::numeric AS value, $2::numeric[] AS values
+        /// ```
+        ///
+        /// Source: `queries_bigdecimal/numerics.sql:1`
+        pub fn echo_numeric() -> EchoNumericStmt {
+            EchoNumericStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT $1::numeric AS value, $2::numeric[] AS values",
+            ))
+        }
+        pub struct EchoNumericStmt(cornucopia_sync::private::Stmt);
+        impl EchoNumericStmt {
+            pub fn bind<
+                'a,
+                C: GenericClient,
+                T1: cornucopia_sync::ArraySql<Item = cornucopia_sync::Numeric>,
+            >(
+                &'a mut self,
+                client: &'a mut C,
+                value: &'a cornucopia_sync::Numeric,
+                values: &'a T1,
+            ) -> EchoNumericQuery<'a, C, EchoNumeric, 2> {
+                EchoNumericQuery {
+                    client,
+                    params: [value, values],
+                    stmt: &mut self.0,
+                    extractor: |row| EchoNumericBorrowed {
+                        value: row.get(0),
+                        values: row.get(1),
+                    },
+                    mapper: |it| <EchoNumeric>::from(it),
+                }
+            }
+        }
+        impl<
+                'a,
+                C: GenericClient,
+                T1: cornucopia_sync::ArraySql<Item = cornucopia_sync::Numeric>,
+            >
+            cornucopia_sync::Params<
+                'a,
+                EchoNumericParams<T1>,
+                EchoNumericQuery<'a, C, EchoNumeric, 2>,
+                C,
+            > for EchoNumericStmt
+        {
+            fn params(
+                &'a mut self,
+                client: &'a mut C,
+                params: &'a EchoNumericParams<T1>,
+            ) -> EchoNumericQuery<'a, C, EchoNumeric, 2> {
+                self.bind(client, &params.value, &params.values)
+            }
+        }
+    }
+}
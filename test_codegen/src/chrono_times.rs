@@ -0,0 +1,193 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#![allow(clippy::all, dead_code)]
+pub mod types {}
+pub mod queries {
+    pub mod times {
+        /// Source: `queries_chrono/times.sql:1`
+        #[derive(Debug)]
+        pub struct EchoTimesParams<
+            T1: cornucopia_sync::ArraySql<Item = chrono::DateTime<chrono::Utc>>,
+        > {
+            pub timestamp: chrono::NaiveDateTime,
+            pub timestamptz: chrono::DateTime<chrono::Utc>,
+            pub date: chrono::NaiveDate,
+            pub time: chrono::NaiveTime,
+            pub timestamps: Option<T1>,
+        }
+        /// Source: `queries_chrono/times.sql:1`
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct EchoTimes {
+            pub timestamp: chrono::NaiveDateTime,
+            pub timestamptz: chrono::DateTime<chrono::Utc>,
+            pub date: chrono::NaiveDate,
+            pub time: chrono::NaiveTime,
+            pub timestamps: Vec<chrono::DateTime<chrono::Utc>>,
+        }
+        pub struct EchoTimesBorrowed<'a> {
+            pub timestamp: chrono::NaiveDateTime,
+            pub timestamptz: chrono::DateTime<chrono::Utc>,
+            pub date: chrono::NaiveDate,
+            pub time: chrono::NaiveTime,
+            pub timestamps: cornucopia_sync::ArrayIterator<'a, chrono::DateTime<chrono::Utc>>,
+        }
+        impl<'a> From<EchoTimesBorrowed<'a>> for EchoTimes {
+            fn from(
+                EchoTimesBorrowed {
+                    timestamp,
+                    timestamptz,
+                    date,
+                    time,
+                    timestamps,
+                }: EchoTimesBorrowed<'a>,
+            ) -> Self {
+                Self {
+                    timestamp,
+                    timestamptz,
+                    date,
+                    time,
+                    timestamps: timestamps.map(|v| v).collect(),
+                }
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for EchoTimesBorrowed<'a> {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                Ok(Self {
+                    timestamp: row.try_get("timestamp")?,
+                    timestamptz: row.try_get("timestamptz")?,
+                    date: row.try_get("date")?,
+                    time: row.try_get("time")?,
+                    timestamps: row.try_get("timestamps")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for EchoTimes {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                <EchoTimesBorrowed<'a> as cornucopia_sync::FromPgRow<'a>>::from_row(row)
+                    .map(Into::into)
+            }
+        }
+        use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+        pub struct EchoTimesQuery<'a, C: GenericClient, T, const N: usize> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> EchoTimesBorrowed,
+            mapper: fn(EchoTimesBorrowed) -> T,
+        }
+        impl<'a, C, T: 'a, const N: usize> EchoTimesQuery<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(EchoTimesBorrowed) -> R) -> EchoTimesQuery<'a, C, R, N> {
+                EchoTimesQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                }
+            }
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                let row = self.client.query_one(stmt, &self.params)?;
+                Ok((self.mapper)((self.extractor)(&row)))
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                self.iter()?.collect()
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                Ok(self
+                    .client
+                    .query_opt(stmt, &self.params)?
+                    .map(|row| (self.mapper)((self.extractor)(&row))))
+            }
+            pub fn iter(
+                self,
+            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
+            {
+                let stmt = self.stmt.prepare(self.client)?;
+                let it = self
+                    .client
+                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .iterator()
+                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                Ok(it)
+            }
+        }
+        /// ```sql
+        /// SELECT
+        ///     $1::timestamp AS timestamp,
+        ///     $2::timestamptz AS timestamptz,
+        ///     $3::date AS date,
+        ///     $4::time AS time,
+        ///     $5::timestamptz[] AS timestamps
+        /// ```
+        ///
+        /// Source: `queries_chrono/times.sql:1`
+        pub fn echo_times() -> EchoTimesStmt {
+            EchoTimesStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT
+    $1::timestamp AS timestamp,
+    $2::timestamptz AS timestamptz,
+    $3::date AS date,
+    $4::time AS time,
+    $5::timestamptz[] AS timestamps",
+            ))
+        }
+        pub struct EchoTimesStmt(cornucopia_sync::private::Stmt);
+        impl EchoTimesStmt {
+            pub fn bind<
+                'a,
+                C: GenericClient,
+                T1: cornucopia_sync::ArraySql<Item = chrono::DateTime<chrono::Utc>>,
+            >(
+                &'a mut self,
+                client: &'a mut C,
+                timestamp: &'a chrono::NaiveDateTime,
+                timestamptz: &'a chrono::DateTime<chrono::Utc>,
+                date: &'a chrono::NaiveDate,
+                time: &'a chrono::NaiveTime,
+                timestamps: &'a Option<T1>,
+            ) -> EchoTimesQuery<'a, C, EchoTimes, 5> {
+                EchoTimesQuery {
+                    client,
+                    params: [timestamp, timestamptz, date, time, timestamps],
+                    stmt: &mut self.0,
+                    extractor: |row| EchoTimesBorrowed {
+                        timestamp: row.get(0),
+                        timestamptz: row.get(1),
+                        date: row.get(2),
+                        time: row.get(3),
+                        timestamps: row.get(4),
+                    },
+                    mapper: |it| <EchoTimes>::from(it),
+                }
+            }
+        }
+        impl<
+                'a,
+                C: GenericClient,
+                T1: cornucopia_sync::ArraySql<Item = chrono::DateTime<chrono::Utc>>,
+            >
+            cornucopia_sync::Params<'a, EchoTimesParams<T1>, EchoTimesQuery<'a, C, EchoTimes, 5>, C>
+            for EchoTimesStmt
+        {
+            fn params(
+                &'a mut self,
+                client: &'a mut C,
+                params: &'a EchoTimesParams<T1>,
+            ) -> EchoTimesQuery<'a, C, EchoTimes, 5> {
+                self.bind(
+                    client,
+                    &params.timestamp,
+                    &params.timestamptz,
+                    &params.date,
+                    &params.time,
+                    &params.timestamps,
+                )
+            }
+        }
+    }
+}
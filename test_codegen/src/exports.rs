@@ -0,0 +1,316 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#![allow(clippy::all, dead_code)]
+pub mod types {}
+pub mod queries {
+    pub mod products {
+        /// Source: `queries_exports/products.sql:3`
+        #[derive(Debug)]
+        pub struct InsertProductParams<T1: cornucopia_sync::StringSql> {
+            pub name: T1,
+            pub price: Option<f64>,
+        }
+        /// Source: `queries_exports/products.sql:5`
+        #[derive(serde::Serialize, Debug, Clone, PartialEq)]
+        pub struct Product {
+            pub id: i32,
+            pub name: String,
+            pub price: Option<f64>,
+            pub show: bool,
+        }
+        impl Product {
+            pub fn arrow_schema() -> cornucopia_sync::arrow::schema::SchemaRef {
+                std::sync::Arc::new(cornucopia_sync::arrow::schema::Schema::new(vec![
+                    cornucopia_sync::arrow::schema::Field::new(
+                        "id",
+                        cornucopia_sync::arrow::schema::DataType::Int32,
+                        false,
+                    ),
+                    cornucopia_sync::arrow::schema::Field::new(
+                        "name",
+                        cornucopia_sync::arrow::schema::DataType::Utf8,
+                        false,
+                    ),
+                    cornucopia_sync::arrow::schema::Field::new(
+                        "price",
+                        cornucopia_sync::arrow::schema::DataType::Float64,
+                        true,
+                    ),
+                    cornucopia_sync::arrow::schema::Field::new(
+                        "show",
+                        cornucopia_sync::arrow::schema::DataType::Boolean,
+                        false,
+                    ),
+                ]))
+            }
+            pub fn to_record_batch<'a>(
+                rows: impl IntoIterator<Item = &'a Self>,
+            ) -> Result<
+                cornucopia_sync::arrow::array::RecordBatch,
+                cornucopia_sync::arrow::schema::ArrowError,
+            > {
+                let rows: Vec<&Self> = rows.into_iter().collect();
+                cornucopia_sync::arrow::array::RecordBatch::try_new(
+                    Self::arrow_schema(),
+                    vec![
+                        std::sync::Arc::new(cornucopia_sync::arrow::array::Int32Array::from(
+                            rows.iter().map(|row| row.id).collect::<Vec<_>>(),
+                        )) as cornucopia_sync::arrow::array::ArrayRef,
+                        std::sync::Arc::new(cornucopia_sync::arrow::array::StringArray::from(
+                            rows.iter().map(|row| row.name.as_str()).collect::<Vec<_>>(),
+                        )) as cornucopia_sync::arrow::array::ArrayRef,
+                        std::sync::Arc::new(cornucopia_sync::arrow::array::Float64Array::from(
+                            rows.iter().map(|row| row.price).collect::<Vec<_>>(),
+                        )) as cornucopia_sync::arrow::array::ArrayRef,
+                        std::sync::Arc::new(cornucopia_sync::arrow::array::BooleanArray::from(
+                            rows.iter().map(|row| row.show).collect::<Vec<_>>(),
+                        )) as cornucopia_sync::arrow::array::ArrayRef,
+                    ],
+                )
+            }
+        }
+        impl Product {
+            pub const CSV_HEADER: [&'static str; 4] = ["id", "name", "price", "show"];
+            pub fn write_csv<'a, W: std::io::Write>(
+                rows: impl IntoIterator<Item = &'a Self>,
+                w: W,
+            ) -> Result<(), cornucopia_sync::csv::CsvError> {
+                cornucopia_sync::csv::write_csv(&Self::CSV_HEADER, rows, w)
+            }
+        }
+        pub struct ProductBorrowed<'a> {
+            pub id: i32,
+            pub name: &'a str,
+            pub price: Option<f64>,
+            pub show: bool,
+        }
+        impl<'a> From<ProductBorrowed<'a>> for Product {
+            fn from(
+                ProductBorrowed {
+                    id,
+                    name,
+                    price,
+                    show,
+                }: ProductBorrowed<'a>,
+            ) -> Self {
+                Self {
+                    id,
+                    name: name.into(),
+                    price,
+                    show,
+                }
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for ProductBorrowed<'a> {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                Ok(Self {
+                    id: row.try_get("id")?,
+                    name: row.try_get("name")?,
+                    price: row.try_get("price")?,
+                    show: row.try_get("show")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for Product {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                <ProductBorrowed<'a> as cornucopia_sync::FromPgRow<'a>>::from_row(row)
+                    .map(Into::into)
+            }
+        }
+        use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+        pub struct I32Query<'a, C: GenericClient, T, const N: usize> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> i32,
+            mapper: fn(i32) -> T,
+        }
+        impl<'a, C, T: 'a, const N: usize> I32Query<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(i32) -> R) -> I32Query<'a, C, R, N> {
+                I32Query {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                }
+            }
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                let row = self.client.query_one(stmt, &self.params)?;
+                Ok((self.mapper)((self.extractor)(&row)))
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                self.iter()?.collect()
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                Ok(self
+                    .client
+                    .query_opt(stmt, &self.params)?
+                    .map(|row| (self.mapper)((self.extractor)(&row))))
+            }
+            pub fn iter(
+                self,
+            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
+            {
+                let stmt = self.stmt.prepare(self.client)?;
+                let it = self
+                    .client
+                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .iterator()
+                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                Ok(it)
+            }
+        }
+        pub struct ProductQuery<'a, C: GenericClient, T, const N: usize> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> ProductBorrowed,
+            mapper: fn(ProductBorrowed) -> T,
+        }
+        impl<'a, C, T: 'a, const N: usize> ProductQuery<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(ProductBorrowed) -> R) -> ProductQuery<'a, C, R, N> {
+                ProductQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                }
+            }
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                let row = self.client.query_one(stmt, &self.params)?;
+                Ok((self.mapper)((self.extractor)(&row)))
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                self.iter()?.collect()
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                Ok(self
+                    .client
+                    .query_opt(stmt, &self.params)?
+                    .map(|row| (self.mapper)((self.extractor)(&row))))
+            }
+            pub fn iter(
+                self,
+            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
+            {
+                let stmt = self.stmt.prepare(self.client)?;
+                let it = self
+                    .client
+                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .iterator()
+                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                Ok(it)
+            }
+        }
+        /// ```sql
+        /// INSERT INTO named (name, price, show) VALUES ($1, $2, true) RETURNING id
+        /// ```
+        ///
+        /// Source: `queries_exports/products.sql:3`
+        pub fn insert_product() -> InsertProductStmt {
+            InsertProductStmt(cornucopia_sync::private::Stmt::new(
+                "INSERT INTO named (name, price, show) VALUES ($1, $2, true) RETURNING id",
+            ))
+        }
+        pub struct InsertProductStmt(cornucopia_sync::private::Stmt);
+        impl InsertProductStmt {
+            pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+                &'a mut self,
+                client: &'a mut C,
+                name: &'a T1,
+                price: &'a Option<f64>,
+            ) -> I32Query<'a, C, i32, 2> {
+                I32Query {
+                    client,
+                    params: [name, price],
+                    stmt: &mut self.0,
+                    extractor: |row| row.get(0),
+                    mapper: |it| it,
+                }
+            }
+        }
+        impl<'a, C: GenericClient, T1: cornucopia_sync::StringSql>
+            cornucopia_sync::Params<'a, InsertProductParams<T1>, I32Query<'a, C, i32, 2>, C>
+            for InsertProductStmt
+        {
+            fn params(
+                &'a mut self,
+                client: &'a mut C,
+                params: &'a InsertProductParams<T1>,
+            ) -> I32Query<'a, C, i32, 2> {
+                self.bind(client, &params.name, &params.price)
+            }
+        }
+        /// ```sql
+        /// SELECT * FROM named WHERE id = $1
+        /// ```
+        ///
+        /// Source: `queries_exports/products.sql:5`
+        pub fn product_by_id() -> ProductByIdStmt {
+            ProductByIdStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT * FROM named WHERE id = $1",
+            ))
+        }
+        pub struct ProductByIdStmt(cornucopia_sync::private::Stmt);
+        impl ProductByIdStmt {
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+                id: &'a i32,
+            ) -> ProductQuery<'a, C, Product, 1> {
+                ProductQuery {
+                    client,
+                    params: [id],
+                    stmt: &mut self.0,
+                    extractor: |row| ProductBorrowed {
+                        id: row.get(0),
+                        name: row.get(1),
+                        price: row.get(2),
+                        show: row.get(3),
+                    },
+                    mapper: |it| <Product>::from(it),
+                }
+            }
+        }
+        /// ```sql
+        /// SELECT * FROM named
+        /// ```
+        ///
+        /// Source: `queries_exports/products.sql:7`
+        pub fn products() -> ProductsStmt {
+            ProductsStmt(cornucopia_sync::private::Stmt::new("SELECT * FROM named"))
+        }
+        pub struct ProductsStmt(cornucopia_sync::private::Stmt);
+        impl ProductsStmt {
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> ProductQuery<'a, C, Product, 0> {
+                ProductQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| ProductBorrowed {
+                        id: row.get(0),
+                        name: row.get(1),
+                        price: row.get(2),
+                        show: row.get(3),
+                    },
+                    mapper: |it| <Product>::from(it),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,569 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#![forbid(unsafe_code)]
+#![allow(clippy::all, dead_code)]
+#[cfg(not(debug_assertions))]
+static SQL_TABLE:
+cornucopia_sync::private::SqlTable = cornucopia_sync::private::SqlTable::new(b"\x38\xe6\x0b\x3f\x2f\x29\xdb\x6c\x00\xae\x80\x2d\x17\x3e\x31\x7d\x3f\x2f\x07\x4a\x28\x5b\x6f\xce\xf8\xd5\xd5\x64\x21\xdb\xbe\xd3\xed\x92\x6d\xf2\x9f\x1b\x87\x94\x13\xa9\x2a\xba\x7e\x73\xad\xf6\x59\x02\xb9\x3b\xe1\xc2\x55\x37\x5c\x76\x25\xb9\x23\x19\x56\x15\x37\xb9\xfa\x16\xbc\xf4\x55\x8f\x9f\xa8\x25\x26\x27\x87\xbb\xac\x91\xf1\x06\xd6\x44\xd2\xc2\xe7\xd5\x43\x27\x12\x62\xec\x24\xae\x35\x2e\x46\x26\x8d\x47\x3d\x7e\x38\x85\x47\x96\x44\x63\x3b\x18\x60\xf5\x22\xa2\x2e\xf6\x70\xae\x9f\xc0\xd4\x80\x22\x0d\xca\x33\xb3\x3b\x0e\xca\xb6\x44\xac\xb9\x32\xb1\x1b\x41\x32\xbc\x62\xb6\x5b\x87\xc1\x98\xca\x23\x91\xf6\xc1\xf3\x5d\xbe\x49\xc8\x3c\xef\x4b\x7b\x8b\xa8\xee\x52\x4d\xee\xe4\x43\xcd\xfa\x15\xe9\xff\x26\xf8\xe3\xc3\x5f\x25\x53\xe4\xba\x61\x47\x69\x83\x31\xce\xe9\xb9\x14\x16\xfb\x9f\x99\x39\x0e\x4c\xc7\x98\x04\x26\xcb\x52\x9d\x72\x1b\x48\xc2\xf0\x86\x88\x90\xa5\x33\xb9\xc9\x15\x1b\xcc\x64\x72\xaf\x54\x4b\xff\xc7\xfb\x52\x91\xe8\x11\xf1\x9c\xe4\xc8\x1d\x43\xba\x75\x77\x54\x1e\x78\x5b\x1a\xcc\x8e\xf4\xa3\x58\xa2\xa5\xb2\xb5\xc0\x95\xf1\x77\xc8\xb6\x6a\x7a\x47\x60\x37\x2c\xde\xd6\xc1\xe1\x0c\x26\xaf\x52\xff\x8e\x9f\x04\x0e\xa4\x54\xc7\xee\x0a\x3b\x18\x60\xd4\xd9\xec\xa7\xed\xc8\x3e\x80\x03\x9e\xeb\xfc\xea\xd7\xf2\xc0\xf0\x9d\x08\x87\x47\x07\x75\x8f\x6c\xbc\x7d\x6e\x19\xaf\x99\xf7\xe6\xac\xb1\x55\x45\xbf\xa4\x69\x9e\xc2\xa9\x57\xff\x4d\x88\x91\x7e\xca\x14\x12\x20\x22\x27\xb2\x0c\x3a\xa4\xbc\xa2\x5a\x5f\x98\x69\x4c\xf5\x4e\x10\xf0\x06\xb1\xdb\x60\xa5\x79\x52\x7b\x3f\xbc\x95\x6a\x81\x35\x78\xfa\xa4\xc8\x18\xba\x72\xc6\x0b\xe7\xdd\x66\xed\x4c\x2c\xc2\x49\xd8\xd8\x1b\x10\x2c\x3f\xd2\xec\x6a\x3a\xdd\x09\x54\x0e\x61\x26\x4b\x01\xb5\x8a\x80\x12\xdb\xde\x66");
+#[cfg(debug_assertions)]
+macro_rules! cornucopia_sql {
+    ($table:path, $hash:literal, $sql:expr) => {
+        $sql
+    };
+}
+#[cfg(not(debug_assertions))]
+macro_rules! cornucopia_sql {
+    ($table:path, $hash:literal, $sql:expr) => {
+        $table.get($hash)
+    };
+}
+pub mod types {}
+pub mod queries {
+    pub mod nested {
+        pub mod sidecar {
+            use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+            pub struct StringQuery<'a, C: GenericClient, T, const N: usize> {
+                client: &'a mut C,
+                params: [&'a (dyn postgres_types::ToSql + Sync); N],
+                stmt: &'a mut cornucopia_sync::private::Stmt,
+                extractor: fn(&postgres::Row) -> &str,
+                mapper: fn(&str) -> T,
+            }
+            impl<'a, C, T: 'a, const N: usize> StringQuery<'a, C, T, N>
+            where
+                C: GenericClient,
+            {
+                pub fn map<R>(self, mapper: fn(&str) -> R) -> StringQuery<'a, C, R, N> {
+                    StringQuery {
+                        client: self.client,
+                        params: self.params,
+                        stmt: self.stmt,
+                        extractor: self.extractor,
+                        mapper,
+                    }
+                }
+                pub fn one(self) -> Result<T, postgres::Error> {
+                    let stmt = self.stmt.prepare(self.client)?;
+                    let row = self.client.query_one(stmt, &self.params)?;
+                    Ok((self.mapper)((self.extractor)(&row)))
+                }
+                pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                    self.iter()?.collect()
+                }
+                pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                    let stmt = self.stmt.prepare(self.client)?;
+                    Ok(self
+                        .client
+                        .query_opt(stmt, &self.params)?
+                        .map(|row| (self.mapper)((self.extractor)(&row))))
+                }
+                pub fn iter(
+                    self,
+                ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
+                {
+                    let stmt = self.stmt.prepare(self.client)?;
+                    let it = self
+                        .client
+                        .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                        .iterator()
+                        .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                    Ok(it)
+                }
+            }
+            /// ```sql
+            /// SELECT 'nested' AS text
+            /// ```
+            ///
+            /// Source: `queries_external/nested/sidecar.sql:1`
+            pub fn nested_text() -> NestedTextStmt {
+                NestedTextStmt(cornucopia_sync::private::Stmt::new(cornucopia_sql!(
+                    super::super::super::SQL_TABLE,
+                    0x07336dec3a10601c,
+                    include_str!("external_sql/nested/sidecar/nested_text.sql")
+                )))
+            }
+            pub struct NestedTextStmt(cornucopia_sync::private::Stmt);
+            impl NestedTextStmt {
+                pub fn bind<'a, C: GenericClient>(
+                    &'a mut self,
+                    client: &'a mut C,
+                ) -> StringQuery<'a, C, String, 0> {
+                    StringQuery {
+                        client,
+                        params: [],
+                        stmt: &mut self.0,
+                        extractor: |row| row.get(0),
+                        mapper: |it| it.into(),
+                    }
+                }
+            }
+        }
+    }
+    pub mod sidecar {
+        /// Source: `queries_external/sidecar.sql:4`
+        #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+        pub struct NamedByName {
+            pub id: i32,
+            pub name: String,
+        }
+        pub struct NamedByNameBorrowed<'a> {
+            pub id: i32,
+            pub name: &'a str,
+        }
+        impl<'a> From<NamedByNameBorrowed<'a>> for NamedByName {
+            fn from(NamedByNameBorrowed { id, name }: NamedByNameBorrowed<'a>) -> Self {
+                Self {
+                    id,
+                    name: name.into(),
+                }
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for NamedByNameBorrowed<'a> {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                Ok(Self {
+                    id: row.try_get("id")?,
+                    name: row.try_get("name")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for NamedByName {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                <NamedByNameBorrowed<'a> as cornucopia_sync::FromPgRow<'a>>::from_row(row)
+                    .map(Into::into)
+            }
+        }
+        /// Partitions of the `event` table.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum EventPartition {
+            Event2023,
+            Event2024,
+        }
+        impl EventPartition {
+            /// Every partition, in catalog order.
+            pub const ALL: [Self; 2] = [Self::Event2023, Self::Event2024];
+            /// Name of the partition, schema-qualified if it is not in the search path.
+            pub fn name(self) -> &'static str {
+                match self {
+                    Self::Event2023 => "event_2023",
+                    Self::Event2024 => "event_2024",
+                }
+            }
+        }
+        use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+        pub struct StringQuery<'a, C: GenericClient, T, const N: usize> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> &str,
+            mapper: fn(&str) -> T,
+        }
+        impl<'a, C, T: 'a, const N: usize> StringQuery<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(&str) -> R) -> StringQuery<'a, C, R, N> {
+                StringQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                }
+            }
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                let row = self.client.query_one(stmt, &self.params)?;
+                Ok((self.mapper)((self.extractor)(&row)))
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                self.iter()?.collect()
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                Ok(self
+                    .client
+                    .query_opt(stmt, &self.params)?
+                    .map(|row| (self.mapper)((self.extractor)(&row))))
+            }
+            pub fn iter(
+                self,
+            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
+            {
+                let stmt = self.stmt.prepare(self.client)?;
+                let it = self
+                    .client
+                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .iterator()
+                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                Ok(it)
+            }
+        }
+        pub struct NamedByNameQuery<'a, C: GenericClient, T, const N: usize> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> NamedByNameBorrowed,
+            mapper: fn(NamedByNameBorrowed) -> T,
+        }
+        impl<'a, C, T: 'a, const N: usize> NamedByNameQuery<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(
+                self,
+                mapper: fn(NamedByNameBorrowed) -> R,
+            ) -> NamedByNameQuery<'a, C, R, N> {
+                NamedByNameQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                }
+            }
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                let row = self.client.query_one(stmt, &self.params)?;
+                Ok((self.mapper)((self.extractor)(&row)))
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                self.iter()?.collect()
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                Ok(self
+                    .client
+                    .query_opt(stmt, &self.params)?
+                    .map(|row| (self.mapper)((self.extractor)(&row))))
+            }
+            pub fn iter(
+                self,
+            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
+            {
+                let stmt = self.stmt.prepare(self.client)?;
+                let it = self
+                    .client
+                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .iterator()
+                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                Ok(it)
+            }
+        }
+        pub struct I64Query<'a, C: GenericClient, T, const N: usize> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> i64,
+            mapper: fn(i64) -> T,
+        }
+        impl<'a, C, T: 'a, const N: usize> I64Query<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(i64) -> R) -> I64Query<'a, C, R, N> {
+                I64Query {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                }
+            }
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                let row = self.client.query_one(stmt, &self.params)?;
+                Ok((self.mapper)((self.extractor)(&row)))
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                self.iter()?.collect()
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                Ok(self
+                    .client
+                    .query_opt(stmt, &self.params)?
+                    .map(|row| (self.mapper)((self.extractor)(&row))))
+            }
+            pub fn iter(
+                self,
+            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
+            {
+                let stmt = self.stmt.prepare(self.client)?;
+                let it = self
+                    .client
+                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .iterator()
+                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                Ok(it)
+            }
+        }
+        pub struct I32Query<'a, C: GenericClient, T, const N: usize> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> i32,
+            mapper: fn(i32) -> T,
+        }
+        impl<'a, C, T: 'a, const N: usize> I32Query<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(i32) -> R) -> I32Query<'a, C, R, N> {
+                I32Query {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                }
+            }
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                let row = self.client.query_one(stmt, &self.params)?;
+                Ok((self.mapper)((self.extractor)(&row)))
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                self.iter()?.collect()
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                Ok(self
+                    .client
+                    .query_opt(stmt, &self.params)?
+                    .map(|row| (self.mapper)((self.extractor)(&row))))
+            }
+            pub fn iter(
+                self,
+            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
+            {
+                let stmt = self.stmt.prepare(self.client)?;
+                let it = self
+                    .client
+                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .iterator()
+                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                Ok(it)
+            }
+        }
+        /// ```sql
+        /// SELECT 'back\slash "quoted" it''s' AS text
+        /// ```
+        ///
+        /// Source: `queries_external/sidecar.sql:1`
+        pub fn escaped_text() -> EscapedTextStmt {
+            EscapedTextStmt(cornucopia_sync::private::Stmt::new(cornucopia_sql!(
+                super::super::SQL_TABLE,
+                0x83c2e77b16b010e1,
+                include_str!("external_sql/sidecar/escaped_text.sql")
+            )))
+        }
+        pub struct EscapedTextStmt(cornucopia_sync::private::Stmt);
+        impl EscapedTextStmt {
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> StringQuery<'a, C, String, 0> {
+                StringQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| row.get(0),
+                    mapper: |it| it.into(),
+                }
+            }
+        }
+        /// ```sql
+        /// SELECT id, name FROM named WHERE name = $1
+        /// ```
+        ///
+        /// Source: `queries_external/sidecar.sql:4`
+        pub fn named_by_name() -> NamedByNameStmt {
+            NamedByNameStmt(cornucopia_sync::private::Stmt::new(cornucopia_sql!(
+                super::super::SQL_TABLE,
+                0x4e5cf915a843a487,
+                include_str!("external_sql/sidecar/named_by_name.sql")
+            )))
+        }
+        pub struct NamedByNameStmt(cornucopia_sync::private::Stmt);
+        impl NamedByNameStmt {
+            pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+                &'a mut self,
+                client: &'a mut C,
+                name: &'a T1,
+            ) -> NamedByNameQuery<'a, C, NamedByName, 1> {
+                NamedByNameQuery {
+                    client,
+                    params: [name],
+                    stmt: &mut self.0,
+                    extractor: |row| NamedByNameBorrowed {
+                        id: row.get(0),
+                        name: row.get(1),
+                    },
+                    mapper: |it| <NamedByName>::from(it),
+                }
+            }
+        }
+        /// ```sql
+        /// SELECT count(*) AS count FROM event
+        /// ```
+        ///
+        /// Source: `queries_external/sidecar.sql:8`
+        pub fn count_events() -> CountEventsStmt {
+            CountEventsStmt(cornucopia_sync::private::Stmt::new(cornucopia_sql!(
+                super::super::SQL_TABLE,
+                0x27a3b357fd9511ad,
+                include_str!("external_sql/sidecar/count_events.sql")
+            )))
+        }
+        pub struct CountEventsStmt(cornucopia_sync::private::Stmt);
+        impl CountEventsStmt {
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> I64Query<'a, C, i64, 0> {
+                I64Query {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| row.get(0),
+                    mapper: |it| it,
+                }
+            }
+        }
+        pub fn count_events_in(partition: EventPartition) -> CountEventsStmt {
+            CountEventsStmt(cornucopia_sync::private::Stmt::new(match partition {
+                EventPartition::Event2023 => cornucopia_sql!(
+                    super::super::SQL_TABLE,
+                    0x820705b30829ddf6,
+                    include_str!("external_sql/sidecar/count_events_in_event_2023.sql")
+                ),
+                EventPartition::Event2024 => cornucopia_sql!(
+                    super::super::SQL_TABLE,
+                    0x820708b30829e30f,
+                    include_str!("external_sql/sidecar/count_events_in_event_2024.sql")
+                ),
+            }))
+        }
+        /// ```sql
+        /// SELECT id FROM named WHERE name LIKE $1
+        /// ```
+        ///
+        /// Source: `queries_external/sidecar.sql:11`
+        pub fn named_ids_paged() -> NamedIdsPagedStmt {
+            NamedIdsPagedStmt(cornucopia_sync::private::Stmt::new(cornucopia_sql!(
+                super::super::SQL_TABLE,
+                0x3876ef24e59c4102,
+                include_str!("external_sql/sidecar/named_ids_paged.sql")
+            )))
+        }
+        /// Fetches the rows of the query by pages, in `id` order.
+        pub fn named_ids_paged_pages() -> NamedIdsPagedPagesStmt {
+            NamedIdsPagedPagesStmt(cornucopia_sync::private::Stmt::new(cornucopia_sql!(
+                super::super::SQL_TABLE,
+                0x69698a2b457fa1c4,
+                include_str!("external_sql/sidecar/named_ids_paged.pages.sql")
+            )))
+        }
+        pub struct NamedIdsPagedPagesStmt(cornucopia_sync::private::Stmt);
+        impl NamedIdsPagedPagesStmt {
+            /// Fetches up to `limit` rows following `cursor`, the next cursor of the
+            /// previous page or `None` for the first page.
+            pub fn page<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+                &'a mut self,
+                client: &'a mut C,
+                pattern: &'a T1,
+                limit: u32,
+                cursor: Option<i32>,
+            ) -> Result<cornucopia_sync::Page<i32, i32>, postgres::Error> {
+                let stmt = self.0.prepare(client)?;
+                let rows = client.query(stmt, &[pattern, &cursor, &(i64::from(limit) + 1)])?;
+                let rows = rows.iter().map(|row| {
+                    let it = {
+                        let it: i32 = row.get(0);
+                        it
+                    };
+                    (it, row.get(0))
+                });
+                Ok(cornucopia_sync::Page::new(rows.collect(), limit))
+            }
+        }
+        pub struct NamedIdsPagedStmt(cornucopia_sync::private::Stmt);
+        impl NamedIdsPagedStmt {
+            pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+                &'a mut self,
+                client: &'a mut C,
+                pattern: &'a T1,
+            ) -> I32Query<'a, C, i32, 1> {
+                I32Query {
+                    client,
+                    params: [pattern],
+                    stmt: &mut self.0,
+                    extractor: |row| row.get(0),
+                    mapper: |it| it,
+                }
+            }
+        }
+        /// ```sql
+        /// SELECT id FROM bulk ORDER BY id
+        /// ```
+        ///
+        /// Source: `queries_external/sidecar.sql:15`
+        pub fn select_bulk_ids() -> SelectBulkIdsStmt {
+            SelectBulkIdsStmt(cornucopia_sync::private::Stmt::new(cornucopia_sql!(
+                super::super::SQL_TABLE,
+                0x2a4ef56b435656f4,
+                include_str!("external_sql/sidecar/select_bulk_ids.sql")
+            )))
+        }
+        pub struct SelectBulkIdsStmt(cornucopia_sync::private::Stmt);
+        impl SelectBulkIdsStmt {
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> I32Query<'a, C, i32, 0> {
+                I32Query {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| row.get(0),
+                    mapper: |it| it,
+                }
+            }
+            /// Copies the rows of the query out in bulk, decoded from `COPY`'s binary format.
+            pub fn copy_out<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> Result<impl Iterator<Item = Result<i32, postgres::Error>> + 'a, postgres::Error>
+            {
+                let stmt = self.0.prepare(client)?;
+                let types: Vec<_> = stmt.columns().iter().map(|c| c.type_().clone()).collect();
+                let reader = client.copy_out(cornucopia_sql!(
+                    super::super::SQL_TABLE,
+                    0xaea845073a43c243,
+                    include_str!("external_sql/sidecar/select_bulk_ids.copy.sql")
+                ))?;
+                Ok(
+                    postgres::binary_copy::BinaryCopyOutIter::new(reader, &types)
+                        .iterator()
+                        .map(|row| {
+                            row.map(|row| {
+                                let it = row.get(0);
+                                it
+                            })
+                        }),
+                )
+            }
+        }
+        /// Runs the statements of the batch in a single round trip. They run in an implicit
+        /// transaction, unless the batch controls its own transactions.
+        pub fn reset_sidecar_scratch<C: GenericClient>(
+            client: &mut C,
+        ) -> Result<(), postgres::Error> {
+            client.batch_execute(cornucopia_sql!(
+                super::super::SQL_TABLE,
+                0xa2ee70f9d1d9e00a,
+                include_str!("external_sql/sidecar/reset_sidecar_scratch.sql")
+            ))
+        }
+    }
+}
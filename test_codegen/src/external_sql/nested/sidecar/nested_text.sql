@@ -0,0 +1 @@
+SELECT 'nested' AS text
\ No newline at end of file
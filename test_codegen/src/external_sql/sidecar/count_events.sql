@@ -0,0 +1 @@
+SELECT count(*) AS count FROM event
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT count(*) AS count FROM event_2023 AS event
\ No newline at end of file
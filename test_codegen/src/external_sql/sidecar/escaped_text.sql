@@ -0,0 +1 @@
+SELECT 'back\slash "quoted" it''s' AS text
\ No newline at end of file
@@ -0,0 +1,2 @@
+CREATE TEMP TABLE IF NOT EXISTS sidecar_scratch (n INT);
+TRUNCATE sidecar_scratch
\ No newline at end of file
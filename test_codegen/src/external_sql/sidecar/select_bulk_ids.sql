@@ -0,0 +1 @@
+SELECT id FROM bulk ORDER BY id
\ No newline at end of file
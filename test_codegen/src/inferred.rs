@@ -0,0 +1,301 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#![allow(clippy::all, dead_code)]
+pub mod types {}
+pub mod queries {
+    pub mod inferred {
+        /// Source: `queries_inferred/inferred.sql:1`
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct InferredNamed {
+            pub id: i32,
+            pub name: String,
+            pub price: Option<f64>,
+            pub show: bool,
+        }
+        pub struct InferredNamedBorrowed<'a> {
+            pub id: i32,
+            pub name: &'a str,
+            pub price: Option<f64>,
+            pub show: bool,
+        }
+        impl<'a> From<InferredNamedBorrowed<'a>> for InferredNamed {
+            fn from(
+                InferredNamedBorrowed {
+                    id,
+                    name,
+                    price,
+                    show,
+                }: InferredNamedBorrowed<'a>,
+            ) -> Self {
+                Self {
+                    id,
+                    name: name.into(),
+                    price,
+                    show,
+                }
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for InferredNamedBorrowed<'a> {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                Ok(Self {
+                    id: row.try_get("id")?,
+                    name: row.try_get("name")?,
+                    price: row.try_get("price")?,
+                    show: row.try_get("show")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for InferredNamed {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                <InferredNamedBorrowed<'a> as cornucopia_sync::FromPgRow<'a>>::from_row(row)
+                    .map(Into::into)
+            }
+        }
+        /// Source: `queries_inferred/inferred.sql:4`
+        #[derive(Debug, Clone, PartialEq, Copy)]
+        pub struct AnnotatedPrice {
+            pub id: i32,
+            pub price: f64,
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for AnnotatedPrice {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                Ok(Self {
+                    id: row.try_get("id")?,
+                    price: row.try_get("price")?,
+                })
+            }
+        }
+        use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+        pub struct InferredNamedQuery<'a, C: GenericClient, T, const N: usize> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> InferredNamedBorrowed,
+            mapper: fn(InferredNamedBorrowed) -> T,
+        }
+        impl<'a, C, T: 'a, const N: usize> InferredNamedQuery<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(
+                self,
+                mapper: fn(InferredNamedBorrowed) -> R,
+            ) -> InferredNamedQuery<'a, C, R, N> {
+                InferredNamedQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                }
+            }
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                let row = self.client.query_one(stmt, &self.params)?;
+                Ok((self.mapper)((self.extractor)(&row)))
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                self.iter()?.collect()
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                Ok(self
+                    .client
+                    .query_opt(stmt, &self.params)?
+                    .map(|row| (self.mapper)((self.extractor)(&row))))
+            }
+            pub fn iter(
+                self,
+            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
+            {
+                let stmt = self.stmt.prepare(self.client)?;
+                let it = self
+                    .client
+                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .iterator()
+                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                Ok(it)
+            }
+        }
+        pub struct AnnotatedPriceQuery<'a, C: GenericClient, T, const N: usize> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> AnnotatedPrice,
+            mapper: fn(AnnotatedPrice) -> T,
+        }
+        impl<'a, C, T: 'a, const N: usize> AnnotatedPriceQuery<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(
+                self,
+                mapper: fn(AnnotatedPrice) -> R,
+            ) -> AnnotatedPriceQuery<'a, C, R, N> {
+                AnnotatedPriceQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                }
+            }
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                let row = self.client.query_one(stmt, &self.params)?;
+                Ok((self.mapper)((self.extractor)(&row)))
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                self.iter()?.collect()
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                Ok(self
+                    .client
+                    .query_opt(stmt, &self.params)?
+                    .map(|row| (self.mapper)((self.extractor)(&row))))
+            }
+            pub fn iter(
+                self,
+            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
+            {
+                let stmt = self.stmt.prepare(self.client)?;
+                let it = self
+                    .client
+                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .iterator()
+                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                Ok(it)
+            }
+        }
+        pub struct F64Query<'a, C: GenericClient, T, const N: usize> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> f64,
+            mapper: fn(f64) -> T,
+        }
+        impl<'a, C, T: 'a, const N: usize> F64Query<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(f64) -> R) -> F64Query<'a, C, R, N> {
+                F64Query {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                }
+            }
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                let row = self.client.query_one(stmt, &self.params)?;
+                Ok((self.mapper)((self.extractor)(&row)))
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                self.iter()?.collect()
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                Ok(self
+                    .client
+                    .query_opt(stmt, &self.params)?
+                    .map(|row| (self.mapper)((self.extractor)(&row))))
+            }
+            pub fn iter(
+                self,
+            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
+            {
+                let stmt = self.stmt.prepare(self.client)?;
+                let it = self
+                    .client
+                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .iterator()
+                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                Ok(it)
+            }
+        }
+        /// ```sql
+        /// SELECT * FROM named
+        /// ```
+        ///
+        /// Source: `queries_inferred/inferred.sql:1`
+        pub fn inferred_named() -> InferredNamedStmt {
+            InferredNamedStmt(cornucopia_sync::private::Stmt::new("SELECT * FROM named"))
+        }
+        pub struct InferredNamedStmt(cornucopia_sync::private::Stmt);
+        impl InferredNamedStmt {
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> InferredNamedQuery<'a, C, InferredNamed, 0> {
+                InferredNamedQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| InferredNamedBorrowed {
+                        id: row.get(0),
+                        name: row.get(1),
+                        price: row.get(2),
+                        show: row.get(3),
+                    },
+                    mapper: |it| <InferredNamed>::from(it),
+                }
+            }
+        }
+        /// ```sql
+        /// SELECT id, price FROM named WHERE price IS NOT NULL
+        /// ```
+        ///
+        /// Source: `queries_inferred/inferred.sql:4`
+        pub fn annotated_price() -> AnnotatedPriceStmt {
+            AnnotatedPriceStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT id, price FROM named WHERE price IS NOT NULL",
+            ))
+        }
+        pub struct AnnotatedPriceStmt(cornucopia_sync::private::Stmt);
+        impl AnnotatedPriceStmt {
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> AnnotatedPriceQuery<'a, C, AnnotatedPrice, 0> {
+                AnnotatedPriceQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| AnnotatedPrice {
+                        id: row.get(0),
+                        price: row.get(1),
+                    },
+                    mapper: |it| <AnnotatedPrice>::from(it),
+                }
+            }
+        }
+        /// ```sql
+        /// SELECT coalesce(price, 0) AS price FROM named
+        /// ```
+        ///
+        /// Source: `queries_inferred/inferred.sql:7`
+        pub fn computed_price() -> ComputedPriceStmt {
+            ComputedPriceStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT coalesce(price, 0) AS price FROM named",
+            ))
+        }
+        pub struct ComputedPriceStmt(cornucopia_sync::private::Stmt);
+        impl ComputedPriceStmt {
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> F64Query<'a, C, f64, 0> {
+                F64Query {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| row.get(0),
+                    mapper: |it| it,
+                }
+            }
+        }
+    }
+}
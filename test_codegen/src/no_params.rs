@@ -0,0 +1,192 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#![allow(clippy::all, dead_code)]
+pub mod types {}
+pub mod queries {
+    pub mod bare {
+        /// Source: `queries_no_params/bare.sql:7`
+        #[derive(Debug)]
+        pub struct CopyBareParams<T1: cornucopia_sync::StringSql> {
+            pub name: T1,
+            pub show: bool,
+        }
+        /// Source: `queries_no_params/bare.sql:4`
+        #[derive(Debug, Clone, PartialEq, Copy)]
+        pub struct BareByName {
+            pub id: i32,
+            pub show: bool,
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for BareByName {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                Ok(Self {
+                    id: row.try_get("id")?,
+                    show: row.try_get("show")?,
+                })
+            }
+        }
+        use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+        pub struct BareByNameQuery<'a, C: GenericClient, T, const N: usize> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> BareByName,
+            mapper: fn(BareByName) -> T,
+        }
+        impl<'a, C, T: 'a, const N: usize> BareByNameQuery<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(BareByName) -> R) -> BareByNameQuery<'a, C, R, N> {
+                BareByNameQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                }
+            }
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                let row = self.client.query_one(stmt, &self.params)?;
+                Ok((self.mapper)((self.extractor)(&row)))
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                self.iter()?.collect()
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                Ok(self
+                    .client
+                    .query_opt(stmt, &self.params)?
+                    .map(|row| (self.mapper)((self.extractor)(&row))))
+            }
+            pub fn iter(
+                self,
+            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
+            {
+                let stmt = self.stmt.prepare(self.client)?;
+                let it = self
+                    .client
+                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .iterator()
+                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                Ok(it)
+            }
+        }
+        /// ```sql
+        /// INSERT INTO named (name, price, show) VALUES ($1, $2, $3)
+        /// ```
+        ///
+        /// Source: `queries_no_params/bare.sql:1`
+        pub fn insert_bare() -> InsertBareStmt {
+            InsertBareStmt(cornucopia_sync::private::Stmt::new(
+                "INSERT INTO named (name, price, show) VALUES ($1, $2, $3)",
+            ))
+        }
+        pub struct InsertBareStmt(cornucopia_sync::private::Stmt);
+        impl InsertBareStmt {
+            pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+                &'a mut self,
+                client: &'a mut C,
+                name: &'a T1,
+                price: &'a Option<f64>,
+                show: &'a bool,
+            ) -> Result<u64, postgres::Error> {
+                let stmt = self.0.prepare(client)?;
+                client.execute(stmt, &[name, price, show])
+            }
+        }
+        /// ```sql
+        /// SELECT id, show FROM named WHERE name = $1 AND show = $2
+        /// ```
+        ///
+        /// Source: `queries_no_params/bare.sql:4`
+        pub fn bare_by_name() -> BareByNameStmt {
+            BareByNameStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT id, show FROM named WHERE name = $1 AND show = $2",
+            ))
+        }
+        pub struct BareByNameStmt(cornucopia_sync::private::Stmt);
+        impl BareByNameStmt {
+            pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+                &'a mut self,
+                client: &'a mut C,
+                name: &'a T1,
+                show: &'a bool,
+            ) -> BareByNameQuery<'a, C, BareByName, 2> {
+                BareByNameQuery {
+                    client,
+                    params: [name, show],
+                    stmt: &mut self.0,
+                    extractor: |row| BareByName {
+                        id: row.get(0),
+                        show: row.get(1),
+                    },
+                    mapper: |it| <BareByName>::from(it),
+                }
+            }
+        }
+        /// ```sql
+        /// INSERT INTO named (name, show) VALUES ($1, $2)
+        /// ```
+        ///
+        /// Source: `queries_no_params/bare.sql:7`
+        pub fn copy_bare() -> CopyBareStmt {
+            CopyBareStmt(cornucopia_sync::private::Stmt::new(
+                "INSERT INTO named (name, show) VALUES ($1, $2)",
+            ))
+        }
+        pub struct CopyBareStmt(cornucopia_sync::private::Stmt);
+        impl CopyBareStmt {
+            pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+                &'a mut self,
+                client: &'a mut C,
+                name: &'a T1,
+                show: &'a bool,
+            ) -> Result<u64, postgres::Error> {
+                let stmt = self.0.prepare(client)?;
+                client.execute(stmt, &[name, show])
+            }
+            /// Copies `rows` in bulk, encoded in `COPY`'s binary format, returning the number of copied rows.
+            pub fn copy_in<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+                &'a mut self,
+                client: &'a mut C,
+                rows: impl IntoIterator<Item = CopyBareParams<T1>>,
+            ) -> Result<u64, postgres::Error> {
+                let stmt = self.0.prepare(client)?;
+                let types = stmt.params().to_vec();
+                let sink = client.copy_in("COPY named (name, show) FROM STDIN (FORMAT binary)")?;
+                let mut writer = postgres::binary_copy::BinaryCopyInWriter::new(sink, &types);
+                for row in rows {
+                    let name = &row.name;
+                    let show = &row.show;
+                    writer.write(&[name, show])?;
+                }
+                writer.finish()
+            }
+        }
+        impl<'a, C: GenericClient, T1: cornucopia_sync::StringSql>
+            cornucopia_sync::Params<'a, CopyBareParams<T1>, Result<u64, postgres::Error>, C>
+            for CopyBareStmt
+        {
+            fn params(
+                &'a mut self,
+                client: &'a mut C,
+                params: &'a CopyBareParams<T1>,
+            ) -> Result<u64, postgres::Error> {
+                self.bind(client, &params.name, &params.show)
+            }
+        }
+        impl<'a, C: GenericClient, T1: cornucopia_sync::StringSql> cornucopia_sync::Execute<C>
+            for CopyBareParams<T1>
+        {
+            fn execute(&self, client: &mut C) -> Result<u64, postgres::Error> {
+                let name = &self.name;
+                let show = &self.show;
+                let mut stmt = copy_bare();
+                let stmt = stmt.0.prepare(client)?;
+                client.execute(stmt, &[name, show])
+            }
+        }
+    }
+}
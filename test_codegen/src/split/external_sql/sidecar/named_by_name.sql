@@ -0,0 +1 @@
+SELECT id, name FROM named WHERE name = $1
\ No newline at end of file
@@ -0,0 +1,2 @@
+SELECT * FROM (SELECT id FROM named WHERE name LIKE $1
+) AS page WHERE page."id" > $2 OR $2 IS NULL ORDER BY page."id" LIMIT $3
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT id FROM named WHERE name LIKE $1
\ No newline at end of file
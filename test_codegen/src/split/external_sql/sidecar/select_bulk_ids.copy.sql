@@ -0,0 +1 @@
+COPY (SELECT id FROM bulk ORDER BY id) TO STDOUT (FORMAT binary)
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#![allow(clippy::all, dead_code)]
+pub mod types;
+pub mod queries {
+    pub mod nested {
+        pub mod sidecar;
+    }
+    pub mod sidecar;
+}
@@ -0,0 +1,75 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+pub struct StringQuery<'a, C: GenericClient, T, const N: usize> {
+    client: &'a mut C,
+    params: [&'a (dyn postgres_types::ToSql + Sync); N],
+    stmt: &'a mut cornucopia_sync::private::Stmt,
+    extractor: fn(&postgres::Row) -> &str,
+    mapper: fn(&str) -> T,
+}
+impl<'a, C, T: 'a, const N: usize> StringQuery<'a, C, T, N>
+where
+    C: GenericClient,
+{
+    pub fn map<R>(self, mapper: fn(&str) -> R) -> StringQuery<'a, C, R, N> {
+        StringQuery {
+            client: self.client,
+            params: self.params,
+            stmt: self.stmt,
+            extractor: self.extractor,
+            mapper,
+        }
+    }
+    pub fn one(self) -> Result<T, postgres::Error> {
+        let stmt = self.stmt.prepare(self.client)?;
+        let row = self.client.query_one(stmt, &self.params)?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    }
+    pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+        self.iter()?.collect()
+    }
+    pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+        let stmt = self.stmt.prepare(self.client)?;
+        Ok(self
+            .client
+            .query_opt(stmt, &self.params)?
+            .map(|row| (self.mapper)((self.extractor)(&row))))
+    }
+    pub fn iter(
+        self,
+    ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error> {
+        let stmt = self.stmt.prepare(self.client)?;
+        let it = self
+            .client
+            .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+            .iterator()
+            .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+        Ok(it)
+    }
+}
+/// ```sql
+/// SELECT 'nested' AS text
+/// ```
+///
+/// Source: `queries_external/nested/sidecar.sql:1`
+pub fn nested_text() -> NestedTextStmt {
+    NestedTextStmt(cornucopia_sync::private::Stmt::new(include_str!(
+        "../../external_sql/nested/sidecar/nested_text.sql"
+    )))
+}
+pub struct NestedTextStmt(cornucopia_sync::private::Stmt);
+impl NestedTextStmt {
+    pub fn bind<'a, C: GenericClient>(
+        &'a mut self,
+        client: &'a mut C,
+    ) -> StringQuery<'a, C, String, 0> {
+        StringQuery {
+            client,
+            params: [],
+            stmt: &mut self.0,
+            extractor: |row| row.get(0),
+            mapper: |it| it.into(),
+        }
+    }
+}
@@ -0,0 +1,132 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#![allow(clippy::all, dead_code)]
+pub mod types {}
+pub mod queries {
+    pub mod accounts {
+        /// Source: `queries_sqitch/accounts.sql:1`
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct TenantAccounts {
+            pub id: i32,
+            pub email: String,
+            pub tenant: String,
+        }
+        pub struct TenantAccountsBorrowed<'a> {
+            pub id: i32,
+            pub email: &'a str,
+            pub tenant: &'a str,
+        }
+        impl<'a> From<TenantAccountsBorrowed<'a>> for TenantAccounts {
+            fn from(
+                TenantAccountsBorrowed { id, email, tenant }: TenantAccountsBorrowed<'a>,
+            ) -> Self {
+                Self {
+                    id,
+                    email: email.into(),
+                    tenant: tenant.into(),
+                }
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for TenantAccountsBorrowed<'a> {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                Ok(Self {
+                    id: row.try_get("id")?,
+                    email: row.try_get("email")?,
+                    tenant: row.try_get("tenant")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for TenantAccounts {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                <TenantAccountsBorrowed<'a> as cornucopia_sync::FromPgRow<'a>>::from_row(row)
+                    .map(Into::into)
+            }
+        }
+        use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+        pub struct TenantAccountsQuery<'a, C: GenericClient, T, const N: usize> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> TenantAccountsBorrowed,
+            mapper: fn(TenantAccountsBorrowed) -> T,
+        }
+        impl<'a, C, T: 'a, const N: usize> TenantAccountsQuery<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(
+                self,
+                mapper: fn(TenantAccountsBorrowed) -> R,
+            ) -> TenantAccountsQuery<'a, C, R, N> {
+                TenantAccountsQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                }
+            }
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                let row = self.client.query_one(stmt, &self.params)?;
+                Ok((self.mapper)((self.extractor)(&row)))
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                self.iter()?.collect()
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                Ok(self
+                    .client
+                    .query_opt(stmt, &self.params)?
+                    .map(|row| (self.mapper)((self.extractor)(&row))))
+            }
+            pub fn iter(
+                self,
+            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
+            {
+                let stmt = self.stmt.prepare(self.client)?;
+                let it = self
+                    .client
+                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .iterator()
+                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                Ok(it)
+            }
+        }
+        /// ```sql
+        /// SELECT accounts.id, email, tenants.name AS tenant
+        /// FROM accounts JOIN tenants ON tenants.id = accounts.tenant_id
+        /// WHERE tenants.id = $1
+        /// ```
+        ///
+        /// Source: `queries_sqitch/accounts.sql:1`
+        pub fn tenant_accounts() -> TenantAccountsStmt {
+            TenantAccountsStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT accounts.id, email, tenants.name AS tenant
+FROM accounts JOIN tenants ON tenants.id = accounts.tenant_id
+WHERE tenants.id = $1",
+            ))
+        }
+        pub struct TenantAccountsStmt(cornucopia_sync::private::Stmt);
+        impl TenantAccountsStmt {
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+                tenant_id: &'a i32,
+            ) -> TenantAccountsQuery<'a, C, TenantAccounts, 1> {
+                TenantAccountsQuery {
+                    client,
+                    params: [tenant_id],
+                    stmt: &mut self.0,
+                    extractor: |row| TenantAccountsBorrowed {
+                        id: row.get(0),
+                        email: row.get(1),
+                        tenant: row.get(2),
+                    },
+                    mapper: |it| <TenantAccounts>::from(it),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,509 @@
+// This file was generated with `cornucopia`. Do not modify.
+
+#![allow(clippy::all, dead_code)]
+pub mod types {}
+pub mod queries {
+    pub mod users {
+        /// Source: `queries_tenant/users.sql:4`
+        #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+        pub struct Users {
+            pub id: i32,
+            pub name: String,
+        }
+        pub struct UsersBorrowed<'a> {
+            pub id: i32,
+            pub name: &'a str,
+        }
+        impl<'a> From<UsersBorrowed<'a>> for Users {
+            fn from(UsersBorrowed { id, name }: UsersBorrowed<'a>) -> Self {
+                Self {
+                    id,
+                    name: name.into(),
+                }
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for UsersBorrowed<'a> {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                Ok(Self {
+                    id: row.try_get("id")?,
+                    name: row.try_get("name")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for Users {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                <UsersBorrowed<'a> as cornucopia_sync::FromPgRow<'a>>::from_row(row).map(Into::into)
+            }
+        }
+        /// Source: `queries_tenant/users.sql:7`
+        #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+        pub struct UserNotes {
+            pub name: String,
+            pub note: String,
+        }
+        pub struct UserNotesBorrowed<'a> {
+            pub name: &'a str,
+            pub note: &'a str,
+        }
+        impl<'a> From<UserNotesBorrowed<'a>> for UserNotes {
+            fn from(UserNotesBorrowed { name, note }: UserNotesBorrowed<'a>) -> Self {
+                Self {
+                    name: name.into(),
+                    note: note.into(),
+                }
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for UserNotesBorrowed<'a> {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                Ok(Self {
+                    name: row.try_get("name")?,
+                    note: row.try_get("note")?,
+                })
+            }
+        }
+        impl<'a> cornucopia_sync::FromPgRow<'a> for UserNotes {
+            fn from_row(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                <UserNotesBorrowed<'a> as cornucopia_sync::FromPgRow<'a>>::from_row(row)
+                    .map(Into::into)
+            }
+        }
+        use postgres::{fallible_iterator::FallibleIterator, GenericClient};
+        pub struct I32Query<'a, C: GenericClient, T, const N: usize, const MAPPED: bool = false> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> i32,
+            mapper: fn(i32) -> T,
+            id: &'static str,
+        }
+        impl<'a, C, T: 'a, const N: usize, const MAPPED: bool> I32Query<'a, C, T, N, MAPPED>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(i32) -> R) -> I32Query<'a, C, R, N, true> {
+                I32Query {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    id: self.id,
+                }
+            }
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                let row = self.client.query_one(stmt, &self.params)?;
+                Ok((self.mapper)((self.extractor)(&row)))
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                self.iter()?.collect()
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                Ok(self
+                    .client
+                    .query_opt(stmt, &self.params)?
+                    .map(|row| (self.mapper)((self.extractor)(&row))))
+            }
+            pub fn iter(
+                self,
+            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
+            {
+                let stmt = self.stmt.prepare(self.client)?;
+                let it = self
+                    .client
+                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .iterator()
+                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                Ok(it)
+            }
+        }
+        pub struct I32CachedQuery<'a, C: GenericClient, T, const N: usize> {
+            query: I32Query<'a, C, T, N>,
+            cache: &'a dyn cornucopia_sync::cache::QueryCache,
+        }
+        impl<'a, C, T: 'a, const N: usize> I32Query<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            /// Fetches the rows through `cache`, the database being only queried on a miss.
+            /// Mapped queries can't be cached, as their key doesn't identify the mapper:
+            /// map the fetched rows instead.
+            pub fn cached(
+                self,
+                cache: &'a dyn cornucopia_sync::cache::QueryCache,
+            ) -> I32CachedQuery<'a, C, T, N> {
+                I32CachedQuery { query: self, cache }
+            }
+        }
+        impl<'a, C, T: 'a + cornucopia_sync::cache::Cacheable, const N: usize> I32CachedQuery<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let query = self.query;
+                let key = cornucopia_sync::private::cache_key(
+                    query.client,
+                    query.stmt,
+                    query.id,
+                    "one",
+                    &query.params,
+                )?;
+                cornucopia_sync::private::read_through(self.cache, key, || query.one())
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let query = self.query;
+                let key = cornucopia_sync::private::cache_key(
+                    query.client,
+                    query.stmt,
+                    query.id,
+                    "opt",
+                    &query.params,
+                )?;
+                cornucopia_sync::private::read_through(self.cache, key, || query.opt())
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                let query = self.query;
+                let key = cornucopia_sync::private::cache_key(
+                    query.client,
+                    query.stmt,
+                    query.id,
+                    "all",
+                    &query.params,
+                )?;
+                cornucopia_sync::private::read_through(self.cache, key, || query.all())
+            }
+        }
+        pub struct UsersQuery<'a, C: GenericClient, T, const N: usize, const MAPPED: bool = false> {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> UsersBorrowed,
+            mapper: fn(UsersBorrowed) -> T,
+            id: &'static str,
+        }
+        impl<'a, C, T: 'a, const N: usize, const MAPPED: bool> UsersQuery<'a, C, T, N, MAPPED>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(self, mapper: fn(UsersBorrowed) -> R) -> UsersQuery<'a, C, R, N, true> {
+                UsersQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    id: self.id,
+                }
+            }
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                let row = self.client.query_one(stmt, &self.params)?;
+                Ok((self.mapper)((self.extractor)(&row)))
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                self.iter()?.collect()
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                Ok(self
+                    .client
+                    .query_opt(stmt, &self.params)?
+                    .map(|row| (self.mapper)((self.extractor)(&row))))
+            }
+            pub fn iter(
+                self,
+            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
+            {
+                let stmt = self.stmt.prepare(self.client)?;
+                let it = self
+                    .client
+                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .iterator()
+                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                Ok(it)
+            }
+        }
+        pub struct UsersCachedQuery<'a, C: GenericClient, T, const N: usize> {
+            query: UsersQuery<'a, C, T, N>,
+            cache: &'a dyn cornucopia_sync::cache::QueryCache,
+        }
+        impl<'a, C, T: 'a, const N: usize> UsersQuery<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            /// Fetches the rows through `cache`, the database being only queried on a miss.
+            /// Mapped queries can't be cached, as their key doesn't identify the mapper:
+            /// map the fetched rows instead.
+            pub fn cached(
+                self,
+                cache: &'a dyn cornucopia_sync::cache::QueryCache,
+            ) -> UsersCachedQuery<'a, C, T, N> {
+                UsersCachedQuery { query: self, cache }
+            }
+        }
+        impl<'a, C, T: 'a + cornucopia_sync::cache::Cacheable, const N: usize> UsersCachedQuery<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let query = self.query;
+                let key = cornucopia_sync::private::cache_key(
+                    query.client,
+                    query.stmt,
+                    query.id,
+                    "one",
+                    &query.params,
+                )?;
+                cornucopia_sync::private::read_through(self.cache, key, || query.one())
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let query = self.query;
+                let key = cornucopia_sync::private::cache_key(
+                    query.client,
+                    query.stmt,
+                    query.id,
+                    "opt",
+                    &query.params,
+                )?;
+                cornucopia_sync::private::read_through(self.cache, key, || query.opt())
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                let query = self.query;
+                let key = cornucopia_sync::private::cache_key(
+                    query.client,
+                    query.stmt,
+                    query.id,
+                    "all",
+                    &query.params,
+                )?;
+                cornucopia_sync::private::read_through(self.cache, key, || query.all())
+            }
+        }
+        pub struct UserNotesQuery<
+            'a,
+            C: GenericClient,
+            T,
+            const N: usize,
+            const MAPPED: bool = false,
+        > {
+            client: &'a mut C,
+            params: [&'a (dyn postgres_types::ToSql + Sync); N],
+            stmt: &'a mut cornucopia_sync::private::Stmt,
+            extractor: fn(&postgres::Row) -> UserNotesBorrowed,
+            mapper: fn(UserNotesBorrowed) -> T,
+            id: &'static str,
+        }
+        impl<'a, C, T: 'a, const N: usize, const MAPPED: bool> UserNotesQuery<'a, C, T, N, MAPPED>
+        where
+            C: GenericClient,
+        {
+            pub fn map<R>(
+                self,
+                mapper: fn(UserNotesBorrowed) -> R,
+            ) -> UserNotesQuery<'a, C, R, N, true> {
+                UserNotesQuery {
+                    client: self.client,
+                    params: self.params,
+                    stmt: self.stmt,
+                    extractor: self.extractor,
+                    mapper,
+                    id: self.id,
+                }
+            }
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                let row = self.client.query_one(stmt, &self.params)?;
+                Ok((self.mapper)((self.extractor)(&row)))
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                self.iter()?.collect()
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let stmt = self.stmt.prepare(self.client)?;
+                Ok(self
+                    .client
+                    .query_opt(stmt, &self.params)?
+                    .map(|row| (self.mapper)((self.extractor)(&row))))
+            }
+            pub fn iter(
+                self,
+            ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
+            {
+                let stmt = self.stmt.prepare(self.client)?;
+                let it = self
+                    .client
+                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
+                    .iterator()
+                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
+                Ok(it)
+            }
+        }
+        pub struct UserNotesCachedQuery<'a, C: GenericClient, T, const N: usize> {
+            query: UserNotesQuery<'a, C, T, N>,
+            cache: &'a dyn cornucopia_sync::cache::QueryCache,
+        }
+        impl<'a, C, T: 'a, const N: usize> UserNotesQuery<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            /// Fetches the rows through `cache`, the database being only queried on a miss.
+            /// Mapped queries can't be cached, as their key doesn't identify the mapper:
+            /// map the fetched rows instead.
+            pub fn cached(
+                self,
+                cache: &'a dyn cornucopia_sync::cache::QueryCache,
+            ) -> UserNotesCachedQuery<'a, C, T, N> {
+                UserNotesCachedQuery { query: self, cache }
+            }
+        }
+        impl<'a, C, T: 'a + cornucopia_sync::cache::Cacheable, const N: usize>
+            UserNotesCachedQuery<'a, C, T, N>
+        where
+            C: GenericClient,
+        {
+            pub fn one(self) -> Result<T, postgres::Error> {
+                let query = self.query;
+                let key = cornucopia_sync::private::cache_key(
+                    query.client,
+                    query.stmt,
+                    query.id,
+                    "one",
+                    &query.params,
+                )?;
+                cornucopia_sync::private::read_through(self.cache, key, || query.one())
+            }
+            pub fn opt(self) -> Result<Option<T>, postgres::Error> {
+                let query = self.query;
+                let key = cornucopia_sync::private::cache_key(
+                    query.client,
+                    query.stmt,
+                    query.id,
+                    "opt",
+                    &query.params,
+                )?;
+                cornucopia_sync::private::read_through(self.cache, key, || query.opt())
+            }
+            pub fn all(self) -> Result<Vec<T>, postgres::Error> {
+                let query = self.query;
+                let key = cornucopia_sync::private::cache_key(
+                    query.client,
+                    query.stmt,
+                    query.id,
+                    "all",
+                    &query.params,
+                )?;
+                cornucopia_sync::private::read_through(self.cache, key, || query.all())
+            }
+        }
+        /// ```sql
+        /// INSERT INTO tenant_template.users (name) VALUES ($1) RETURNING id
+        /// ```
+        ///
+        /// Source: `queries_tenant/users.sql:1`
+        pub fn insert_user() -> InsertUserStmt {
+            InsertUserStmt(cornucopia_sync::private::Stmt::new(
+                "INSERT INTO tenant_template.users (name) VALUES ($1) RETURNING id",
+            ))
+        }
+
+        /// Runs the query against the `schema` of a tenant instead of `tenant_template`.
+        pub fn insert_user_for(schema: &cornucopia_sync::tenancy::TenantSchema) -> InsertUserStmt {
+            InsertUserStmt(
+                cornucopia_sync::private::Stmt::new(
+                    "INSERT INTO tenant_template.users (name) VALUES ($1) RETURNING id",
+                )
+                .in_schema(&[12..27], schema),
+            )
+        }
+        pub struct InsertUserStmt(cornucopia_sync::private::Stmt);
+        impl InsertUserStmt {
+            pub fn bind<'a, C: GenericClient, T1: cornucopia_sync::StringSql>(
+                &'a mut self,
+                client: &'a mut C,
+                name: &'a T1,
+            ) -> I32Query<'a, C, i32, 1> {
+                I32Query {
+                    client,
+                    params: [name],
+                    stmt: &mut self.0,
+                    extractor: |row| row.get(0),
+                    mapper: |it| it,
+                    id: "users.insert_user",
+                }
+            }
+        }
+        /// ```sql
+        /// SELECT id, name FROM tenant_template.users ORDER BY id
+        /// ```
+        ///
+        /// Source: `queries_tenant/users.sql:4`
+        pub fn users() -> UsersStmt {
+            UsersStmt(cornucopia_sync::private::Stmt::new(
+                "SELECT id, name FROM tenant_template.users ORDER BY id",
+            ))
+        }
+
+        /// Runs the query against the `schema` of a tenant instead of `tenant_template`.
+        pub fn users_for(schema: &cornucopia_sync::tenancy::TenantSchema) -> UsersStmt {
+            UsersStmt(
+                cornucopia_sync::private::Stmt::new(
+                    "SELECT id, name FROM tenant_template.users ORDER BY id",
+                )
+                .in_schema(&[21..36], schema),
+            )
+        }
+        pub struct UsersStmt(cornucopia_sync::private::Stmt);
+        impl UsersStmt {
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> UsersQuery<'a, C, Users, 0> {
+                UsersQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| UsersBorrowed {
+                        id: row.get(0),
+                        name: row.get(1),
+                    },
+                    mapper: |it| <Users>::from(it),
+                    id: "users.users",
+                }
+            }
+        }
+        /// ```sql
+        /// SELECT name, 'tenant_template.users' AS note FROM "tenant_template".users /* tenant_template.users */ ORDER BY id
+        /// ```
+        ///
+        /// Source: `queries_tenant/users.sql:7`
+        pub fn user_notes() -> UserNotesStmt {
+            UserNotesStmt(cornucopia_sync::private::Stmt::new("SELECT name, 'tenant_template.users' AS note FROM \"tenant_template\".users /* tenant_template.users */ ORDER BY id"))
+        }
+
+        /// Runs the query against the `schema` of a tenant instead of `tenant_template`.
+        pub fn user_notes_for(schema: &cornucopia_sync::tenancy::TenantSchema) -> UserNotesStmt {
+            UserNotesStmt(cornucopia_sync::private::Stmt::new("SELECT name, 'tenant_template.users' AS note FROM \"tenant_template\".users /* tenant_template.users */ ORDER BY id").in_schema(&[50..67],
+    schema))
+        }
+        pub struct UserNotesStmt(cornucopia_sync::private::Stmt);
+        impl UserNotesStmt {
+            pub fn bind<'a, C: GenericClient>(
+                &'a mut self,
+                client: &'a mut C,
+            ) -> UserNotesQuery<'a, C, UserNotes, 0> {
+                UserNotesQuery {
+                    client,
+                    params: [],
+                    stmt: &mut self.0,
+                    extractor: |row| UserNotesBorrowed {
+                        name: row.get(0),
+                        note: row.get(1),
+                    },
+                    mapper: |it| <UserNotes>::from(it),
+                    id: "users.user_notes",
+                }
+            }
+        }
+    }
+}
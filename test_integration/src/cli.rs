@@ -0,0 +1,76 @@
+use std::io::Write;
+
+use clap::ValueEnum;
+use cornucopia::Shell;
+use owo_colors::OwoColorize;
+
+/// Output closed before anything could be written, like a pipe whose reader exited
+struct ClosedOutput;
+
+impl Write for ClosedOutput {
+    fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::ErrorKind::BrokenPipe.into())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Run CLI output test, return true if all test are successful
+pub(crate) fn run_cli_test() -> Result<bool, Box<dyn std::error::Error>> {
+    println!(
+        "{} {}",
+        "[cli]".magenta(),
+        "completions and man page".magenta()
+    );
+    let mut successful = true;
+    let mut check = |name: &str, result: Result<(), String>| match result {
+        Ok(()) => println!("{name} {}", "OK".green()),
+        Err(err) => {
+            successful = false;
+            println!("{name} {}\n{err}\n", "ERR".red());
+        }
+    };
+
+    for shell in Shell::value_variants() {
+        let mut script = Vec::new();
+        let result = match cornucopia::write_completions(*shell, &mut script) {
+            Ok(()) if String::from_utf8_lossy(&script).contains("completions") => Ok(()),
+            Ok(()) => Err(String::from_utf8_lossy(&script).into_owned()),
+            Err(err) => Err(err.report()),
+        };
+        check(&format!("Completions{shell:?}"), result);
+    }
+
+    let mut page = Vec::new();
+    let result = match cornucopia::write_man_page(&mut page) {
+        Ok(()) if String::from_utf8_lossy(&page).starts_with(".ie") => Ok(()),
+        Ok(()) => Err(String::from_utf8_lossy(&page).into_owned()),
+        Err(err) => Err(err.report()),
+    };
+    check("Man", result);
+
+    // Closed outputs are reported instead of panicking
+    let outputs = [
+        (
+            "CompletionsClosed",
+            cornucopia::write_completions(Shell::Bash, &mut ClosedOutput),
+            "Could not write the completion script",
+        ),
+        (
+            "ManClosed",
+            cornucopia::write_man_page(&mut ClosedOutput),
+            "Could not write the man page",
+        ),
+    ];
+    for (name, result, expected) in outputs {
+        let result = match result {
+            Err(err) if err.to_string().contains(expected) => Ok(()),
+            result => Err(format!("{result:?}")),
+        };
+        check(name, result);
+    }
+
+    Ok(successful)
+}
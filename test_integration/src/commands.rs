@@ -0,0 +1,721 @@
+use std::{
+    fs::File,
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use cornucopia::{CodegenSettings, DiagramFormat, ExportFormat};
+use owo_colors::OwoColorize;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+
+use crate::utils::reset_db;
+
+/// Run commands test, return true if all test are successful
+pub(crate) fn run_commands_test(
+    client: &mut postgres::Client,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    Ok(run_new_query_test()?
+        & run_export_sql_test(client)?
+        & run_bench_test(client)?
+        & run_export_test(client)?
+        & run_schema_info_test(client)?
+        & run_types_test(client)?
+        & run_watch_test(client)?
+        & run_drift_test(client)?
+        & run_diagram_test(client)?)
+}
+
+/// Prints the outcome of the test `name`, return true if it is successful
+fn display_case(name: &str, result: Result<(), String>) -> bool {
+    match result {
+        Ok(()) => {
+            println!("{name} {}", "OK".green());
+            true
+        }
+        Err(err) => {
+            println!("{name} {}\n{err}\n", "ERR".red());
+            false
+        }
+    }
+}
+
+/// Compares `expected` with what a command produced
+fn expect(expected: &str, got: &str) -> Result<(), String> {
+    if got == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "{}\n{expected}\n{}\n{got}",
+            "Expected:".bright_black(),
+            "Got:".bright_black(),
+        ))
+    }
+}
+
+fn run_new_query_test() -> Result<bool, Box<dyn std::error::Error>> {
+    println!("{} {}", "[commands]".magenta(), "new-query".magenta());
+    let temp_dir = tempfile::tempdir()?;
+    let queries = temp_dir.path().join("queries");
+    let mut successful = true;
+
+    let cases = [
+        (
+            "RowsStub",
+            "authors\nauthor_by_id\nid, name?\n\nid, name?\n",
+            "-- TODO: replace `table_name` with your own table
+--! author_by_id (name?) : (name?)
+SELECT id, name
+FROM table_name
+WHERE id = :id
+  AND name = :name;
+",
+        ),
+        (
+            // Stubs are appended to the query file of their module
+            "AppendedStub",
+            "authors\ninsert_author\nid, name\nn\n",
+            "-- TODO: replace `table_name` with your own table
+--! author_by_id (name?) : (name?)
+SELECT id, name
+FROM table_name
+WHERE id = :id
+  AND name = :name;
+
+-- TODO: replace `table_name` with your own table
+--! insert_author
+INSERT INTO table_name (id, name)
+VALUES (:id, :name);
+",
+        ),
+        (
+            // Invalid identifiers are asked again
+            "InvalidIdentifiers",
+            "books\nall-books\nall_books\n\nY\ntitle, author id\nauthor_id?\n",
+            "-- TODO: replace `table_name` with your own table
+--! all_books () : (author_id?)
+SELECT author_id
+FROM table_name;
+",
+        ),
+    ];
+    for (name, input, expected) in cases {
+        let mut output = Vec::new();
+        let result = cornucopia::new_query(&queries, &mut input.as_bytes(), &mut output)
+            .map_err(|err| err.report())
+            .and_then(|()| {
+                let module = input.lines().find(|it| !it.contains('-')).unwrap();
+                let got = std::fs::read_to_string(queries.join(format!("{module}.sql")))
+                    .map_err(|err| err.to_string())?;
+                expect(expected, &got)
+            });
+        successful &= display_case(name, result);
+    }
+
+    let name = "EndOfInput";
+    let result = match cornucopia::new_query(&queries, &mut "authors\n".as_bytes(), &mut Vec::new())
+    {
+        Err(err) if err.to_string().contains("<terminal>") => Ok(()),
+        result => Err(format!("{result:?}")),
+    };
+    successful &= display_case(name, result);
+
+    Ok(successful)
+}
+
+fn run_export_sql_test(client: &mut postgres::Client) -> Result<bool, Box<dyn std::error::Error>> {
+    println!("{} {}", "[commands]".magenta(), "export-sql".magenta());
+    reset_db(client)?;
+    client.batch_execute("CREATE TABLE author (id INT PRIMARY KEY, name TEXT);")?;
+    let temp_dir = tempfile::tempdir()?;
+    let queries = temp_dir.path().join("queries");
+    std::fs::create_dir(&queries)?;
+    std::fs::write(
+        queries.join("authors.sql"),
+        "--! authors\nSELECT id, name FROM author;\n\n--! insert_author (name?)\nINSERT INTO author (id, name) VALUES (:id, :name);",
+    )?;
+    let destination = temp_dir.path().join("queries.sql");
+    let expected = "-- This file was generated with `cornucopia`. Do not modify.
+-- It contains every SQL statement the application can run.
+
+-- authors::authors
+-- Rust: queries::authors::authors
+SELECT id, name FROM author;
+
+-- authors::insert_author
+-- Rust: queries::authors::insert_author
+--   $1 id (int4)
+--   $2 name (text, nullable)
+INSERT INTO author (id, name) VALUES ($1, $2);
+";
+
+    let name = "ExportSql";
+    let result = cornucopia::export_sql_live(
+        client,
+        &queries,
+        Some(&destination),
+        &CodegenSettings::default(),
+    )
+    .map_err(|err| err.report())
+    .and_then(|sql| {
+        // The file holds the returned SQL
+        let written = std::fs::read_to_string(&destination).map_err(|err| err.to_string())?;
+        expect(expected, &sql).and_then(|()| expect(expected, &written))
+    });
+    Ok(display_case(name, result))
+}
+
+fn run_bench_test(client: &mut postgres::Client) -> Result<bool, Box<dyn std::error::Error>> {
+    println!("{} {}", "[commands]".magenta(), "bench".magenta());
+    reset_db(client)?;
+    client.batch_execute(
+        "CREATE TABLE author (id INT PRIMARY KEY, name TEXT);
+        INSERT INTO author VALUES (1, 'Frank'), (2, 'Ursula'), (3, 'Frank');",
+    )?;
+    let temp_dir = tempfile::tempdir()?;
+    let queries = temp_dir.path().join("queries");
+    std::fs::create_dir(&queries)?;
+    std::fs::write(
+        queries.join("authors.sql"),
+        "--! authors_named\nSELECT id FROM author WHERE name = :name;\n\n--! insert_author\nINSERT INTO author (id, name) VALUES (:id, :name);",
+    )?;
+    let fixtures = temp_dir.path().join("fixtures.toml");
+    std::fs::write(
+        &fixtures,
+        "[\"authors::authors_named\"]\nname = \"Frank\"\n\n[\"authors::insert_author\"]\nid = 4\nname = \"Octavia\"",
+    )?;
+    let settings = CodegenSettings::default();
+    let mut successful = true;
+
+    let cases = [
+        ("SelectRows", "authors::authors_named", 10, 10, (2, 2)),
+        // Every iteration is rolled back, so the same row can be inserted each time
+        ("InsertRows", "authors::insert_author", 5, 5, (1, 1)),
+        // At least one iteration is needed to compute percentiles
+        ("NoIterations", "authors::authors_named", 0, 1, (2, 2)),
+    ];
+    for (name, query, iterations, expected_iterations, expected_rows) in cases {
+        let result = cornucopia::bench_live(
+            client,
+            &queries,
+            Some(&fixtures),
+            query,
+            iterations,
+            &settings,
+        )
+        .map_err(|err| err.report())
+        .and_then(|report| {
+            if report.query == query
+                && report.iterations == expected_iterations
+                && report.rows == expected_rows
+                && report.p50 <= report.p95
+                && report.p95 <= report.p99
+            {
+                Ok(())
+            } else {
+                Err(format!("{report:?}"))
+            }
+        });
+        successful &= display_case(name, result);
+    }
+
+    let name = "RolledBack";
+    let count: i64 = client.query_one("SELECT count(*) FROM author", &[])?.get(0);
+    successful &= display_case(name, expect("3", &count.to_string()));
+
+    let errors = [
+        (
+            "UnknownQuery",
+            Some(&fixtures),
+            "authors::missing",
+            "No query named `authors::missing`",
+        ),
+        (
+            "MissingFixtures",
+            None,
+            "authors::insert_author",
+            "Query `authors::insert_author` has parameters but no fixtures were given",
+        ),
+    ];
+    for (name, fixtures, query, expected) in errors {
+        let result = match cornucopia::bench_live(client, &queries, fixtures, query, 1, &settings) {
+            Err(err) if err.to_string() == expected => Ok(()),
+            Err(err) => Err(err.report()),
+            Ok(report) => Err(format!("{report:?}")),
+        };
+        successful &= display_case(name, result);
+    }
+
+    Ok(successful)
+}
+
+fn run_export_test(client: &mut postgres::Client) -> Result<bool, Box<dyn std::error::Error>> {
+    println!("{} {}", "[commands]".magenta(), "export".magenta());
+    reset_db(client)?;
+    client.batch_execute(
+        "CREATE TABLE reading (id INT2, at BIGINT, ok BOOL, value FLOAT8, note TEXT);
+        INSERT INTO reading VALUES
+            (1, 10, true, 0.5, 'first'),
+            (2, 20, false, 'NaN', NULL),
+            (3, 30, NULL, NULL, 'last');",
+    )?;
+    let temp_dir = tempfile::tempdir()?;
+    let queries = temp_dir.path().join("queries");
+    std::fs::create_dir(&queries)?;
+    std::fs::write(
+        queries.join("readings.sql"),
+        "--! readings_after\nSELECT id, at, ok, value, note FROM reading WHERE id > :id ORDER BY id;\n\n--! ratios\nSELECT 1 / (id - :id) AS ratio FROM reading;",
+    )?;
+    let fixtures = temp_dir.path().join("fixtures.toml");
+    std::fs::write(
+        &fixtures,
+        "[\"readings::readings_after\"]\nid = 0\n\n[\"readings::ratios\"]\nid = 2",
+    )?;
+    let out = temp_dir.path().join("readings.parquet");
+    let settings = CodegenSettings::default();
+    let mut successful = true;
+    let export = |client: &mut postgres::Client, query: &str| {
+        cornucopia::export_live(
+            client,
+            &queries,
+            Some(&fixtures),
+            query,
+            ExportFormat::Parquet,
+            &out,
+            &settings,
+        )
+        .map_err(Box::new)
+    };
+
+    let name = "Parquet";
+    let result = export(client, "readings::readings_after")
+        .map_err(|err| err.report())
+        .and_then(|nb_rows| {
+            // Numbers and booleans keep their type, other columns are strings
+            let rows = SerializedFileReader::new(File::open(&out).map_err(|e| e.to_string())?)
+                .and_then(|reader| reader.get_row_iter(None)?.collect::<Result<Vec<_>, _>>())
+                .map_err(|err| err.to_string())?;
+            let rows = rows.iter().map(ToString::to_string).collect::<Vec<_>>();
+            expect("3", &nb_rows.to_string()).and_then(|()| {
+                expect(
+                    r#"{id: 1, at: 10, ok: true, value: 0.5, note: "first"}
+{id: 2, at: 20, ok: false, value: NaN, note: null}
+{id: 3, at: 30, ok: null, value: null, note: "last"}"#,
+                    &rows.join("\n"),
+                )
+            })
+        });
+    successful &= display_case(name, result);
+
+    let errors = [
+        (
+            "UnknownQuery",
+            "readings::missing",
+            "No query named `readings::missing`",
+        ),
+        (
+            "FailingQuery",
+            "readings::ratios",
+            "Couldn't run query `readings::ratios`: db error: ERROR: division by zero",
+        ),
+    ];
+    for (name, query, expected) in errors {
+        let result = match export(client, query) {
+            Err(err) if err.to_string() == expected => Ok(()),
+            Err(err) => Err(err.report()),
+            Ok(nb_rows) => Err(format!("exported {nb_rows} rows")),
+        };
+        successful &= display_case(name, result);
+    }
+
+    // Failed exports don't leave their prepared statement behind
+    let name = "ExportAfterFailure";
+    let result = export(client, "readings::readings_after")
+        .map(drop)
+        .map_err(|err| err.report());
+    successful &= display_case(name, result);
+
+    Ok(successful)
+}
+
+/// Schema version generated by `--schema-info` in `code`
+fn generated_schema_version(code: &str) -> Option<&str> {
+    let prefix = "SCHEMA_VERSION: &str = \"";
+    let start = code.find(prefix)? + prefix.len();
+    let end = start + code[start..].find('"')?;
+    Some(&code[start..end])
+}
+
+/// Generates the `authors` queries against a schema created by `setup`
+fn generate_schema_info(
+    client: &mut postgres::Client,
+    queries: &Path,
+    setup: &str,
+) -> Result<String, String> {
+    reset_db(client)
+        .and_then(|()| client.batch_execute(&format!("CREATE TABLE author (id INT);\n{setup}")))
+        .map_err(|err| err.to_string())?;
+    let settings = CodegenSettings {
+        gen_schema_info: true,
+        ..CodegenSettings::default()
+    };
+    cornucopia::generate_live(client, queries, None, settings).map_err(|err| err.report())
+}
+
+fn run_schema_info_test(client: &mut postgres::Client) -> Result<bool, Box<dyn std::error::Error>> {
+    println!("{} {}", "[commands]".magenta(), "schema-info".magenta());
+    let temp_dir = tempfile::tempdir()?;
+    let queries = temp_dir.path().join("queries");
+    std::fs::create_dir(&queries)?;
+    std::fs::write(
+        queries.join("authors.sql"),
+        "--! authors\nSELECT id FROM author;",
+    )?;
+    let mut successful = true;
+
+    let cases = [
+        (
+            // The latest applied migration is the version
+            "MigrationVersion",
+            "CREATE TABLE _sqlx_migrations (version BIGINT);
+            INSERT INTO _sqlx_migrations VALUES (20240101), (20230101);",
+            "20240101",
+            r#"db_schema_info{version="20240101"} 1"#,
+        ),
+        (
+            // Label values are escaped in the OpenMetrics sample
+            "EscapedVersion",
+            r#"CREATE TABLE __diesel_schema_migrations (version TEXT);
+            INSERT INTO __diesel_schema_migrations VALUES ('2024"rc\1');"#,
+            r#"2024"rc\1"#,
+            r#"db_schema_info{version="2024\"rc\\1"} 1"#,
+        ),
+    ];
+    for (name, setup, version, sample) in cases {
+        let result = generate_schema_info(client, &queries, setup).and_then(|code| {
+            let version = format!("SCHEMA_VERSION: &str = {version:?}");
+            let sample = format!("{sample:?}");
+            if code.contains(&version) && code.contains(&sample) {
+                Ok(())
+            } else {
+                Err(format!("missing `{version}` or {sample}"))
+            }
+        });
+        successful &= display_case(name, result);
+    }
+
+    // Without applied migrations, the version fingerprints the schema
+    let name = "Fingerprint";
+    let empty_history = "CREATE TABLE _sqlx_migrations (version BIGINT);";
+    let result = [
+        empty_history,
+        empty_history,
+        "ALTER TABLE author ADD COLUMN name TEXT;",
+    ]
+    .map(|setup| generate_schema_info(client, &queries, setup))
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()
+    .and_then(|codes| {
+        match codes
+            .iter()
+            .map(|code| generated_schema_version(code))
+            .collect::<Vec<_>>()[..]
+        {
+            [Some(first), Some(again), Some(changed)]
+                if first.len() == 16
+                    && first.chars().all(|c| c.is_ascii_hexdigit())
+                    && first == again
+                    && first != changed =>
+            {
+                Ok(())
+            }
+            ref versions => Err(format!("{versions:?}")),
+        }
+    });
+    successful &= display_case(name, result);
+
+    Ok(successful)
+}
+
+fn run_types_test(client: &mut postgres::Client) -> Result<bool, Box<dyn std::error::Error>> {
+    println!("{} {}", "[commands]".magenta(), "types".magenta());
+    let temp_dir = tempfile::tempdir()?;
+    let queries = temp_dir.path().join("queries");
+    std::fs::create_dir(&queries)?;
+    std::fs::write(queries.join("items.sql"), "--! items\nSELECT * FROM item;")?;
+    let settings = CodegenSettings::default();
+    let dump = |client: &mut postgres::Client, schema: &str| {
+        reset_db(client)
+            .and_then(|()| client.batch_execute(schema))
+            .map_err(|err| err.to_string())?;
+        cornucopia::dump_types_live(client, &queries, &settings).map_err(|err| err.report())
+    };
+    let schema = "CREATE SCHEMA extra;
+        CREATE TYPE mood AS ENUM ('sad', 'happy');
+        CREATE TYPE spot AS (x INT, label TEXT);
+        CREATE TYPE extra.tag AS ENUM ('new');
+        CREATE TABLE item (mood mood, spot spot, tag extra.tag);";
+    let mut successful = true;
+
+    let name = "Dump";
+    let previous = dump(client, schema);
+    let result = previous
+        .as_deref()
+        .map_err(Clone::clone)
+        .and_then(|previous| {
+            expect(
+                "# Custom types registered by `cornucopia`
+enum extra.tag -> extra::Tag
+    new -> new
+enum public.mood -> public::Mood
+    sad -> sad
+    happy -> happy
+composite public.spot -> public::Spot
+    x: i32
+    label: String
+",
+                previous,
+            )
+        });
+    successful &= display_case(name, result);
+    let previous = previous.unwrap_or_default();
+
+    let cases = [
+        ("DiffUnchanged", schema.to_string(), ""),
+        (
+            "DiffChanged",
+            schema
+                .replace("'happy'", "'happy', 'angry'")
+                .replace("(x INT", "(x BIGINT")
+                .replace("label TEXT", "label VARCHAR, z INT")
+                .replace("CREATE TYPE extra.tag AS ENUM ('new');", "CREATE TYPE extra.tag AS ENUM ('new');\nCREATE TYPE extra.level AS ENUM ('low');")
+                .replace("tag extra.tag", "tag extra.tag, level extra.level"),
+            "+ enum extra.level -> extra::Level
+~ enum public.mood -> public::Mood
+    + angry -> angry
+~ composite public.spot -> public::Spot
+    - x: i32
+    + x: i64
+    + z: i32",
+        ),
+        (
+            "DiffRemoved",
+            schema
+                .replace("CREATE TYPE extra.tag AS ENUM ('new');", "")
+                .replace(", tag extra.tag", ""),
+            "- enum extra.tag -> extra::Tag",
+        ),
+    ];
+    for (name, schema, expected) in cases {
+        let result = dump(client, &schema).and_then(|current| {
+            expect(
+                expected,
+                &cornucopia::diff_types(&previous, &current).join("\n"),
+            )
+        });
+        successful &= display_case(name, result);
+    }
+
+    Ok(successful)
+}
+
+/// Longest time a watch is given to regenerate the code
+const WATCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Waits until the code at `destination` satisfies `done`, return false on timeout
+fn wait_for_code(destination: &Path, done: impl Fn(&str) -> bool) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < WATCH_TIMEOUT {
+        if std::fs::read_to_string(destination).is_ok_and(|code| done(&code)) {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    false
+}
+
+fn run_watch_test(client: &mut postgres::Client) -> Result<bool, Box<dyn std::error::Error>> {
+    println!("{} {}", "[commands]".magenta(), "watch".magenta());
+    reset_db(client)?;
+    client.batch_execute("CREATE TABLE author (id INT, name TEXT);")?;
+    let temp_dir = tempfile::tempdir()?;
+    let queries = temp_dir.path().join("queries");
+    std::fs::create_dir(&queries)?;
+    let query_file = queries.join("authors.sql");
+    std::fs::write(&query_file, "--! authors\nSELECT id FROM author;")?;
+    let destination = temp_dir.path().join("cornucopia.rs");
+    let settings = CodegenSettings::default();
+    let stop = AtomicBool::new(false);
+    let mut successful = true;
+
+    std::thread::scope(|scope| -> Result<(), Box<dyn std::error::Error>> {
+        let watch = scope.spawn(|| {
+            cornucopia::watch_live(client, &queries, &destination, &settings, None, &stop)
+                .map_err(Box::new)
+        });
+
+        let name = "InitialGeneration";
+        let generated = wait_for_code(&destination, |code| code.contains("fn authors"));
+        successful &= display_case(name, generated.then_some(()).ok_or("timed out".into()));
+
+        let name = "RegenerateOnChange";
+        std::fs::write(
+            &query_file,
+            "--! authors\nSELECT id FROM author;\n\n--! author_names\nSELECT name FROM author;",
+        )?;
+        let generated = wait_for_code(&destination, |code| code.contains("fn author_names"));
+        successful &= display_case(name, generated.then_some(()).ok_or("timed out".into()));
+
+        // Invalid queries are reported without stopping the watch
+        let name = "RecoverFromError";
+        std::fs::write(&query_file, "--! broken\nSELECT missing FROM author;")?;
+        std::thread::sleep(Duration::from_secs(1));
+        std::fs::write(&query_file, "--! author_ids\nSELECT id FROM author;")?;
+        let generated = wait_for_code(&destination, |code| {
+            code.contains("fn author_ids") && !code.contains("fn author_names")
+        });
+        successful &= display_case(name, generated.then_some(()).ok_or("timed out".into()));
+
+        let name = "Stop";
+        stop.store(true, Ordering::Relaxed);
+        let result = match watch.join() {
+            Ok(result) => result.map_err(|err| err.report()),
+            Err(_) => Err("watch panicked".to_string()),
+        };
+        successful &= display_case(name, result);
+        Ok(())
+    })?;
+
+    Ok(successful)
+}
+
+fn run_drift_test(client: &mut postgres::Client) -> Result<bool, Box<dyn std::error::Error>> {
+    println!("{} {}", "[commands]".magenta(), "check generated".magenta());
+    reset_db(client)?;
+    client.batch_execute("CREATE TABLE author (id INT, name TEXT);")?;
+    let temp_dir = tempfile::tempdir()?;
+    let queries = temp_dir.path().join("queries");
+    std::fs::create_dir(&queries)?;
+    std::fs::write(
+        queries.join("authors.sql"),
+        "--! authors\nSELECT id FROM author;",
+    )?;
+    let destination = temp_dir.path().join("cornucopia.rs");
+    let check = |client: &mut postgres::Client| {
+        cornucopia::check_generated_live(client, &queries, &destination, CodegenSettings::default())
+            .map_err(Box::new)
+    };
+    let mut successful = true;
+
+    let name = "UpToDate";
+    let code = cornucopia::generate_live(
+        client,
+        &queries,
+        Some(&destination),
+        CodegenSettings::default(),
+    )?;
+    let result = check(client).map_err(|err| err.report());
+    successful &= display_case(name, result);
+
+    // The lines that differ are reported along with the file
+    let name = "DriftedLines";
+    std::fs::write(
+        &destination,
+        code.replace("fn authors", "fn renamed_authors"),
+    )?;
+    let result = match check(client) {
+        Ok(()) => Err("no drift reported".to_string()),
+        Err(err) => {
+            let report = err.report();
+            let drifted = report.contains("- pub fn renamed_authors()")
+                && report.contains("+ pub fn authors()");
+            drifted.then_some(()).ok_or(report)
+        }
+    };
+    successful &= display_case(name, result);
+
+    let name = "MissingFile";
+    std::fs::remove_file(&destination)?;
+    let result = match check(client) {
+        Ok(()) => Err("no drift reported".to_string()),
+        Err(err) => {
+            let report = err.report();
+            report
+                .contains("cornucopia.rs (missing)")
+                .then_some(())
+                .ok_or(report)
+        }
+    };
+    successful &= display_case(name, result);
+
+    Ok(successful)
+}
+
+fn run_diagram_test(client: &mut postgres::Client) -> Result<bool, Box<dyn std::error::Error>> {
+    println!("{} {}", "[commands]".magenta(), "schema diagram".magenta());
+    reset_db(client)?;
+    client.batch_execute(
+        r#"CREATE SCHEMA shop;
+        CREATE TABLE author (id INT PRIMARY KEY, name TEXT NOT NULL);
+        CREATE TABLE book (
+            id INT PRIMARY KEY,
+            author_id INT NOT NULL REFERENCES author,
+            "co""author" INT,
+            CONSTRAINT "co\author ""fk""" FOREIGN KEY ("co""author") REFERENCES author
+        );
+        CREATE TABLE shop.sale (book_id INT REFERENCES book, at DATE NOT NULL)
+            PARTITION BY RANGE (at);
+        CREATE TABLE shop.sale_2024 PARTITION OF shop.sale
+            FOR VALUES FROM ('2024-01-01') TO ('2025-01-01');"#,
+    )?;
+    let mut successful = true;
+
+    let cases = [
+        (
+            // Quotes and backslashes are escaped in identifiers and labels, partitions skipped
+            "Dot",
+            DiagramFormat::Dot,
+            r#"digraph schema {
+    rankdir=LR;
+    node [shape=plaintext];
+    "public.author" [label=<<table border="0" cellborder="1" cellspacing="0"><tr><td bgcolor="lightgrey"><b>author</b></td></tr><tr><td port="id" align="left">id: integer PK</td></tr><tr><td port="name" align="left">name: text</td></tr></table>>];
+    "public.book" [label=<<table border="0" cellborder="1" cellspacing="0"><tr><td bgcolor="lightgrey"><b>book</b></td></tr><tr><td port="id" align="left">id: integer PK</td></tr><tr><td port="author_id" align="left">author_id: integer FK</td></tr><tr><td port="co&quot;author" align="left">co&quot;author: integer? FK</td></tr></table>>];
+    "shop.sale" [label=<<table border="0" cellborder="1" cellspacing="0"><tr><td bgcolor="lightgrey"><b>shop.sale</b></td></tr><tr><td port="book_id" align="left">book_id: integer? FK</td></tr><tr><td port="at" align="left">at: date</td></tr></table>>];
+    "public.book":"author_id" -> "public.author":"id" [label="book_author_id_fkey"];
+    "public.book":"co\"author" -> "public.author":"id" [label="co\\author \"fk\"", style=dashed];
+    "shop.sale":"book_id" -> "public.book":"id" [label="sale_book_id_fkey", style=dashed];
+}
+"#,
+        ),
+        (
+            "Mermaid",
+            DiagramFormat::Mermaid,
+            r#"erDiagram
+    author {
+        integer id PK
+        text name
+    }
+    book {
+        integer id PK
+        integer author_id FK
+        integer co_author FK "nullable"
+    }
+    shop_sale {
+        integer book_id FK "nullable"
+        date at
+    }
+    author ||--o{ book : "book_author_id_fkey"
+    author |o--o{ book : "co\author #quot;fk#quot;"
+    book |o--o{ shop_sale : "sale_book_id_fkey"
+"#,
+        ),
+    ];
+    for (name, format, expected) in cases {
+        let result = cornucopia::schema_diagram_live(client, format)
+            .map_err(|err| err.report())
+            .and_then(|diagram| expect(expected, &diagram));
+        successful &= display_case(name, result);
+    }
+
+    Ok(successful)
+}
@@ -0,0 +1,85 @@
+use owo_colors::OwoColorize;
+
+/// Generated API the compatibility tests start from
+const PREVIOUS: &str = r#"
+pub mod queries {
+    pub mod author {
+        pub struct AuthorParams<T1: AsRef<str>> {
+            pub id: i32,
+            pub name: T1,
+        }
+        pub struct Author {
+            pub id: i32,
+            pub name: String,
+        }
+        pub fn insert_author(id: &i32, name: &str) -> u64 {
+            0
+        }
+        pub fn authors() -> Vec<Author> {
+            Vec::new()
+        }
+    }
+}
+"#;
+
+/// Run compatibility report test, return true if all test are successful
+pub(crate) fn run_compat_test() -> Result<bool, Box<dyn std::error::Error>> {
+    println!("{} {}", "[compat]".magenta(), "breaking changes".magenta());
+    let cases = [
+        ("Unchanged", PREVIOUS.to_string(), vec![]),
+        (
+            // Callers passing arguments by position silently swap them
+            "MovedParameters",
+            PREVIOUS.replace(
+                "insert_author(id: &i32, name: &str)",
+                "insert_author(name: &str, id: &i32)",
+            ),
+            vec![
+                "moved parameter `id` of `queries::author::insert_author` from position 1 to 2",
+                "moved parameter `name` of `queries::author::insert_author` from position 2 to 1",
+            ],
+        ),
+        (
+            "ChangedTypes",
+            PREVIOUS
+                .replace("id: &i32, name", "id: &i64, name")
+                .replace("pub name: String", "pub name: Option<String>"),
+            vec![
+                "changed type of parameter `id` in `queries::author::insert_author` from `&i32` to `&i64`",
+                "changed type of field `name` in `queries::author::Author` from `String` to `Option<String>`",
+            ],
+        ),
+        (
+            "ChangedReturnType",
+            PREVIOUS.replace("-> Vec<Author>", "-> Option<Author>"),
+            vec![
+                "changed return type of `queries::author::authors` from `Vec<Author>` to `Option<Author>`",
+            ],
+        ),
+    ];
+
+    let mut successful = true;
+    for (name, new, expected) in cases {
+        let changes = match cornucopia::compat_report(PREVIOUS, &new) {
+            Ok(()) => String::new(),
+            Err(err) => err.report(),
+        };
+        let missing: Vec<_> = expected
+            .iter()
+            .filter(|change| !changes.contains(*change))
+            .collect();
+        if missing.is_empty() && changes.is_empty() == expected.is_empty() {
+            println!("{name} {}", "OK".green());
+        } else {
+            successful = false;
+            println!(
+                "{name} {}\n{}\n{expected:#?}\n{}\n{changes}\n",
+                "ERR".red(),
+                "Expected:".bright_black(),
+                "Got:".bright_black(),
+            );
+        }
+    }
+
+    Ok(successful)
+}
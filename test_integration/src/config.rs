@@ -0,0 +1,142 @@
+use std::path::PathBuf;
+
+use cornucopia::Config;
+use owo_colors::OwoColorize;
+
+/// Configuration file declaring profiles, shared by the profile tests
+const PROFILES: &str = r#"
+queries-path = "queries/"
+sync = true
+serialize = true
+search-path = ["app", "public"]
+
+[profile.ci]
+podman = true
+queries-path = "ci/queries/"
+
+[profile.plain]
+serialize = false
+search-path = ["public"]
+"#;
+
+/// Run configuration test, return true if all test are successful
+pub(crate) fn run_config_test() -> Result<bool, Box<dyn std::error::Error>> {
+    println!("{} {}", "[config]".magenta(), "profiles".magenta());
+    let temp_dir = tempfile::tempdir()?;
+    let path = temp_dir.path().join("cornucopia.toml");
+    std::fs::write(&path, PROFILES)?;
+    let base = Config {
+        queries_path: Some(PathBuf::from("queries/")),
+        sync: Some(true),
+        serialize: Some(true),
+        search_path: Some(vec!["app".to_string(), "public".to_string()]),
+        ..Config::default()
+    };
+
+    let cases = [
+        ("NoProfile", None, base.clone()),
+        (
+            // Profiles only override the keys they set
+            "ProfileOverrides",
+            Some("ci"),
+            Config {
+                podman: Some(true),
+                queries_path: Some(PathBuf::from("ci/queries/")),
+                ..base.clone()
+            },
+        ),
+        (
+            // Unlike flags, profiles disable switches, and replace lists as a whole
+            "ProfileDisables",
+            Some("plain"),
+            Config {
+                serialize: Some(false),
+                search_path: Some(vec!["public".to_string()]),
+                ..base.clone()
+            },
+        ),
+    ];
+
+    let mut successful = true;
+    for (name, profile, expected) in cases {
+        match Config::load_profile(&path, profile) {
+            Ok(config) if config == expected => println!("{name} {}", "OK".green()),
+            Ok(config) => {
+                successful = false;
+                println!(
+                    "{name} {}\n{}\n{expected:?}\n{}\n{config:?}\n",
+                    "ERR".red(),
+                    "Expected:".bright_black(),
+                    "Got:".bright_black(),
+                );
+            }
+            Err(err) => {
+                successful = false;
+                println!("{name} {}\n{err}\n", "ERR".red());
+            }
+        }
+    }
+
+    let name = "UnknownProfile";
+    match Config::load_profile(&path, Some("staging")) {
+        Err(err) if err.to_string().contains("has no `staging` profile") => {
+            println!("{name} {}", "OK".green());
+        }
+        result => {
+            successful = false;
+            println!("{name} {}\n{result:?}\n", "ERR".red());
+        }
+    }
+
+    // Merging overrides the keys the other configuration sets, switches included
+    let file = Config {
+        workspace_dependencies: Some(true),
+        forbid_unsafe: Some(true),
+        ..base.clone()
+    };
+    let merge_cases = [
+        ("MergeUnset", Config::default(), file.clone()),
+        (
+            "MergeEnables",
+            Config {
+                pool: Some(true),
+                ..Config::default()
+            },
+            Config {
+                pool: Some(true),
+                ..file.clone()
+            },
+        ),
+        (
+            "MergeDisables",
+            Config {
+                serialize: Some(false),
+                workspace_dependencies: Some(false),
+                forbid_unsafe: Some(false),
+                ..Config::default()
+            },
+            Config {
+                serialize: Some(false),
+                workspace_dependencies: Some(false),
+                forbid_unsafe: Some(false),
+                ..file.clone()
+            },
+        ),
+    ];
+    for (name, other, expected) in merge_cases {
+        let merged = file.clone().merge(other);
+        if merged == expected {
+            println!("{name} {}", "OK".green());
+        } else {
+            successful = false;
+            println!(
+                "{name} {}\n{}\n{expected:?}\n{}\n{merged:?}\n",
+                "ERR".red(),
+                "Expected:".bright_black(),
+                "Got:".bright_black(),
+            );
+        }
+    }
+
+    Ok(successful)
+}
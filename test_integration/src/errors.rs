@@ -14,10 +14,20 @@ pub(crate) fn run_errors_test(
     let mut successful = true;
     let original_pwd = std::env::current_dir().unwrap();
     let test_suites = TestSuite::<ErrorTest>::read("fixtures/errors");
+    let server_version: i32 = client
+        .query_one("SELECT current_setting('server_version_num')::int", &[])?
+        .get(0);
 
     for mut suite in test_suites {
         println!("{} {}", "[error]".magenta(), suite.name.magenta());
         for test in suite.tests.iter_mut() {
+            if test
+                .max_version
+                .is_some_and(|max| server_version >= max * 10_000)
+            {
+                println!("{} {}", test.name, "SKIPPED".yellow());
+                continue;
+            }
             // Reset db
             reset_db(client)?;
 
@@ -38,23 +48,42 @@ pub(crate) fn run_errors_test(
             )?;
 
             // Generate queries files
-            std::fs::create_dir("queries")?;
+            let queries_dir = match &test.dir {
+                Some(dir) => format!("queries/{dir}"),
+                None => "queries".to_string(),
+            };
+            std::fs::create_dir_all(&queries_dir)?;
             std::fs::write(
-                "queries/test.sql",
+                format!("{queries_dir}/test.sql"),
                 test.query.as_deref().unwrap_or_default(),
             )?;
 
+            // Generate parameter fixtures
+            if let Some(fixtures) = &test.fixtures {
+                std::fs::write("fixtures.toml", fixtures)?;
+            }
+
             // Run codegen
-            let result = cornucopia::load_schema(client, &["schema.sql"])
-                .map_err(Error::from)
-                .and_then(|_| {
-                    cornucopia::generate_live(
-                        client,
-                        "queries",
-                        None,
-                        CodegenSettings::from(&*test),
-                    )
-                });
+            let result = match cornucopia::load_schema(client, &["schema.sql"]) {
+                Err(err) => Err(Error::from(err)),
+                Ok(()) => {
+                    if let Some(role) = &test.role {
+                        client.batch_execute(&format!("SET ROLE {role}"))?;
+                    }
+                    let settings = CodegenSettings::from(&*test);
+                    if test.check {
+                        cornucopia::check_live(
+                            client,
+                            "queries",
+                            test.fixtures.as_ref().map(|_| "fixtures.toml"),
+                            &settings,
+                        )
+                    } else {
+                        cornucopia::generate_live(client, "queries", None, settings).map(drop)
+                    }
+                }
+            };
+            client.batch_execute("RESET ROLE")?;
 
             let err = result.unwrap_err().report();
             let err_trimmed = err.trim();
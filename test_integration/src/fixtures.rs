@@ -1,9 +1,11 @@
 use std::{
+    collections::BTreeMap,
     error::Error,
     path::{Path, PathBuf},
 };
 
-use cornucopia::CodegenSettings;
+use clap::ValueEnum;
+use cornucopia::{CodegenSettings, DecimalCrate, ParamSyntax, TimeCrate};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -60,6 +62,79 @@ pub(crate) struct CodegenTest {
     #[serde(default)]
     pub(crate) derive_ser: bool,
     #[serde(default)]
+    pub(crate) pool: bool,
+    #[serde(default)]
+    pub(crate) tests: bool,
+    #[serde(default)]
+    pub(crate) registry: bool,
+    #[serde(default)]
+    pub(crate) partitions: bool,
+    #[serde(default)]
+    pub(crate) normalize_sql: bool,
+    #[serde(default)]
+    pub(crate) external_sql: Option<String>,
+    #[serde(default)]
+    pub(crate) split_modules: bool,
+    #[serde(default)]
+    pub(crate) crate_name: Option<String>,
+    #[serde(default)]
+    pub(crate) statement_prefix: Option<String>,
+    #[serde(default)]
+    pub(crate) workspace_dependencies: bool,
+    #[serde(default)]
+    pub(crate) obfuscate_sql: bool,
+    #[serde(default)]
+    pub(crate) replicas: bool,
+    #[serde(default)]
+    pub(crate) maps: bool,
+    #[serde(default)]
+    pub(crate) csv: bool,
+    #[serde(default)]
+    pub(crate) arrow: bool,
+    #[serde(default)]
+    pub(crate) sqlx: bool,
+    #[serde(default)]
+    pub(crate) diesel: bool,
+    #[serde(default)]
+    pub(crate) explain: bool,
+    #[serde(default)]
+    pub(crate) statement_cache: bool,
+    #[serde(default)]
+    pub(crate) facade: bool,
+    #[serde(default)]
+    pub(crate) schema_info: bool,
+    #[serde(default)]
+    pub(crate) benches: bool,
+    #[serde(default)]
+    pub(crate) infer_nullability: bool,
+    #[serde(default)]
+    pub(crate) no_params_structs: bool,
+    #[serde(default)]
+    pub(crate) params_struct_threshold: Option<usize>,
+    #[serde(default)]
+    pub(crate) cache: bool,
+    #[serde(default)]
+    pub(crate) domain_checks: bool,
+    #[serde(default)]
+    pub(crate) text_limits: bool,
+    /// Schema files or migration folders loaded before generating, `schema.sql` by default
+    #[serde(default)]
+    pub(crate) schema_files: Vec<String>,
+    #[serde(default)]
+    pub(crate) tenant_schema: Option<String>,
+    #[serde(default)]
+    pub(crate) search_path: Vec<String>,
+    #[serde(default)]
+    pub(crate) row_derives: Vec<String>,
+    #[serde(default)]
+    pub(crate) time_crate: TimeCrate,
+    #[serde(default)]
+    pub(crate) decimal_crate: DecimalCrate,
+    #[serde(default)]
+    pub(crate) lint_allows: Option<Vec<String>>,
+    #[serde(default)]
+    pub(crate) forbid_unsafe: bool,
+    #[serde(default)]
     pub(crate) run: bool,
 }
 
@@ -77,25 +152,106 @@ impl From<&CodegenTest> for CodegenSettings {
             gen_async: codegen_test.r#async || !codegen_test.sync,
             gen_sync: codegen_test.sync,
             derive_ser: codegen_test.derive_ser,
+            gen_pool: codegen_test.pool,
+            statement_prefix: codegen_test.statement_prefix.clone(),
+            param_syntax: ParamSyntax::Colon,
+            gen_tests: codegen_test.tests,
+            gen_registry: codegen_test.registry,
+            gen_partitions: codegen_test.partitions,
+            normalize_sql: codegen_test.normalize_sql,
+            external_sql: codegen_test.external_sql.clone(),
+            split_modules: codegen_test.split_modules,
+            crate_name: codegen_test.crate_name.clone(),
+            workspace_dependencies: codegen_test.workspace_dependencies,
+            obfuscate_sql: codegen_test.obfuscate_sql,
+            gen_replicas: codegen_test.replicas,
+            gen_maps: codegen_test.maps,
+            gen_csv: codegen_test.csv,
+            gen_arrow: codegen_test.arrow,
+            gen_sqlx: codegen_test.sqlx,
+            gen_diesel: codegen_test.diesel,
+            gen_explain: codegen_test.explain,
+            gen_statement_cache: codegen_test.statement_cache,
+            gen_facade: codegen_test.facade,
+            gen_schema_info: codegen_test.schema_info,
+            gen_benches: codegen_test.benches,
+            infer_nullability: codegen_test.infer_nullability,
+            gen_params_structs: !codegen_test.no_params_structs,
+            params_struct_threshold: codegen_test.params_struct_threshold,
+            gen_cache: codegen_test.cache,
+            gen_domain_checks: codegen_test.domain_checks,
+            gen_text_limits: codegen_test.text_limits,
+            tenant_schema: codegen_test.tenant_schema.clone(),
+            search_path: codegen_test.search_path.clone(),
+            row_derives: codegen_test.row_derives.clone(),
+            time_crate: codegen_test.time_crate,
+            decimal_crate: codegen_test.decimal_crate,
+            lint_allows: codegen_test.lint_allows.clone(),
+            forbid_unsafe: codegen_test.forbid_unsafe,
         }
     }
 }
 
+/// Migrations test case
+#[derive(Debug, Deserialize)]
+pub(crate) struct MigrationTest {
+    pub(crate) name: String,
+    /// Content of the files of the migrations folder, by path
+    pub(crate) files: BTreeMap<String, String>,
+    /// Tables of the database once the migrations are loaded, sorted by name
+    pub(crate) tables: Vec<String>,
+}
+
 /// Error test case
 #[derive(Debug, Deserialize, Serialize)]
 pub(crate) struct ErrorTest {
     pub(crate) name: String,
     pub(crate) query: Option<String>,
+    /// Folder of the queries folder the query file is written in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) dir: Option<String>,
     pub(crate) schema: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) check: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) param_syntax: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) csv: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) arrow: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) partitions: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) facade: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) statement_prefix: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) fixtures: Option<String>,
+    /// Role the queries are prepared as, after loading the schema
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) role: Option<String>,
+    /// First major PostgreSQL version the error doesn't occur on, skipping the test from it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max_version: Option<i32>,
     pub(crate) error: String,
 }
 
 impl From<&ErrorTest> for CodegenSettings {
-    fn from(_error_test: &ErrorTest) -> Self {
+    fn from(error_test: &ErrorTest) -> Self {
         Self {
-            derive_ser: false,
             gen_async: false,
             gen_sync: true,
+            param_syntax: error_test
+                .param_syntax
+                .as_deref()
+                .map(|s| ParamSyntax::from_str(s, true).unwrap())
+                .unwrap_or_default(),
+            gen_csv: error_test.csv,
+            gen_arrow: error_test.arrow,
+            gen_partitions: error_test.partitions,
+            gen_facade: error_test.facade,
+            statement_prefix: error_test.statement_prefix.clone(),
+            ..Self::default()
         }
     }
 }
@@ -1,12 +1,23 @@
 use std::{fmt::Display, process::ExitCode};
 
-use crate::{codegen::run_codegen_test, errors::run_errors_test};
+use crate::{
+    cli::run_cli_test, codegen::run_codegen_test, commands::run_commands_test,
+    compat::run_compat_test, config::run_config_test, errors::run_errors_test,
+    managed::run_managed_test, manifest::run_manifest_test, migrations::run_migrations_test,
+};
 use clap::Parser;
-use cornucopia::container;
+use cornucopia::container::{self, ManagedContainer};
 
+mod cli;
 mod codegen;
+mod commands;
+mod compat;
+mod config;
 mod errors;
 mod fixtures;
+mod managed;
+mod manifest;
+mod migrations;
 mod utils;
 
 /// Integration test CLI arguments
@@ -42,14 +53,25 @@ fn test(
 ) -> bool {
     // Start by removing previous container if it was left open
     container::cleanup(podman).ok();
-    container::setup(podman).unwrap();
+    container::setup(&ManagedContainer {
+        podman,
+        ..Default::default()
+    })
+    .unwrap();
     let successful = std::panic::catch_unwind(|| {
         let mut client = cornucopia::conn::cornucopia_conn().unwrap();
         display(run_errors_test(&mut client, apply_errors)).unwrap()
+            && display(run_migrations_test()).unwrap()
             && display(run_codegen_test(&mut client, apply_codegen)).unwrap()
+            && display(run_manifest_test(&mut client)).unwrap()
+            && display(run_config_test()).unwrap()
+            && display(run_compat_test()).unwrap()
+            && display(run_cli_test()).unwrap()
+            && display(run_commands_test(&mut client)).unwrap()
     });
     container::cleanup(podman).unwrap();
-    successful.unwrap()
+    // Commands managing their own container are run once the shared one is removed
+    successful.unwrap() && display(run_managed_test(podman)).unwrap()
 }
 
 /// Main entry point
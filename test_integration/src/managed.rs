@@ -0,0 +1,93 @@
+use std::path::PathBuf;
+
+use cornucopia::{
+    check_generated_managed, check_managed,
+    container::{self, ManagedContainer},
+    generate_managed, CodegenSettings, Error,
+};
+use owo_colors::OwoColorize;
+
+/// Run the tests of the commands managing their own container, return true if all test
+/// are successful. Successful commands remove their container, while failed ones leave it
+/// to the caller, which may keep it for debugging.
+pub(crate) fn run_managed_test(podman: bool) -> Result<bool, Box<dyn std::error::Error>> {
+    println!("{} {}", "[managed]".magenta(), "cleanup".magenta());
+    let managed = ManagedContainer {
+        podman,
+        ..Default::default()
+    };
+    let temp_dir = tempfile::tempdir()?;
+    let queries = temp_dir.path().join("queries");
+    std::fs::create_dir(&queries)?;
+    std::fs::write(
+        queries.join("module.sql"),
+        "--! answer\nSELECT 42 AS answer;",
+    )?;
+    let broken = temp_dir.path().join("broken");
+    std::fs::create_dir(&broken)?;
+    std::fs::write(
+        broken.join("module.sql"),
+        "--! missing\nSELECT * FROM missing;",
+    )?;
+    let schema = temp_dir.path().join("schema.sql");
+    std::fs::write(&schema, "CREATE TABLE answer (id INT);")?;
+    let schema = [schema];
+    let destination = temp_dir.path().join("cornucopia.rs");
+
+    let mut successful = true;
+    let mut test = |name: &str, result: Result<(), Error>, failed: bool| {
+        let running = container::is_running(podman);
+        if result.is_err() == failed && running == failed {
+            println!("{name} {}", "OK".green());
+        } else {
+            successful = false;
+            println!(
+                "{name} {}\n{}\n{:?}\n{} {running}\n",
+                "ERR".red(),
+                "Got:".bright_black(),
+                result,
+                "Container running:".bright_black(),
+            );
+        }
+        container::cleanup(podman).ok();
+    };
+    test(
+        "GenerateSucceeded",
+        generate_managed(
+            queries.clone(),
+            &schema,
+            Some(destination.clone()),
+            podman,
+            CodegenSettings::default(),
+        )
+        .map(drop),
+        false,
+    );
+    // The generated code is only compared once the queries are prepared
+    std::fs::write(&destination, "// Drifted")?;
+    test(
+        "CheckDrifted",
+        check_generated_managed(
+            queries.clone(),
+            &schema,
+            destination,
+            &managed,
+            None,
+            CodegenSettings::default(),
+        ),
+        true,
+    );
+    test(
+        "CheckFailed",
+        check_managed(
+            broken,
+            &schema,
+            None::<PathBuf>,
+            &managed,
+            &CodegenSettings::default(),
+        ),
+        true,
+    );
+
+    Ok(successful)
+}
@@ -0,0 +1,120 @@
+use cornucopia::{CodegenSettings, Error};
+use owo_colors::OwoColorize;
+
+use crate::utils::reset_db;
+
+/// Version of the workspace's crate in `dir`.
+fn workspace_version(dir: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let manifest: toml::Table = toml::from_str(&std::fs::read_to_string(format!(
+        "../crates/{dir}/Cargo.toml"
+    ))?)?;
+    Ok(manifest["package"]["version"].as_str().unwrap().to_string())
+}
+
+/// Run manifest test, return true if all test are successful
+pub(crate) fn run_manifest_test(
+    client: &mut postgres::Client,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    println!("{} {}", "[manifest]".magenta(), "dependencies".magenta());
+    reset_db(client)?;
+    client.batch_execute("CREATE TABLE event (id UUID, at TIMESTAMPTZ, data JSONB);")?;
+    let temp_dir = tempfile::tempdir()?;
+    let queries = temp_dir.path().join("queries");
+    std::fs::create_dir(&queries)?;
+    std::fs::write(
+        queries.join("events.sql"),
+        "--! events\nSELECT id, at, data FROM event;",
+    )?;
+    let sync_version = workspace_version("client_sync")?;
+    let async_version = workspace_version("client_async")?;
+
+    let cases = [
+        (
+            "Sync",
+            CodegenSettings {
+                gen_sync: true,
+                gen_async: false,
+                obfuscate_sql: true,
+                ..CodegenSettings::default()
+            },
+            format!(
+                r#"cornucopia_sync = {{ version = "{sync_version}", features = ["obfuscated-sql", "with-serde_json-1"] }}
+                postgres = {{ version = "0.19.4", features = ["with-serde_json-1", "with-time-0_3", "with-uuid-1"] }}
+                postgres-types = {{ version = "0.2.4", features = ["derive"] }}
+                serde_json = {{ version = "1.0.91", features = ["raw_value"] }}
+                time = "0.3.17"
+                uuid = "1.2.2""#
+            ),
+        ),
+        (
+            "AsyncSerialized",
+            CodegenSettings {
+                derive_ser: true,
+                gen_pool: true,
+                ..CodegenSettings::default()
+            },
+            format!(
+                r#"cornucopia_async = {{ version = "{async_version}", features = ["serde", "with-serde_json-1"] }}
+                tokio-postgres = {{ version = "0.7.7", features = ["with-serde_json-1", "with-time-0_3", "with-uuid-1"] }}
+                postgres-types = {{ version = "0.2.4", features = ["derive"] }}
+                futures = "0.3.25"
+                deadpool-postgres = "0.12.1"
+                serde = {{ version = "1.0.152", features = ["derive"] }}
+                serde_json = {{ version = "1.0.91", features = ["raw_value"] }}
+                time = {{ version = "0.3.17", features = ["serde"] }}
+                uuid = {{ version = "1.2.2", features = ["serde"] }}"#
+            ),
+        ),
+        (
+            "Workspace",
+            CodegenSettings {
+                gen_sync: true,
+                gen_async: false,
+                workspace_dependencies: true,
+                ..CodegenSettings::default()
+            },
+            r#"cornucopia_sync = { workspace = true, features = ["with-serde_json-1"] }
+            postgres = { workspace = true, features = ["with-serde_json-1", "with-time-0_3", "with-uuid-1"] }
+            postgres-types = { workspace = true, features = ["derive"] }
+            serde_json = { workspace = true, features = ["raw_value"] }
+            time = { workspace = true }
+            uuid = { workspace = true }"#
+                .to_string(),
+        ),
+    ];
+
+    let mut successful = true;
+    for (name, settings, expected) in cases {
+        let destination = temp_dir.path().join(name).join("src").join("lib.rs");
+        cornucopia::generate_live(
+            client,
+            &queries,
+            Some(&destination),
+            CodegenSettings {
+                split_modules: true,
+                crate_name: Some(name.to_lowercase()),
+                ..settings
+            },
+        )
+        .map_err(Error::report)?;
+        let manifest: toml::Table = toml::from_str(&std::fs::read_to_string(
+            temp_dir.path().join(name).join("Cargo.toml"),
+        )?)?;
+        let expected: toml::Table = toml::from_str(&expected)?;
+        if manifest["dependencies"].as_table() == Some(&expected) {
+            println!("{name} {}", "OK".green());
+        } else {
+            successful = false;
+            println!(
+                "{name} {}\n{}\n{}\n{}\n{}\n",
+                "ERR".red(),
+                "Expected:".bright_black(),
+                expected,
+                "Got:".bright_black(),
+                manifest["dependencies"],
+            );
+        }
+    }
+
+    Ok(successful)
+}
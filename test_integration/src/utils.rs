@@ -6,7 +6,19 @@ use std::{
 
 /// Reset the current database
 pub(crate) fn reset_db(client: &mut postgres::Client) -> Result<(), postgres::Error> {
-    client.batch_execute("DROP SCHEMA public CASCADE;CREATE SCHEMA public;")
+    // Drop every user schema, as schema files can create their own
+    client.batch_execute(
+        "DO $$
+        DECLARE schema_name TEXT;
+        BEGIN
+            FOR schema_name IN SELECT nspname FROM pg_namespace
+                WHERE nspname NOT LIKE 'pg\\_%' AND nspname <> 'information_schema'
+            LOOP
+                EXECUTE format('DROP SCHEMA %I CASCADE', schema_name);
+            END LOOP;
+        END $$;
+        CREATE SCHEMA public;",
+    )
 }
 
 pub(crate) fn rustfmt_file(path: &Path) {